@@ -6,6 +6,8 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::status_reporter::WRONG_INSTANCE_STATUS;
+
 #[derive(Debug, Error)]
 pub enum IntelError {
     #[error("HTTP request failed: {0}")]
@@ -14,10 +16,26 @@ pub enum IntelError {
     JsonError(#[from] serde_json::Error),
     #[error("Intel hub not configured (no base_url)")]
     NotConfigured,
+    #[error("Rate limited by Chorus, slow down before broadcasting again: {0}")]
+    RateLimited(String),
+    #[error("Rejected by Chorus: this session belongs to a different Chorus instance than the one listening at the configured URL")]
+    WrongInstance,
     #[error("Server error (HTTP {status}): {body}")]
     ServerError { status: u16, body: String },
 }
 
+impl IntelError {
+    /// Error for a non-success HTTP response.
+    fn from_status(status: u16, body: String) -> Self {
+        if status == WRONG_INSTANCE_STATUS {
+            eprintln!("[intel-client] Request reached a different Chorus instance; it was not applied");
+            Self::WrongInstance
+        } else {
+            Self::ServerError { status, body }
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BroadcastMessage {
     pub id: String,
@@ -28,6 +46,16 @@ pub struct BroadcastMessage {
     pub message: String,
     pub metadata: Option<serde_json::Value>,
     pub timestamp: String,
+    #[serde(default)]
+    pub project_hash: Option<String>,
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+    #[serde(default = "default_repeat_count")]
+    pub repeat_count: u32,
+}
+
+fn default_repeat_count() -> u32 {
+    1
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +64,8 @@ pub struct FileActivity {
     pub file_path: String,
     pub action: String,
     pub timestamp: String,
+    #[serde(default)]
+    pub project_hash: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,6 +76,8 @@ pub struct ScratchpadEntry {
     pub title: String,
     pub content: String,
     pub timestamp: String,
+    #[serde(default)]
+    pub project_hash: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -54,8 +86,27 @@ pub struct FileConflict {
     pub sessions: Vec<u32>,
     #[serde(default)]
     pub actions: Vec<FileActivity>,
+    #[serde(default)]
+    pub acknowledged: bool,
+    #[serde(default)]
+    pub project_hash: Option<String>,
 }
 
+/// A broadcast or scratchpad entry matching a search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub kind: String,
+    pub id: String,
+    pub session_id: u32,
+    pub category: String,
+    pub title: Option<String>,
+    pub snippet: String,
+    pub timestamp: String,
+}
+
+/// Header the StatusServer checks against its instance ID on intel requests.
+const INSTANCE_TOKEN_HEADER: &str = "x-chorus-instance";
+
 /// Client for the IntelHub HTTP endpoints on the StatusServer.
 #[derive(Clone)]
 pub struct IntelClient {
@@ -83,12 +134,21 @@ impl IntelClient {
         self.base_url.as_ref().map(|base| format!("{}{}", base, path))
     }
 
-    /// Broadcast a message to all other sessions.
+    /// Attaches the instance token so the StatusServer accepts the request.
+    fn authed(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.instance_id {
+            Some(id) => builder.header(INSTANCE_TOKEN_HEADER, id),
+            None => builder,
+        }
+    }
+
+    /// Broadcast a message to all other sessions, optionally expiring after `ttl_secs`.
     pub async fn broadcast(
         &self,
         category: &str,
         message: &str,
         metadata: Option<serde_json::Value>,
+        ttl_secs: Option<u64>,
     ) -> Result<BroadcastMessage, IntelError> {
         let url = match self.url("/broadcast") {
             Some(u) => u,
@@ -101,11 +161,11 @@ impl IntelClient {
             "category": category,
             "message": message,
             "metadata": metadata,
+            "ttl_secs": ttl_secs,
         });
 
         let resp = self
-            .client
-            .post(&url)
+            .authed(self.client.post(&url))
             .json(&payload)
             .timeout(std::time::Duration::from_secs(5))
             .send()
@@ -115,15 +175,35 @@ impl IntelClient {
         if !status.is_success() {
             let body = resp.text().await.unwrap_or_default();
             eprintln!("[intel-client] broadcast failed: HTTP {} - {}", status.as_u16(), body);
-            return Err(IntelError::ServerError { status: status.as_u16(), body });
+            if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+                let reason = serde_json::from_str::<BroadcastMessage>(&body)
+                    .map(|m| m.message)
+                    .unwrap_or(body);
+                return Err(IntelError::RateLimited(reason));
+            }
+            return Err(IntelError::from_status(status.as_u16(), body));
         }
 
         let msg: BroadcastMessage = resp.json().await?;
         Ok(msg)
     }
 
-    /// Get messages from other sessions.
-    pub async fn get_messages(&self) -> Result<Vec<BroadcastMessage>, IntelError> {
+    /// Query parameters limiting intel reads to this session's project, or
+    /// lifting that limit when `cross_project` is set.
+    fn scope_params(&self, cross_project: bool) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(id) = self.session_id {
+            params.push(("session_id", id.to_string()));
+        }
+        if cross_project {
+            params.push(("cross_project", "true".to_string()));
+        }
+        params
+    }
+
+    /// Get messages from other sessions in this project (or every project
+    /// when `cross_project` is set).
+    pub async fn get_messages(&self, cross_project: bool) -> Result<Vec<BroadcastMessage>, IntelError> {
         let session_id = self.session_id.unwrap_or(0);
         let url = match self.url(&format!("/messages/{}", session_id)) {
             Some(u) => u,
@@ -131,8 +211,8 @@ impl IntelClient {
         };
 
         let resp = self
-            .client
-            .get(&url)
+            .authed(self.client.get(&url))
+            .query(&[("cross_project", cross_project)])
             .timeout(std::time::Duration::from_secs(5))
             .send()
             .await?;
@@ -141,7 +221,7 @@ impl IntelClient {
         if !status.is_success() {
             let body = resp.text().await.unwrap_or_default();
             eprintln!("[intel-client] get_messages failed: HTTP {} - {}", status.as_u16(), body);
-            return Err(IntelError::ServerError { status: status.as_u16(), body });
+            return Err(IntelError::from_status(status.as_u16(), body));
         }
 
         let messages: Vec<BroadcastMessage> = resp.json().await?;
@@ -169,8 +249,7 @@ impl IntelClient {
         });
 
         let resp = self
-            .client
-            .post(&url)
+            .authed(self.client.post(&url))
             .json(&payload)
             .timeout(std::time::Duration::from_secs(5))
             .send()
@@ -180,23 +259,24 @@ impl IntelClient {
         if !status.is_success() {
             let body = resp.text().await.unwrap_or_default();
             eprintln!("[intel-client] write_scratchpad failed: HTTP {} - {}", status.as_u16(), body);
-            return Err(IntelError::ServerError { status: status.as_u16(), body });
+            return Err(IntelError::from_status(status.as_u16(), body));
         }
 
         let entry: ScratchpadEntry = resp.json().await?;
         Ok(entry)
     }
 
-    /// Read all scratchpad entries.
-    pub async fn read_scratchpad(&self) -> Result<Vec<ScratchpadEntry>, IntelError> {
+    /// Read scratchpad entries for this project (or every project when
+    /// `cross_project` is set).
+    pub async fn read_scratchpad(&self, cross_project: bool) -> Result<Vec<ScratchpadEntry>, IntelError> {
         let url = match self.url("/scratchpad") {
             Some(u) => u,
             None => return Err(IntelError::NotConfigured),
         };
 
         let resp = self
-            .client
-            .get(&url)
+            .authed(self.client.get(&url))
+            .query(&self.scope_params(cross_project))
             .timeout(std::time::Duration::from_secs(5))
             .send()
             .await?;
@@ -205,7 +285,7 @@ impl IntelClient {
         if !status.is_success() {
             let body = resp.text().await.unwrap_or_default();
             eprintln!("[intel-client] read_scratchpad failed: HTTP {} - {}", status.as_u16(), body);
-            return Err(IntelError::ServerError { status: status.as_u16(), body });
+            return Err(IntelError::from_status(status.as_u16(), body));
         }
 
         let entries: Vec<ScratchpadEntry> = resp.json().await?;
@@ -233,8 +313,7 @@ impl IntelClient {
         eprintln!("[intel-client] report_file: url={} payload={}", url, payload);
 
         let resp = self
-            .client
-            .post(&url)
+            .authed(self.client.post(&url))
             .json(&payload)
             .timeout(std::time::Duration::from_secs(5))
             .send()
@@ -244,7 +323,7 @@ impl IntelClient {
         if !status.is_success() {
             let body = resp.text().await.unwrap_or_default();
             eprintln!("[intel-client] report_file failed: HTTP {} - {}", status.as_u16(), body);
-            return Err(IntelError::ServerError { status: status.as_u16(), body });
+            return Err(IntelError::from_status(status.as_u16(), body));
         }
 
         let body_text = resp.text().await?;
@@ -252,4 +331,128 @@ impl IntelClient {
         let conflicts: Vec<FileConflict> = serde_json::from_str(&body_text)?;
         Ok(conflicts)
     }
+
+    /// Get current file conflicts between sessions in this project (or every
+    /// project when `cross_project` is set).
+    pub async fn get_conflicts(&self, cross_project: bool) -> Result<Vec<FileConflict>, IntelError> {
+        let url = match self.url("/conflicts") {
+            Some(u) => u,
+            None => return Err(IntelError::NotConfigured),
+        };
+
+        let resp = self
+            .authed(self.client.get(&url))
+            .query(&self.scope_params(cross_project))
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            eprintln!("[intel-client] get_conflicts failed: HTTP {} - {}", status.as_u16(), body);
+            return Err(IntelError::from_status(status.as_u16(), body));
+        }
+
+        let conflicts: Vec<FileConflict> = resp.json().await?;
+        Ok(conflicts)
+    }
+
+    /// Acknowledge a conflict on `file_path` so it stops warning this session.
+    pub async fn ack_conflict(&self, file_path: &str) -> Result<(), IntelError> {
+        let url = match self.url("/conflicts/ack") {
+            Some(u) => u,
+            None => return Err(IntelError::NotConfigured),
+        };
+
+        let payload = serde_json::json!({
+            "session_id": self.session_id.unwrap_or(0),
+            "instance_id": self.instance_id.clone().unwrap_or_default(),
+            "file_path": file_path,
+        });
+
+        let resp = self
+            .authed(self.client.post(&url))
+            .json(&payload)
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            eprintln!("[intel-client] ack_conflict failed: HTTP {} - {}", status.as_u16(), body);
+            return Err(IntelError::from_status(status.as_u16(), body));
+        }
+        Ok(())
+    }
+
+    /// Forward a log line to the Chorus debug panel for this session.
+    pub async fn send_log(&self, level: &str, message: &str, logger: &str) -> Result<(), IntelError> {
+        let url = match self.url("/log") {
+            Some(u) => u,
+            None => return Err(IntelError::NotConfigured),
+        };
+
+        let payload = serde_json::json!({
+            "session_id": self.session_id.unwrap_or(0),
+            "instance_id": self.instance_id.clone().unwrap_or_default(),
+            "level": level,
+            "message": message,
+            "logger": logger,
+        });
+
+        let resp = self
+            .authed(self.client.post(&url))
+            .json(&payload)
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            return Err(IntelError::from_status(status.as_u16(), body));
+        }
+        Ok(())
+    }
+
+    /// Search broadcasts and scratchpad notes.
+    ///
+    /// `kinds` restricts the search to `broadcast` and/or `scratchpad`; empty searches both.
+    /// Only this project's entries are searched unless `cross_project` is set.
+    pub async fn search(
+        &self,
+        query: &str,
+        kinds: &[&str],
+        cross_project: bool,
+    ) -> Result<Vec<SearchResult>, IntelError> {
+        let url = match self.url("/search") {
+            Some(u) => u,
+            None => return Err(IntelError::NotConfigured),
+        };
+
+        let mut params = vec![("q", query.to_string())];
+        if !kinds.is_empty() {
+            params.push(("kinds", kinds.join(",")));
+        }
+        params.extend(self.scope_params(cross_project));
+
+        let resp = self
+            .authed(self.client.get(&url))
+            .query(&params)
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await?;
+
+        let status = resp.status();
+        if !status.is_success() {
+            let body = resp.text().await.unwrap_or_default();
+            eprintln!("[intel-client] search failed: HTTP {} - {}", status.as_u16(), body);
+            return Err(IntelError::from_status(status.as_u16(), body));
+        }
+
+        let results: Vec<SearchResult> = resp.json().await?;
+        Ok(results)
+    }
 }
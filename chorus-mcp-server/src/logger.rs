@@ -0,0 +1,136 @@
+//! MCP logging.
+//!
+//! Log lines always go to stderr. Lines at or above the level the client set
+//! with `logging/setLevel` are also sent as `notifications/message`, and
+//! notable ones are forwarded to the Chorus status server's `/log` endpoint
+//! so they show up in the session's debug panel.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+use serde_json::json;
+
+use crate::intel_client::{IntelClient, IntelError};
+use crate::mcp_protocol::MessageWriter;
+
+/// MCP (RFC 5424) log levels, least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
+impl LogLevel {
+    const ALL: [LogLevel; 8] = [
+        LogLevel::Debug,
+        LogLevel::Info,
+        LogLevel::Notice,
+        LogLevel::Warning,
+        LogLevel::Error,
+        LogLevel::Critical,
+        LogLevel::Alert,
+        LogLevel::Emergency,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Notice => "notice",
+            LogLevel::Warning => "warning",
+            LogLevel::Error => "error",
+            LogLevel::Critical => "critical",
+            LogLevel::Alert => "alert",
+            LogLevel::Emergency => "emergency",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.as_str() == s)
+    }
+
+    fn from_u8(v: u8) -> Self {
+        Self::ALL[usize::from(v).min(Self::ALL.len() - 1)]
+    }
+}
+
+/// Level used until the client calls `logging/setLevel`.
+const DEFAULT_LOG_LEVEL: LogLevel = LogLevel::Info;
+/// Only lines at least this severe are forwarded to Chorus.
+const FORWARD_MIN_LEVEL: LogLevel = LogLevel::Notice;
+/// Logger name reported in notifications and forwarded lines.
+const LOGGER_NAME: &str = "chorus-mcp-server";
+
+/// Sends log lines to stderr, the MCP client and Chorus.
+#[derive(Clone)]
+pub struct McpLogger {
+    level: Arc<AtomicU8>,
+    writer: MessageWriter,
+    intel_client: IntelClient,
+}
+
+impl McpLogger {
+    pub fn new(writer: MessageWriter, intel_client: IntelClient) -> Self {
+        Self {
+            level: Arc::new(AtomicU8::new(DEFAULT_LOG_LEVEL as u8)),
+            writer,
+            intel_client,
+        }
+    }
+
+    /// The minimum level currently sent to the client.
+    pub fn level(&self) -> LogLevel {
+        LogLevel::from_u8(self.level.load(Ordering::Relaxed))
+    }
+
+    pub fn set_level(&self, level: LogLevel) {
+        self.level.store(level as u8, Ordering::Relaxed);
+    }
+
+    /// Log `message`, filtered by the client's requested level.
+    pub fn log(&self, level: LogLevel, message: &str) {
+        eprintln!("[{}] {}", LOGGER_NAME, message);
+        if level < self.level() {
+            return;
+        }
+
+        self.writer.notify(
+            "notifications/message",
+            json!({
+                "level": level.as_str(),
+                "logger": LOGGER_NAME,
+                "data": message
+            }),
+        );
+
+        if level >= FORWARD_MIN_LEVEL {
+            let client = self.intel_client.clone();
+            let message = message.to_string();
+            tokio::spawn(async move {
+                match client.send_log(level.as_str(), &message, LOGGER_NAME).await {
+                    Ok(()) | Err(IntelError::NotConfigured) => {}
+                    // Not logged through `log` to avoid a feedback loop
+                    Err(e) => eprintln!("[{}] Failed to forward log line: {}", LOGGER_NAME, e),
+                }
+            });
+        }
+    }
+
+    pub fn debug(&self, message: &str) {
+        self.log(LogLevel::Debug, message);
+    }
+
+    pub fn info(&self, message: &str) {
+        self.log(LogLevel::Info, message);
+    }
+
+    pub fn warning(&self, message: &str) {
+        self.log(LogLevel::Warning, message);
+    }
+}
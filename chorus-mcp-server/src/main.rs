@@ -5,6 +5,7 @@
 //! the Chorus application via HTTP POST.
 
 mod intel_client;
+mod logger;
 mod mcp_protocol;
 mod status_reporter;
 
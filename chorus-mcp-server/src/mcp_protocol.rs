@@ -6,17 +6,32 @@
 //! Status is reported automatically:
 //! - "idle" when initialized or after completing a tool call
 //! - "working" when a tool call is received
+//! - "finished" when stdin closes or the process receives SIGINT/SIGTERM
+//!
+//! Status reports are best-effort: they are queued to one background task
+//! that sends them in order, each with a timeout, so a slow or hung status
+//! server never stalls the stdin loop, and failures are only logged. Reports
+//! queued while another is in flight collapse to the latest. Intel calls (broadcast, inbox, scratchpad,
+//! search, file reports) are awaited, since their result is the tool's output.
+//!
+//! Intel hub data is also exposed as `chorus://` resources; subscribed
+//! resources are polled by the idle task and announced via
+//! `notifications/resources/updated` when they change.
 
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::io::{self, BufRead, Write};
+use std::collections::HashMap;
+use std::io::{self, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thiserror::Error;
-use tokio::sync::Mutex;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::sync::{mpsc, Mutex};
+use tokio::task::JoinHandle;
 
-use crate::intel_client::IntelClient;
+use crate::intel_client::{IntelClient, IntelError};
+use crate::logger::{LogLevel, McpLogger};
 use crate::status_reporter::StatusReporter;
 
 #[derive(Debug, Error)]
@@ -25,8 +40,6 @@ pub enum McpError {
     Io(#[from] io::Error),
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
-    #[error("Status reporting error: {0}")]
-    Status(#[from] crate::status_reporter::StatusError),
 }
 
 /// JSON-RPC request structure.
@@ -57,6 +70,137 @@ struct JsonRpcError {
     message: String,
 }
 
+/// Builds the JSON-RPC "Invalid Request" (-32600) response for `id`.
+fn invalid_request(id: Value) -> JsonRpcResponse {
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: None,
+        error: Some(JsonRpcError {
+            code: -32600,
+            message: "Invalid Request".to_string(),
+        }),
+    }
+}
+
+/// Renders broadcast metadata compactly as `{key=value, ...}` for inbox output.
+///
+/// String values are shown unquoted; other values as JSON. Returns None for
+/// missing, empty or non-object metadata.
+fn render_metadata(metadata: &Value) -> Option<String> {
+    let fields = metadata.as_object().filter(|m| !m.is_empty())?;
+    let rendered: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| match value {
+            Value::String(s) => format!("{}={}", key, s),
+            other => format!("{}={}", key, other),
+        })
+        .collect();
+    Some(format!("{{{}}}", rendered.join(", ")))
+}
+
+/// Reads the optional `cross_project` flag of an intel read tool call.
+fn cross_project_arg(params: &Value) -> bool {
+    params
+        .get("arguments")
+        .and_then(|a| a.get("cross_project"))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Machine-readable reason a tool call failed, sent as `_meta.code` next to
+/// the human-readable text so clients can tell what is worth retrying.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ToolErrorCode {
+    /// The arguments were missing or invalid; retrying won't help.
+    InvalidArgument,
+    UnknownTool,
+    /// Chorus didn't pass a status URL to this server.
+    NotConfigured,
+    /// Chorus couldn't be reached or didn't answer in time.
+    Network,
+    RateLimited,
+    /// Chorus failed to handle the request (HTTP 5xx).
+    ServerError,
+    /// Chorus refused the request (HTTP 4xx).
+    Rejected,
+    /// The status URL belongs to another Chorus instance.
+    WrongInstance,
+    /// Chorus answered with something that couldn't be parsed.
+    InvalidResponse,
+}
+
+impl ToolErrorCode {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::InvalidArgument => "invalid_argument",
+            Self::UnknownTool => "unknown_tool",
+            Self::NotConfigured => "not_configured",
+            Self::Network => "network",
+            Self::RateLimited => "rate_limited",
+            Self::ServerError => "server_error",
+            Self::Rejected => "rejected",
+            Self::WrongInstance => "wrong_instance",
+            Self::InvalidResponse => "invalid_response",
+        }
+    }
+
+    /// Whether the same call may succeed if made again later.
+    fn retryable(self) -> bool {
+        matches!(self, Self::Network | Self::RateLimited | Self::ServerError)
+    }
+
+    fn from_http(e: &reqwest::Error) -> Self {
+        if e.is_decode() {
+            Self::InvalidResponse
+        } else {
+            Self::Network
+        }
+    }
+}
+
+impl From<&IntelError> for ToolErrorCode {
+    fn from(e: &IntelError) -> Self {
+        match e {
+            IntelError::HttpError(e) => Self::from_http(e),
+            IntelError::JsonError(_) => Self::InvalidResponse,
+            IntelError::NotConfigured => Self::NotConfigured,
+            IntelError::RateLimited(_) => Self::RateLimited,
+            IntelError::WrongInstance => Self::WrongInstance,
+            IntelError::ServerError { status: 400 | 422, .. } => Self::InvalidArgument,
+            IntelError::ServerError { status, .. } if *status >= 500 => Self::ServerError,
+            IntelError::ServerError { .. } => Self::Rejected,
+        }
+    }
+}
+
+/// Builds a failed tools/call result: `text` for display, `code` in `_meta`.
+fn tool_error(code: ToolErrorCode, text: impl Into<String>) -> Value {
+    json!({
+        "content": [{ "type": "text", "text": text.into() }],
+        "isError": true,
+        "_meta": { "code": code.as_str(), "retryable": code.retryable() }
+    })
+}
+
+/// Tool result for arguments that failed validation.
+fn invalid_argument(text: impl Into<String>) -> Value {
+    tool_error(ToolErrorCode::InvalidArgument, text)
+}
+
+/// Extracts and resolves the `uri` parameter of a resources/* request.
+fn resource_param(params: &Value) -> Result<(&str, Resource), JsonRpcError> {
+    let uri = params.get("uri").and_then(|v| v.as_str()).ok_or_else(|| JsonRpcError {
+        code: -32602,
+        message: "Missing 'uri' parameter".to_string(),
+    })?;
+    let resource = Resource::from_uri(uri).ok_or_else(|| JsonRpcError {
+        code: RESOURCE_NOT_FOUND,
+        message: format!("Resource not found: {}", uri),
+    })?;
+    Ok((uri, resource))
+}
+
 /// Tracks the current working state for automatic status reporting.
 struct ActivityTracker {
     /// Last time we saw activity
@@ -87,11 +231,271 @@ impl ActivityTracker {
     }
 }
 
+/// Upper bound on a background status report, retries included.
+const STATUS_REPORT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A status report waiting in the `StatusQueue`.
+struct StatusUpdate {
+    state: String,
+    message: String,
+    needs_input_prompt: Option<String>,
+}
+
+/// Sends a session's status reports one at a time from a single task, so
+/// they reach Chorus in the order they were made. Reports queued while one
+/// is in flight collapse to the latest: a slow status server delays updates
+/// but never lets an older state land after a newer one.
+struct StatusQueue {
+    tx: mpsc::UnboundedSender<StatusUpdate>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl StatusQueue {
+    fn start(reporter: StatusReporter, logger: McpLogger) -> Self {
+        let (tx, mut rx) = mpsc::unbounded_channel::<StatusUpdate>();
+        let worker = tokio::spawn(async move {
+            while let Some(mut update) = rx.recv().await {
+                while let Ok(newer) = rx.try_recv() {
+                    update = newer;
+                }
+                let report = reporter.report_status(
+                    &update.state,
+                    &update.message,
+                    update.needs_input_prompt,
+                );
+                match tokio::time::timeout(STATUS_REPORT_TIMEOUT, report).await {
+                    Ok(Ok(())) => {}
+                    Ok(Err(e)) => {
+                        logger.warning(&format!("Status update '{}' failed: {}", update.state, e))
+                    }
+                    Err(_) => logger.warning(&format!(
+                        "Status update '{}' timed out after {:?}",
+                        update.state, STATUS_REPORT_TIMEOUT
+                    )),
+                }
+            }
+        });
+        Self {
+            tx,
+            worker: Mutex::new(Some(worker)),
+        }
+    }
+
+    /// Queues a report and returns at once.
+    fn send(&self, state: &str, message: &str, needs_input_prompt: Option<String>) {
+        let update = StatusUpdate {
+            state: state.to_string(),
+            message: message.to_string(),
+            needs_input_prompt,
+        };
+        if self.tx.send(update).is_err() {
+            eprintln!("[chorus-mcp-server] Status queue stopped, dropping '{}'", state);
+        }
+    }
+
+    /// Stops the worker, dropping queued and in-flight reports, so a report
+    /// made afterwards is the last one Chorus sees.
+    async fn stop(&self) {
+        if let Some(worker) = self.worker.lock().await.take() {
+            worker.abort();
+            let _ = worker.await;
+        }
+    }
+}
+
+/// Upper bound on the final status report at shutdown, so a dead status
+/// server can't keep the process alive.
+const SHUTDOWN_REPORT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Resolves when the process receives SIGINT, or SIGTERM on Unix.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut term) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = term.recv() => {}
+                }
+                return;
+            }
+            Err(e) => eprintln!("[chorus-mcp-server] Failed to install SIGTERM handler: {}", e),
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// How often the idle task re-reads subscribed resources to detect changes.
+const RESOURCE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// JSON-RPC error code MCP uses for an unknown resource URI.
+const RESOURCE_NOT_FOUND: i32 = -32002;
+
+/// Intel data exposed as MCP resources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Resource {
+    Inbox,
+    Scratchpad,
+    Conflicts,
+}
+
+impl Resource {
+    const ALL: [Resource; 3] = [Resource::Inbox, Resource::Scratchpad, Resource::Conflicts];
+
+    fn from_uri(uri: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|r| r.uri() == uri)
+    }
+
+    fn uri(self) -> &'static str {
+        match self {
+            Resource::Inbox => "chorus://inbox",
+            Resource::Scratchpad => "chorus://scratchpad",
+            Resource::Conflicts => "chorus://conflicts",
+        }
+    }
+
+    fn describe(self) -> Value {
+        let (name, description) = match self {
+            Resource::Inbox => ("Inbox", "Messages broadcast by other Chorus sessions"),
+            Resource::Scratchpad => ("Scratchpad", "Shared notes written by all sessions"),
+            Resource::Conflicts => (
+                "File conflicts",
+                "Files currently being edited by more than one session",
+            ),
+        };
+        json!({
+            "uri": self.uri(),
+            "name": name,
+            "description": description,
+            "mimeType": "application/json"
+        })
+    }
+
+    /// Fetch the resource's current contents as JSON text.
+    async fn read(self, client: &IntelClient) -> Result<String, IntelError> {
+        let text = match self {
+            Resource::Inbox => serde_json::to_string_pretty(&client.get_messages(false).await?)?,
+            Resource::Scratchpad => serde_json::to_string_pretty(&client.read_scratchpad(false).await?)?,
+            Resource::Conflicts => serde_json::to_string_pretty(&client.get_conflicts(false).await?)?,
+        };
+        Ok(text)
+    }
+}
+
+/// Subscribed resources mapped to the contents last seen, so the idle task
+/// only notifies on change. `None` until the first poll sets a baseline.
+type ResourceSubscriptions = Arc<Mutex<HashMap<Resource, Option<String>>>>;
+
+/// Shared, line-oriented JSON-RPC writer.
+///
+/// Responses from the request loop and out-of-band notifications (progress,
+/// resource updates) go through the same mutex so their lines never interleave.
+#[derive(Clone)]
+pub(crate) struct MessageWriter {
+    inner: Arc<std::sync::Mutex<Box<dyn Write + Send>>>,
+}
+
+impl MessageWriter {
+    fn stdout() -> Self {
+        Self::new(Box::new(io::stdout()))
+    }
+
+    fn new(writer: Box<dyn Write + Send>) -> Self {
+        Self {
+            inner: Arc::new(std::sync::Mutex::new(writer)),
+        }
+    }
+
+    /// Writes one message as a single line and flushes.
+    fn send(&self, message: &Value) -> io::Result<()> {
+        let mut writer = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        writeln!(writer, "{}", message)?;
+        writer.flush()
+    }
+
+    /// Sends a notification, logging rather than failing if stdout is gone.
+    pub(crate) fn notify(&self, method: &str, params: Value) {
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params
+        });
+        if let Err(e) = self.send(&notification) {
+            eprintln!("[chorus-mcp-server] Failed to send {}: {}", method, e);
+        }
+    }
+}
+
+/// Sends `notifications/progress` for a tool call that supplied a `progressToken`.
+struct Progress {
+    token: Value,
+    writer: MessageWriter,
+}
+
+impl Progress {
+    /// Returns a reporter if the request's `_meta` carries a progress token.
+    fn from_params(params: &Value, writer: &MessageWriter) -> Option<Self> {
+        let token = params.get("_meta")?.get("progressToken")?;
+        (token.is_string() || token.is_number()).then(|| Self {
+            token: token.clone(),
+            writer: writer.clone(),
+        })
+    }
+
+    fn report(&self, progress: u64, total: Option<u64>, message: &str) {
+        let mut params = json!({
+            "progressToken": self.token,
+            "progress": progress,
+            "message": message
+        });
+        if let Some(total) = total {
+            params["total"] = json!(total);
+        }
+        self.writer.notify("notifications/progress", params);
+    }
+}
+
+/// Re-reads each subscribed resource and sends `notifications/resources/updated`
+/// for those whose contents changed since the last poll.
+async fn notify_resource_updates(
+    client: &IntelClient,
+    subscriptions: &ResourceSubscriptions,
+    writer: &MessageWriter,
+) {
+    let subscribed: Vec<Resource> = subscriptions.lock().await.keys().copied().collect();
+    for resource in subscribed {
+        let Ok(current) = resource.read(client).await else {
+            continue;
+        };
+        let changed = {
+            let mut subs = subscriptions.lock().await;
+            // Unsubscribed while we were reading
+            let Some(last) = subs.get_mut(&resource) else {
+                continue;
+            };
+            let changed = last.as_ref().is_some_and(|prev| *prev != current);
+            *last = Some(current);
+            changed
+        };
+        if changed {
+            writer.notify(
+                "notifications/resources/updated",
+                json!({ "uri": resource.uri() }),
+            );
+        }
+    }
+}
+
 /// MCP server implementation with automatic status reporting.
 pub struct McpServer {
     status_reporter: StatusReporter,
+    status_queue: Arc<StatusQueue>,
     intel_client: IntelClient,
     activity: Arc<ActivityTracker>,
+    subscriptions: ResourceSubscriptions,
+    writer: MessageWriter,
+    logger: McpLogger,
 }
 
 impl McpServer {
@@ -105,28 +509,42 @@ impl McpServer {
             url.trim_end_matches("/status").to_string()
         });
 
+        let intel_client = IntelClient::new(base_url, session_id, instance_id.clone());
+        let writer = MessageWriter::stdout();
+        let status_reporter = StatusReporter::new(status_url, session_id, instance_id);
+        let logger = McpLogger::new(writer.clone(), intel_client.clone());
+
         Self {
-            status_reporter: StatusReporter::new(
-                status_url,
-                session_id,
-                instance_id.clone(),
-            ),
-            intel_client: IntelClient::new(base_url, session_id, instance_id),
+            status_queue: Arc::new(StatusQueue::start(status_reporter.clone(), logger.clone())),
+            status_reporter,
+            logger,
+            intel_client,
             activity: Arc::new(ActivityTracker::new()),
+            subscriptions: Arc::new(Mutex::new(HashMap::new())),
+            writer,
         }
     }
 
     /// Run the MCP server, reading from stdin and writing to stdout.
     /// Automatically reports status based on MCP activity.
+    ///
+    /// Returns when stdin reaches EOF (Claude disconnected) or a shutdown
+    /// signal arrives. In both cases the idle-detection task is stopped first
+    /// and a final "finished" status is reported so the UI doesn't keep
+    /// showing a stale "working" state.
     pub async fn run(&self) -> Result<(), McpError> {
-        let stdin = io::stdin();
-        let mut stdout = io::stdout();
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
 
         // Spawn idle detection task
         let activity = self.activity.clone();
-        let reporter = self.status_reporter.clone();
-        tokio::spawn(async move {
+        let status_queue = self.status_queue.clone();
+        let intel_client = self.intel_client.clone();
+        let subscriptions = self.subscriptions.clone();
+        let writer = self.writer.clone();
+        let logger = self.logger.clone();
+        let idle_task = tokio::spawn(async move {
             let idle_threshold = Duration::from_secs(2);
+            let mut last_resource_poll = Instant::now();
             loop {
                 tokio::time::sleep(Duration::from_millis(500)).await;
 
@@ -136,14 +554,38 @@ impl McpServer {
                 // If we were working but haven't seen activity for a while, go idle
                 if activity.is_working() && elapsed > idle_threshold {
                     activity.set_working(false);
-                    eprintln!("[chorus-mcp-server] No activity for {:?}, reporting idle", elapsed);
-                    let _ = reporter.report_status("idle", "Ready", None).await;
+                    logger.debug(&format!("No activity for {:?}, reporting idle", elapsed));
+                    status_queue.send("idle", "Ready", None);
+                }
+
+                if last_resource_poll.elapsed() >= RESOURCE_POLL_INTERVAL {
+                    last_resource_poll = Instant::now();
+                    notify_resource_updates(&intel_client, &subscriptions, &writer).await;
                 }
             }
         });
 
-        for line in stdin.lock().lines() {
-            let line = line?;
+        let shutdown = shutdown_signal();
+        tokio::pin!(shutdown);
+
+        let result = loop {
+            let line = tokio::select! {
+                line = lines.next_line() => line,
+                _ = &mut shutdown => {
+                    eprintln!("[chorus-mcp-server] Received shutdown signal");
+                    break Ok(());
+                }
+            };
+
+            let line = match line {
+                Ok(Some(line)) => line,
+                Ok(None) => {
+                    eprintln!("[chorus-mcp-server] stdin closed");
+                    break Ok(());
+                }
+                Err(e) => break Err(e.into()),
+            };
+
             if line.is_empty() {
                 continue;
             }
@@ -151,24 +593,92 @@ impl McpServer {
             // Mark activity on every message
             self.activity.mark_activity().await;
 
-            let request: JsonRpcRequest = match serde_json::from_str(&line) {
+            if let Some(resp) = self.handle_line(&line).await {
+                self.writer.send(&resp)?;
+            }
+        };
+
+        // Stop idle detection and queued reports before the final report so
+        // nothing can overwrite it
+        idle_task.abort();
+        let _ = idle_task.await;
+        self.status_queue.stop().await;
+        self.report_shutdown().await;
+
+        result
+    }
+
+    /// Reports a final "finished" status, bounded by `SHUTDOWN_REPORT_TIMEOUT`.
+    async fn report_shutdown(&self) {
+        let report = self
+            .status_reporter
+            .report_status("finished", "Disconnected", None);
+        match tokio::time::timeout(SHUTDOWN_REPORT_TIMEOUT, report).await {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => eprintln!("[chorus-mcp-server] Final status report failed: {}", e),
+            Err(_) => eprintln!(
+                "[chorus-mcp-server] Final status report timed out after {:?}",
+                SHUTDOWN_REPORT_TIMEOUT
+            ),
+        }
+    }
+
+    /// Queues a status report for the background sender, so the caller
+    /// returns at once. Failures and timeouts are only logged.
+    fn report_status_in_background(&self, state: &str, message: &str, needs_input_prompt: Option<String>) {
+        self.status_queue.send(state, message, needs_input_prompt);
+    }
+
+    /// Handle one line of input, which is either a single request or a
+    /// JSON-RPC batch (array of requests).
+    ///
+    /// Batches yield an array of responses with notifications omitted, or
+    /// None if every element was a notification. Malformed batch elements
+    /// get a per-element "Invalid Request" error instead of failing the batch.
+    async fn handle_line(&self, line: &str) -> Option<Value> {
+        let value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(e) => {
+                eprintln!("Failed to parse request: {}", e);
+                return None;
+            }
+        };
+
+        let Value::Array(elements) = value else {
+            let request: JsonRpcRequest = match serde_json::from_value(value) {
                 Ok(req) => req,
                 Err(e) => {
                     eprintln!("Failed to parse request: {}", e);
-                    continue;
+                    return None;
                 }
             };
+            let response = self.handle_request(&request).await?;
+            return serde_json::to_value(response).ok();
+        };
 
-            let response = self.handle_request(&request).await;
+        // An empty batch is itself an invalid request
+        if elements.is_empty() {
+            return serde_json::to_value(invalid_request(Value::Null)).ok();
+        }
 
-            if let Some(resp) = response {
-                let output = serde_json::to_string(&resp)?;
-                writeln!(stdout, "{}", output)?;
-                stdout.flush()?;
-            }
+        let mut responses = Vec::with_capacity(elements.len());
+        for element in elements {
+            let id = element.get("id").cloned().unwrap_or(Value::Null);
+            let response = match serde_json::from_value::<JsonRpcRequest>(element) {
+                Ok(request) => self.handle_request(&request).await,
+                Err(e) => {
+                    eprintln!("Failed to parse batch element: {}", e);
+                    Some(invalid_request(id))
+                }
+            };
+            responses.extend(response);
         }
 
-        Ok(())
+        if responses.is_empty() {
+            None
+        } else {
+            serde_json::to_value(responses).ok()
+        }
     }
 
     /// Handle a single JSON-RPC request.
@@ -180,10 +690,28 @@ impl McpServer {
             "initialize" => (Some(self.handle_initialize()), None),
             "notifications/initialized" => {
                 // Auto-report "idle" status when Claude connects
-                eprintln!("[chorus-mcp-server] Initialized - reporting idle status");
-                let _ = self.status_reporter.report_status("idle", "Ready", None).await;
+                self.logger.info("Initialized - reporting idle status");
+                self.report_status_in_background("idle", "Ready", None);
                 return None;
             }
+            "logging/setLevel" => match request
+                .params
+                .get("level")
+                .and_then(|v| v.as_str())
+                .and_then(LogLevel::parse)
+            {
+                Some(level) => {
+                    self.logger.set_level(level);
+                    (Some(json!({})), None)
+                }
+                None => (
+                    None,
+                    Some(JsonRpcError {
+                        code: -32602,
+                        message: "Invalid or missing 'level' parameter".to_string(),
+                    }),
+                ),
+            },
             "tools/list" => (Some(self.handle_tools_list()), None),
             "tools/call" => match self.handle_tools_call(&request.params).await {
                 Ok(result) => (Some(result), None),
@@ -195,6 +723,18 @@ impl McpServer {
                     }),
                 ),
             },
+            "resources/list" => (Some(self.handle_resources_list()), None),
+            "resources/read" => match self.handle_resources_read(&request.params).await {
+                Ok(result) => (Some(result), None),
+                Err(e) => (None, Some(e)),
+            },
+            "resources/subscribe" | "resources/unsubscribe" => {
+                let subscribe = request.method == "resources/subscribe";
+                match self.handle_resources_subscribe(&request.params, subscribe).await {
+                    Ok(result) => (Some(result), None),
+                    Err(e) => (None, Some(e)),
+                }
+            }
             "ping" => (Some(json!({})), None),
             _ => (
                 None,
@@ -218,7 +758,12 @@ impl McpServer {
         json!({
             "protocolVersion": "2024-11-05",
             "capabilities": {
-                "tools": {}
+                "tools": {},
+                "logging": {},
+                "resources": {
+                    "subscribe": true,
+                    "listChanged": false
+                }
             },
             "serverInfo": {
                 "name": "chorus-mcp-server",
@@ -227,6 +772,44 @@ impl McpServer {
         })
     }
 
+    /// Handle the resources/list request.
+    fn handle_resources_list(&self) -> Value {
+        let resources: Vec<Value> = Resource::ALL.into_iter().map(Resource::describe).collect();
+        json!({ "resources": resources })
+    }
+
+    /// Handle the resources/read request.
+    async fn handle_resources_read(&self, params: &Value) -> Result<Value, JsonRpcError> {
+        let (uri, resource) = resource_param(params)?;
+        let text = resource.read(&self.intel_client).await.map_err(|e| JsonRpcError {
+            code: -32603,
+            message: format!("Failed to read {}: {}", uri, e),
+        })?;
+        Ok(json!({
+            "contents": [{
+                "uri": uri,
+                "mimeType": "application/json",
+                "text": text
+            }]
+        }))
+    }
+
+    /// Handle resources/subscribe and resources/unsubscribe.
+    async fn handle_resources_subscribe(
+        &self,
+        params: &Value,
+        subscribe: bool,
+    ) -> Result<Value, JsonRpcError> {
+        let (_, resource) = resource_param(params)?;
+        let mut subs = self.subscriptions.lock().await;
+        if subscribe {
+            subs.entry(resource).or_insert(None);
+        } else {
+            subs.remove(&resource);
+        }
+        Ok(json!({}))
+    }
+
     /// Handle the tools/list request.
     fn handle_tools_list(&self) -> Value {
         json!({
@@ -268,6 +851,15 @@ impl McpServer {
                             "message": {
                                 "type": "string",
                                 "description": "The message to broadcast to other sessions"
+                            },
+                            "metadata": {
+                                "type": "object",
+                                "description": "Optional structured context, e.g. {\"file\": \"src/api.rs\", \"pr\": \"https://...\", \"severity\": \"high\"}"
+                            },
+                            "ttl_secs": {
+                                "type": "integer",
+                                "minimum": 1,
+                                "description": "Optional lifetime in seconds for time-sensitive messages such as \"working on X\"; omit to keep the message until it is evicted"
                             }
                         },
                         "required": ["category", "message"]
@@ -275,10 +867,15 @@ impl McpServer {
                 },
                 {
                     "name": "chorus_inbox",
-                    "description": "Read messages broadcast by other Chorus sessions. Returns messages from other agents, excluding your own broadcasts.",
+                    "description": "Read messages broadcast by other Chorus sessions working on the same project. Returns messages from other agents, excluding your own broadcasts.",
                     "inputSchema": {
                         "type": "object",
-                        "properties": {},
+                        "properties": {
+                            "cross_project": {
+                                "type": "boolean",
+                                "description": "Include sessions working on other projects (default: only your project)"
+                            }
+                        },
                         "required": []
                     }
                 },
@@ -307,13 +904,55 @@ impl McpServer {
                 },
                 {
                     "name": "chorus_scratchpad_read",
-                    "description": "Read all notes from the shared scratchpad. Returns notes written by all sessions.",
+                    "description": "Read all notes from the shared scratchpad. Returns notes written by all sessions on the same project.",
                     "inputSchema": {
                         "type": "object",
-                        "properties": {},
+                        "properties": {
+                            "cross_project": {
+                                "type": "boolean",
+                                "description": "Include sessions working on other projects (default: only your project)"
+                            }
+                        },
                         "required": []
                     }
                 },
+                {
+                    "name": "chorus_ack_conflict",
+                    "description": "Acknowledge a file conflict reported by chorus_report_file once you have coordinated with the other session(s). Silences the CONFLICT warning for this file until another session edits it again.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "file_path": {
+                                "type": "string",
+                                "description": "Path of the conflicted file, relative to the project root or absolute"
+                            }
+                        },
+                        "required": ["file_path"]
+                    }
+                },
+                {
+                    "name": "chorus_search",
+                    "description": "Search broadcasts and scratchpad notes from all sessions. Use to find prior discoveries or decisions without reading everything. Returns the newest matches with a snippet around each match.",
+                    "inputSchema": {
+                        "type": "object",
+                        "properties": {
+                            "query": {
+                                "type": "string",
+                                "description": "Words to search for (matches any word, case-insensitive). Wrap in double quotes to match an exact phrase."
+                            },
+                            "kinds": {
+                                "type": "array",
+                                "items": { "type": "string", "enum": ["broadcast", "scratchpad"] },
+                                "description": "Limit the search to these sources (default: both)"
+                            },
+                            "cross_project": {
+                                "type": "boolean",
+                                "description": "Include sessions working on other projects (default: only your project)"
+                            }
+                        },
+                        "required": ["query"]
+                    }
+                },
                 {
                     "name": "chorus_report_file",
                     "description": "Report that you are modifying a file. This enables conflict detection — if another session is also editing the same file, a conflict alert is raised. Call this BEFORE you start editing a file.",
@@ -338,11 +977,15 @@ impl McpServer {
     }
 
     /// Handle the tools/call request.
+    ///
+    /// If the call's `_meta` carries a `progressToken`, long-running tools
+    /// report `notifications/progress` while they work.
     async fn handle_tools_call(&self, params: &Value) -> Result<Value, McpError> {
         let name = params
             .get("name")
             .and_then(|v| v.as_str())
             .unwrap_or("");
+        let progress = Progress::from_params(params, &self.writer);
 
         match name {
             "chorus_status" => {
@@ -350,27 +993,18 @@ impl McpServer {
 
                 let state = match arguments.get("state").and_then(|v| v.as_str()) {
                     Some(s) => s,
-                    None => return Ok(json!({
-                        "content": [{ "type": "text", "text": "Error: 'state' is required" }],
-                        "isError": true
-                    })),
+                    None => return Ok(invalid_argument("Error: 'state' is required")),
                 };
 
                 // Validate state enum
                 const VALID_STATES: &[&str] = &["idle", "working", "needs_input", "finished", "error"];
                 if !VALID_STATES.contains(&state) {
-                    return Ok(json!({
-                        "content": [{ "type": "text", "text": format!("Error: 'state' must be one of {:?}", VALID_STATES) }],
-                        "isError": true
-                    }));
+                    return Ok(invalid_argument(format!("Error: 'state' must be one of {:?}", VALID_STATES)));
                 }
 
                 let message = match arguments.get("message").and_then(|v| v.as_str()) {
                     Some(m) => m,
-                    None => return Ok(json!({
-                        "content": [{ "type": "text", "text": "Error: 'message' is required" }],
-                        "isError": true
-                    })),
+                    None => return Ok(invalid_argument("Error: 'message' is required")),
                 };
 
                 let needs_input_prompt = arguments
@@ -378,10 +1012,7 @@ impl McpServer {
                     .and_then(|v| v.as_str())
                     .map(|s| s.to_string());
 
-                // Report status via HTTP
-                self.status_reporter
-                    .report_status(state, message, needs_input_prompt)
-                    .await?;
+                self.report_status_in_background(state, message, needs_input_prompt);
 
                 Ok(json!({
                     "content": [
@@ -397,40 +1028,45 @@ impl McpServer {
 
                 let category = match arguments.get("category").and_then(|v| v.as_str()) {
                     Some(c) => c,
-                    None => return Ok(json!({
-                        "content": [{ "type": "text", "text": "Error: 'category' is required" }],
-                        "isError": true
-                    })),
+                    None => return Ok(invalid_argument("Error: 'category' is required")),
                 };
 
                 const VALID_CATEGORIES: &[&str] = &["discovery", "warning", "knowledge", "info"];
                 if !VALID_CATEGORIES.contains(&category) {
-                    return Ok(json!({
-                        "content": [{ "type": "text", "text": format!("Error: 'category' must be one of {:?}", VALID_CATEGORIES) }],
-                        "isError": true
-                    }));
+                    return Ok(invalid_argument(format!("Error: 'category' must be one of {:?}", VALID_CATEGORIES)));
                 }
 
                 let message = match arguments.get("message").and_then(|v| v.as_str()) {
                     Some(m) => m,
-                    None => return Ok(json!({
-                        "content": [{ "type": "text", "text": "Error: 'message' is required" }],
-                        "isError": true
-                    })),
+                    None => return Ok(invalid_argument("Error: 'message' is required")),
+                };
+
+                let metadata = match arguments.get("metadata") {
+                    None | Some(Value::Null) => None,
+                    Some(m) if m.is_object() => Some(m.clone()),
+                    Some(_) => return Ok(invalid_argument("Error: 'metadata' must be an object")),
+                };
+
+                let ttl_secs = match arguments.get("ttl_secs") {
+                    None | Some(Value::Null) => None,
+                    Some(v) => match v.as_u64() {
+                        Some(ttl) if ttl > 0 => Some(ttl),
+                        _ => return Ok(invalid_argument("Error: 'ttl_secs' must be a positive integer")),
+                    },
                 };
 
-                match self.intel_client.broadcast(category, message, None).await {
+                match self.intel_client.broadcast(category, message, metadata, ttl_secs).await {
+                    Ok(msg) if msg.repeat_count > 1 => Ok(json!({
+                        "content": [{ "type": "text", "text": format!("Already broadcast recently [{}]: {} (now repeated {}x)", msg.category, msg.message, msg.repeat_count) }]
+                    })),
                     Ok(msg) => Ok(json!({
                         "content": [{ "type": "text", "text": format!("Broadcast sent [{}]: {}", msg.category, msg.message) }]
                     })),
-                    Err(e) => Ok(json!({
-                        "content": [{ "type": "text", "text": format!("Broadcast failed: {}", e) }],
-                        "isError": true
-                    })),
+                    Err(e) => Ok(tool_error(ToolErrorCode::from(&e), format!("Broadcast failed: {}", e))),
                 }
             }
             "chorus_inbox" => {
-                match self.intel_client.get_messages().await {
+                match self.intel_client.get_messages(cross_project_arg(params)).await {
                     Ok(messages) => {
                         if messages.is_empty() {
                             Ok(json!({
@@ -438,17 +1074,21 @@ impl McpServer {
                             }))
                         } else {
                             let formatted: Vec<String> = messages.iter().map(|m| {
-                                format!("[Session #{} | {}] {}", m.session_id, m.category, m.message)
+                                let mut line = format!("[Session #{} | {}] {}", m.session_id, m.category, m.message);
+                                if m.repeat_count > 1 {
+                                    line.push_str(&format!(" (repeated {}x)", m.repeat_count));
+                                }
+                                match m.metadata.as_ref().and_then(render_metadata) {
+                                    Some(meta) => format!("{} {}", line, meta),
+                                    None => line,
+                                }
                             }).collect();
                             Ok(json!({
                                 "content": [{ "type": "text", "text": format!("{} message(s) from other sessions:\n{}", messages.len(), formatted.join("\n")) }]
                             }))
                         }
                     }
-                    Err(e) => Ok(json!({
-                        "content": [{ "type": "text", "text": format!("Failed to read inbox: {}", e) }],
-                        "isError": true
-                    })),
+                    Err(e) => Ok(tool_error(ToolErrorCode::from(&e), format!("Failed to read inbox: {}", e))),
                 }
             }
             "chorus_scratchpad_write" => {
@@ -456,48 +1096,33 @@ impl McpServer {
 
                 let category = match arguments.get("category").and_then(|v| v.as_str()) {
                     Some(c) => c,
-                    None => return Ok(json!({
-                        "content": [{ "type": "text", "text": "Error: 'category' is required" }],
-                        "isError": true
-                    })),
+                    None => return Ok(invalid_argument("Error: 'category' is required")),
                 };
 
                 const VALID_SP_CATEGORIES: &[&str] = &["architecture", "api", "decision", "note"];
                 if !VALID_SP_CATEGORIES.contains(&category) {
-                    return Ok(json!({
-                        "content": [{ "type": "text", "text": format!("Error: 'category' must be one of {:?}", VALID_SP_CATEGORIES) }],
-                        "isError": true
-                    }));
+                    return Ok(invalid_argument(format!("Error: 'category' must be one of {:?}", VALID_SP_CATEGORIES)));
                 }
 
                 let title = match arguments.get("title").and_then(|v| v.as_str()) {
                     Some(t) => t,
-                    None => return Ok(json!({
-                        "content": [{ "type": "text", "text": "Error: 'title' is required" }],
-                        "isError": true
-                    })),
+                    None => return Ok(invalid_argument("Error: 'title' is required")),
                 };
 
                 let content = match arguments.get("content").and_then(|v| v.as_str()) {
                     Some(c) => c,
-                    None => return Ok(json!({
-                        "content": [{ "type": "text", "text": "Error: 'content' is required" }],
-                        "isError": true
-                    })),
+                    None => return Ok(invalid_argument("Error: 'content' is required")),
                 };
 
                 match self.intel_client.write_scratchpad(category, title, content).await {
                     Ok(entry) => Ok(json!({
                         "content": [{ "type": "text", "text": format!("Scratchpad note added: [{}] {}", entry.category, entry.title) }]
                     })),
-                    Err(e) => Ok(json!({
-                        "content": [{ "type": "text", "text": format!("Scratchpad write failed: {}", e) }],
-                        "isError": true
-                    })),
+                    Err(e) => Ok(tool_error(ToolErrorCode::from(&e), format!("Scratchpad write failed: {}", e))),
                 }
             }
             "chorus_scratchpad_read" => {
-                match self.intel_client.read_scratchpad().await {
+                match self.intel_client.read_scratchpad(cross_project_arg(params)).await {
                     Ok(entries) => {
                         if entries.is_empty() {
                             Ok(json!({
@@ -512,10 +1137,72 @@ impl McpServer {
                             }))
                         }
                     }
-                    Err(e) => Ok(json!({
-                        "content": [{ "type": "text", "text": format!("Scratchpad read failed: {}", e) }],
-                        "isError": true
+                    Err(e) => Ok(tool_error(ToolErrorCode::from(&e), format!("Scratchpad read failed: {}", e))),
+                }
+            }
+            "chorus_ack_conflict" => {
+                let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+
+                let file_path = match arguments.get("file_path").and_then(|v| v.as_str()) {
+                    Some(p) => p,
+                    None => return Ok(invalid_argument("Error: 'file_path' is required")),
+                };
+
+                match self.intel_client.ack_conflict(file_path).await {
+                    Ok(()) => Ok(json!({
+                        "content": [{ "type": "text", "text": format!("Conflict on {} acknowledged. You will be warned again if another session edits it.", file_path) }]
                     })),
+                    Err(e) => Ok(tool_error(ToolErrorCode::from(&e), format!("Acknowledge failed: {}", e))),
+                }
+            }
+            "chorus_search" => {
+                let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+
+                let query = match arguments.get("query").and_then(|v| v.as_str()) {
+                    Some(q) if !q.trim().is_empty() => q,
+                    _ => return Ok(invalid_argument("Error: 'query' is required")),
+                };
+
+                const VALID_KINDS: &[&str] = &["broadcast", "scratchpad"];
+                let kinds: Vec<&str> = arguments
+                    .get("kinds")
+                    .and_then(|v| v.as_array())
+                    .map(|a| a.iter().filter_map(|k| k.as_str()).collect())
+                    .unwrap_or_default();
+                if let Some(bad) = kinds.iter().find(|k| !VALID_KINDS.contains(k)) {
+                    return Ok(invalid_argument(format!("Error: unknown kind '{}', expected one of {:?}", bad, VALID_KINDS)));
+                }
+
+                if let Some(progress) = &progress {
+                    progress.report(0, Some(1), "Searching intel hub");
+                }
+                let searched = self.intel_client
+                    .search(query, &kinds, cross_project_arg(params))
+                    .await;
+                if let (Some(progress), Ok(results)) = (&progress, &searched) {
+                    progress.report(1, Some(1), &format!("Found {} match(es)", results.len()));
+                }
+
+                match searched {
+                    Ok(results) => {
+                        if results.is_empty() {
+                            Ok(json!({
+                                "content": [{ "type": "text", "text": format!("No matches for {}.", query) }]
+                            }))
+                        } else {
+                            let formatted: Vec<String> = results.iter().map(|r| {
+                                let label = match &r.title {
+                                    Some(title) => format!("{} | {} | {}", r.kind, r.category, title),
+                                    None => format!("{} | {}", r.kind, r.category),
+                                };
+                                format!("[Session #{} | {}] {}", r.session_id, label, r.snippet)
+                            }).collect();
+                            Ok(json!({
+                                "content": [{ "type": "text", "text": format!("{} match(es):\n{}", results.len(), formatted.join("\n")) }]
+                            }))
+                        }
+                    }
+                    Err(e) => Ok(tool_error(ToolErrorCode::from(&e), format!("Search failed: {}", e))),
                 }
             }
             "chorus_report_file" => {
@@ -523,34 +1210,22 @@ impl McpServer {
 
                 let file_path = match arguments.get("file_path").and_then(|v| v.as_str()) {
                     Some(p) => p,
-                    None => return Ok(json!({
-                        "content": [{ "type": "text", "text": "Error: 'file_path' is required" }],
-                        "isError": true
-                    })),
+                    None => return Ok(invalid_argument("Error: 'file_path' is required")),
                 };
 
-                // Reject path traversal
-                if file_path.contains("..") {
-                    return Ok(json!({
-                        "content": [{ "type": "text", "text": "Error: path traversal ('..') not allowed in file_path" }],
-                        "isError": true
-                    }));
+                // Reject path traversal; only Chorus knows the session's root to check against
+                if let Err(e) = chorus_paths::check_reported_path(file_path, None) {
+                    return Ok(invalid_argument(format!("Error: {} in file_path", e)));
                 }
 
                 let action = match arguments.get("action").and_then(|v| v.as_str()) {
                     Some(a) => a,
-                    None => return Ok(json!({
-                        "content": [{ "type": "text", "text": "Error: 'action' is required" }],
-                        "isError": true
-                    })),
+                    None => return Ok(invalid_argument("Error: 'action' is required")),
                 };
 
                 const VALID_ACTIONS: &[&str] = &["editing", "created", "deleted"];
                 if !VALID_ACTIONS.contains(&action) {
-                    return Ok(json!({
-                        "content": [{ "type": "text", "text": format!("Error: 'action' must be one of {:?}", VALID_ACTIONS) }],
-                        "isError": true
-                    }));
+                    return Ok(invalid_argument(format!("Error: 'action' must be one of {:?}", VALID_ACTIONS)));
                 }
 
                 match self.intel_client.report_file(file_path, action).await {
@@ -568,21 +1243,404 @@ impl McpServer {
                             }))
                         }
                     }
-                    Err(e) => Ok(json!({
-                        "content": [{ "type": "text", "text": format!("File report failed: {}", e) }],
-                        "isError": true
-                    })),
+                    Err(e) => Ok(tool_error(ToolErrorCode::from(&e), format!("File report failed: {}", e))),
                 }
             }
-            _ => Ok(json!({
-                "content": [
-                    {
-                        "type": "text",
-                        "text": format!("Unknown tool: {}", name)
+            _ => Ok(tool_error(ToolErrorCode::UnknownTool, format!("Unknown tool: {}", name))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn batch_returns_array_of_responses() {
+        let server = McpServer::new(None, None, None);
+        let line = r#"[{"jsonrpc":"2.0","id":1,"method":"ping"},{"jsonrpc":"2.0","id":2,"method":"tools/list"}]"#;
+
+        let response = server.handle_line(line).await.unwrap();
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["id"], 1);
+        assert_eq!(responses[1]["id"], 2);
+        assert!(responses[1]["result"]["tools"].is_array());
+    }
+
+    #[tokio::test]
+    async fn batch_skips_notifications_and_reports_malformed_elements() {
+        let server = McpServer::new(None, None, None);
+        let line = r#"[{"jsonrpc":"2.0","method":"ping"},{"id":7,"method":42}]"#;
+
+        let response = server.handle_line(line).await.unwrap();
+        let responses = response.as_array().unwrap();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["id"], 7);
+        assert_eq!(responses[0]["error"]["code"], -32600);
+    }
+
+    #[tokio::test]
+    async fn single_request_returns_object() {
+        let server = McpServer::new(None, None, None);
+        let response = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":"a","method":"ping"}"#)
+            .await
+            .unwrap();
+        assert_eq!(response["id"], "a");
+        assert!(response.is_object());
+    }
+
+    #[tokio::test]
+    async fn intel_read_tools_accept_cross_project_flag() {
+        let server = McpServer::new(None, None, None);
+        let response = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"tools/list"}"#)
+            .await
+            .unwrap();
+        let tools = response["result"]["tools"].as_array().unwrap();
+        for name in ["chorus_inbox", "chorus_scratchpad_read", "chorus_search"] {
+            let tool = tools.iter().find(|t| t["name"] == name).unwrap();
+            assert_eq!(tool["inputSchema"]["properties"]["cross_project"]["type"], "boolean", "{name}");
+        }
+
+        assert!(cross_project_arg(&json!({ "arguments": { "cross_project": true } })));
+        assert!(!cross_project_arg(&json!({ "arguments": {} })));
+        assert!(!cross_project_arg(&json!({})));
+    }
+
+    #[tokio::test]
+    async fn search_validates_arguments_before_calling_hub() {
+        let server = McpServer::new(None, None, None);
+        let call = |arguments: Value| {
+            json!({ "name": "chorus_search", "arguments": arguments })
+        };
+
+        let missing = server.handle_tools_call(&call(json!({ "query": " " }))).await.unwrap();
+        assert_eq!(missing["isError"], true);
+
+        let bad_kind = server
+            .handle_tools_call(&call(json!({ "query": "auth", "kinds": ["files"] })))
+            .await
+            .unwrap();
+        assert_eq!(bad_kind["isError"], true);
+
+        let unconfigured = server
+            .handle_tools_call(&call(json!({ "query": "auth", "kinds": ["broadcast"] })))
+            .await
+            .unwrap();
+        assert!(unconfigured["content"][0]["text"]
+            .as_str()
+            .unwrap()
+            .starts_with("Search failed"));
+    }
+
+    #[tokio::test]
+    async fn resources_are_listed_and_subscribable() {
+        let server = McpServer::new(None, None, None);
+
+        let init = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"initialize"}"#)
+            .await
+            .unwrap();
+        assert_eq!(init["result"]["capabilities"]["resources"]["subscribe"], true);
+
+        let list = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":2,"method":"resources/list"}"#)
+            .await
+            .unwrap();
+        let uris: Vec<&str> = list["result"]["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["uri"].as_str().unwrap())
+            .collect();
+        assert_eq!(uris, ["chorus://inbox", "chorus://scratchpad", "chorus://conflicts"]);
+
+        let sub = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":3,"method":"resources/subscribe","params":{"uri":"chorus://inbox"}}"#)
+            .await
+            .unwrap();
+        assert!(sub["error"].is_null());
+        assert!(server.subscriptions.lock().await.contains_key(&Resource::Inbox));
+
+        server
+            .handle_line(r#"{"jsonrpc":"2.0","id":4,"method":"resources/unsubscribe","params":{"uri":"chorus://inbox"}}"#)
+            .await
+            .unwrap();
+        assert!(server.subscriptions.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn resources_read_reports_unknown_and_unreachable_resources() {
+        let server = McpServer::new(None, None, None);
+
+        let unknown = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"resources/read","params":{"uri":"chorus://nope"}}"#)
+            .await
+            .unwrap();
+        assert_eq!(unknown["error"]["code"], RESOURCE_NOT_FOUND);
+
+        let unconfigured = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":2,"method":"resources/read","params":{"uri":"chorus://scratchpad"}}"#)
+            .await
+            .unwrap();
+        assert_eq!(unconfigured["error"]["code"], -32603);
+    }
+
+    /// Captures everything written through a `MessageWriter`.
+    #[derive(Clone, Default)]
+    struct Captured(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Captured {
+        fn messages(&self) -> Vec<Value> {
+            String::from_utf8(self.0.lock().unwrap().clone())
+                .unwrap()
+                .lines()
+                .map(|l| serde_json::from_str(l).unwrap())
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn tool_call_with_progress_token_sends_progress_notifications() {
+        let mut server = McpServer::new(None, None, None);
+        let captured = Captured::default();
+        server.writer = MessageWriter::new(Box::new(captured.clone()));
+
+        server
+            .handle_tools_call(&json!({
+                "name": "chorus_search",
+                "arguments": { "query": "auth" },
+                "_meta": { "progressToken": "tok-1" }
+            }))
+            .await
+            .unwrap();
+
+        let messages = captured.messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["method"], "notifications/progress");
+        assert_eq!(messages[0]["params"]["progressToken"], "tok-1");
+        assert_eq!(messages[0]["params"]["progress"], 0);
+        assert_eq!(messages[0]["params"]["total"], 1);
+
+        // No token, no notifications
+        server
+            .handle_tools_call(&json!({ "name": "chorus_search", "arguments": { "query": "auth" } }))
+            .await
+            .unwrap();
+        assert_eq!(captured.messages().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn set_level_filters_log_notifications() {
+        let mut server = McpServer::new(None, None, None);
+        let captured = Captured::default();
+        server.writer = MessageWriter::new(Box::new(captured.clone()));
+        server.logger = McpLogger::new(server.writer.clone(), server.intel_client.clone());
+
+        server.logger.debug("hidden at default level");
+        server.logger.warning("shown");
+        let messages = captured.messages();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["method"], "notifications/message");
+        assert_eq!(messages[0]["params"]["level"], "warning");
+        assert_eq!(messages[0]["params"]["data"], "shown");
+
+        let resp = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"logging/setLevel","params":{"level":"error"}}"#)
+            .await
+            .unwrap();
+        assert!(resp["error"].is_null());
+        assert_eq!(server.logger.level(), LogLevel::Error);
+
+        server.logger.warning("now silenced");
+        // Only the setLevel response was added
+        assert_eq!(captured.messages().len(), 1);
+
+        let bad = server
+            .handle_line(r#"{"jsonrpc":"2.0","id":2,"method":"logging/setLevel","params":{"level":"loud"}}"#)
+            .await
+            .unwrap();
+        assert_eq!(bad["error"]["code"], -32602);
+    }
+
+    #[test]
+    fn metadata_renders_compactly() {
+        let meta = json!({ "file": "src/api.rs", "lines": [10, 12], "blocking": true });
+        assert_eq!(
+            render_metadata(&meta).unwrap(),
+            "{blocking=true, file=src/api.rs, lines=[10,12]}"
+        );
+        assert_eq!(render_metadata(&json!({})), None);
+        assert_eq!(render_metadata(&json!("text")), None);
+    }
+
+    #[tokio::test]
+    async fn broadcast_rejects_non_object_metadata() {
+        let server = McpServer::new(None, None, None);
+        let result = server
+            .handle_tools_call(&json!({
+                "name": "chorus_broadcast",
+                "arguments": { "category": "info", "message": "hi", "metadata": ["a"] }
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result["isError"], true);
+    }
+
+    #[tokio::test]
+    async fn report_file_rejects_parent_components_but_not_dotted_names() {
+        let server = McpServer::new(None, None, None);
+        let report = |file_path: &str| {
+            json!({
+                "name": "chorus_report_file",
+                "arguments": { "file_path": file_path, "action": "editing" }
+            })
+        };
+
+        let traversal = server.handle_tools_call(&report("../../etc/passwd")).await.unwrap();
+        assert_eq!(traversal["isError"], true);
+        assert!(traversal["content"][0]["text"].as_str().unwrap().contains("traversal"));
+
+        // Passes validation and only fails because no hub is configured
+        let dotted = server.handle_tools_call(&report("my..notes.txt")).await.unwrap();
+        assert!(!dotted["content"][0]["text"].as_str().unwrap().contains("traversal"));
+    }
+
+    #[tokio::test]
+    async fn status_tool_returns_while_the_status_server_hangs() {
+        // Accepts connections (via the backlog) but never answers
+        let hung = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/status", hung.local_addr().unwrap());
+        let server = McpServer::new(Some(url), Some(1), Some("test".into()));
+
+        let started = Instant::now();
+        let result = server
+            .handle_tools_call(&json!({
+                "name": "chorus_status",
+                "arguments": { "state": "working", "message": "Refactoring" }
+            }))
+            .await
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert!(result.get("isError").is_none());
+    }
+
+    /// Serves status POSTs one connection at a time, recording each reported
+    /// state in arrival order. The first response is held back for `delay`.
+    fn recording_status_server(delay: Duration) -> (String, Arc<std::sync::Mutex<Vec<String>>>) {
+        use std::io::Read;
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/status", listener.local_addr().unwrap());
+        let states = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = states.clone();
+
+        std::thread::spawn(move || {
+            for (n, stream) in listener.incoming().enumerate() {
+                let Ok(mut stream) = stream else { break };
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                let body = loop {
+                    let read = stream.read(&mut buf).unwrap_or(0);
+                    if read == 0 {
+                        break None;
+                    }
+                    request.extend_from_slice(&buf[..read]);
+                    let text = String::from_utf8_lossy(&request).into_owned();
+                    let Some(end) = text.find("\r\n\r\n") else { continue };
+                    let len = text[..end]
+                        .lines()
+                        .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap_or(0)))
+                        .unwrap_or(0);
+                    if request.len() >= end + 4 + len {
+                        break Some(text[end + 4..].to_string());
                     }
-                ],
-                "isError": true
-            })),
+                };
+                if let Some(payload) = body.and_then(|b| serde_json::from_str::<Value>(&b).ok()) {
+                    let state = payload["state"].as_str().unwrap_or_default().to_string();
+                    recorded.lock().unwrap().push(state);
+                }
+                if n == 0 {
+                    std::thread::sleep(delay);
+                }
+                let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            }
+        });
+
+        (url, states)
+    }
+
+    #[tokio::test]
+    async fn status_reports_arrive_in_order_with_the_latest_winning() {
+        let (url, states) = recording_status_server(Duration::from_millis(300));
+        let server = McpServer::new(Some(url), Some(1), Some("test".into()));
+
+        server.report_status_in_background("working", "Editing", None);
+        // Let the first report go out, then queue two behind it
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        server.report_status_in_background("idle", "Ready", None);
+        server.report_status_in_background("needs_input", "Approve?", None);
+
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while states.lock().unwrap().len() < 2 {
+            assert!(Instant::now() < deadline, "status reports never arrived");
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        // Nothing older trails in after the latest state
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(*states.lock().unwrap(), ["working", "needs_input"]);
+    }
+
+    #[tokio::test]
+    async fn validation_and_network_failures_have_distinct_codes() {
+        // Bind and drop to get a port with nothing listening
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let url = format!("http://127.0.0.1:{}/status", port);
+        let server = McpServer::new(Some(url), Some(1), Some("test".into()));
+        let broadcast = |category: &str| {
+            json!({
+                "name": "chorus_broadcast",
+                "arguments": { "category": category, "message": "hi" }
+            })
+        };
+
+        let invalid = server.handle_tools_call(&broadcast("gossip")).await.unwrap();
+        assert_eq!(invalid["isError"], true);
+        assert_eq!(invalid["_meta"]["code"], "invalid_argument");
+        assert_eq!(invalid["_meta"]["retryable"], false);
+
+        let unreachable = server.handle_tools_call(&broadcast("info")).await.unwrap();
+        assert_eq!(unreachable["isError"], true);
+        assert_eq!(unreachable["_meta"]["code"], "network");
+        assert_eq!(unreachable["_meta"]["retryable"], true);
+        assert!(unreachable["content"][0]["text"].as_str().unwrap().starts_with("Broadcast failed"));
+    }
+
+    #[tokio::test]
+    async fn broadcast_rejects_non_positive_ttl() {
+        let server = McpServer::new(None, None, None);
+        for ttl in [json!(0), json!(-5), json!("60")] {
+            let result = server
+                .handle_tools_call(&json!({
+                    "name": "chorus_broadcast",
+                    "arguments": { "category": "info", "message": "hi", "ttl_secs": ttl }
+                }))
+                .await
+                .unwrap();
+            assert_eq!(result["isError"], true);
         }
     }
 }
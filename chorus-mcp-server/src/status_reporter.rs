@@ -3,14 +3,38 @@
 //! Reports agent status via HTTP POST to the Chorus application's
 //! status endpoint. This replaces the previous file-based approach
 //! to eliminate race conditions and provide real-time updates.
+//!
+//! Transient failures (connection errors, timeouts, 5xx) are retried with
+//! exponential backoff within a short time budget; 4xx responses are not.
+//! Any response other than 2xx that is not retried is an error.
+
+use std::time::{Duration, Instant};
 
 use serde::Serialize;
 use thiserror::Error;
 
+/// Per-request timeout for a single status POST, further capped to what is
+/// left of `RETRY_BUDGET`.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(1);
+/// Maximum number of attempts (including the first) per status report.
+const MAX_ATTEMPTS: u32 = 4;
+/// Delay before the first retry; doubled after each failed attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+/// Total time a report may take, retries included.
+const RETRY_BUDGET: Duration = Duration::from_secs(2);
+
+/// Status the StatusServer answers with when a request carries another
+/// instance's ID (421 Misdirected Request). Shared by every client of it.
+pub(crate) const WRONG_INSTANCE_STATUS: u16 = 421;
+
 #[derive(Debug, Error)]
 pub enum StatusError {
     #[error("HTTP request failed: {0}")]
     HttpError(#[from] reqwest::Error),
+    #[error("Status rejected: the Chorus instance at the status URL is not the one that started this session")]
+    WrongInstance,
+    #[error("Status rejected with HTTP {0}")]
+    Rejected(reqwest::StatusCode),
 }
 
 /// Payload sent to Chorus's status endpoint.
@@ -50,7 +74,7 @@ impl StatusReporter {
 
     /// Report status to Chorus.
     ///
-    /// Returns Ok(()) if the status was successfully reported, or if
+    /// Returns Ok(()) if the status was accepted with a 2xx response, or if
     /// no status URL is configured (graceful degradation).
     pub async fn report_status(
         &self,
@@ -84,18 +108,199 @@ impl StatusReporter {
             status_url, payload.session_id, payload.state, payload.message
         );
 
-        let response = self.client
-            .post(status_url)
-            .json(&payload)
-            .timeout(std::time::Duration::from_secs(5))
-            .send()
-            .await?;
+        let started = Instant::now();
+        let mut backoff = INITIAL_BACKOFF;
+        let mut attempt = 1;
 
-        eprintln!(
-            "[chorus-mcp-server] Status response: {}",
-            response.status()
-        );
+        loop {
+            let result = self
+                .client
+                .post(status_url)
+                .json(&payload)
+                .timeout(REQUEST_TIMEOUT.min(RETRY_BUDGET.saturating_sub(started.elapsed())))
+                .send()
+                .await;
+
+            let retryable = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(e) => e.is_connect() || e.is_timeout() || e.is_request(),
+            };
+            let out_of_budget = attempt >= MAX_ATTEMPTS || started.elapsed() + backoff > RETRY_BUDGET;
+
+            if !retryable || out_of_budget {
+                return match result {
+                    Ok(response) if response.status().as_u16() == WRONG_INSTANCE_STATUS => {
+                        eprintln!(
+                            "[chorus-mcp-server] Status rejected by {}: it belongs to a different Chorus instance (ours: {})",
+                            status_url, payload.instance_id
+                        );
+                        Err(StatusError::WrongInstance)
+                    }
+                    Ok(response) if response.status().is_success() => {
+                        eprintln!(
+                            "[chorus-mcp-server] Status response: {} (attempt {})",
+                            response.status(),
+                            attempt
+                        );
+                        Ok(())
+                    }
+                    Ok(response) => {
+                        eprintln!(
+                            "[chorus-mcp-server] Status rejected by {}: {} (attempt {})",
+                            status_url,
+                            response.status(),
+                            attempt
+                        );
+                        Err(StatusError::Rejected(response.status()))
+                    }
+                    Err(e) => {
+                        eprintln!(
+                            "[chorus-mcp-server] Status report failed after {} attempt(s): {}",
+                            attempt, e
+                        );
+                        Err(e.into())
+                    }
+                };
+            }
+
+            match &result {
+                Ok(response) => eprintln!(
+                    "[chorus-mcp-server] Status attempt {} got {}, retrying in {:?}",
+                    attempt,
+                    response.status(),
+                    backoff
+                ),
+                Err(e) => eprintln!(
+                    "[chorus-mcp-server] Status attempt {} failed: {}, retrying in {:?}",
+                    attempt, e, backoff
+                ),
+            }
+
+            tokio::time::sleep(backoff).await;
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Serves one HTTP response per connection, using `statuses` in order
+    /// (the last one repeats). Returns the URL and a request counter.
+    fn mock_server(statuses: Vec<u16>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/status", listener.local_addr().unwrap());
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = count.clone();
+
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else { break };
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                let status = statuses[n.min(statuses.len() - 1)];
+
+                // Read headers and body so the client sees a complete exchange
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while let Ok(read) = stream.read(&mut buf) {
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..read]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some(header_end) = text.find("\r\n\r\n") {
+                        let body_len = text[..header_end]
+                            .lines()
+                            .find_map(|l| l.to_ascii_lowercase().strip_prefix("content-length:").map(|v| v.trim().parse::<usize>().unwrap_or(0)))
+                            .unwrap_or(0);
+                        if request.len() >= header_end + 4 + body_len {
+                            break;
+                        }
+                    }
+                }
+
+                let response = format!(
+                    "HTTP/1.1 {} X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                    status
+                );
+                let _ = stream.write_all(response.as_bytes());
+            }
+        });
+
+        (url, count)
+    }
+
+    #[tokio::test]
+    async fn retries_server_errors_until_success() {
+        let (url, count) = mock_server(vec![503, 500, 200]);
+        let reporter = StatusReporter::new(Some(url), Some(1), Some("test".into()));
+
+        reporter.report_status("working", "busy", None).await.unwrap();
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn wrong_instance_is_reported_without_retrying() {
+        let (url, count) = mock_server(vec![421, 200]);
+        let reporter = StatusReporter::new(Some(url), Some(1), Some("test".into()));
+
+        let err = reporter.report_status("working", "busy", None).await.unwrap_err();
+        assert!(matches!(err, StatusError::WrongInstance));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn client_errors_fail_without_retrying() {
+        let (url, count) = mock_server(vec![404, 200]);
+        let reporter = StatusReporter::new(Some(url), Some(1), Some("test".into()));
+
+        let err = reporter.report_status("working", "busy", None).await.unwrap_err();
+        assert!(matches!(err, StatusError::Rejected(s) if s.as_u16() == 404), "{err:?}");
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn server_errors_fail_once_retries_run_out() {
+        let (url, count) = mock_server(vec![503]);
+        let reporter = StatusReporter::new(Some(url), Some(1), Some("test".into()));
+
+        let err = reporter.report_status("working", "busy", None).await.unwrap_err();
+        assert!(matches!(err, StatusError::Rejected(s) if s.as_u16() == 503), "{err:?}");
+        assert_eq!(count.load(Ordering::SeqCst), MAX_ATTEMPTS as usize);
+    }
+
+    #[tokio::test]
+    async fn unresponsive_server_cannot_exceed_the_budget() {
+        // Accepts connections but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/status", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            let held: Vec<_> = listener.incoming().take(MAX_ATTEMPTS as usize).collect();
+            std::thread::sleep(RETRY_BUDGET * 2);
+            drop(held);
+        });
+        let reporter = StatusReporter::new(Some(url), Some(1), Some("test".into()));
+
+        let started = Instant::now();
+        assert!(reporter.report_status("idle", "Ready", None).await.is_err());
+        assert!(started.elapsed() < RETRY_BUDGET + Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn gives_up_on_connection_errors() {
+        // Bind and drop to get a port with nothing listening
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let url = format!("http://127.0.0.1:{}/status", port);
+        let reporter = StatusReporter::new(Some(url), Some(1), Some("test".into()));
 
-        Ok(())
+        let started = Instant::now();
+        assert!(reporter.report_status("idle", "Ready", None).await.is_err());
+        assert!(started.elapsed() < RETRY_BUDGET + Duration::from_millis(500));
     }
 }
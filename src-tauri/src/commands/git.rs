@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use crate::git::{BranchInfo, CommitInfo, FileChange, Git, GitError, GitUserConfig, RemoteInfo, WorkingChange, WorktreeInfo};
+use crate::git::{BranchInfo, CommitInfo, DiffOutput, FetchSummary, FileChange, Git, GitError, GitUserConfig, RemoteInfo, StashEntry, WorkingChange, WorktreeInfo};
 
 /// Returns `Err(GitError::NotARepo)` if the given path string is empty.
 fn validate_repo_path(repo_path: &str) -> Result<(), GitError> {
@@ -95,6 +95,33 @@ pub async fn git_commit_log(
     git.commit_log(max_count, all_branches).await
 }
 
+/// Exposes `Git::log` to the frontend.
+/// Returns up to `limit` (capped) commits from `branch` or HEAD; empty for a repo with no commits.
+#[tauri::command]
+pub async fn git_log(
+    repo_path: String,
+    limit: usize,
+    branch: Option<String>,
+) -> Result<Vec<CommitInfo>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.log(limit, branch.as_deref()).await
+}
+
+/// Exposes `Git::diff` to the frontend.
+/// Returns the unstaged (or staged) diff of the worktree, optionally for one path and size-capped.
+#[tauri::command]
+pub async fn git_diff(
+    repo_path: String,
+    path: Option<String>,
+    staged: bool,
+    max_bytes: Option<usize>,
+) -> Result<DiffOutput, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.diff(path.as_deref(), staged, max_bytes).await
+}
+
 /// Checks out a branch by name.
 /// Handles both local and remote branches.
 #[tauri::command]
@@ -292,3 +319,35 @@ pub async fn git_push(
     let git = Git::new(&repo_path);
     git.push(remote.as_deref(), branch.as_deref(), set_upstream).await
 }
+
+/// Fetches from a remote (the upstream's by default) and reports updated refs.
+#[tauri::command]
+pub async fn git_fetch(repo_path: String, remote: Option<String>) -> Result<FetchSummary, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.fetch(remote.as_deref()).await
+}
+
+/// Stashes all changes, including untracked files.
+#[tauri::command]
+pub async fn git_stash_save(repo_path: String, message: Option<String>) -> Result<(), GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.stash_save(message.as_deref()).await
+}
+
+/// Lists stash entries, newest first.
+#[tauri::command]
+pub async fn git_stash_list(repo_path: String) -> Result<Vec<StashEntry>, GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.stash_list().await
+}
+
+/// Applies and drops a stash entry; conflicts keep the entry.
+#[tauri::command]
+pub async fn git_stash_pop(repo_path: String, index: usize) -> Result<(), GitError> {
+    validate_repo_path(&repo_path)?;
+    let git = Git::new(&repo_path);
+    git.stash_pop(index).await
+}
@@ -5,16 +5,38 @@
 
 use std::sync::Arc;
 
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, State};
 
+use crate::core::event_bus::EventBus;
+use crate::core::intel_hub::{IntelCleared, IntelScope};
 use crate::core::status_server::StatusServer;
 
-/// Get all broadcast messages.
+/// Event emitted after intel is cleared so connected clients refetch.
+const INTEL_CLEARED_EVENT: &str = "intel-cleared";
+
+/// Notify the desktop and web clients which intel stores were cleared.
+fn emit_cleared(app: &AppHandle, cleared: IntelCleared) {
+    let _ = app.emit(INTEL_CLEARED_EVENT, cleared);
+    if let Some(bus) = app.try_state::<Arc<EventBus>>() {
+        match serde_json::to_value(cleared) {
+            Ok(v) => bus.send(INTEL_CLEARED_EVENT.to_string(), v),
+            Err(e) => log::error!("Failed to serialize intel-cleared payload: {e}"),
+        }
+    }
+}
+
+/// Get a newest-first page of broadcast messages with the total count.
+/// Without `offset`/`limit` the page holds every message.
 #[tauri::command]
 pub async fn get_intel_broadcasts(
     status_server: State<'_, Arc<StatusServer>>,
+    offset: Option<usize>,
+    limit: Option<usize>,
 ) -> Result<serde_json::Value, String> {
-    let messages = status_server.intel_hub().get_all_messages().await;
+    let messages = status_server
+        .intel_hub()
+        .messages_page(offset.unwrap_or(0), limit)
+        .await;
     serde_json::to_value(messages).map_err(|e| e.to_string())
 }
 
@@ -23,19 +45,39 @@ pub async fn get_intel_broadcasts(
 pub async fn get_intel_conflicts(
     status_server: State<'_, Arc<StatusServer>>,
 ) -> Result<serde_json::Value, String> {
-    let conflicts = status_server.intel_hub().get_all_conflicts().await;
+    let conflicts = status_server.intel_hub().get_all_conflicts(&IntelScope::All).await;
     serde_json::to_value(conflicts).map_err(|e| e.to_string())
 }
 
-/// Get all scratchpad entries.
+/// Get a newest-first page of scratchpad entries with the total count.
+/// Without `offset`/`limit` the page holds every entry.
 #[tauri::command]
 pub async fn get_intel_scratchpad(
     status_server: State<'_, Arc<StatusServer>>,
+    offset: Option<usize>,
+    limit: Option<usize>,
 ) -> Result<serde_json::Value, String> {
-    let entries = status_server.intel_hub().read_scratchpad().await;
+    let entries = status_server
+        .intel_hub()
+        .scratchpad_page(&IntelScope::All, offset.unwrap_or(0), limit)
+        .await;
     serde_json::to_value(entries).map_err(|e| e.to_string())
 }
 
+/// Get a bounded digest of broadcasts, conflicts and scratchpad entries
+/// newer than `since` (an RFC 3339 timestamp).
+#[tauri::command]
+pub async fn get_intel_digest(
+    status_server: State<'_, Arc<StatusServer>>,
+    since: String,
+) -> Result<serde_json::Value, String> {
+    let since = chrono::DateTime::parse_from_rfc3339(&since)
+        .map_err(|e| format!("Invalid 'since' timestamp: {}", e))?
+        .with_timezone(&chrono::Utc);
+    let digest = status_server.intel_hub().digest_since(since).await;
+    serde_json::to_value(digest).map_err(|e| e.to_string())
+}
+
 /// Write a scratchpad entry from the frontend.
 #[tauri::command]
 pub async fn write_intel_scratchpad(
@@ -62,11 +104,74 @@ pub async fn write_intel_scratchpad(
     serde_json::to_value(entry).map_err(|e| e.to_string())
 }
 
+/// Write the scratchpad, and unless `include_broadcasts` is false the notable
+/// broadcasts, to `path` as a markdown document.
+#[tauri::command]
+pub async fn export_intel_markdown(
+    status_server: State<'_, Arc<StatusServer>>,
+    path: String,
+    include_broadcasts: Option<bool>,
+) -> Result<(), String> {
+    let markdown = status_server
+        .intel_hub()
+        .export_markdown(include_broadcasts.unwrap_or(true))
+        .await;
+    let target = crate::core::path_utils::normalize_path_buf(std::path::Path::new(&path));
+    tokio::fs::write(&target, markdown)
+        .await
+        .map_err(|e| format!("Failed to write intel export: {}", e))
+}
+
 /// Clear all scratchpad entries.
 #[tauri::command]
 pub async fn clear_intel_scratchpad(
+    app: AppHandle,
     status_server: State<'_, Arc<StatusServer>>,
 ) -> Result<(), String> {
     status_server.intel_hub().clear_scratchpad().await;
+    emit_cleared(&app, IntelCleared { scratchpad: true, ..Default::default() });
+    Ok(())
+}
+
+/// Clear all broadcast messages.
+#[tauri::command]
+pub async fn clear_intel_broadcasts(
+    app: AppHandle,
+    status_server: State<'_, Arc<StatusServer>>,
+) -> Result<(), String> {
+    status_server.intel_hub().clear_messages().await;
+    emit_cleared(&app, IntelCleared { broadcasts: true, ..Default::default() });
+    Ok(())
+}
+
+/// Clear tracked file activity, resolving every conflict.
+#[tauri::command]
+pub async fn clear_intel_conflicts(
+    app: AppHandle,
+    status_server: State<'_, Arc<StatusServer>>,
+) -> Result<(), String> {
+    status_server.intel_hub().clear_file_activity().await;
+    emit_cleared(&app, IntelCleared { conflicts: true, ..Default::default() });
+    Ok(())
+}
+
+/// Clear broadcasts, file activity and the scratchpad in one go.
+#[tauri::command]
+pub async fn clear_all_intel(
+    app: AppHandle,
+    status_server: State<'_, Arc<StatusServer>>,
+) -> Result<(), String> {
+    let hub = status_server.intel_hub();
+    hub.clear_messages().await;
+    hub.clear_file_activity().await;
+    hub.clear_scratchpad().await;
+    emit_cleared(
+        &app,
+        IntelCleared {
+            broadcasts: true,
+            conflicts: true,
+            scratchpad: true,
+        },
+    );
     Ok(())
 }
@@ -9,6 +9,7 @@ use crate::core::event_bus::EventBus;
 
 use crate::core::marketplace_manager::MarketplaceManager;
 use crate::core::marketplace_models::*;
+use crate::core::plugin_manager::PluginManager;
 
 /// Store filename for marketplace data persistence.
 const MARKETPLACE_STORE: &str = "marketplace.json";
@@ -164,6 +165,16 @@ pub async fn get_available_plugins(
     Ok(state.get_available_plugins())
 }
 
+/// Searches available plugins by keyword and optional category.
+#[tauri::command]
+pub async fn search_marketplace_plugins(
+    state: State<'_, MarketplaceManager>,
+    query: String,
+    category: Option<PluginCategory>,
+) -> Result<Vec<MarketplaceSearchResult>, String> {
+    Ok(state.search(&query, category).await)
+}
+
 // ========== Plugin Installation Commands ==========
 
 /// Gets all installed plugins.
@@ -199,6 +210,58 @@ pub async fn install_marketplace_plugin(
     Ok(installed)
 }
 
+/// Checks out a plugin from a Git URL and returns its manifest and
+/// components without installing it.
+#[tauri::command]
+pub async fn preview_git_plugin(
+    state: State<'_, MarketplaceManager>,
+    url: String,
+    git_ref: Option<String>,
+) -> Result<GitPluginPreview, String> {
+    state
+        .preview_from_git(&url, git_ref.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Installs a plugin directly from a Git URL into ~/.chorus/plugins/.
+#[tauri::command]
+pub async fn install_plugin_from_git(
+    app: AppHandle,
+    state: State<'_, MarketplaceManager>,
+    url: String,
+    git_ref: Option<String>,
+) -> Result<InstalledPlugin, String> {
+    let installed = state
+        .install_from_git(&url, git_ref.as_deref())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    save_marketplace_data(&app, &state).await?;
+    app.state::<PluginManager>().invalidate_all();
+
+    // Emit event
+    let _ = app.emit("marketplace:plugin-installed", &installed);
+    if let Some(bus) = app.try_state::<std::sync::Arc<EventBus>>() {
+        bus.send("marketplace:plugin-installed".to_string(), serde_json::to_value(&installed).unwrap_or_default());
+    }
+
+    Ok(installed)
+}
+
+/// Checks installed plugins for newer versions. Unreachable sources are
+/// reported as warnings alongside whatever updates were found.
+#[tauri::command]
+pub async fn check_plugin_updates(
+    state: State<'_, MarketplaceManager>,
+) -> Result<UpdateCheckResult, String> {
+    let result = state.check_updates().await;
+    for warning in &result.warnings {
+        log::warn!("{}", warning);
+    }
+    Ok(result)
+}
+
 /// Uninstalls a plugin by its installed ID.
 #[tauri::command]
 pub async fn uninstall_plugin(
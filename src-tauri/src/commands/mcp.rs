@@ -7,15 +7,26 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tauri::path::BaseDirectory;
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 use tauri_plugin_store::StoreExt;
 
+use crate::core::event_bus::EventBus;
 use crate::core::mcp_config_writer::{self, ChorusStatusConfig};
-use crate::core::mcp_manager::{McpManager, McpServerConfig};
-use crate::core::status_server::StatusServer;
+use crate::core::mcp_manager::{
+    self, McpManager, McpServerConfig, McpServerUsage, McpServerValidationError,
+};
+use crate::core::session_manager::SessionManager;
+use crate::core::status_server::{McpLogEntry, RegisteredStatusSession, StatusServer};
+use crate::core::store_debouncer::StoreDebouncer;
 
 /// Store filename for custom MCP servers (global, user-level).
 const CUSTOM_MCP_SERVERS_STORE: &str = "mcp-custom-servers.json";
+/// Store filename for global MCP settings.
+const MCP_SETTINGS_STORE: &str = "mcp-settings.json";
+/// Store key holding a user-chosen chorus-mcp-server binary path.
+const MCP_SERVER_PATH_KEY: &str = "chorusMcpServerPath";
+/// Env var that overrides where the chorus-mcp-server binary is looked up.
+const MCP_SERVER_PATH_ENV: &str = "CHORUS_MCP_SERVER_PATH";
 
 /// A custom MCP server configured by the user.
 /// Stored globally (user-level) and available across all projects.
@@ -82,6 +93,40 @@ pub async fn refresh_project_mcp_servers(
     Ok(state.refresh_project_servers(&canonical))
 }
 
+/// Starts watching the project's `.mcp.json` for external edits.
+///
+/// Each change refreshes the cache and emits `mcp:servers-changed` with the
+/// project path and its new server list. Watching stops when the project's
+/// sessions are removed or `unwatch_project_mcp_servers` is called.
+#[tauri::command]
+pub async fn watch_project_mcp_servers(
+    app: AppHandle,
+    state: State<'_, McpManager>,
+    project_path: String,
+) -> Result<(), String> {
+    let canonical = crate::core::path_utils::normalize_path(&project_path);
+
+    state.watch_project(&canonical, move |project, servers| {
+        let payload = serde_json::json!({ "projectPath": project, "servers": servers });
+        let _ = app.emit("mcp:servers-changed", &payload);
+        if let Some(bus) = app.try_state::<Arc<EventBus>>() {
+            bus.send("mcp:servers-changed".to_string(), payload);
+        }
+    })
+}
+
+/// Stops watching the project's `.mcp.json`.
+#[tauri::command]
+pub async fn unwatch_project_mcp_servers(
+    state: State<'_, McpManager>,
+    project_path: String,
+) -> Result<(), String> {
+    let canonical = crate::core::path_utils::normalize_path(&project_path);
+
+    state.unwatch_project(&canonical);
+    Ok(())
+}
+
 /// Gets the enabled MCP server names for a specific session.
 ///
 /// If not explicitly set, returns all available servers as enabled.
@@ -110,6 +155,35 @@ pub async fn set_session_mcp_servers(
     Ok(())
 }
 
+/// Gets the env var overrides for a specific session.
+#[tauri::command]
+pub async fn get_session_mcp_env(
+    state: State<'_, McpManager>,
+    project_path: String,
+    session_id: u32,
+) -> Result<HashMap<String, String>, String> {
+    let canonical = crate::core::path_utils::normalize_path(&project_path);
+
+    Ok(state.get_session_env(&canonical, session_id))
+}
+
+/// Sets env var overrides for a specific session.
+///
+/// They are merged over each stdio server's env when the session's
+/// `.mcp.json` is next written; an empty map clears them.
+#[tauri::command]
+pub async fn set_session_mcp_env(
+    state: State<'_, McpManager>,
+    project_path: String,
+    session_id: u32,
+    env: HashMap<String, String>,
+) -> Result<(), String> {
+    let canonical = crate::core::path_utils::normalize_path(&project_path);
+
+    state.set_session_env(&canonical, session_id, env);
+    Ok(())
+}
+
 /// Returns the count of enabled MCP servers for a session.
 #[tauri::command]
 pub async fn get_session_mcp_count(
@@ -122,13 +196,40 @@ pub async fn get_session_mcp_count(
     Ok(state.get_enabled_count(&canonical, session_id))
 }
 
+/// Reports, per server name, how many sessions and projects have it enabled.
+///
+/// Custom servers that are not enabled anywhere are included with zero
+/// counts so the user can spot ones that are safe to delete.
+#[tauri::command]
+pub async fn get_mcp_server_usage(
+    app: AppHandle,
+    state: State<'_, McpManager>,
+) -> Result<Vec<McpServerUsage>, String> {
+    let mut usage = state.server_usage();
+
+    for custom in get_custom_mcp_servers_internal(&app)? {
+        if !usage.iter().any(|u| u.name == custom.name) {
+            usage.push(McpServerUsage {
+                name: custom.name,
+                session_count: 0,
+                project_count: 0,
+            });
+        }
+    }
+    usage.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(usage)
+}
+
 /// Saves the default enabled MCP servers for a project.
 ///
 /// These defaults are loaded when a new session starts, so server selections
-/// persist across app restarts.
+/// persist across app restarts. The store is updated in memory immediately;
+/// the disk write is debounced so rapid toggling coalesces into one save.
 #[tauri::command]
 pub async fn save_project_mcp_defaults(
     app: AppHandle,
+    debouncer: State<'_, StoreDebouncer>,
     project_path: String,
     enabled_servers: Vec<String>,
 ) -> Result<(), String> {
@@ -138,9 +239,9 @@ pub async fn save_project_mcp_defaults(
     let store = app.store(&store_name).map_err(|e| e.to_string())?;
 
     store.set("enabled_mcp_servers", serde_json::json!(enabled_servers));
-    store.save().map_err(|e| e.to_string())?;
+    debouncer.schedule(&store_name);
 
-    log::debug!("Saved MCP server defaults for project: {}", canonical);
+    log::debug!("Queued save of MCP server defaults for project: {}", canonical);
     Ok(())
 }
 
@@ -235,6 +336,25 @@ pub async fn get_status_server_info(
     })
 }
 
+/// Lists sessions registered with the status server and their last
+/// reported status. A session missing here, or with no status, never had
+/// MCP status reporting wired up.
+#[tauri::command]
+pub async fn get_registered_status_sessions(
+    status_server: State<'_, Arc<StatusServer>>,
+) -> Result<Vec<RegisteredStatusSession>, String> {
+    Ok(status_server.registered_session_details().await)
+}
+
+/// Gets recent log lines forwarded by a session's MCP server, oldest first.
+#[tauri::command]
+pub async fn get_mcp_logs(
+    status_server: State<'_, Arc<StatusServer>>,
+    session_id: u32,
+) -> Result<Vec<McpLogEntry>, String> {
+    Ok(status_server.mcp_logs(session_id).await)
+}
+
 /// Writes a session-specific `.mcp.json` file to the working directory.
 ///
 /// This must be called BEFORE launching the Claude CLI so it can discover
@@ -246,49 +366,67 @@ pub async fn get_status_server_info(
 ///
 /// Existing user-defined servers in the working directory's `.mcp.json` are
 /// preserved (only Chorus-managed servers are replaced).
+///
+/// Does nothing for sessions whose AI mode doesn't read `.mcp.json`.
 #[tauri::command]
 pub async fn write_session_mcp_config(
     app: AppHandle,
-    mcp_state: State<'_, McpManager>,
-    status_server: State<'_, Arc<StatusServer>>,
     working_dir: String,
     session_id: u32,
     project_path: String,
     enabled_server_names: Vec<String>,
 ) -> Result<(), String> {
-    let canonical = crate::core::path_utils::normalize_path(&project_path);
+    write_session_mcp_config_internal(
+        &app,
+        &working_dir,
+        session_id,
+        &project_path,
+        &enabled_server_names,
+    )
+    .await
+}
+
+/// Shared implementation of `write_session_mcp_config`, also used when a
+/// session is restarted.
+pub(crate) async fn write_session_mcp_config_internal(
+    app: &AppHandle,
+    working_dir: &str,
+    session_id: u32,
+    project_path: &str,
+    enabled_server_names: &[String],
+) -> Result<(), String> {
+    let mcp_state = app.state::<McpManager>();
+    let status_server = app.state::<Arc<StatusServer>>();
+    let session_manager = app.state::<SessionManager>();
+    let canonical = crate::core::path_utils::normalize_path(project_path);
+
+    if let Some(session) = session_manager.get_session(session_id) {
+        if !session.mode.uses_mcp_config() {
+            log::debug!(
+                "Skipping MCP config for session {} ({:?} doesn't read .mcp.json)",
+                session_id,
+                session.mode
+            );
+            return Ok(());
+        }
+    }
 
     // Register this session with the status server (for cleanup tracking)
+    let canonical_working_dir = crate::core::path_utils::normalize_path(working_dir);
     status_server
-        .register_session(session_id, &canonical)
+        .register_session(session_id, &canonical, &canonical_working_dir)
         .await;
 
-    // Get full server configs for enabled discovered servers
-    let all_discovered = mcp_state.get_project_servers(&canonical);
-    let enabled_discovered: Vec<_> = all_discovered
-        .into_iter()
-        .filter(|s| enabled_server_names.contains(&s.name))
-        .collect();
-
-    // Get enabled custom servers
-    let custom_servers = get_custom_mcp_servers_internal(&app)?;
-    let enabled_custom: Vec<_> = custom_servers
-        .into_iter()
-        .filter(|s| s.is_enabled)
-        .collect();
-
-    // Resolve the path to the chorus-mcp-server binary
-    // In development, it's in the target directory; in production, it's bundled as a resource
-    let chorus_status_config = resolve_chorus_mcp_server_path(&app)
-        .map(|binary_path| {
-            ChorusStatusConfig {
-                binary_path,
-                status_url: status_server.status_url(),
-                instance_id: status_server.instance_id().to_string(),
-            }
-        });
+    let inputs = SessionMcpInputs::gather(
+        app,
+        &mcp_state,
+        &status_server,
+        &canonical,
+        session_id,
+        enabled_server_names,
+    )?;
 
-    if chorus_status_config.is_none() {
+    if inputs.chorus_status.is_none() {
         log::warn!("chorus-mcp-server binary not found - status reporting will be disabled");
     }
 
@@ -296,14 +434,14 @@ pub async fn write_session_mcp_config(
         "Writing MCP config for session {} to {} ({} discovered + {} custom servers, chorus-status={})",
         session_id,
         working_dir,
-        enabled_discovered.len(),
-        enabled_custom.len(),
-        chorus_status_config.is_some(),
+        inputs.discovered.len(),
+        inputs.custom.len(),
+        inputs.chorus_status.is_some(),
     );
 
     // Write .chorus-session file for hooks to find session config
     // This file allows Claude Code hooks to know the status URL and session ID
-    let session_file_path = Path::new(&working_dir).join(".chorus-session");
+    let session_file_path = Path::new(working_dir).join(".chorus-session");
     let session_file_content = format!(
         "# Chorus session configuration - auto-generated, do not edit\n\
          # This file is used by Claude Code hooks to report status\n\
@@ -321,27 +459,170 @@ pub async fn write_session_mcp_config(
     }
 
     mcp_config_writer::write_session_mcp_config(
+        Path::new(working_dir),
+        session_id,
+        &inputs.discovered,
+        &inputs.custom,
+        &inputs.env_overrides,
+        inputs.chorus_status.as_ref(),
+    )
+    .await
+}
+
+/// Returns the `.mcp.json` that `write_session_mcp_config` would write for
+/// these arguments, without writing it or registering the session. Returns
+/// `None` if the session's CLI doesn't read `.mcp.json`, since nothing would
+/// be written.
+#[tauri::command]
+pub async fn preview_session_mcp_config(
+    app: AppHandle,
+    mcp_state: State<'_, McpManager>,
+    status_server: State<'_, Arc<StatusServer>>,
+    session_manager: State<'_, SessionManager>,
+    working_dir: String,
+    session_id: u32,
+    project_path: String,
+    enabled_server_names: Vec<String>,
+) -> Result<Option<String>, String> {
+    if session_manager
+        .get_session(session_id)
+        .is_some_and(|s| !s.mode.uses_mcp_config())
+    {
+        return Ok(None);
+    }
+    let canonical = crate::core::path_utils::normalize_path(&project_path);
+
+    let inputs = SessionMcpInputs::gather(
+        &app,
+        &mcp_state,
+        &status_server,
+        &canonical,
+        session_id,
+        &enabled_server_names,
+    )?;
+
+    mcp_config_writer::preview_session_mcp_config(
         Path::new(&working_dir),
         session_id,
-        &enabled_discovered,
-        &enabled_custom,
-        chorus_status_config.as_ref(),
+        &inputs.discovered,
+        &inputs.custom,
+        &inputs.env_overrides,
+        inputs.chorus_status.as_ref(),
     )
     .await
+    .map(Some)
+}
+
+/// Everything the config writer needs for a session, shared by the write
+/// and preview commands so they can't drift apart.
+struct SessionMcpInputs {
+    discovered: Vec<McpServerConfig>,
+    custom: Vec<McpCustomServer>,
+    env_overrides: HashMap<String, String>,
+    chorus_status: Option<ChorusStatusConfig>,
+}
+
+impl SessionMcpInputs {
+    fn gather(
+        app: &AppHandle,
+        mcp_state: &McpManager,
+        status_server: &StatusServer,
+        canonical: &str,
+        session_id: u32,
+        enabled_server_names: &[String],
+    ) -> Result<Self, String> {
+        // Get full server configs for enabled discovered servers
+        let discovered = mcp_state
+            .get_project_servers(canonical)
+            .into_iter()
+            .filter(|s| enabled_server_names.contains(&s.name))
+            .collect();
+
+        // Get enabled custom servers
+        let custom = get_custom_mcp_servers_internal(app)?
+            .into_iter()
+            .filter(|s| s.is_enabled)
+            .collect();
+
+        // Resolve the path to the chorus-mcp-server binary
+        // In development, it's in the target directory; in production, it's bundled as a resource
+        let chorus_status = resolve_chorus_mcp_server_path(app).map(|binary_path| {
+            ChorusStatusConfig {
+                binary_path,
+                status_url: status_server.status_url(),
+                instance_id: status_server.instance_id().to_string(),
+            }
+        });
+
+        Ok(Self {
+            discovered,
+            custom,
+            env_overrides: mcp_state.get_session_env(canonical, session_id),
+            chorus_status,
+        })
+    }
+}
+
+/// Returns the resolved chorus-mcp-server binary path, or `None` when it
+/// can't be found and status reporting is disabled.
+#[tauri::command]
+pub async fn get_chorus_mcp_server_path(app: AppHandle) -> Result<Option<String>, String> {
+    Ok(resolve_chorus_mcp_server_path(&app).map(|p| p.to_string_lossy().to_string()))
+}
+
+/// Stores a chorus-mcp-server binary path to use before the built-in
+/// locations, or clears it with `None`. Returns the newly resolved path.
+#[tauri::command]
+pub async fn set_chorus_mcp_server_path(
+    app: AppHandle,
+    path: Option<String>,
+) -> Result<Option<String>, String> {
+    let store = app.store(MCP_SETTINGS_STORE).map_err(|e| e.to_string())?;
+    match path.as_deref().map(str::trim).filter(|p| !p.is_empty()) {
+        Some(path) => {
+            if !Path::new(path).is_file() {
+                return Err(format!("No chorus-mcp-server binary at {}", path));
+            }
+            store.set(MCP_SERVER_PATH_KEY, path);
+        }
+        None => {
+            store.delete(MCP_SERVER_PATH_KEY);
+        }
+    }
+    store.save().map_err(|e| e.to_string())?;
+
+    Ok(resolve_chorus_mcp_server_path(&app).map(|p| p.to_string_lossy().to_string()))
 }
 
 /// Resolves the path to the chorus-mcp-server binary.
 ///
 /// Tries multiple locations in order:
-/// 1. Bundled resource (production builds)
-/// 2. Target directory relative to executable (development)
-/// 3. Same directory as the executable
+/// 1. The `CHORUS_MCP_SERVER_PATH` env var, then the stored setting
+/// 2. Bundled resource (production builds)
+/// 3. Target directory relative to executable (development)
+/// 4. Same directory as the executable
 fn resolve_chorus_mcp_server_path(app: &AppHandle) -> Option<PathBuf> {
     #[cfg(target_os = "windows")]
     let binary_name = "chorus-mcp-server.exe";
     #[cfg(not(target_os = "windows"))]
     let binary_name = "chorus-mcp-server";
 
+    // User overrides come first, for install layouts the defaults don't cover
+    let env_override = std::env::var(MCP_SERVER_PATH_ENV).ok().map(|p| (p, "env var"));
+    let stored_override = app
+        .store(MCP_SETTINGS_STORE)
+        .ok()
+        .and_then(|store| store.get(MCP_SERVER_PATH_KEY))
+        .and_then(|v| v.as_str().map(|p| (p.to_string(), "setting")));
+    for (path, source) in env_override.into_iter().chain(stored_override) {
+        let path = PathBuf::from(path);
+        if path.is_file() {
+            log::info!("Found chorus-mcp-server via {}: {:?}", source, path);
+            return Some(path);
+        }
+        log::warn!("chorus-mcp-server {} points at a missing file: {:?}", source, path);
+    }
+
     // Try 1: Bundled resource path (production)
     if let Ok(resource_path) = app.path().resolve(binary_name, BaseDirectory::Resource) {
         if resource_path.exists() {
@@ -466,9 +747,14 @@ pub async fn get_custom_mcp_servers(app: AppHandle) -> Result<Vec<McpCustomServe
 /// Saves a custom MCP server configuration.
 ///
 /// If a server with the same ID already exists, it will be updated.
-/// Otherwise, the new server is added to the list.
+/// Otherwise, the new server is added to the list. The server is validated
+/// first; the error names the offending field, and non-fatal problems
+/// (such as a command not found on PATH) are returned as warnings.
 #[tauri::command]
-pub async fn save_custom_mcp_server(app: AppHandle, server: McpCustomServer) -> Result<(), String> {
+pub async fn save_custom_mcp_server(
+    app: AppHandle,
+    server: McpCustomServer,
+) -> Result<Vec<String>, McpServerValidationError> {
     let store = app
         .store(CUSTOM_MCP_SERVERS_STORE)
         .map_err(|e| e.to_string())?;
@@ -479,6 +765,17 @@ pub async fn save_custom_mcp_server(app: AppHandle, server: McpCustomServer) ->
         .and_then(|v| serde_json::from_value(v.clone()).ok())
         .unwrap_or_default();
 
+    let other_names: Vec<&str> = servers
+        .iter()
+        .filter(|s| s.id != server.id)
+        .map(|s| s.name.as_str())
+        .collect();
+    let warnings =
+        mcp_manager::validate_custom_server(&server.name, &server.command, &other_names)?;
+    for warning in &warnings {
+        log::warn!("Custom MCP server {}: {}", server.name, warning);
+    }
+
     // Update or add the server
     if let Some(index) = servers.iter().position(|s| s.id == server.id) {
         servers[index] = server.clone();
@@ -495,7 +792,7 @@ pub async fn save_custom_mcp_server(app: AppHandle, server: McpCustomServer) ->
     );
     store.save().map_err(|e| e.to_string())?;
 
-    Ok(())
+    Ok(warnings)
 }
 
 /// Deletes a custom MCP server by ID.
@@ -18,6 +18,7 @@ pub struct BranchConfig {
 
 use crate::core::plugin_config_writer;
 use crate::core::plugin_manager::{PluginManager, ProjectPlugins};
+use crate::core::store_debouncer::StoreDebouncer;
 
 /// Creates a stable hash of a project path for use in store filenames.
 fn hash_project_path(path: &str) -> String {
@@ -67,17 +68,22 @@ pub async fn get_session_skills(
 }
 
 /// Sets the enabled skill IDs for a specific session.
+///
+/// Required skills are enabled too unless `auto_enable_deps` is false, in
+/// which case missing requirements are an error. Returns the effective set.
 #[tauri::command]
 pub async fn set_session_skills(
     state: State<'_, PluginManager>,
     project_path: String,
     session_id: u32,
     enabled: Vec<String>,
-) -> Result<(), String> {
+    auto_enable_deps: Option<bool>,
+) -> Result<Vec<String>, String> {
     let canonical = crate::core::path_utils::normalize_path(&project_path);
 
-    state.set_session_skills(&canonical, session_id, enabled);
-    Ok(())
+    state
+        .set_session_skills(&canonical, session_id, enabled, auto_enable_deps.unwrap_or(true))
+        .map_err(|e| e.to_string())
 }
 
 /// Gets the enabled plugin IDs for a specific session.
@@ -135,10 +141,12 @@ pub async fn get_session_plugins_count(
 /// Saves the default enabled skills for a project.
 ///
 /// These defaults are loaded when a new session starts, so skill selections
-/// persist across app restarts.
+/// persist across app restarts. The disk write is debounced like the MCP
+/// defaults.
 #[tauri::command]
 pub async fn save_project_skill_defaults(
     app: AppHandle,
+    debouncer: State<'_, StoreDebouncer>,
     project_path: String,
     enabled_skills: Vec<String>,
 ) -> Result<(), String> {
@@ -148,9 +156,9 @@ pub async fn save_project_skill_defaults(
     let store = app.store(&store_name).map_err(|e| e.to_string())?;
 
     store.set("enabled_skills", serde_json::json!(enabled_skills));
-    store.save().map_err(|e| e.to_string())?;
+    debouncer.schedule(&store_name);
 
-    log::debug!("Saved skill defaults for project: {}", canonical);
+    log::debug!("Queued save of skill defaults for project: {}", canonical);
     Ok(())
 }
 
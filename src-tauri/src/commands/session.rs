@@ -1,37 +1,73 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 use std::sync::Arc;
 
+use serde::Serialize;
 use tauri::State;
 
 use crate::core::mcp_config_writer;
 use crate::core::mcp_manager::McpManager;
 use crate::core::plugin_manager::PluginManager;
 use crate::core::process_manager::ProcessManager;
-use crate::core::session_manager::{AiMode, SessionConfig, SessionManager, SessionStatus};
+use crate::core::session_manager::{
+    validate_session_env, AiMode, AiModeInfo, BulkStatusUpdate, SessionConfig, SessionManager,
+    SessionStatus,
+};
 use crate::core::status_server::StatusServer;
+use crate::git::Git;
+
+/// Result of comparing a session's assigned branch with its worktree.
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionBranchCheck {
+    pub session_id: u32,
+    /// The branch Chorus assigned to the session, if any.
+    pub expected_branch: Option<String>,
+    /// The branch actually checked out in the session's working directory
+    /// (a short commit hash when HEAD is detached).
+    pub actual_branch: String,
+    /// `true` when a branch was assigned and the checkout no longer matches it.
+    pub mismatch: bool,
+}
 
 /// Exposes `SessionManager::all_sessions` to the frontend.
-/// Returns a snapshot of all active sessions in arbitrary order.
+/// Returns a snapshot of all active sessions in display order.
 #[tauri::command]
 pub async fn get_sessions(state: State<'_, SessionManager>) -> Result<Vec<SessionConfig>, String> {
     Ok(state.all_sessions())
 }
 
+/// Lists the AI modes a session can be created with.
+#[tauri::command]
+pub async fn get_ai_modes() -> Result<Vec<AiModeInfo>, String> {
+    Ok(AiMode::ALL.into_iter().map(AiModeInfo::from).collect())
+}
+
 /// Exposes `SessionManager::create_session` to the frontend.
 /// Registers a new session with `Idle` status. Returns an error if the
-/// session ID already exists.
+/// session ID already exists. `env` records the session's custom environment
+/// so later respawns get it too.
 #[tauri::command]
 pub async fn create_session(
     state: State<'_, SessionManager>,
     id: u32,
     mode: AiMode,
     project_path: String,
+    env: Option<BTreeMap<String, String>>,
 ) -> Result<SessionConfig, String> {
+    if let Some(ref env) = env {
+        validate_session_env(env)?;
+    }
     // Normalize path for consistent storage
     let canonical = crate::core::path_utils::normalize_path(&project_path);
 
-    state.create_session(id, mode, canonical)
-        .map_err(|existing| format!("Session {} already exists", existing.id))
+    let session = state.create_session(id, mode, canonical)
+        .map_err(|existing| format!("Session {} already exists", existing.id))?;
+    match env {
+        Some(env) if !env.is_empty() => state
+            .set_env(id, env)
+            .ok_or_else(|| format!("Session {} not found", id)),
+        _ => Ok(session),
+    }
 }
 
 /// Exposes `SessionManager::update_status` to the frontend.
@@ -45,6 +81,18 @@ pub async fn update_session_status(
     Ok(state.update_status(session_id, status))
 }
 
+/// Exposes `SessionManager::update_status_bulk` to the frontend.
+/// Sets one status on every listed session; unknown IDs are reported in
+/// `missing` rather than failing the batch.
+#[tauri::command]
+pub async fn update_sessions_status(
+    state: State<'_, SessionManager>,
+    session_ids: Vec<u32>,
+    status: SessionStatus,
+) -> Result<BulkStatusUpdate, String> {
+    Ok(state.update_status_bulk(&session_ids, status))
+}
+
 /// Updates a session's title (auto-generated from first user message).
 /// Returns `false` if the session does not exist (no error raised).
 #[tauri::command]
@@ -56,6 +104,70 @@ pub async fn update_session_title(
     Ok(state.update_title(session_id, title))
 }
 
+/// Exposes `SessionManager::set_note` to the frontend.
+/// Pass `None` (or a blank string) to clear the note. Returns an error
+/// string if the session does not exist.
+#[tauri::command]
+pub async fn set_session_note(
+    state: State<'_, SessionManager>,
+    session_id: u32,
+    note: Option<String>,
+) -> Result<SessionConfig, String> {
+    state
+        .set_note(session_id, note)
+        .ok_or_else(|| format!("Session {} not found", session_id))
+}
+
+/// Exposes `SessionManager::set_tags` to the frontend.
+/// An empty list clears the tags. Returns an error string if the session
+/// does not exist.
+#[tauri::command]
+pub async fn set_session_tags(
+    state: State<'_, SessionManager>,
+    session_id: u32,
+    tags: Vec<String>,
+) -> Result<SessionConfig, String> {
+    state
+        .set_tags(session_id, tags)
+        .ok_or_else(|| format!("Session {} not found", session_id))
+}
+
+/// Returns the session's custom environment variables.
+#[tauri::command]
+pub async fn get_session_env(
+    state: State<'_, SessionManager>,
+    session_id: u32,
+) -> Result<BTreeMap<String, String>, String> {
+    state
+        .get_env(session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))
+}
+
+/// Replaces the session's custom environment variables.
+///
+/// A running shell keeps the environment it was spawned with; the new
+/// variables apply from the next spawn or restart of the session.
+#[tauri::command]
+pub async fn set_session_env(
+    state: State<'_, SessionManager>,
+    session_id: u32,
+    env: BTreeMap<String, String>,
+) -> Result<SessionConfig, String> {
+    validate_session_env(&env)?;
+    state
+        .set_env(session_id, env)
+        .ok_or_else(|| format!("Session {} not found", session_id))
+}
+
+/// Exposes `SessionManager::get_sessions_by_tag` to the frontend.
+#[tauri::command]
+pub async fn get_sessions_by_tag(
+    state: State<'_, SessionManager>,
+    tag: String,
+) -> Result<Vec<SessionConfig>, String> {
+    Ok(state.get_sessions_by_tag(&tag))
+}
+
 /// Exposes `SessionManager::assign_branch` to the frontend.
 /// Links a session to a branch and optional worktree path. Returns an error
 /// string if the session does not exist.
@@ -71,14 +183,82 @@ pub async fn assign_session_branch(
         .ok_or_else(|| format!("Session {} not found", session_id))
 }
 
+/// Detects out-of-band branch switches in a session's worktree.
+///
+/// Reads the current branch of the session's worktree (or project directory
+/// when no worktree is assigned) and compares it to the stored `branch`.
+/// Sessions without an assigned branch never report a mismatch.
+#[tauri::command]
+pub async fn verify_session_branch(
+    state: State<'_, SessionManager>,
+    session_id: u32,
+) -> Result<SessionBranchCheck, String> {
+    let session = state
+        .get_session(session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+
+    let working_dir = session
+        .worktree_path
+        .as_deref()
+        .unwrap_or(&session.project_path);
+    let actual_branch = Git::new(working_dir)
+        .current_branch()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let mismatch = session
+        .branch
+        .as_deref()
+        .is_some_and(|expected| expected != actual_branch);
+    if mismatch {
+        log::warn!(
+            "Session {} expected branch {:?} but {} has '{}' checked out",
+            session_id,
+            session.branch,
+            working_dir,
+            actual_branch
+        );
+    }
+
+    Ok(SessionBranchCheck {
+        session_id,
+        expected_branch: session.branch,
+        actual_branch,
+        mismatch,
+    })
+}
+
+/// Exposes `SessionManager::reorder` to the frontend.
+/// Takes the desired session ID sequence and returns all sessions in the
+/// resulting display order.
+#[tauri::command]
+pub async fn reorder_sessions(
+    state: State<'_, SessionManager>,
+    ids: Vec<u32>,
+) -> Result<Vec<SessionConfig>, String> {
+    Ok(state.reorder(&ids))
+}
+
 /// Exposes `SessionManager::remove_session` to the frontend.
 /// Returns the removed session config, or `None` if it was not found.
 #[tauri::command]
 pub async fn remove_session(
     state: State<'_, SessionManager>,
+    process_manager: State<'_, ProcessManager>,
+    mcp_manager: State<'_, McpManager>,
     session_id: u32,
 ) -> Result<Option<SessionConfig>, String> {
-    Ok(state.remove_session(session_id))
+    let removed = state.remove_session(session_id);
+
+    // Stop watching .mcp.json once the project has no sessions left
+    if let Some(session) = &removed {
+        process_manager.remove_persisted_scrollback(session.id);
+        if state.get_sessions_for_project(&session.project_path).is_empty() {
+            mcp_manager.unwatch_project(&session.project_path);
+        }
+    }
+
+    Ok(removed)
 }
 
 /// Gets all sessions for a specific project.
@@ -135,8 +315,11 @@ pub async fn remove_sessions_for_project(
         if let Err(e) = process_manager.kill_session(session.id).await {
             log::warn!("Failed to kill PTY for session {}: {}", session.id, e);
         }
+        process_manager.remove_persisted_scrollback(session.id);
     }
 
+    mcp_manager.unwatch_project(&canonical);
+
     log::debug!(
         "Removed {} sessions for project {}",
         removed.len(),
@@ -2,21 +2,35 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use serde::Serialize;
-use tauri::{AppHandle, State};
+use tauri::{AppHandle, Emitter, Manager, State};
+use tauri_plugin_store::StoreExt;
 
-use crate::core::session_manager::SessionManager;
+use crate::core::event_bus::EventBus;
+use crate::core::mcp_manager::McpManager;
+use crate::core::process_manager::{
+    SessionOutputChunk, SessionOutputInfo, SessionRestartedPayload, SessionTerminalState,
+    DEFAULT_OUTPUT_CHUNK_BYTES, SESSION_RESTARTED_EVENT,
+};
+#[cfg(feature = "vte-backend")]
+use crate::core::TerminalBackend;
+use crate::core::session_manager::{AiMode, SessionManager, SessionStatus};
 use crate::core::status_server::StatusServer;
 use crate::core::windows_process::TokioCommandExt;
-use crate::core::{BackendCapabilities, BackendType, ProcessManager, PtyError, SessionProcessTree};
+use crate::core::{
+    BackendCapabilities, BackendType, CliNotFound, ProcessManager, PtyError, ResizeGranularity,
+    ScreenSnapshot, SessionProcessTree,
+};
 
 /// Backend information returned to the frontend.
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BackendInfo {
-    /// The active backend type.
+    /// The backend new sessions are spawned with.
     pub backend_type: BackendType,
     /// Backend capabilities.
     pub capabilities: BackendCapabilitiesDto,
+    /// Backends compiled into this build that sessions can be spawned with.
+    pub available_backends: Vec<BackendType>,
 }
 
 /// DTO for backend capabilities (frontend-friendly naming).
@@ -27,6 +41,9 @@ pub struct BackendCapabilitiesDto {
     pub text_reflow: bool,
     pub kitty_graphics: bool,
     pub shell_integration: bool,
+    pub true_color: bool,
+    pub mouse_reporting: bool,
+    pub resize_granularity: ResizeGranularity,
     pub backend_name: String,
 }
 
@@ -37,6 +54,9 @@ impl From<BackendCapabilities> for BackendCapabilitiesDto {
             text_reflow: caps.text_reflow,
             kitty_graphics: caps.kitty_graphics,
             shell_integration: caps.shell_integration,
+            true_color: caps.true_color,
+            mouse_reporting: caps.mouse_reporting,
+            resize_granularity: caps.resize_granularity,
             backend_name: caps.backend_name.to_string(),
         }
     }
@@ -47,32 +67,31 @@ impl From<BackendCapabilities> for BackendCapabilitiesDto {
 /// The frontend can use this to enable/disable features based on
 /// backend capabilities (e.g., enhanced terminal state queries).
 #[tauri::command]
-pub fn get_backend_info() -> BackendInfo {
-    let backend_type = BackendType::platform_default();
-
-    let capabilities = match backend_type {
-        BackendType::XtermPassthrough => BackendCapabilities {
-            enhanced_state: false,
-            text_reflow: false,
-            kitty_graphics: false,
-            shell_integration: false,
-            backend_name: "xterm-passthrough",
-        },
-        BackendType::VteParser => BackendCapabilities {
-            enhanced_state: true,
-            text_reflow: false,
-            kitty_graphics: false,
-            shell_integration: false,
-            backend_name: "vte-parser",
-        },
-    };
+pub fn get_backend_info(state: State<'_, ProcessManager>) -> BackendInfo {
+    backend_info(&state)
+}
 
+/// Builds the `BackendInfo` for the manager's current default backend.
+pub fn backend_info(pm: &ProcessManager) -> BackendInfo {
+    let backend_type = pm.default_backend();
     BackendInfo {
         backend_type,
-        capabilities: capabilities.into(),
+        capabilities: backend_type.capabilities().into(),
+        available_backends: BackendType::available(),
     }
 }
 
+/// Sets the backend used by sessions spawned without an explicit choice.
+/// Fails if the backend isn't compiled into this build.
+#[tauri::command]
+pub fn set_default_backend(
+    state: State<'_, ProcessManager>,
+    backend: BackendType,
+) -> Result<BackendInfo, String> {
+    state.set_default_backend(backend)?;
+    Ok(backend_info(&state))
+}
+
 /// Exposes `ProcessManager::spawn_shell` to the frontend.
 ///
 /// Validates that `cwd` (if provided) exists and is a directory before
@@ -84,12 +103,21 @@ pub fn get_backend_info() -> BackendInfo {
 /// These are inherited by all child processes (including Claude CLI → MCP server).
 /// Common usage: `{ "CHORUS_PROJECT_HASH": "<hash>" }` for MCP status identification.
 /// Note: `CHORUS_SESSION_ID` is automatically set by the process manager.
+///
+/// `backend` picks the terminal backend for this session; when omitted the
+/// manager's default (see `set_default_backend`) is used.
+///
+/// `session_id` spawns the shell of an existing session (e.g. one restored as
+/// `Stopped`) under that ID, with its custom env applied; otherwise a new ID
+/// is allocated.
 #[tauri::command]
 pub async fn spawn_shell(
     app_handle: AppHandle,
     state: State<'_, ProcessManager>,
     cwd: Option<String>,
     env: Option<HashMap<String, String>>,
+    backend: Option<BackendType>,
+    session_id: Option<u32>,
 ) -> Result<u32, PtyError> {
     // Validate cwd if provided: must exist and be a directory
     let canonical_cwd = if let Some(ref dir) = cwd {
@@ -104,7 +132,12 @@ pub async fn spawn_shell(
         None
     };
     let pm = state.inner().clone();
-    pm.spawn_shell(app_handle, canonical_cwd, env)
+    match session_id {
+        Some(id) => pm
+            .spawn_session_shell(app_handle, id, canonical_cwd, env, backend)
+            .map(|_| id),
+        None => pm.spawn_shell(app_handle, canonical_cwd, env, backend),
+    }
 }
 
 /// Exposes `ProcessManager::write_stdin` to the frontend.
@@ -171,6 +204,319 @@ pub async fn kill_session(
     result
 }
 
+/// Restarts a session's shell in place, keeping its ID, tags and branch.
+///
+/// See `ProcessManager::restart_session`. Afterwards the session's MCP config
+/// is rewritten (when it has a project) so config changes are picked up by the
+/// next CLI launch, and `session:restarted` is emitted. Returns the new PID.
+#[tauri::command]
+pub async fn restart_session(app: AppHandle, session_id: u32) -> Result<i32, PtyError> {
+    restart_session_internal(&app, session_id).await
+}
+
+/// Shared implementation of `restart_session`, also used by `web_dispatch`.
+pub async fn restart_session_internal(app: &AppHandle, session_id: u32) -> Result<i32, PtyError> {
+    let pm = app.state::<ProcessManager>().inner().clone();
+    let pid = pm.restart_session(app.clone(), session_id).await?;
+
+    let session_mgr = app.state::<SessionManager>();
+    if let Some(session) = session_mgr.get_session(session_id) {
+        session_mgr.update_status(session_id, SessionStatus::Idle);
+
+        let canonical = crate::core::path_utils::normalize_path(&session.project_path);
+        let working_dir = session.worktree_path.as_deref().unwrap_or(&canonical);
+        let enabled = app
+            .state::<McpManager>()
+            .get_session_enabled(&canonical, session_id);
+        if let Err(e) = crate::commands::mcp::write_session_mcp_config_internal(
+            app,
+            working_dir,
+            session_id,
+            &canonical,
+            &enabled,
+        )
+        .await
+        {
+            log::warn!("Session {session_id}: failed to rewrite MCP config after restart: {e}");
+        }
+    }
+
+    let payload = SessionRestartedPayload { session_id, pid };
+    let _ = app.emit(SESSION_RESTARTED_EVENT, &payload);
+    if let Some(bus) = app.try_state::<Arc<EventBus>>() {
+        match serde_json::to_value(&payload) {
+            Ok(v) => bus.send(SESSION_RESTARTED_EVENT.to_string(), v),
+            Err(e) => log::error!("Failed to serialize session restart payload: {e}"),
+        }
+    }
+    Ok(pid)
+}
+
+/// Returns a snapshot of the session's visible screen at the given size.
+///
+/// Intended for compact previews (e.g. mobile) where the rendered screen is
+/// more useful than raw scrollback. Rejects dimensions that are zero or
+/// exceed `MAX_SCREEN_DIMENSION`. Returns None if the session doesn't exist.
+#[tauri::command]
+pub async fn get_session_screen(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+    rows: u16,
+    cols: u16,
+) -> Result<Option<ScreenSnapshot>, String> {
+    ScreenSnapshot::check_size(rows, cols)?;
+    let pm = state.inner().clone();
+    Ok(pm.get_session_screen(session_id, rows, cols))
+}
+
+/// Starts recording a session's output to an asciicast v2 file at `path`.
+#[tauri::command]
+pub async fn start_recording(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+    path: String,
+) -> Result<(), String> {
+    state
+        .start_recording(session_id, std::path::Path::new(&path))
+        .map_err(|e| e.to_string())
+}
+
+/// Stops a session's recording. Returns the cast file path, or None if the
+/// session wasn't recording.
+#[tauri::command]
+pub async fn stop_recording(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+) -> Result<Option<String>, String> {
+    state
+        .stop_recording(session_id)
+        .map(|path| path.map(|p| p.to_string_lossy().into_owned()))
+        .map_err(|e| e.to_string())
+}
+
+/// Returns buffered output after the absolute byte `offset`, at most
+/// `max_bytes` (default 64 KiB) per call. Pass back `nextOffset` to page
+/// through scrollback, then tail with the live `pty-output-{id}` event.
+/// With `strip_ansi`, escape sequences are removed from the returned data.
+#[tauri::command]
+pub async fn get_session_output_since(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+    offset: u64,
+    max_bytes: Option<usize>,
+    strip_ansi: Option<bool>,
+) -> Result<Option<SessionOutputChunk>, String> {
+    let chunk = state.get_session_output_since(
+        session_id,
+        offset,
+        max_bytes.unwrap_or(DEFAULT_OUTPUT_CHUNK_BYTES),
+    );
+    Ok(if strip_ansi.unwrap_or(false) {
+        chunk.map(SessionOutputChunk::strip_ansi)
+    } else {
+        chunk
+    })
+}
+
+/// Returns how much scrollback a session holds, so the UI can show how much
+/// history is available before fetching it. Returns None if the session
+/// doesn't exist.
+#[tauri::command]
+pub async fn get_session_output_info(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+) -> Result<Option<SessionOutputInfo>, String> {
+    Ok(state.get_session_output_info(session_id))
+}
+
+/// Sets how many bytes of scrollback a session retains (clamped to a sane
+/// range). Returns the limit actually applied.
+#[tauri::command]
+pub async fn set_scrollback_limit(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+    limit: usize,
+) -> Result<usize, String> {
+    state
+        .set_scrollback_limit(session_id, limit)
+        .map_err(|e| e.to_string())
+}
+
+/// Store filename for backend terminal settings.
+const SCROLLBACK_STORE: &str = "scrollback.json";
+/// Store key for whether session scrollback is persisted to disk.
+const SCROLLBACK_PERSISTENCE_KEY: &str = "persist";
+
+/// Turns scrollback persistence back on at startup if the user left it on,
+/// so logs from the previous run can be read and new output is logged.
+pub fn load_scrollback_persistence(app: &AppHandle) {
+    let enabled = app
+        .store(SCROLLBACK_STORE)
+        .ok()
+        .and_then(|store| store.get(SCROLLBACK_PERSISTENCE_KEY))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if !enabled {
+        return;
+    }
+    let pm = app.state::<ProcessManager>();
+    if let Err(e) = pm.set_scrollback_persistence(true) {
+        log::warn!("Cannot restore scrollback persistence: {}", e);
+    }
+}
+
+/// Applies a scrollback persistence choice and saves it for later runs.
+/// Shared by the Tauri command and web dispatch.
+pub fn save_scrollback_persistence(app: &AppHandle, enabled: bool) -> Result<(), String> {
+    app.state::<ProcessManager>()
+        .set_scrollback_persistence(enabled)?;
+    let store = app.store(SCROLLBACK_STORE).map_err(|e| e.to_string())?;
+    store.set(SCROLLBACK_PERSISTENCE_KEY, enabled);
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Turns on or off persisting each session's scrollback to
+/// `~/.chorus/sessions/<id>.log`, so output survives a crash. Turning it
+/// off deletes the logs of running sessions. The choice is kept across
+/// restarts.
+#[tauri::command]
+pub async fn set_scrollback_persistence(app: AppHandle, enabled: bool) -> Result<(), String> {
+    save_scrollback_persistence(&app, enabled)
+}
+
+/// Whether session scrollback is currently persisted to disk.
+#[tauri::command]
+pub async fn get_scrollback_persistence(state: State<'_, ProcessManager>) -> Result<bool, String> {
+    Ok(state.scrollback_dir().is_some())
+}
+
+/// Stops emitting a session's output events; output keeps going to
+/// scrollback and is delivered on `resume_session_output`.
+#[tauri::command]
+pub async fn pause_session_output(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+) -> Result<(), String> {
+    state
+        .set_output_paused(session_id, true)
+        .map_err(|e| e.to_string())
+}
+
+/// Resumes a session's output events, first emitting what was held back.
+#[tauri::command]
+pub async fn resume_session_output(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+) -> Result<(), String> {
+    state
+        .set_output_paused(session_id, false)
+        .map_err(|e| e.to_string())
+}
+
+/// Sets how long PTY output is buffered to merge bursts into one event,
+/// for all sessions (0 disables coalescing). Returns the window applied, in ms.
+#[tauri::command]
+pub async fn set_output_coalesce_window(
+    state: State<'_, ProcessManager>,
+    window_ms: u64,
+) -> Result<u64, String> {
+    Ok(state.set_output_coalesce_window(window_ms))
+}
+
+/// Returns the session's current terminal size, cursor position and
+/// alt-screen flag so remote renderers can position output after reconnect.
+/// Returns None if the session doesn't exist.
+#[tauri::command]
+pub async fn get_session_terminal_state(
+    state: State<'_, ProcessManager>,
+    session_id: u32,
+) -> Result<Option<SessionTerminalState>, String> {
+    let pm = state.inner().clone();
+    Ok(pm.get_terminal_state(session_id))
+}
+
+/// Spawns a shell directly on the VTE backend, bypassing `ProcessManager`.
+///
+/// Developer/QA aid for comparing the parsed grid against the raw passthrough
+/// stream. Output is emitted on `pty-output-{id}` like regular sessions, but
+/// input and teardown go through the `*_vte_session` commands.
+#[cfg(feature = "vte-backend")]
+#[tauri::command]
+pub async fn spawn_vte_session(
+    app_handle: AppHandle,
+    pm: State<'_, ProcessManager>,
+    vte_sessions: State<'_, crate::core::VteSessions>,
+    cwd: Option<String>,
+    rows: Option<u16>,
+    cols: Option<u16>,
+) -> Result<u32, String> {
+    let rows = rows.unwrap_or(24);
+    let cols = cols.unwrap_or(80);
+    if rows == 0 || cols == 0 || rows > 500 || cols > 500 {
+        return Err("Invalid dimensions".to_string());
+    }
+    let cwd = match cwd {
+        Some(dir) => {
+            let canonical = crate::core::path_utils::normalize_path_buf(std::path::Path::new(&dir));
+            if !canonical.is_dir() {
+                return Err(format!("cwd '{dir}' is not a directory"));
+            }
+            Some(canonical.to_string_lossy().into_owned())
+        }
+        None => None,
+    };
+
+    let session_id = pm.allocate_session_id().map_err(|e| e.to_string())?;
+    vte_sessions
+        .spawn(crate::core::TerminalConfig {
+            session_id,
+            rows,
+            cols,
+            cwd,
+            app_handle,
+        })
+        .map_err(|e| e.to_string())?;
+    Ok(session_id)
+}
+
+/// Sends raw input to a session created with `spawn_vte_session`.
+#[cfg(feature = "vte-backend")]
+#[tauri::command]
+pub async fn write_vte_session(
+    vte_sessions: State<'_, crate::core::VteSessions>,
+    session_id: u32,
+    data: String,
+) -> Result<(), String> {
+    let backend = vte_sessions
+        .get(session_id)
+        .ok_or_else(|| format!("VTE session {session_id} not found"))?;
+    backend.write(data.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Shuts down a session created with `spawn_vte_session`.
+/// Returns false if the session doesn't exist.
+#[cfg(feature = "vte-backend")]
+#[tauri::command]
+pub async fn kill_vte_session(
+    vte_sessions: State<'_, crate::core::VteSessions>,
+    session_id: u32,
+) -> Result<bool, String> {
+    let sessions = vte_sessions.inner();
+    // Shutdown sleeps and joins the reader thread, so keep it off the runtime
+    tokio::task::block_in_place(|| sessions.kill(session_id)).map_err(|e| e.to_string())
+}
+
+/// Returns the VTE backend's parsed screen grid for a `spawn_vte_session`
+/// session. Returns None if the session doesn't exist.
+#[cfg(feature = "vte-backend")]
+#[tauri::command]
+pub async fn get_backend_grid_dump(
+    vte_sessions: State<'_, crate::core::VteSessions>,
+    session_id: u32,
+) -> Result<Option<ScreenSnapshot>, String> {
+    Ok(vte_sessions.get(session_id).and_then(|b| b.grid_dump()))
+}
+
 /// Returns the process tree for a specific session.
 ///
 /// The tree includes the root shell process and all its descendants.
@@ -233,6 +579,29 @@ pub async fn kill_all_sessions(state: State<'_, ProcessManager>) -> Result<u32,
     pm.kill_all_sessions().await
 }
 
+/// Pre-flight for launching a session: verifies the CLI for `mode` is
+/// installed. Plain sessions always pass.
+///
+/// If the check itself can't run, the launch is allowed and the shell will
+/// report any problem as before.
+#[tauri::command]
+pub async fn check_session_cli(mode: AiMode) -> Result<(), CliNotFound> {
+    let (Some(command), Some(install_hint)) = (mode.cli_command(), mode.install_hint()) else {
+        return Ok(());
+    };
+    match check_cli_available(command.to_string()).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(CliNotFound {
+            command: command.to_string(),
+            install_hint: install_hint.to_string(),
+        }),
+        Err(e) => {
+            log::warn!("Could not check for '{}': {}", command, e);
+            Ok(())
+        }
+    }
+}
+
 /// Checks if a command is available in the user's PATH.
 /// Uses platform-appropriate method:
 /// - Unix: runs `command -v <cmd>` via interactive login shell to get user's real PATH
@@ -4,12 +4,128 @@ use std::sync::Arc;
 
 use serde::Serialize;
 use tauri::{AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
 
-use crate::core::event_bus::EventBus;
+use crate::core::event_bus::{EventBus, KnownEventName};
 use crate::core::process_manager::ProcessManager;
 use crate::core::session_manager::SessionManager;
 use crate::core::tunnel_manager::TunnelManager;
-use crate::core::web_access_server::{WebAccessServer, WebAccessStatus, WebAccessTokenResult};
+use crate::core::web_access_server::{
+    self, IpAllowlist, PortRange, WebAccessQr, WebAccessServer, WebAccessStatus, WebAccessTokenResult,
+};
+use crate::core::web_tls::TlsIdentity;
+
+/// Store filename for web access settings.
+const WEB_ACCESS_STORE: &str = "web-access.json";
+/// Store key holding the persisted `PortRange`.
+const PORT_RANGE_KEY: &str = "portRange";
+/// Store key for whether web access is served over HTTPS.
+const TLS_KEY: &str = "tls";
+/// Store key holding the client address allowlist entries.
+const ALLOWLIST_KEY: &str = "allowlist";
+
+/// Port range saved by the user, or the default if none (or an invalid one)
+/// was saved. Read at startup before the server binds.
+pub fn load_port_range(app: &AppHandle) -> PortRange {
+    let saved = app
+        .store(WEB_ACCESS_STORE)
+        .ok()
+        .and_then(|store| store.get(PORT_RANGE_KEY))
+        .and_then(|v| serde_json::from_value::<PortRange>(v).ok());
+
+    match saved.map(|r| PortRange::new(r.start, r.end)) {
+        Some(Ok(range)) => range,
+        Some(Err(e)) => {
+            log::warn!("Ignoring saved web access port range: {}", e);
+            PortRange::default()
+        }
+        None => PortRange::default(),
+    }
+}
+
+/// The TLS identity to serve with, if the user turned HTTPS on.
+///
+/// The certificate lives in `~/.chorus/tls/` and is generated on first use.
+/// Falls back to plain HTTP (None) if it can't be loaded.
+pub fn load_tls_identity(app: &AppHandle) -> Option<TlsIdentity> {
+    let enabled = app
+        .store(WEB_ACCESS_STORE)
+        .ok()
+        .and_then(|store| store.get(TLS_KEY))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    if !enabled {
+        return None;
+    }
+
+    let dir = dirs::home_dir()?.join(".chorus").join("tls");
+    match TlsIdentity::load_or_create(&dir) {
+        Ok(identity) => Some(identity),
+        Err(e) => {
+            log::warn!("Web access TLS unavailable, serving plain HTTP: {}", e);
+            None
+        }
+    }
+}
+
+/// Turn HTTPS for web access on or off.
+///
+/// Like the port range, this takes effect the next time Chorus starts.
+#[tauri::command]
+pub fn set_web_access_tls(app: AppHandle, enabled: bool) -> Result<(), String> {
+    let store = app.store(WEB_ACCESS_STORE).map_err(|e| e.to_string())?;
+    store.set(TLS_KEY, enabled);
+    store.save().map_err(|e| e.to_string())
+}
+
+/// Client address allowlist saved by the user; empty (allow all) by default.
+pub fn load_allowlist(app: &AppHandle) -> IpAllowlist {
+    let entries: Vec<String> = app
+        .store(WEB_ACCESS_STORE)
+        .ok()
+        .and_then(|store| store.get(ALLOWLIST_KEY))
+        .and_then(|v| serde_json::from_value(v).ok())
+        .unwrap_or_default();
+
+    IpAllowlist::parse(&entries).unwrap_or_else(|e| {
+        log::warn!("Ignoring saved web access allowlist: {}", e);
+        IpAllowlist::default()
+    })
+}
+
+/// Restrict which addresses may connect to web access.
+///
+/// Entries are IPs or CIDR ranges; an empty list allows everyone. Applies to
+/// new connections right away and is saved for later runs. Only meaningful
+/// for direct LAN access, since tunnelled clients all share the tunnel's
+/// address. Returns the normalized entries.
+#[tauri::command]
+pub async fn set_web_access_allowlist(app: AppHandle, entries: Vec<String>) -> Result<Vec<String>, String> {
+    let allowlist = IpAllowlist::parse(&entries)?;
+    let normalized = allowlist.entries();
+
+    let store = app.store(WEB_ACCESS_STORE).map_err(|e| e.to_string())?;
+    store.set(ALLOWLIST_KEY, serde_json::json!(normalized));
+    store.save().map_err(|e| e.to_string())?;
+
+    if let Some(server) = app.try_state::<WebAccessServer>() {
+        server.set_allowlist(allowlist).await;
+    }
+    Ok(normalized)
+}
+
+/// Save the port range the web access server binds in.
+///
+/// Takes effect the next time Chorus starts; the running server keeps its
+/// port. Returns the saved range.
+#[tauri::command]
+pub fn set_web_access_port_range(app: AppHandle, start: u16, end: u16) -> Result<PortRange, String> {
+    let range = PortRange::new(start, end)?;
+    let store = app.store(WEB_ACCESS_STORE).map_err(|e| e.to_string())?;
+    store.set(PORT_RANGE_KEY, serde_json::to_value(range).map_err(|e| e.to_string())?);
+    store.save().map_err(|e| e.to_string())?;
+    Ok(range)
+}
 
 /// Generate a new web access token and return the URL + token + expiry.
 #[tauri::command]
@@ -40,6 +156,17 @@ pub async fn generate_web_access_token(
     })
 }
 
+/// Render a scannable QR code for the web access URL.
+///
+/// Pass the URL and token from `generate_web_access_token`; with a token the
+/// QR opens an already-authenticated session. Call again after the token
+/// rotates. The encoded URL is returned alongside for copy/paste.
+#[tauri::command]
+pub fn get_web_access_qr(url: String, token: Option<String>) -> Result<WebAccessQr, String> {
+    let full_url = web_access_server::access_url(&url, token.as_deref());
+    web_access_server::render_access_qr(&full_url)
+}
+
 /// Get the current web access server status.
 #[tauri::command]
 pub async fn get_web_access_status(
@@ -50,12 +177,29 @@ pub async fn get_web_access_status(
         None => Ok(WebAccessStatus {
             running: false,
             port: 0,
+            port_range: load_port_range(&app),
+            tls: false,
+            cert_fingerprint: None,
+            allowlist: load_allowlist(&app).entries(),
             connected_clients: 0,
+            max_clients: web_access_server::DEFAULT_MAX_CLIENTS,
             has_valid_token: false,
         }),
     }
 }
 
+/// Change how many web clients may be connected at once.
+///
+/// Clients already connected stay connected; returns the limit actually
+/// applied after clamping.
+#[tauri::command]
+pub fn set_web_access_max_clients(app: AppHandle, max: usize) -> Result<usize, String> {
+    let server = app
+        .try_state::<WebAccessServer>()
+        .ok_or("Web access server not running")?;
+    Ok(server.set_max_clients(max))
+}
+
 /// Revoke the current token and disconnect web clients.
 #[tauri::command]
 pub async fn revoke_web_access(
@@ -86,7 +230,12 @@ pub async fn start_web_tunnel(
     let server = app
         .try_state::<WebAccessServer>()
         .ok_or("Web access server not running")?;
-    let port = server.get_status().await.port;
+    let status = server.get_status().await;
+    // The tunnel forwards plain HTTP and terminates HTTPS itself
+    if status.tls {
+        return Err("The tunnel needs plain HTTP; turn off HTTPS for web access and restart Chorus".to_string());
+    }
+    let port = status.port;
 
     let tunnel = app.state::<TunnelManager>();
     tunnel.start(port).await
@@ -139,3 +288,12 @@ pub fn push_session_to_mobile(
 
     Ok(())
 }
+
+/// List the event names seen on the EventBus with per-name counts and
+/// last-seen timestamps, so clients can discover what to subscribe to.
+#[tauri::command]
+pub fn get_known_event_names(app: AppHandle) -> Vec<KnownEventName> {
+    app.try_state::<Arc<EventBus>>()
+        .map(|bus| bus.known_event_names())
+        .unwrap_or_default()
+}
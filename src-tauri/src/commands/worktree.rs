@@ -1,10 +1,12 @@
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
 use serde::Serialize;
 use tauri::State;
 
-use crate::core::worktree_manager::WorktreeManager;
-use crate::git::Git;
+use crate::core::session_manager::SessionManager;
+use crate::core::worktree_manager::{OrphanPruneResult, WorktreeManager};
+use crate::git::{Git, WorktreeInfo};
 
 /// Result of preparing a worktree for a session.
 #[derive(Debug, Clone, Serialize)]
@@ -30,11 +32,15 @@ pub struct WorktreePreparationResult {
 ///
 /// On any failure, falls back to the project path so sessions always launch.
 /// The caller is responsible for updating the session with the worktree path.
+///
+/// If `prepare_handle` is given, the preparation can be aborted with
+/// `cancel_worktree_prepare`; a cancelled call returns an error.
 #[tauri::command]
 pub async fn prepare_session_worktree(
     worktree_manager: State<'_, WorktreeManager>,
     project_path: String,
     branch: Option<String>,
+    prepare_handle: Option<String>,
 ) -> Result<WorktreePreparationResult, String> {
     // No branch specified - just use the project path
     let branch = match branch {
@@ -49,6 +55,46 @@ pub async fn prepare_session_worktree(
         }
     };
 
+    let manager = worktree_manager.inner();
+    match prepare_handle {
+        Some(handle) => {
+            let repo_path = PathBuf::from(&project_path);
+            manager
+                .run_cancellable(
+                    handle,
+                    &repo_path,
+                    &branch,
+                    prepare_branch_worktree(manager, project_path.clone(), branch.clone()),
+                )
+                .await
+                .ok_or_else(|| format!("Worktree preparation for {} was cancelled", branch))?
+        }
+        None => prepare_branch_worktree(manager, project_path, branch).await,
+    }
+}
+
+/// Aborts an in-flight `prepare_session_worktree` call started with the
+/// given handle and removes any partially-created worktree.
+///
+/// Returns whether cleanup succeeded, or an error if no preparation with
+/// this handle is in progress.
+#[tauri::command]
+pub async fn cancel_worktree_prepare(
+    worktree_manager: State<'_, WorktreeManager>,
+    handle: String,
+) -> Result<bool, String> {
+    worktree_manager
+        .cancel_prepare(&handle)
+        .await
+        .ok_or_else(|| format!("No worktree preparation in progress for handle {}", handle))
+}
+
+/// Does the actual work of `prepare_session_worktree` once a branch is known.
+async fn prepare_branch_worktree(
+    worktree_manager: &WorktreeManager,
+    project_path: String,
+    branch: String,
+) -> Result<WorktreePreparationResult, String> {
     let repo_path = PathBuf::from(&project_path);
     let git = Git::new(&repo_path);
 
@@ -200,6 +246,187 @@ pub async fn cleanup_session_worktree(
     }
 }
 
+/// How a worktree relates to the sessions registered in `SessionManager`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WorktreeOwnership {
+    /// The project's main checkout; never considered orphaned.
+    Main,
+    /// At least one session has this worktree assigned.
+    Owned,
+    /// Git knows about the worktree but no session references it.
+    Orphaned,
+    /// A session references this path but git has no such worktree.
+    Missing,
+}
+
+/// A worktree joined with the sessions that own it.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorktreeSessionEntry {
+    pub path: String,
+    /// Branch checked out in the worktree, or the session's assigned branch
+    /// for `Missing` entries.
+    pub branch: Option<String>,
+    /// Commit SHA the worktree points to. `None` for `Missing` entries.
+    pub head: Option<String>,
+    pub session_ids: Vec<u32>,
+    pub ownership: WorktreeOwnership,
+}
+
+/// Lists every worktree of a project along with the sessions that own it.
+///
+/// Joins `git worktree list` with the `worktree_path` assignments tracked by
+/// `SessionManager`. Worktrees without a session are flagged `Orphaned`, and
+/// session assignments pointing at worktrees git no longer knows about are
+/// reported as `Missing`. Paths are normalized before comparison. The main
+/// checkout comes first, then the rest sorted by path.
+#[tauri::command]
+pub async fn list_worktrees_with_sessions(
+    session_manager: State<'_, SessionManager>,
+    project_path: String,
+) -> Result<Vec<WorktreeSessionEntry>, String> {
+    let canonical = crate::core::path_utils::normalize_path(&project_path);
+    let git = Git::new(&canonical);
+    let worktrees = git.worktree_list().await.map_err(|e| e.to_string())?;
+
+    // Group session ids by their normalized worktree path
+    let mut owners: HashMap<String, Vec<(u32, Option<String>)>> = HashMap::new();
+    for session in session_manager.get_sessions_for_project(&canonical) {
+        if let Some(ref wt_path) = session.worktree_path {
+            let key = crate::core::path_utils::normalize_path(wt_path);
+            owners
+                .entry(key)
+                .or_default()
+                .push((session.id, session.branch.clone()));
+        }
+    }
+
+    let mut entries = Vec::with_capacity(worktrees.len());
+    for wt in worktrees {
+        let key = crate::core::path_utils::normalize_path(&wt.path);
+        let session_ids: Vec<u32> = owners
+            .remove(&key)
+            .map(|v| v.into_iter().map(|(id, _)| id).collect())
+            .unwrap_or_default();
+
+        let ownership = if key == canonical {
+            WorktreeOwnership::Main
+        } else if session_ids.is_empty() {
+            WorktreeOwnership::Orphaned
+        } else {
+            WorktreeOwnership::Owned
+        };
+
+        entries.push(WorktreeSessionEntry {
+            path: wt.path,
+            branch: wt.branch,
+            head: Some(wt.head),
+            session_ids,
+            ownership,
+        });
+    }
+
+    // Anything left over is referenced by a session but unknown to git
+    for (path, sessions) in owners {
+        let branch = sessions.iter().find_map(|(_, b)| b.clone());
+        entries.push(WorktreeSessionEntry {
+            path,
+            branch,
+            head: None,
+            session_ids: sessions.into_iter().map(|(id, _)| id).collect(),
+            ownership: WorktreeOwnership::Missing,
+        });
+    }
+
+    for entry in &mut entries {
+        entry.session_ids.sort_unstable();
+    }
+    entries.sort_by(|a, b| {
+        (a.ownership != WorktreeOwnership::Main, &a.path)
+            .cmp(&(b.ownership != WorktreeOwnership::Main, &b.path))
+    });
+
+    Ok(entries)
+}
+
+/// A worktree from `git worktree list` with the bytes its checkout uses.
+#[derive(Debug, Clone, Serialize)]
+pub struct WorktreeDiskUsage {
+    pub path: String,
+    pub head: String,
+    pub branch: Option<String>,
+    pub is_bare: bool,
+    /// Bytes used by the checkout, excluding `.git` and symlinks.
+    pub bytes: u64,
+}
+
+/// Lists every worktree of a project with its disk usage, largest first.
+///
+/// Takes the same fields as `git_worktree_list` plus a byte count, so a
+/// cleanup UI can sort by size before pruning.
+#[tauri::command]
+pub async fn worktree_disk_usage(
+    worktree_manager: State<'_, WorktreeManager>,
+    repo_path: String,
+) -> Result<Vec<WorktreeDiskUsage>, String> {
+    let git = Git::new(&repo_path);
+    let worktrees = git.worktree_list().await.map_err(|e| e.to_string())?;
+
+    let mut usage = Vec::with_capacity(worktrees.len());
+    for wt in worktrees {
+        let bytes = worktree_manager.disk_usage(Path::new(&wt.path)).await;
+        usage.push(WorktreeDiskUsage {
+            path: wt.path,
+            head: wt.head,
+            branch: wt.branch,
+            is_bare: wt.is_bare,
+            bytes,
+        });
+    }
+    usage.sort_by_key(|u| std::cmp::Reverse(u.bytes));
+    Ok(usage)
+}
+
+/// Worktree paths currently assigned to any session of the project.
+fn session_worktree_paths(session_manager: &SessionManager, project_path: &str) -> Vec<PathBuf> {
+    session_manager
+        .get_sessions_for_project(project_path)
+        .into_iter()
+        .filter_map(|s| s.worktree_path.map(PathBuf::from))
+        .collect()
+}
+
+/// Lists Chorus-managed worktrees of a project that no session references.
+#[tauri::command]
+pub async fn list_orphaned_worktrees(
+    worktree_manager: State<'_, WorktreeManager>,
+    session_manager: State<'_, SessionManager>,
+    project_path: String,
+) -> Result<Vec<WorktreeInfo>, String> {
+    let canonical = crate::core::path_utils::normalize_path(&project_path);
+    let known = session_worktree_paths(&session_manager, &canonical);
+    worktree_manager
+        .list_orphaned(Path::new(&canonical), &known)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Removes orphaned worktrees of a project. Worktrees with uncommitted
+/// changes are kept and reported as skipped.
+#[tauri::command]
+pub async fn prune_orphaned_worktrees(
+    worktree_manager: State<'_, WorktreeManager>,
+    session_manager: State<'_, SessionManager>,
+    project_path: String,
+) -> Result<OrphanPruneResult, String> {
+    let canonical = crate::core::path_utils::normalize_path(&project_path);
+    let known = session_worktree_paths(&session_manager, &canonical);
+    worktree_manager
+        .prune_orphaned(Path::new(&canonical), &known)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// Gets a fallback branch to switch to when the target branch is checked out.
 ///
 /// Tries init.defaultBranch config, then looks for main/master.
@@ -0,0 +1,181 @@
+//! ANSI escape sequence stripping for plain-text views of PTY output.
+//!
+//! This is a small hand-rolled state machine rather than a full VT parser so
+//! it is available regardless of which terminal backend feature is enabled.
+//! It removes CSI, OSC, DCS/SOS/PM/APC strings and two-byte escapes, and
+//! drops C0 control characters other than newline, carriage return and tab.
+
+/// Parser state while scanning for escape sequences.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Ground,
+    /// Saw `ESC`, waiting for the sequence introducer.
+    Escape,
+    /// Inside `ESC` followed by intermediate bytes (e.g. `ESC ( B`).
+    EscapeIntermediate,
+    /// Inside a CSI sequence (`ESC [` ... final byte).
+    Csi,
+    /// Inside an OSC string, terminated by BEL or ST.
+    Osc,
+    /// Inside a DCS/SOS/PM/APC string, terminated by ST.
+    String,
+    /// Saw `ESC` inside a string; a following `\` completes ST.
+    StringEscape,
+}
+
+/// Returns `input` with all ANSI escape sequences and non-printing control
+/// characters removed. Newlines, carriage returns and tabs are preserved.
+pub fn strip_ansi(input: &str) -> String {
+    scan(input).0
+}
+
+/// Like [`strip_ansi`], but also returns the byte index where an escape
+/// sequence left unterminated at the end of `input` begins, so a caller
+/// reading output in chunks can hold it back until the rest arrives.
+pub fn strip_ansi_partial(input: &str) -> (String, Option<usize>) {
+    scan(input)
+}
+
+fn scan(input: &str) -> (String, Option<usize>) {
+    let mut out = String::with_capacity(input.len());
+    let mut state = State::Ground;
+    // Whether the current string sequence is an OSC (which may end with BEL)
+    let mut in_osc = false;
+    // Where the sequence being scanned started
+    let mut seq_start = 0;
+
+    for (i, c) in input.char_indices() {
+        state = match state {
+            State::Ground => match c {
+                '\x1b' => {
+                    seq_start = i;
+                    State::Escape
+                }
+                '\n' | '\r' | '\t' => {
+                    out.push(c);
+                    State::Ground
+                }
+                c if c.is_control() => State::Ground,
+                c => {
+                    out.push(c);
+                    State::Ground
+                }
+            },
+            State::Escape => match c {
+                '[' => State::Csi,
+                ']' => {
+                    in_osc = true;
+                    State::Osc
+                }
+                'P' | 'X' | '^' | '_' => {
+                    in_osc = false;
+                    State::String
+                }
+                ' '..='/' => State::EscapeIntermediate,
+                _ => State::Ground,
+            },
+            State::EscapeIntermediate => match c {
+                ' '..='/' => State::EscapeIntermediate,
+                _ => State::Ground,
+            },
+            State::Csi => match c {
+                '@'..='~' => State::Ground,
+                _ => State::Csi,
+            },
+            State::Osc => match c {
+                '\x07' => State::Ground,
+                '\x1b' => State::StringEscape,
+                _ => State::Osc,
+            },
+            State::String => match c {
+                '\x1b' => State::StringEscape,
+                _ => State::String,
+            },
+            State::StringEscape => match c {
+                '\\' => State::Ground,
+                _ if in_osc => State::Osc,
+                _ => State::String,
+            },
+        };
+    }
+
+    let pending = (state != State::Ground).then_some(seq_start);
+    (out, pending)
+}
+
+/// Reports whether `output` leaves the terminal on the alternate screen.
+///
+/// Scans for the last DEC private mode 47/1047/1049 set or reset sequence.
+/// This is a cheap approximation for backends that don't keep a cell grid.
+pub fn ends_in_alt_screen(output: &str) -> bool {
+    const ENTER: [&str; 3] = ["\x1b[?1049h", "\x1b[?1047h", "\x1b[?47h"];
+    const LEAVE: [&str; 3] = ["\x1b[?1049l", "\x1b[?1047l", "\x1b[?47l"];
+
+    let last = |seqs: &[&str]| seqs.iter().filter_map(|s| output.rfind(s)).max();
+    match (last(&ENTER), last(&LEAVE)) {
+        (Some(enter), Some(leave)) => enter > leave,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_sgr_and_cursor_sequences() {
+        assert_eq!(strip_ansi("\x1b[1;31mred\x1b[0m \x1b[2Kdone"), "red done");
+    }
+
+    #[test]
+    fn strips_extended_colors() {
+        assert_eq!(
+            strip_ansi("\x1b[38;5;208morange\x1b[39m \x1b[48;2;10;20;30mbg\x1b[m"),
+            "orange bg"
+        );
+    }
+
+    #[test]
+    fn strips_cursor_movement_and_private_modes() {
+        assert_eq!(
+            strip_ansi("\x1b[?25l\x1b[10;5Hat\x1b[3A\x1b[2Cup\x1b[J\x1b[?25h"),
+            "atup"
+        );
+    }
+
+    #[test]
+    fn reports_where_an_unterminated_sequence_starts() {
+        assert_eq!(
+            strip_ansi_partial("ok\x1b[1;3"),
+            ("ok".to_string(), Some(2))
+        );
+        assert_eq!(
+            strip_ansi_partial("a\x1b]0;title\x1b"),
+            ("a".to_string(), Some(1))
+        );
+        assert_eq!(strip_ansi_partial("a\x1b[0mb"), ("ab".to_string(), None));
+    }
+
+    #[test]
+    fn strips_osc_title_with_bel_and_st() {
+        assert_eq!(strip_ansi("\x1b]0;title\x07a\x1b]2;t\x1b\\b"), "ab");
+    }
+
+    #[test]
+    fn keeps_newlines_tabs_and_unicode() {
+        assert_eq!(strip_ansi("a\tb\r\n\u{1F600}\x07"), "a\tb\r\n\u{1F600}");
+    }
+
+    #[test]
+    fn strips_charset_designation() {
+        assert_eq!(strip_ansi("\x1b(Bplain"), "plain");
+    }
+
+    #[test]
+    fn detects_alt_screen_from_last_toggle() {
+        assert!(!ends_in_alt_screen("plain"));
+        assert!(ends_in_alt_screen("a\x1b[?1049hvim"));
+        assert!(!ends_in_alt_screen("\x1b[?1049hvim\x1b[?1049lshell"));
+    }
+}
@@ -11,6 +11,7 @@ pub enum PtyErrorCode {
     ResizeFailed,
     KillFailed,
     IdOverflow,
+    RecordingFailed,
 }
 
 /// Structured PTY error with a machine-readable code and human-readable message.
@@ -73,6 +74,14 @@ impl PtyError {
         }
     }
 
+    /// Starting, writing or finishing a session recording failed.
+    pub fn recording_failed(msg: impl Into<String>) -> Self {
+        Self {
+            code: PtyErrorCode::RecordingFailed,
+            message: msg.into(),
+        }
+    }
+
     /// Atomic session ID counter overflowed u32::MAX.
     pub fn id_overflow() -> Self {
         Self {
@@ -81,3 +90,22 @@ impl PtyError {
         }
     }
 }
+
+/// A session's AI CLI is not installed, reported before its shell is spawned.
+///
+/// Serialized with the install hint so the UI can tell the user how to fix
+/// it instead of showing a raw "command not found" from the shell.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliNotFound {
+    pub command: String,
+    pub install_hint: String,
+}
+
+impl fmt::Display for CliNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}' not found. Install with: {}", self.command, self.install_hint)
+    }
+}
+
+impl std::error::Error for CliNotFound {}
@@ -3,31 +3,180 @@
 //! The EventBus sits between Tauri's event system and external consumers
 //! (e.g., WebSocket clients). Backend code emits events both through
 //! `app.emit()` (for desktop) and `EventBus::send()` (for web clients).
+//!
+//! The bus also records which event names it has carried (bounded to
+//! `MAX_KNOWN_EVENT_NAMES`) so clients can discover what to subscribe to,
+//! and keeps the last `REPLAY_BUFFER_SIZE` events so a late subscriber can
+//! catch up on what it just missed.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
 
+use dashmap::DashMap;
+use serde::Serialize;
 use tokio::sync::broadcast;
 use serde_json::Value;
 
+/// Upper bound on distinct event names tracked. Per-session names like
+/// `pty-output-{id}` grow without limit, so the least recently seen name is
+/// evicted once this is reached.
+const MAX_KNOWN_EVENT_NAMES: usize = 256;
+
+/// Events a subscriber may fall behind by before it starts missing them.
+pub const DEFAULT_CHANNEL_CAPACITY: usize = 4096;
+
+/// Number of recent events retained for replay to late subscribers.
+pub const REPLAY_BUFFER_SIZE: usize = 256;
+
 /// A single event carried through the bus.
 #[derive(Clone, Debug)]
 pub struct BusEvent {
     pub event: String,
     pub payload: Value,
+    /// Monotonic sequence number assigned by the bus, starting at 1.
+    pub seq: u64,
+}
+
+/// Whether an event is kept in the replay buffer. PTY output is excluded:
+/// it would evict everything else within seconds, and clients recover
+/// scrollback through `get_session_output_since` instead.
+fn is_replayable(event: &str) -> bool {
+    !event.starts_with("pty-output-")
+}
+
+/// Recent replayable events plus the last assigned sequence number, guarded
+/// together so sequence order always matches broadcast order.
+struct ReplayBuffer {
+    events: VecDeque<BusEvent>,
+    last_seq: u64,
+}
+
+/// Usage statistics for an event name seen on the bus.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KnownEventName {
+    pub name: String,
+    /// Number of events sent under this name since it was first tracked.
+    pub count: u64,
+    /// ISO8601 timestamp of the most recent event.
+    pub last_seen: String,
+}
+
+/// Per-name counters kept alongside the broadcast channel.
+struct EventNameStats {
+    count: u64,
+    last_seen: chrono::DateTime<chrono::Utc>,
 }
 
 /// Broadcast channel that fans out events to all subscribers.
 pub struct EventBus {
     sender: broadcast::Sender<BusEvent>,
+    known_names: DashMap<String, EventNameStats>,
+    recent: Mutex<ReplayBuffer>,
 }
 
 impl EventBus {
     pub fn new() -> Self {
-        let (sender, _) = broadcast::channel(1024);
-        Self { sender }
+        Self::with_capacity(DEFAULT_CHANNEL_CAPACITY)
+    }
+
+    /// A bus whose subscribers lag (and miss events) once `capacity` events
+    /// are queued for them.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            sender,
+            known_names: DashMap::new(),
+            recent: Mutex::new(ReplayBuffer {
+                events: VecDeque::with_capacity(REPLAY_BUFFER_SIZE),
+                last_seq: 0,
+            }),
+        }
     }
 
     /// Send an event to all subscribers. Silently drops if no receivers.
     pub fn send(&self, event: String, payload: Value) {
-        let _ = self.sender.send(BusEvent { event, payload });
+        self.record_name(&event);
+        let Ok(mut recent) = self.recent.lock() else {
+            return;
+        };
+        recent.last_seq += 1;
+        let bus_event = BusEvent {
+            event,
+            payload,
+            seq: recent.last_seq,
+        };
+        if is_replayable(&bus_event.event) {
+            if recent.events.len() >= REPLAY_BUFFER_SIZE {
+                recent.events.pop_front();
+            }
+            recent.events.push_back(bus_event.clone());
+        }
+        // Broadcast while still holding the lock so receivers see events in
+        // sequence order.
+        let _ = self.sender.send(bus_event);
+    }
+
+    /// Returns up to `n` of the most recent buffered events accepted by
+    /// `matches`, oldest first.
+    pub fn recent_events(&self, n: usize, matches: impl Fn(&str) -> bool) -> Vec<BusEvent> {
+        let Ok(recent) = self.recent.lock() else {
+            return Vec::new();
+        };
+        let mut events: Vec<BusEvent> = recent
+            .events
+            .iter()
+            .rev()
+            .filter(|e| matches(&e.event))
+            .take(n)
+            .cloned()
+            .collect();
+        events.reverse();
+        events
+    }
+
+    /// Returns every tracked event name, sorted by name.
+    pub fn known_event_names(&self) -> Vec<KnownEventName> {
+        let mut names: Vec<KnownEventName> = self
+            .known_names
+            .iter()
+            .map(|entry| KnownEventName {
+                name: entry.key().clone(),
+                count: entry.count,
+                last_seen: entry.last_seen.to_rfc3339(),
+            })
+            .collect();
+        names.sort_by(|a, b| a.name.cmp(&b.name));
+        names
+    }
+
+    /// Bumps the stats for `event`, evicting the stalest name if at capacity.
+    fn record_name(&self, event: &str) {
+        let now = chrono::Utc::now();
+        if let Some(mut stats) = self.known_names.get_mut(event) {
+            stats.count += 1;
+            stats.last_seen = now;
+            return;
+        }
+
+        if self.known_names.len() >= MAX_KNOWN_EVENT_NAMES {
+            let stalest = self
+                .known_names
+                .iter()
+                .min_by_key(|entry| entry.last_seen)
+                .map(|entry| entry.key().clone());
+            if let Some(name) = stalest {
+                self.known_names.remove(&name);
+            }
+        }
+
+        self.known_names.insert(
+            event.to_string(),
+            EventNameStats {
+                count: 1,
+                last_seen: now,
+            },
+        );
     }
 
     /// Create a new receiver that will get all future events.
@@ -35,3 +184,66 @@ impl EventBus {
         self.sender.subscribe()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tracks_counts_per_event_name() {
+        let bus = EventBus::new();
+        bus.send("b-event".to_string(), Value::Null);
+        bus.send("a-event".to_string(), Value::Null);
+        bus.send("b-event".to_string(), Value::Null);
+
+        let names = bus.known_event_names();
+        assert_eq!(names.len(), 2);
+        assert_eq!((names[0].name.as_str(), names[0].count), ("a-event", 1));
+        assert_eq!((names[1].name.as_str(), names[1].count), ("b-event", 2));
+    }
+
+    #[test]
+    fn replays_last_matching_events_in_order() {
+        let bus = EventBus::new();
+        for i in 0..5 {
+            bus.send("session-status-changed".to_string(), Value::from(i));
+            bus.send("pty-output-1".to_string(), Value::from("x"));
+            bus.send("other".to_string(), Value::Null);
+        }
+
+        let mut rx = bus.subscribe();
+        let replayed = bus.recent_events(3, |e| e == "session-status-changed");
+        let payloads: Vec<Value> = replayed.iter().map(|e| e.payload.clone()).collect();
+        assert_eq!(payloads, vec![Value::from(2), Value::from(3), Value::from(4)]);
+        assert!(replayed.windows(2).all(|w| w[0].seq < w[1].seq));
+
+        // PTY output is never buffered for replay
+        assert!(bus.recent_events(10, |e| e.starts_with("pty-output-")).is_empty());
+
+        // Live events carry later sequence numbers than anything replayed
+        bus.send("session-status-changed".to_string(), Value::from(5));
+        let live = rx.try_recv().unwrap();
+        assert_eq!(live.payload, Value::from(5));
+        assert!(live.seq > replayed.last().unwrap().seq);
+    }
+
+    #[test]
+    fn replay_buffer_is_bounded() {
+        let bus = EventBus::new();
+        for i in 0..REPLAY_BUFFER_SIZE + 50 {
+            bus.send("status".to_string(), Value::from(i));
+        }
+        let all = bus.recent_events(usize::MAX, |_| true);
+        assert_eq!(all.len(), REPLAY_BUFFER_SIZE);
+        assert_eq!(all[0].payload, Value::from(50));
+    }
+
+    #[test]
+    fn known_names_are_bounded() {
+        let bus = EventBus::new();
+        for i in 0..MAX_KNOWN_EVENT_NAMES + 10 {
+            bus.send(format!("pty-output-{i}"), Value::Null);
+        }
+        assert_eq!(bus.known_event_names().len(), MAX_KNOWN_EVENT_NAMES);
+    }
+}
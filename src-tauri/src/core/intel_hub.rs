@@ -4,18 +4,27 @@
 //! shared between sessions. Provides conflict detection when multiple
 //! sessions edit the same file.
 
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
 
+use super::path_utils::{check_reported_path, normalize_path_buf};
+
 /// Maximum number of broadcast messages to keep in memory.
 const MAX_MESSAGES: usize = 200;
 /// Maximum number of scratchpad entries.
 const MAX_SCRATCHPAD: usize = 50;
 /// File activity entries older than this are pruned on each report.
 const FILE_ACTIVITY_TTL_SECS: i64 = 300; // 5 minutes
+/// A broadcast repeating one from the same session within this window bumps
+/// the earlier entry instead of being appended.
+const BROADCAST_DEDUP_WINDOW_SECS: i64 = 60;
+/// Upper bound on a broadcast's `ttl_secs`.
+const MAX_BROADCAST_TTL_SECS: u64 = 7 * 24 * 60 * 60; // 1 week
 
 /// Maximum size (bytes) for a broadcast message body.
 const MAX_MESSAGE_LEN: usize = 10_000;
@@ -26,8 +35,27 @@ const MAX_CONTENT_LEN: usize = 100_000;
 /// Maximum size (bytes) for a file path.
 const MAX_FILE_PATH_LEN: usize = 4_096;
 
+/// Broadcasts a single session may send per rate window.
+const BROADCAST_RATE_LIMIT: u32 = 10;
+/// Window over which `BROADCAST_RATE_LIMIT` broadcasts refill.
+const BROADCAST_RATE_WINDOW: Duration = Duration::from_secs(10);
+
+/// Maximum number of highlights per section in an intel digest.
+const MAX_DIGEST_HIGHLIGHTS: usize = 5;
+/// Maximum number of conflicted file paths listed in an intel digest.
+const MAX_DIGEST_FILES: usize = 10;
+/// Highlight text is cut to this many characters.
+const DIGEST_EXCERPT_CHARS: usize = 200;
+
+/// Maximum number of results returned by a search.
+const MAX_SEARCH_RESULTS: usize = 20;
+/// Search snippets show about this many characters around the match.
+const SEARCH_SNIPPET_CHARS: usize = 160;
+
 /// Valid broadcast categories.
 const BROADCAST_CATEGORIES: &[&str] = &["discovery", "warning", "knowledge", "info"];
+/// Broadcast categories included in a markdown export; `info` is status chatter.
+const EXPORT_BROADCAST_CATEGORIES: &[&str] = &["discovery", "warning", "knowledge"];
 /// Valid scratchpad categories.
 const SCRATCHPAD_CATEGORIES: &[&str] = &["architecture", "api", "decision", "note"];
 /// Valid file activity actions.
@@ -43,6 +71,43 @@ pub struct BroadcastMessage {
     pub message: String,
     pub metadata: Option<serde_json::Value>,
     pub timestamp: String,
+    /// Project of the sending session; `None` if it wasn't registered.
+    #[serde(default)]
+    pub project_hash: Option<String>,
+    /// Seconds after `timestamp` the message stops being delivered; `None` never expires.
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+    /// How many times the session sent this message within the dedup window.
+    #[serde(default = "default_repeat_count")]
+    pub repeat_count: u32,
+}
+
+fn default_repeat_count() -> u32 {
+    1
+}
+
+impl BroadcastMessage {
+    /// Whether the message's TTL has elapsed at `now`. Messages without a TTL
+    /// or with an unparseable timestamp never expire.
+    fn is_expired(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let Some(ttl) = self.ttl_secs else {
+            return false;
+        };
+        chrono::DateTime::parse_from_rfc3339(&self.timestamp)
+            .map(|ts| (now - ts.with_timezone(&chrono::Utc)).num_seconds() >= ttl as i64)
+            .unwrap_or(false)
+    }
+
+    /// Whether `incoming` repeats this message: same sender, category and
+    /// text, with this one sent within the dedup window of `now`.
+    fn is_repeated_by(&self, incoming: &BroadcastMessage, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.session_id == incoming.session_id
+            && self.category == incoming.category
+            && self.message == incoming.message
+            && chrono::DateTime::parse_from_rfc3339(&self.timestamp)
+                .map(|ts| (now - ts.with_timezone(&chrono::Utc)).num_seconds() < BROADCAST_DEDUP_WINDOW_SECS)
+                .unwrap_or(false)
+    }
 }
 
 /// Tracks a session's file modification activity.
@@ -52,6 +117,9 @@ pub struct FileActivity {
     pub file_path: String,
     pub action: String, // "editing", "created", "deleted"
     pub timestamp: String,
+    /// Project of the reporting session; only activity within one project conflicts.
+    #[serde(default)]
+    pub project_hash: Option<String>,
 }
 
 /// A file conflict detected between sessions.
@@ -60,6 +128,13 @@ pub struct FileConflict {
     pub file_path: String,
     pub sessions: Vec<u32>,
     pub actions: Vec<FileActivity>,
+    /// Every session involved has acknowledged the conflict and nobody else
+    /// has edited the file since.
+    #[serde(default)]
+    pub acknowledged: bool,
+    /// Project the conflicting sessions belong to.
+    #[serde(default)]
+    pub project_hash: Option<String>,
 }
 
 /// A shared scratchpad entry visible to all sessions.
@@ -71,6 +146,9 @@ pub struct ScratchpadEntry {
     pub title: String,
     pub content: String,
     pub timestamp: String,
+    /// Project of the writing session; `None` if it wasn't registered.
+    #[serde(default)]
+    pub project_hash: Option<String>,
 }
 
 /// Request payloads received from MCP servers.
@@ -81,6 +159,8 @@ pub struct BroadcastRequest {
     pub category: String,
     pub message: String,
     pub metadata: Option<serde_json::Value>,
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -91,6 +171,13 @@ pub struct FileActivityRequest {
     pub action: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ConflictAckRequest {
+    pub session_id: u32,
+    pub instance_id: String,
+    pub file_path: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct ScratchpadWriteRequest {
     pub session_id: u32,
@@ -100,11 +187,140 @@ pub struct ScratchpadWriteRequest {
     pub content: String,
 }
 
+/// One page of a newest-first intel listing.
+#[derive(Debug, Clone, Serialize)]
+pub struct IntelPage<T> {
+    pub items: Vec<T>,
+    /// Number of entries across all pages.
+    pub total: usize,
+    pub offset: usize,
+}
+
+impl<T: Clone> IntelPage<T> {
+    /// Page through `entries`, given oldest first, from the newest end.
+    /// Only the entries on the page are cloned.
+    fn newest_first<'a, I>(entries: I, offset: usize, limit: Option<usize>) -> Self
+    where
+        I: DoubleEndedIterator<Item = &'a T> + Clone,
+        T: 'a,
+    {
+        let total = entries.clone().count();
+        let items = entries
+            .rev()
+            .skip(offset)
+            .take(limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
+        Self { items, total, offset }
+    }
+}
+
+/// Which intel stores were cleared, sent with the `intel-cleared` event.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct IntelCleared {
+    pub broadcasts: bool,
+    pub conflicts: bool,
+    pub scratchpad: bool,
+}
+
+/// One entry surfaced in an intel digest, with its text shortened.
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestHighlight {
+    pub session_id: u32,
+    pub category: String,
+    pub text: String,
+    pub timestamp: String,
+}
+
+/// Compact summary of intel activity since a point in time.
+///
+/// Counts cover everything new; highlight and file lists are bounded and
+/// newest first.
+#[derive(Debug, Clone, Serialize)]
+pub struct IntelDigest {
+    pub since: String,
+    pub broadcast_count: usize,
+    pub broadcasts_by_category: BTreeMap<String, usize>,
+    pub broadcast_highlights: Vec<DigestHighlight>,
+    pub conflict_count: usize,
+    pub conflicted_files: Vec<String>,
+    pub scratchpad_count: usize,
+    pub scratchpad_highlights: Vec<DigestHighlight>,
+}
+
+/// Which intel store a search result came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchKind {
+    Broadcast,
+    Scratchpad,
+}
+
+impl std::str::FromStr for SearchKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "broadcast" => Ok(Self::Broadcast),
+            "scratchpad" => Ok(Self::Scratchpad),
+            other => Err(format!("unknown search kind '{}'", other)),
+        }
+    }
+}
+
+/// A broadcast or scratchpad entry matching a search, with a snippet around the match.
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    pub kind: SearchKind,
+    pub id: String,
+    pub session_id: u32,
+    pub category: String,
+    /// Scratchpad title; `None` for broadcasts.
+    pub title: Option<String>,
+    pub snippet: String,
+    pub timestamp: String,
+}
+
 /// Validation error returned when input constraints are violated.
 #[derive(Debug, Clone, Serialize)]
 pub struct IntelValidationError {
     pub field: String,
     pub message: String,
+    /// True when the request was well-formed but the session is sending too fast.
+    pub rate_limited: bool,
+}
+
+/// Per-session token bucket for broadcasts.
+///
+/// Holds up to `BROADCAST_RATE_LIMIT` tokens and refills continuously over
+/// `BROADCAST_RATE_WINDOW`, so short bursts are fine but a tight loop is not.
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn full(now: Instant) -> Self {
+        Self {
+            tokens: BROADCAST_RATE_LIMIT as f64,
+            refilled_at: now,
+        }
+    }
+
+    /// Refills for the time elapsed since the last call, then takes a token if one is available.
+    fn try_take(&mut self, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.refilled_at).as_secs_f64();
+        let rate = BROADCAST_RATE_LIMIT as f64 / BROADCAST_RATE_WINDOW.as_secs_f64();
+        self.tokens = (self.tokens + elapsed * rate).min(BROADCAST_RATE_LIMIT as f64);
+        self.refilled_at = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
 }
 
 impl std::fmt::Display for IntelValidationError {
@@ -113,36 +329,170 @@ impl std::fmt::Display for IntelValidationError {
     }
 }
 
+/// Which entries an intel query sees.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IntelScope {
+    /// Every project; the desktop view and `cross_project` reads.
+    All,
+    /// Entries from sessions of this project hash.
+    Project(String),
+    /// Only this session's own entries, for a caller whose project is unknown.
+    Session(u32),
+    /// Nothing, for a caller that didn't say which session it is.
+    Nothing,
+}
+
+impl IntelScope {
+    /// True if an entry tagged with `project` and written by `session_id` is visible.
+    fn contains(&self, project: Option<&str>, session_id: u32) -> bool {
+        match self {
+            Self::All => true,
+            Self::Project(hash) => project == Some(hash.as_str()),
+            Self::Session(id) => session_id == *id,
+            Self::Nothing => false,
+        }
+    }
+}
+
 /// Central hub for inter-session intelligence data.
+///
+/// Entries are tagged with the project of the session that produced them, so
+/// sessions only see their own project's intel unless they ask for the
+/// cross-project view. Queries take an [`IntelScope`].
 pub struct IntelHub {
     messages: RwLock<Vec<BroadcastMessage>>,
     file_activities: RwLock<HashMap<String, Vec<FileActivity>>>,
+    /// file_path -> session_id -> when that session acknowledged the conflict.
+    conflict_acks: RwLock<HashMap<String, HashMap<u32, chrono::DateTime<chrono::Utc>>>>,
     scratchpad: RwLock<Vec<ScratchpadEntry>>,
+    broadcast_buckets: Mutex<HashMap<u32, TokenBucket>>,
+    /// session_id -> project, mirrored from status server registration.
+    session_projects: RwLock<HashMap<u32, SessionProject>>,
+    /// (file_path, project) -> sessions of the conflict last announced for it,
+    /// so an ongoing conflict is only announced again when someone new joins.
+    announced_conflicts: RwLock<HashMap<ConflictKey, Vec<u32>>>,
+}
+
+/// A file within a project: `(file_path, project_hash)`.
+type ConflictKey = (String, Option<String>);
+
+/// The project a registered session works in.
+struct SessionProject {
+    hash: String,
+    /// Directory the session works in (its worktree, if it has one).
+    /// Reported file paths are checked and resolved against it.
+    root: PathBuf,
 }
 
 impl IntelHub {
     pub fn new() -> Arc<Self> {
         Arc::new(Self {
+            session_projects: RwLock::new(HashMap::new()),
+            announced_conflicts: RwLock::new(HashMap::new()),
             messages: RwLock::new(Vec::new()),
             file_activities: RwLock::new(HashMap::new()),
+            conflict_acks: RwLock::new(HashMap::new()),
             scratchpad: RwLock::new(Vec::new()),
+            broadcast_buckets: Mutex::new(HashMap::new()),
         })
     }
 
+    /// Record which project a session belongs to and the directory it works in.
+    pub async fn set_session_project(&self, session_id: u32, project_hash: String, root: &str) {
+        let project = SessionProject {
+            hash: project_hash,
+            root: PathBuf::from(root),
+        };
+        self.session_projects.write().await.insert(session_id, project);
+    }
+
+    /// Forget a session's project when it is unregistered.
+    pub async fn remove_session_project(&self, session_id: u32) {
+        self.session_projects.write().await.remove(&session_id);
+    }
+
+    /// The project hash a session was registered with, if any.
+    pub async fn project_of(&self, session_id: u32) -> Option<String> {
+        self.session_projects.read().await.get(&session_id).map(|p| p.hash.clone())
+    }
+
+    /// The working directory a session was registered with, if any.
+    async fn project_root_of(&self, session_id: u32) -> Option<PathBuf> {
+        self.session_projects.read().await.get(&session_id).map(|p| p.root.clone())
+    }
+
+    /// The key a reported file is tracked under, so different spellings of one
+    /// file meet: relative paths are resolved against the session's project
+    /// root and `.`/`..` are folded, without touching the filesystem.
+    async fn file_key(&self, session_id: u32, file_path: &str) -> String {
+        let path = Path::new(file_path);
+        let key = match self.project_root_of(session_id).await {
+            _ if path.is_absolute() => normalize_path_buf(path),
+            Some(root) => normalize_path_buf(&root.join(path)),
+            // Unknown root: keep it relative, minus `./` noise.
+            None => path.components().filter(|c| !matches!(c, Component::CurDir)).collect(),
+        };
+        key.to_string_lossy().into_owned()
+    }
+
+    /// Resolve the query scope for a caller: the global view when
+    /// `cross_project` is set, else its own project. Fails closed: a caller
+    /// whose project is unknown only sees its own entries, and an anonymous
+    /// one sees nothing.
+    pub async fn scope_for(&self, session_id: Option<u32>, cross_project: bool) -> IntelScope {
+        if cross_project {
+            return IntelScope::All;
+        }
+        match session_id {
+            Some(id) => match self.project_of(id).await {
+                Some(hash) => IntelScope::Project(hash),
+                None => IntelScope::Session(id),
+            },
+            None => IntelScope::Nothing,
+        }
+    }
+
     /// Validate a broadcast request.
     fn validate_broadcast(req: &BroadcastRequest) -> Result<(), IntelValidationError> {
         if !BROADCAST_CATEGORIES.contains(&req.category.as_str()) {
             return Err(IntelValidationError {
                 field: "category".into(),
                 message: format!("must be one of {:?}", BROADCAST_CATEGORIES),
+                rate_limited: false,
             });
         }
+        if let Some(ttl) = req.ttl_secs {
+            if ttl == 0 || ttl > MAX_BROADCAST_TTL_SECS {
+                return Err(IntelValidationError {
+                    field: "ttl_secs".into(),
+                    message: format!("must be between 1 and {} seconds", MAX_BROADCAST_TTL_SECS),
+                    rate_limited: false,
+                });
+            }
+        }
         if req.message.len() > MAX_MESSAGE_LEN {
             return Err(IntelValidationError {
                 field: "message".into(),
                 message: format!("exceeds max length of {} bytes", MAX_MESSAGE_LEN),
+                rate_limited: false,
             });
         }
+        if let Some(metadata) = &req.metadata {
+            if !metadata.is_object() {
+                return Err(IntelValidationError {
+                    field: "metadata".into(),
+                    message: "must be a JSON object".into(),
+                    rate_limited: false,
+                });
+            }
+            if metadata.to_string().len() > MAX_MESSAGE_LEN {
+                return Err(IntelValidationError {
+                    field: "metadata".into(),
+                    message: format!("exceeds max serialized size of {} bytes", MAX_MESSAGE_LEN),
+                    rate_limited: false,
+                });
+            }
+        }
         Ok(())
     }
 
@@ -152,18 +502,14 @@ impl IntelHub {
             return Err(IntelValidationError {
                 field: "action".into(),
                 message: format!("must be one of {:?}", FILE_ACTIONS),
+                rate_limited: false,
             });
         }
         if req.file_path.len() > MAX_FILE_PATH_LEN {
             return Err(IntelValidationError {
                 field: "file_path".into(),
                 message: format!("exceeds max length of {} bytes", MAX_FILE_PATH_LEN),
-            });
-        }
-        if req.file_path.contains("..") {
-            return Err(IntelValidationError {
-                field: "file_path".into(),
-                message: "path traversal not allowed".into(),
+                rate_limited: false,
             });
         }
         Ok(())
@@ -175,18 +521,21 @@ impl IntelHub {
             return Err(IntelValidationError {
                 field: "category".into(),
                 message: format!("must be one of {:?}", SCRATCHPAD_CATEGORIES),
+                rate_limited: false,
             });
         }
         if req.title.len() > MAX_TITLE_LEN {
             return Err(IntelValidationError {
                 field: "title".into(),
                 message: format!("exceeds max length of {} bytes", MAX_TITLE_LEN),
+                rate_limited: false,
             });
         }
         if req.content.len() > MAX_CONTENT_LEN {
             return Err(IntelValidationError {
                 field: "content".into(),
                 message: format!("exceeds max length of {} bytes", MAX_CONTENT_LEN),
+                rate_limited: false,
             });
         }
         Ok(())
@@ -198,6 +547,7 @@ impl IntelHub {
         req: BroadcastRequest,
     ) -> Result<BroadcastMessage, IntelValidationError> {
         Self::validate_broadcast(&req)?;
+        let project_hash = self.project_of(req.session_id).await;
 
         let msg = BroadcastMessage {
             id: uuid::Uuid::new_v4().to_string(),
@@ -207,9 +557,26 @@ impl IntelHub {
             message: req.message,
             metadata: req.metadata,
             timestamp: chrono::Utc::now().to_rfc3339(),
+            project_hash,
+            ttl_secs: req.ttl_secs,
+            repeat_count: 1,
         };
 
         let mut messages = self.messages.write().await;
+        let now = chrono::Utc::now();
+        messages.retain(|m| !m.is_expired(now));
+        if let Some(pos) = messages.iter().rposition(|m| m.is_repeated_by(&msg, now)) {
+            // Move the bumped entry to the end so the buffer stays in time order.
+            let mut existing = messages.remove(pos);
+            existing.repeat_count += 1;
+            existing.timestamp = msg.timestamp;
+            existing.metadata = msg.metadata;
+            existing.ttl_secs = msg.ttl_secs;
+            messages.push(existing.clone());
+            return Ok(existing);
+        }
+        // Only new messages count against the rate limit; repeats store nothing.
+        self.take_broadcast_token(msg.session_id)?;
         messages.push(msg.clone());
         // Ring buffer: drop oldest if over limit
         if messages.len() > MAX_MESSAGES {
@@ -220,19 +587,54 @@ impl IntelHub {
         Ok(msg)
     }
 
-    /// Get messages for a session (excludes messages sent by that session).
-    pub async fn get_messages_for(&self, session_id: u32) -> Vec<BroadcastMessage> {
+    /// Consume one broadcast token for `session_id`, or fail if it is sending too fast.
+    fn take_broadcast_token(&self, session_id: u32) -> Result<(), IntelValidationError> {
+        let now = Instant::now();
+        let mut buckets = self.broadcast_buckets.lock().unwrap_or_else(|e| e.into_inner());
+        let allowed = buckets
+            .entry(session_id)
+            .or_insert_with(|| TokenBucket::full(now))
+            .try_take(now);
+        if allowed {
+            Ok(())
+        } else {
+            Err(IntelValidationError {
+                field: "session_id".into(),
+                message: format!(
+                    "rate limit of {} broadcasts per {}s exceeded; slow down",
+                    BROADCAST_RATE_LIMIT,
+                    BROADCAST_RATE_WINDOW.as_secs()
+                ),
+                rate_limited: true,
+            })
+        }
+    }
+
+    /// Get unexpired messages for a session (excludes messages sent by that session).
+    pub async fn get_messages_for(&self, session_id: u32, scope: &IntelScope) -> Vec<BroadcastMessage> {
+        let now = chrono::Utc::now();
         let messages = self.messages.read().await;
         messages
             .iter()
-            .filter(|m| m.session_id != session_id)
+            .filter(|m| m.session_id != session_id && scope.contains(m.project_hash.as_deref(), m.session_id))
+            .filter(|m| !m.is_expired(now))
             .cloned()
             .collect()
     }
 
-    /// Get all broadcast messages (for frontend).
+    /// Get all unexpired broadcast messages, oldest first.
     pub async fn get_all_messages(&self) -> Vec<BroadcastMessage> {
-        self.messages.read().await.clone()
+        let now = chrono::Utc::now();
+        let messages = self.messages.read().await;
+        messages.iter().filter(|m| !m.is_expired(now)).cloned().collect()
+    }
+
+    /// Get a newest-first page of unexpired broadcast messages (for frontend).
+    /// A `limit` of `None` returns everything from `offset` on.
+    pub async fn messages_page(&self, offset: usize, limit: Option<usize>) -> IntelPage<BroadcastMessage> {
+        let now = chrono::Utc::now();
+        let messages = self.messages.read().await;
+        IntelPage::newest_first(messages.iter().filter(|m| !m.is_expired(now)), offset, limit)
     }
 
     /// Report file activity and return any conflicts detected.
@@ -241,37 +643,148 @@ impl IntelHub {
         req: FileActivityRequest,
     ) -> Result<Vec<FileConflict>, IntelValidationError> {
         Self::validate_file_activity(&req)?;
+        let root = self.project_root_of(req.session_id).await;
+        check_reported_path(&req.file_path, root.as_deref()).map_err(|message| IntelValidationError {
+            field: "file_path".into(),
+            message,
+            rate_limited: false,
+        })?;
+        let project_hash = self.project_of(req.session_id).await;
+        let file_path = self.file_key(req.session_id, &req.file_path).await;
 
         let activity = FileActivity {
             session_id: req.session_id,
-            file_path: req.file_path.clone(),
+            file_path: file_path.clone(),
             action: req.action,
             timestamp: chrono::Utc::now().to_rfc3339(),
+            project_hash: project_hash.clone(),
         };
 
         let mut activities = self.file_activities.write().await;
 
         // Prune old entries for this file
         let now = chrono::Utc::now();
-        Self::prune_old_entries(&mut activities, &req.file_path, now);
+        Self::prune_old_entries(&mut activities, &file_path, now);
 
         // Add the new activity
         activities
-            .entry(req.file_path.clone())
+            .entry(file_path.clone())
             .or_default()
             .push(activity);
 
-        // Detect conflicts: multiple sessions editing the same file
+        // Detect conflicts: multiple sessions of the same project editing the same file
         let mut conflicts = Vec::new();
-        if let Some(entries) = activities.get(&req.file_path) {
-            if let Some(conflict) = Self::detect_conflict(req.file_path, entries.clone()) {
-                conflicts.push(conflict);
+        if let Some(entries) = activities.get(&file_path) {
+            let acks = self.conflict_acks.read().await;
+            let file_acks = acks.get(&file_path);
+            let same_project = same_project_entries(entries, project_hash.as_deref());
+            match Self::detect_conflict(file_path.clone(), same_project) {
+                Some(mut conflict) => {
+                    // The reporter already acknowledged this conflict and nobody else has edited since
+                    let reporter_acked = file_acks
+                        .and_then(|a| a.get(&req.session_id))
+                        .is_some_and(|at| Self::ack_holds(&conflict.actions, req.session_id, *at));
+                    if !reporter_acked {
+                        conflict.acknowledged = Self::is_acknowledged(&conflict, file_acks);
+                        conflicts.push(conflict);
+                    }
+                }
+                None => {
+                    // Resolved (or never was); a future conflict here is news again
+                    self.announced_conflicts
+                        .write()
+                        .await
+                        .remove(&(file_path.clone(), project_hash.clone()));
+                }
             }
         }
 
         Ok(conflicts)
     }
 
+    /// Of the conflicts just returned by `report_file`, those not announced
+    /// before: new files in conflict, or a session joining an existing one.
+    /// Records them so the same ongoing conflict isn't announced on every edit.
+    pub async fn announce_new_conflicts(&self, conflicts: &[FileConflict]) -> Vec<FileConflict> {
+        let mut announced = self.announced_conflicts.write().await;
+        conflicts
+            .iter()
+            .filter(|conflict| {
+                let key = (conflict.file_path.clone(), conflict.project_hash.clone());
+                let seen = announced.get(&key);
+                let is_new = seen.is_none_or(|seen| conflict.sessions.iter().any(|s| !seen.contains(s)));
+                if is_new {
+                    announced.insert(key, conflict.sessions.clone());
+                }
+                is_new
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Acknowledge a conflict on `file_path` on behalf of `session_id`.
+    ///
+    /// Suppresses the conflict warning for this session until another
+    /// session edits the file again.
+    pub async fn acknowledge_conflict(
+        &self,
+        file_path: &str,
+        session_id: u32,
+    ) -> Result<(), IntelValidationError> {
+        let project_hash = self.project_of(session_id).await;
+        let file_path = self.file_key(session_id, file_path).await;
+        let activities = self.file_activities.read().await;
+        let involved = activities
+            .get(&file_path)
+            .map(|entries| same_project_entries(entries, project_hash.as_deref()))
+            .and_then(|entries| Self::detect_conflict(file_path.clone(), entries))
+            .is_some_and(|c| c.sessions.contains(&session_id));
+        if !involved {
+            return Err(IntelValidationError {
+                field: "file_path".into(),
+                message: "no active conflict involving this session".into(),
+                rate_limited: false,
+            });
+        }
+
+        self.conflict_acks
+            .write()
+            .await
+            .entry(file_path)
+            .or_default()
+            .insert(session_id, chrono::Utc::now());
+        Ok(())
+    }
+
+    /// True if no other session has touched the file since `acked_at`.
+    fn ack_holds(
+        actions: &[FileActivity],
+        session_id: u32,
+        acked_at: chrono::DateTime<chrono::Utc>,
+    ) -> bool {
+        !actions.iter().any(|a| {
+            a.session_id != session_id
+                && chrono::DateTime::parse_from_rfc3339(&a.timestamp)
+                    .map(|ts| ts.with_timezone(&chrono::Utc) > acked_at)
+                    .unwrap_or(false)
+        })
+    }
+
+    /// True if every session in the conflict holds a still-valid ack.
+    fn is_acknowledged(
+        conflict: &FileConflict,
+        file_acks: Option<&HashMap<u32, chrono::DateTime<chrono::Utc>>>,
+    ) -> bool {
+        let Some(file_acks) = file_acks else {
+            return false;
+        };
+        conflict.sessions.iter().all(|session_id| {
+            file_acks
+                .get(session_id)
+                .is_some_and(|at| Self::ack_holds(&conflict.actions, *session_id, *at))
+        })
+    }
+
     /// Prune file activity entries older than the TTL.
     /// Entries with unparseable timestamps are kept (and logged) to avoid silent data loss.
     fn prune_old_entries(
@@ -293,6 +806,7 @@ impl IntelHub {
     }
 
     /// Detect a file conflict when multiple sessions are editing the same file.
+    /// `entries` must all belong to one project.
     fn detect_conflict(file_path: String, entries: Vec<FileActivity>) -> Option<FileConflict> {
         let mut session_ids: Vec<u32> = entries.iter().map(|e| e.session_id).collect();
         session_ids.sort();
@@ -302,16 +816,60 @@ impl IntelHub {
             Some(FileConflict {
                 file_path,
                 sessions: session_ids,
+                project_hash: entries.first().and_then(|e| e.project_hash.clone()),
                 actions: entries,
+                acknowledged: false,
             })
         } else {
             None
         }
     }
 
-    /// Get all current file conflicts.
-    pub async fn get_all_conflicts(&self) -> Vec<FileConflict> {
+    /// Number of broadcast messages currently held.
+    pub async fn broadcast_count(&self) -> usize {
+        self.messages.read().await.len()
+    }
+
+    /// Number of scratchpad entries currently held.
+    pub async fn scratchpad_count(&self) -> usize {
+        self.scratchpad.read().await.len()
+    }
+
+    /// Number of current conflicts across all projects, counted the same way
+    /// as `get_all_conflicts` but without cloning any activity.
+    pub async fn active_conflict_count(&self) -> usize {
+        let activities = self.file_activities.read().await;
+        let now = chrono::Utc::now();
+        activities
+            .values()
+            .map(|entries| {
+                let mut first_session: HashMap<Option<&str>, u32> = HashMap::new();
+                let mut conflicted: Vec<Option<&str>> = Vec::new();
+                let recent = entries.iter().filter(|e| {
+                    chrono::DateTime::parse_from_rfc3339(&e.timestamp)
+                        .map(|ts| {
+                            (now - ts.with_timezone(&chrono::Utc)).num_seconds()
+                                < FILE_ACTIVITY_TTL_SECS
+                        })
+                        .unwrap_or(true)
+                });
+                for e in recent {
+                    let project = e.project_hash.as_deref();
+                    let first = *first_session.entry(project).or_insert(e.session_id);
+                    if first != e.session_id && !conflicted.contains(&project) {
+                        conflicted.push(project);
+                    }
+                }
+                conflicted.len()
+            })
+            .sum()
+    }
+
+    /// Get current file conflicts within `scope`.
+    /// A file edited in two projects yields one conflict per project at most.
+    pub async fn get_all_conflicts(&self, scope: &IntelScope) -> Vec<FileConflict> {
         let activities = self.file_activities.read().await;
+        let acks = self.conflict_acks.read().await;
         let now = chrono::Utc::now();
         let mut conflicts = Vec::new();
 
@@ -331,8 +889,17 @@ impl IntelHub {
                 .cloned()
                 .collect();
 
-            if let Some(conflict) = Self::detect_conflict(file_path.clone(), recent) {
-                conflicts.push(conflict);
+            let mut by_project: BTreeMap<Option<String>, Vec<FileActivity>> = BTreeMap::new();
+            for activity in recent {
+                if scope.contains(activity.project_hash.as_deref(), activity.session_id) {
+                    by_project.entry(activity.project_hash.clone()).or_default().push(activity);
+                }
+            }
+            for entries in by_project.into_values() {
+                if let Some(mut conflict) = Self::detect_conflict(file_path.clone(), entries) {
+                    conflict.acknowledged = Self::is_acknowledged(&conflict, acks.get(file_path));
+                    conflicts.push(conflict);
+                }
             }
         }
 
@@ -345,6 +912,7 @@ impl IntelHub {
         req: ScratchpadWriteRequest,
     ) -> Result<ScratchpadEntry, IntelValidationError> {
         Self::validate_scratchpad(&req)?;
+        let project_hash = self.project_of(req.session_id).await;
 
         let entry = ScratchpadEntry {
             id: uuid::Uuid::new_v4().to_string(),
@@ -353,6 +921,7 @@ impl IntelHub {
             title: req.title,
             content: req.content,
             timestamp: chrono::Utc::now().to_rfc3339(),
+            project_hash,
         };
 
         let mut scratchpad = self.scratchpad.write().await;
@@ -365,13 +934,871 @@ impl IntelHub {
         Ok(entry)
     }
 
-    /// Read all scratchpad entries.
-    pub async fn read_scratchpad(&self) -> Vec<ScratchpadEntry> {
-        self.scratchpad.read().await.clone()
+    /// Read the scratchpad entries within `scope`, oldest first.
+    pub async fn read_scratchpad(&self, scope: &IntelScope) -> Vec<ScratchpadEntry> {
+        self.scratchpad
+            .read()
+            .await
+            .iter()
+            .filter(|e| scope.contains(e.project_hash.as_deref(), e.session_id))
+            .cloned()
+            .collect()
+    }
+
+    /// Read a newest-first page of the scratchpad entries within `scope`.
+    /// A `limit` of `None` returns everything from `offset` on.
+    pub async fn scratchpad_page(
+        &self,
+        scope: &IntelScope,
+        offset: usize,
+        limit: Option<usize>,
+    ) -> IntelPage<ScratchpadEntry> {
+        let scratchpad = self.scratchpad.read().await;
+        let visible = scratchpad
+            .iter()
+            .filter(|e| scope.contains(e.project_hash.as_deref(), e.session_id));
+        IntelPage::newest_first(visible, offset, limit)
     }
 
     /// Clear all scratchpad entries.
     pub async fn clear_scratchpad(&self) {
         self.scratchpad.write().await.clear();
     }
+
+    /// Clear all broadcast messages.
+    pub async fn clear_messages(&self) {
+        self.messages.write().await.clear();
+    }
+
+    /// Clear tracked file activity, and with it every conflict and acknowledgement.
+    pub async fn clear_file_activity(&self) {
+        self.file_activities.write().await.clear();
+        self.conflict_acks.write().await.clear();
+        self.announced_conflicts.write().await.clear();
+    }
+
+    /// Summarize broadcasts, conflicts and scratchpad entries newer than `since`.
+    ///
+    /// A conflict counts as new if any of its file activities happened after
+    /// `since`. Entries with unparseable timestamps are left out.
+    pub async fn digest_since(&self, since: chrono::DateTime<chrono::Utc>) -> IntelDigest {
+        let is_new = |timestamp: &str| {
+            chrono::DateTime::parse_from_rfc3339(timestamp)
+                .map(|ts| ts.with_timezone(&chrono::Utc) > since)
+                .unwrap_or(false)
+        };
+
+        let broadcasts: Vec<BroadcastMessage> = self
+            .messages
+            .read()
+            .await
+            .iter()
+            .filter(|m| is_new(&m.timestamp) && !m.is_expired(chrono::Utc::now()))
+            .cloned()
+            .collect();
+        let mut broadcasts_by_category = BTreeMap::new();
+        for msg in &broadcasts {
+            *broadcasts_by_category.entry(msg.category.clone()).or_insert(0) += 1;
+        }
+        let broadcast_highlights = broadcasts
+            .iter()
+            .rev()
+            .take(MAX_DIGEST_HIGHLIGHTS)
+            .map(|m| DigestHighlight {
+                session_id: m.session_id,
+                category: m.category.clone(),
+                text: excerpt(&m.message),
+                timestamp: m.timestamp.clone(),
+            })
+            .collect();
+
+        let mut conflicts: Vec<(String, String)> = self
+            .get_all_conflicts(&IntelScope::All)
+            .await
+            .into_iter()
+            .filter_map(|c| {
+                let latest = c
+                    .actions
+                    .iter()
+                    .filter(|a| is_new(&a.timestamp))
+                    .map(|a| a.timestamp.clone())
+                    .max()?;
+                Some((latest, c.file_path))
+            })
+            .collect();
+        conflicts.sort_by(|a, b| b.0.cmp(&a.0));
+        let conflict_count = conflicts.len();
+        let conflicted_files = conflicts
+            .into_iter()
+            .take(MAX_DIGEST_FILES)
+            .map(|(_, path)| path)
+            .collect();
+
+        let scratchpad = self.scratchpad.read().await;
+        let new_entries: Vec<&ScratchpadEntry> =
+            scratchpad.iter().filter(|e| is_new(&e.timestamp)).collect();
+        let scratchpad_highlights = new_entries
+            .iter()
+            .rev()
+            .take(MAX_DIGEST_HIGHLIGHTS)
+            .map(|e| DigestHighlight {
+                session_id: e.session_id,
+                category: e.category.clone(),
+                text: excerpt(&e.title),
+                timestamp: e.timestamp.clone(),
+            })
+            .collect();
+
+        IntelDigest {
+            since: since.to_rfc3339(),
+            broadcast_count: broadcasts.len(),
+            broadcasts_by_category,
+            broadcast_highlights,
+            conflict_count,
+            conflicted_files,
+            scratchpad_count: new_entries.len(),
+            scratchpad_highlights,
+        }
+    }
+
+    /// Case-insensitive search over broadcasts and scratchpad entries.
+    ///
+    /// A query wrapped in double quotes must appear as a phrase; otherwise an
+    /// entry matches if it contains any of the query's words. `kinds` limits
+    /// which stores are searched (empty means all) and `scope` which project.
+    /// Results are newest first and capped at `MAX_SEARCH_RESULTS`.
+    pub async fn search(
+        &self,
+        query: &str,
+        kinds: &[SearchKind],
+        scope: &IntelScope,
+    ) -> Vec<SearchResult> {
+        let terms = parse_search_terms(query);
+        if terms.is_empty() {
+            return Vec::new();
+        }
+        let wants = |kind| kinds.is_empty() || kinds.contains(&kind);
+
+        let mut results = Vec::new();
+        if wants(SearchKind::Broadcast) {
+            let now = chrono::Utc::now();
+            for msg in self.messages.read().await.iter() {
+                if !scope.contains(msg.project_hash.as_deref(), msg.session_id) || msg.is_expired(now) {
+                    continue;
+                }
+                if let Some(snippet) = match_snippet(&msg.message, &terms) {
+                    results.push(SearchResult {
+                        kind: SearchKind::Broadcast,
+                        id: msg.id.clone(),
+                        session_id: msg.session_id,
+                        category: msg.category.clone(),
+                        title: None,
+                        snippet,
+                        timestamp: msg.timestamp.clone(),
+                    });
+                }
+            }
+        }
+        if wants(SearchKind::Scratchpad) {
+            for entry in self.scratchpad.read().await.iter() {
+                if !scope.contains(entry.project_hash.as_deref(), entry.session_id) {
+                    continue;
+                }
+                let snippet = match_snippet(&entry.content, &terms)
+                    .or_else(|| match_snippet(&entry.title, &terms));
+                if let Some(snippet) = snippet {
+                    results.push(SearchResult {
+                        kind: SearchKind::Scratchpad,
+                        id: entry.id.clone(),
+                        session_id: entry.session_id,
+                        category: entry.category.clone(),
+                        title: Some(entry.title.clone()),
+                        snippet,
+                        timestamp: entry.timestamp.clone(),
+                    });
+                }
+            }
+        }
+
+        // RFC 3339 timestamps from the same clock sort lexically
+        results.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        results.truncate(MAX_SEARCH_RESULTS);
+        results
+    }
+
+    /// Render the scratchpad, grouped by category, as a markdown document.
+    ///
+    /// With `include_broadcasts`, unexpired discovery/warning/knowledge
+    /// broadcasts are appended in the order they were sent.
+    pub async fn export_markdown(&self, include_broadcasts: bool) -> String {
+        let mut out = String::from("# Chorus Intel\n\n");
+        out.push_str(&format!("_Exported {}_\n\n", chrono::Utc::now().to_rfc3339()));
+
+        out.push_str("## Scratchpad\n\n");
+        let scratchpad = self.scratchpad.read().await;
+        if scratchpad.is_empty() {
+            out.push_str("_No notes._\n\n");
+        }
+        for category in SCRATCHPAD_CATEGORIES {
+            let entries: Vec<&ScratchpadEntry> =
+                scratchpad.iter().filter(|e| e.category == *category).collect();
+            if entries.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("### {}\n\n", capitalize(category)));
+            for entry in entries {
+                out.push_str(&format!("#### {}\n\n", entry.title));
+                out.push_str(&format!(
+                    "_{}, {}_\n\n",
+                    session_label(entry.session_id),
+                    entry.timestamp
+                ));
+                out.push_str(entry.content.trim_end());
+                out.push_str("\n\n");
+            }
+        }
+        drop(scratchpad);
+
+        if include_broadcasts {
+            out.push_str("## Broadcasts\n\n");
+            let now = chrono::Utc::now();
+            let messages = self.messages.read().await;
+            let notable: Vec<&BroadcastMessage> = messages
+                .iter()
+                .filter(|m| EXPORT_BROADCAST_CATEGORIES.contains(&m.category.as_str()))
+                .filter(|m| !m.is_expired(now))
+                .collect();
+            if notable.is_empty() {
+                out.push_str("_No notable broadcasts._\n\n");
+            }
+            for msg in notable {
+                let mut line = format!(
+                    "- **{}**: {} ({}, {}",
+                    msg.category,
+                    msg.message.trim(),
+                    session_label(msg.session_id),
+                    msg.timestamp
+                );
+                if msg.repeat_count > 1 {
+                    line.push_str(&format!(", repeated {}x", msg.repeat_count));
+                }
+                line.push_str(")\n");
+                out.push_str(&line);
+            }
+        }
+
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+        out
+    }
+}
+
+/// Attribution for exported entries; session 0 is the desktop app itself.
+fn session_label(session_id: u32) -> String {
+    if session_id == 0 {
+        "Desktop".to_string()
+    } else {
+        format!("Session #{}", session_id)
+    }
+}
+
+/// Upper-cases the first character of a category name for headings.
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+
+/// The entries recorded by sessions of `project`, for per-project conflict detection.
+fn same_project_entries(entries: &[FileActivity], project: Option<&str>) -> Vec<FileActivity> {
+    entries
+        .iter()
+        .filter(|e| e.project_hash.as_deref() == project)
+        .cloned()
+        .collect()
+}
+
+/// Splits a search query into lowercase terms.
+///
+/// `"exact phrase"` yields the phrase as a single term; anything else yields
+/// one term per word.
+fn parse_search_terms(query: &str) -> Vec<String> {
+    let query = query.trim();
+    if let Some(phrase) = query
+        .strip_prefix('"')
+        .and_then(|q| q.strip_suffix('"'))
+        .map(str::trim)
+    {
+        if phrase.is_empty() {
+            return Vec::new();
+        }
+        return vec![phrase.to_lowercase()];
+    }
+    query.split_whitespace().map(str::to_lowercase).collect()
+}
+
+/// Returns a snippet around the first place any of `terms` occurs in `text`.
+fn match_snippet(text: &str, terms: &[String]) -> Option<String> {
+    // Lowercase char by char so match offsets map back to original characters
+    let mut lowered = String::with_capacity(text.len());
+    let mut starts = Vec::with_capacity(text.len());
+    for c in text.chars() {
+        starts.push(lowered.len());
+        lowered.extend(c.to_lowercase());
+    }
+
+    let byte_pos = terms.iter().filter_map(|t| lowered.find(t.as_str())).min()?;
+    let char_pos = starts.partition_point(|&start| start <= byte_pos) - 1;
+
+    let total = starts.len();
+    let begin = char_pos.saturating_sub(SEARCH_SNIPPET_CHARS / 4);
+    let end = (begin + SEARCH_SNIPPET_CHARS).min(total);
+    let mut snippet = String::new();
+    if begin > 0 {
+        snippet.push('…');
+    }
+    snippet.extend(text.chars().skip(begin).take(end - begin));
+    if end < total {
+        snippet.push('…');
+    }
+    Some(snippet)
+}
+
+/// Shortens `text` to `DIGEST_EXCERPT_CHARS` characters, marking the cut.
+fn excerpt(text: &str) -> String {
+    if text.chars().count() <= DIGEST_EXCERPT_CHARS {
+        return text.to_string();
+    }
+    let mut short: String = text.chars().take(DIGEST_EXCERPT_CHARS - 1).collect();
+    short.push('…');
+    short
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn broadcast(category: &str, message: &str) -> BroadcastRequest {
+        BroadcastRequest {
+            session_id: 1,
+            instance_id: "test".to_string(),
+            category: category.to_string(),
+            message: message.to_string(),
+            metadata: None,
+            ttl_secs: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn digest_counts_only_entries_after_since() {
+        let hub = IntelHub::new();
+        hub.add_broadcast(broadcast("info", "old")).await.unwrap();
+        let since = chrono::Utc::now();
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+
+        hub.add_broadcast(broadcast("warning", "careful")).await.unwrap();
+        hub.add_broadcast(broadcast("warning", &"x".repeat(500))).await.unwrap();
+        hub.add_broadcast(broadcast("discovery", "found it")).await.unwrap();
+        for session_id in [1, 2] {
+            hub.report_file(FileActivityRequest {
+                session_id,
+                instance_id: "test".to_string(),
+                file_path: "src/lib.rs".to_string(),
+                action: "editing".to_string(),
+            })
+            .await
+            .unwrap();
+        }
+
+        let digest = hub.digest_since(since).await;
+        assert_eq!(digest.broadcast_count, 3);
+        assert_eq!(digest.broadcasts_by_category["warning"], 2);
+        assert_eq!(digest.broadcasts_by_category["discovery"], 1);
+        assert!(!digest.broadcasts_by_category.contains_key("info"));
+        assert_eq!(digest.broadcast_highlights[0].text, "found it");
+        assert_eq!(digest.broadcast_highlights[1].text.chars().count(), DIGEST_EXCERPT_CHARS);
+        assert_eq!(digest.conflict_count, 1);
+        assert_eq!(digest.conflicted_files, vec!["src/lib.rs"]);
+        assert_eq!(digest.scratchpad_count, 0);
+    }
+
+    #[tokio::test]
+    async fn counts_match_stored_entries() {
+        let hub = IntelHub::new();
+        hub.add_broadcast(broadcast("info", "one")).await.unwrap();
+        hub.add_broadcast(broadcast("info", "two")).await.unwrap();
+        for (session_id, file_path) in [(1, "a.rs"), (1, "a.rs"), (1, "b.rs"), (2, "b.rs")] {
+            hub.report_file(FileActivityRequest {
+                session_id,
+                instance_id: "test".to_string(),
+                file_path: file_path.to_string(),
+                action: "editing".to_string(),
+            })
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(hub.broadcast_count().await, 2);
+        assert_eq!(hub.scratchpad_count().await, 0);
+        assert_eq!(hub.active_conflict_count().await, 1);
+        assert_eq!(hub.get_all_conflicts(&IntelScope::All).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn digest_highlights_are_bounded() {
+        let hub = IntelHub::new();
+        let since = chrono::Utc::now() - chrono::Duration::seconds(1);
+        for i in 0..(MAX_DIGEST_HIGHLIGHTS + 3) {
+            hub.add_broadcast(broadcast("info", &format!("msg {}", i))).await.unwrap();
+        }
+
+        let digest = hub.digest_since(since).await;
+        assert_eq!(digest.broadcast_count, MAX_DIGEST_HIGHLIGHTS + 3);
+        assert_eq!(digest.broadcast_highlights.len(), MAX_DIGEST_HIGHLIGHTS);
+    }
+
+    #[tokio::test]
+    async fn rapid_broadcasts_are_rate_limited_per_session() {
+        let hub = IntelHub::new();
+        for i in 0..BROADCAST_RATE_LIMIT {
+            hub.add_broadcast(broadcast("info", &format!("msg {}", i))).await.unwrap();
+        }
+
+        let err = hub.add_broadcast(broadcast("info", "one too many")).await.unwrap_err();
+        assert!(err.rate_limited);
+        assert_eq!(hub.broadcast_count().await, BROADCAST_RATE_LIMIT as usize);
+
+        // Invalid requests are reported as such, not as rate limited
+        let err = hub.add_broadcast(broadcast("bogus", "x")).await.unwrap_err();
+        assert!(!err.rate_limited);
+
+        // Other sessions have their own bucket
+        let mut other = broadcast("info", "from session 2");
+        other.session_id = 2;
+        hub.add_broadcast(other).await.unwrap();
+    }
+
+    #[test]
+    fn token_bucket_refills_over_the_window() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::full(start);
+        for _ in 0..BROADCAST_RATE_LIMIT {
+            assert!(bucket.try_take(start));
+        }
+        assert!(!bucket.try_take(start));
+
+        let one_token = BROADCAST_RATE_WINDOW / BROADCAST_RATE_LIMIT;
+        assert!(bucket.try_take(start + one_token));
+        assert!(!bucket.try_take(start + one_token));
+        assert!(bucket.try_take(start + one_token + BROADCAST_RATE_WINDOW));
+    }
+
+    #[tokio::test]
+    async fn search_matches_words_or_quoted_phrase() {
+        let hub = IntelHub::new();
+        hub.add_broadcast(broadcast("discovery", "The Auth middleware caches tokens"))
+            .await
+            .unwrap();
+        hub.add_broadcast(broadcast("info", "tokens are cached per user")).await.unwrap();
+        hub.write_scratchpad(ScratchpadWriteRequest {
+            session_id: 2,
+            instance_id: "test".to_string(),
+            category: "api".to_string(),
+            title: "Middleware contract".to_string(),
+            content: "Handlers must not touch the session store".to_string(),
+        })
+        .await
+        .unwrap();
+
+        let any = hub.search("MIDDLEWARE user", &[], &IntelScope::All).await;
+        assert_eq!(any.len(), 3);
+
+        let phrase = hub.search("\"auth middleware\"", &[], &IntelScope::All).await;
+        assert_eq!(phrase.len(), 1);
+        assert_eq!(phrase[0].kind, SearchKind::Broadcast);
+        assert_eq!(phrase[0].snippet, "The Auth middleware caches tokens");
+
+        let scratch = hub.search("middleware", &[SearchKind::Scratchpad], &IntelScope::All).await;
+        assert_eq!(scratch.len(), 1);
+        assert_eq!(scratch[0].title.as_deref(), Some("Middleware contract"));
+        assert_eq!(scratch[0].snippet, "Middleware contract");
+
+        assert!(hub.search("  ", &[], &IntelScope::All).await.is_empty());
+        assert!(hub.search("\"\"", &[], &IntelScope::All).await.is_empty());
+    }
+
+    #[test]
+    fn search_snippet_is_centered_on_match_and_bounded() {
+        let text = format!("{}needle{}", "ä".repeat(500), "z".repeat(500));
+        let snippet = match_snippet(&text, &["NEEDLE".to_lowercase()]).unwrap();
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(snippet.contains("needle"));
+        assert_eq!(snippet.chars().count(), SEARCH_SNIPPET_CHARS + 2);
+    }
+
+    #[tokio::test]
+    async fn pages_are_newest_first_with_bounded_offsets() {
+        let hub = IntelHub::new();
+        for i in 0..5 {
+            let req = BroadcastRequest { session_id: i + 1, ..broadcast("info", &format!("msg {}", i)) };
+            hub.add_broadcast(req).await.unwrap();
+        }
+        let texts = |page: IntelPage<BroadcastMessage>| -> Vec<String> {
+            page.items.into_iter().map(|m| m.message).collect()
+        };
+
+        let all = hub.messages_page(0, None).await;
+        assert_eq!(all.total, 5);
+        assert_eq!(texts(all), ["msg 4", "msg 3", "msg 2", "msg 1", "msg 0"]);
+        assert_eq!(texts(hub.messages_page(0, Some(2)).await), ["msg 4", "msg 3"]);
+        assert_eq!(texts(hub.messages_page(4, Some(2)).await), ["msg 0"]);
+
+        let past_end = hub.messages_page(5, Some(2)).await;
+        assert!(past_end.items.is_empty());
+        assert_eq!(past_end.total, 5);
+        assert!(hub.messages_page(usize::MAX, None).await.items.is_empty());
+        assert!(hub.messages_page(0, Some(0)).await.items.is_empty());
+
+        let page = hub.scratchpad_page(&IntelScope::All, 0, Some(10)).await;
+        assert_eq!((page.items.len(), page.total), (0, 0));
+    }
+
+    #[tokio::test]
+    async fn scratchpad_reads_stay_oldest_first_while_pages_are_newest_first() {
+        let hub = IntelHub::new();
+        for title in ["first", "second", "third"] {
+            hub.write_scratchpad(ScratchpadWriteRequest {
+                session_id: 2,
+                instance_id: "test".to_string(),
+                category: "api".to_string(),
+                title: title.to_string(),
+                content: format!("{} note", title),
+            })
+            .await
+            .unwrap();
+        }
+
+        let titles = |entries: Vec<ScratchpadEntry>| -> Vec<String> { entries.into_iter().map(|e| e.title).collect() };
+        assert_eq!(titles(hub.read_scratchpad(&IntelScope::All).await), ["first", "second", "third"]);
+        let page = hub.scratchpad_page(&IntelScope::All, 1, Some(1)).await;
+        assert_eq!(page.total, 3);
+        assert_eq!(titles(page.items), ["second"]);
+    }
+
+    #[tokio::test]
+    async fn clearing_broadcasts_and_file_activity() {
+        let hub = IntelHub::new();
+        hub.clear_messages().await;
+        hub.clear_file_activity().await;
+
+        hub.add_broadcast(broadcast("info", "hello")).await.unwrap();
+        for session_id in [1, 2] {
+            hub.report_file(FileActivityRequest {
+                session_id,
+                instance_id: "test".to_string(),
+                file_path: "src/main.rs".to_string(),
+                action: "editing".to_string(),
+            })
+            .await
+            .unwrap();
+        }
+        assert_eq!(hub.active_conflict_count().await, 1);
+
+        hub.clear_messages().await;
+        assert_eq!(hub.broadcast_count().await, 0);
+        hub.clear_file_activity().await;
+        assert!(hub.get_all_conflicts(&IntelScope::All).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn markdown_export_groups_scratchpad_and_optionally_broadcasts() {
+        let hub = IntelHub::new();
+        let note = |session_id, category: &str, title: &str| ScratchpadWriteRequest {
+            session_id,
+            instance_id: "test".to_string(),
+            category: category.to_string(),
+            title: title.to_string(),
+            content: format!("{} details", title),
+        };
+        hub.write_scratchpad(note(2, "decision", "Use sqlite")).await.unwrap();
+        hub.write_scratchpad(note(0, "architecture", "Layering")).await.unwrap();
+        hub.add_broadcast(broadcast("warning", "flaky login test")).await.unwrap();
+        hub.add_broadcast(broadcast("info", "starting work")).await.unwrap();
+
+        let md = hub.export_markdown(true).await;
+        let arch = md.find("### Architecture").unwrap();
+        let decision = md.find("### Decision").unwrap();
+        assert!(arch < decision, "categories follow the canonical order");
+        assert!(md.contains("#### Layering\n\n_Desktop, "));
+        assert!(md.contains("#### Use sqlite\n\n_Session #2, "));
+        assert!(md.contains("Use sqlite details"));
+        assert!(md.contains("- **warning**: flaky login test (Session #1, "));
+        assert!(!md.contains("starting work"));
+
+        let scratchpad_only = hub.export_markdown(false).await;
+        assert!(!scratchpad_only.contains("## Broadcasts"));
+        assert!(scratchpad_only.ends_with("Use sqlite details\n"));
+    }
+
+    #[tokio::test]
+    async fn repeated_broadcasts_are_collapsed_within_window() {
+        let hub = IntelHub::new();
+        let first = hub.add_broadcast(broadcast("discovery", "cache is stale")).await.unwrap();
+        let again = hub.add_broadcast(broadcast("discovery", "cache is stale")).await.unwrap();
+        assert_eq!(again.id, first.id);
+        assert_eq!(again.repeat_count, 2);
+        assert_eq!(hub.broadcast_count().await, 1);
+
+        // Other senders, categories and texts are distinct.
+        let other_session = BroadcastRequest { session_id: 2, ..broadcast("discovery", "cache is stale") };
+        hub.add_broadcast(other_session).await.unwrap();
+        hub.add_broadcast(broadcast("warning", "cache is stale")).await.unwrap();
+        assert_eq!(hub.broadcast_count().await, 3);
+
+        // Outside the window the message is appended again.
+        let past = (chrono::Utc::now() - chrono::Duration::seconds(BROADCAST_DEDUP_WINDOW_SECS)).to_rfc3339();
+        for msg in hub.messages.write().await.iter_mut() {
+            msg.timestamp = past.clone();
+        }
+        let fresh = hub.add_broadcast(broadcast("discovery", "cache is stale")).await.unwrap();
+        assert_ne!(fresh.id, first.id);
+        assert_eq!(fresh.repeat_count, 1);
+        assert_eq!(hub.broadcast_count().await, 4);
+    }
+
+    #[tokio::test]
+    async fn repeats_do_not_use_up_the_rate_limit() {
+        let hub = IntelHub::new();
+        for _ in 0..BROADCAST_RATE_LIMIT * 2 {
+            hub.add_broadcast(broadcast("discovery", "cache is stale")).await.unwrap();
+        }
+        let repeated = hub.add_broadcast(broadcast("discovery", "cache is stale")).await.unwrap();
+        assert_eq!(repeated.repeat_count, BROADCAST_RATE_LIMIT * 2 + 1);
+
+        // The sender's whole budget is still available for new messages.
+        for i in 1..BROADCAST_RATE_LIMIT {
+            hub.add_broadcast(broadcast("info", &format!("msg {i}"))).await.unwrap();
+        }
+        assert!(hub.add_broadcast(broadcast("info", "one too many")).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn expired_broadcasts_leave_the_inbox() {
+        let hub = IntelHub::new();
+        let short = BroadcastRequest { ttl_secs: Some(60), ..broadcast("info", "working on auth") };
+        let short_id = hub.add_broadcast(short).await.unwrap().id;
+        hub.add_broadcast(broadcast("knowledge", "tests need --offline")).await.unwrap();
+        assert_eq!(hub.get_messages_for(2, &IntelScope::All).await.len(), 2);
+
+        // Backdate the short-lived message past its TTL.
+        let past = (chrono::Utc::now() - chrono::Duration::seconds(61)).to_rfc3339();
+        for msg in hub.messages.write().await.iter_mut() {
+            msg.timestamp = past.clone();
+        }
+
+        let inbox = hub.get_messages_for(2, &IntelScope::All).await;
+        assert_eq!(inbox.len(), 1);
+        assert_eq!(inbox[0].message, "tests need --offline");
+        assert!(hub.get_all_messages().await.iter().all(|m| m.id != short_id));
+
+        // The next add prunes it from memory.
+        let other = BroadcastRequest { session_id: 3, ..broadcast("info", "hi") };
+        hub.add_broadcast(other).await.unwrap();
+        assert_eq!(hub.broadcast_count().await, 2);
+
+        let zero = BroadcastRequest { ttl_secs: Some(0), ..broadcast("info", "x") };
+        assert_eq!(hub.add_broadcast(zero).await.unwrap_err().field, "ttl_secs");
+    }
+
+    #[tokio::test]
+    async fn broadcast_metadata_must_be_a_bounded_object() {
+        let hub = IntelHub::new();
+        let with_metadata = |metadata| BroadcastRequest {
+            metadata: Some(metadata),
+            ..broadcast("warning", "flaky test")
+        };
+
+        let msg = hub
+            .add_broadcast(with_metadata(serde_json::json!({ "file": "src/lib.rs" })))
+            .await
+            .unwrap();
+        assert_eq!(msg.metadata.unwrap()["file"], "src/lib.rs");
+
+        let err = hub.add_broadcast(with_metadata(serde_json::json!(["a"]))).await.unwrap_err();
+        assert_eq!(err.field, "metadata");
+
+        let huge = serde_json::json!({ "blob": "x".repeat(MAX_MESSAGE_LEN) });
+        let err = hub.add_broadcast(with_metadata(huge)).await.unwrap_err();
+        assert_eq!(err.field, "metadata");
+    }
+
+    fn activity(session_id: u32, file_path: &str) -> FileActivityRequest {
+        FileActivityRequest {
+            session_id,
+            instance_id: "test".to_string(),
+            file_path: file_path.to_string(),
+            action: "editing".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn report_file_rejects_traversal_and_paths_outside_the_project() {
+        let hub = IntelHub::new();
+        hub.set_session_project(1, "aaa".to_string(), "/work/proj").await;
+
+        assert!(hub.report_file(activity(1, "my..notes.txt")).await.is_ok());
+        let err = hub.report_file(activity(1, "../../etc/passwd")).await.unwrap_err();
+        assert_eq!(err.field, "file_path");
+        let err = hub.report_file(activity(1, "/etc/passwd")).await.unwrap_err();
+        assert_eq!(err.field, "file_path");
+        assert!(hub.report_file(activity(1, "/work/proj/README.md")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn differently_spelled_paths_to_one_file_conflict() {
+        let hub = IntelHub::new();
+        hub.set_session_project(1, "aaa".to_string(), "/work/proj").await;
+        hub.set_session_project(2, "aaa".to_string(), "/work/proj/").await;
+
+        assert!(hub.report_file(activity(1, "./src/main.rs")).await.unwrap().is_empty());
+        let conflicts = hub.report_file(activity(2, "/work/proj/src/./main.rs")).await.unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].file_path, "/work/proj/src/main.rs");
+        assert_eq!(conflicts[0].sessions, vec![1, 2]);
+
+        // Acknowledging accepts any spelling too
+        hub.acknowledge_conflict("src/main.rs", 1).await.unwrap();
+
+        // Without a known root, relative spellings still meet
+        let unregistered = IntelHub::new();
+        unregistered.report_file(activity(1, "src/lib.rs")).await.unwrap();
+        let conflicts = unregistered.report_file(activity(2, "./src/lib.rs")).await.unwrap();
+        assert_eq!(conflicts[0].file_path, "src/lib.rs");
+    }
+
+    #[tokio::test]
+    async fn projects_do_not_see_each_others_intel() {
+        let hub = IntelHub::new();
+        for (session_id, project) in [(1, "aaa"), (2, "aaa"), (3, "bbb"), (4, "bbb")] {
+            hub.set_session_project(session_id, project.to_string(), &format!("/work/{}", project))
+                .await;
+        }
+        let mut from_b = broadcast("info", "shared word from B");
+        from_b.session_id = 3;
+        hub.add_broadcast(broadcast("info", "shared word from A")).await.unwrap();
+        hub.add_broadcast(from_b).await.unwrap();
+        hub.write_scratchpad(ScratchpadWriteRequest {
+            session_id: 4,
+            instance_id: "test".to_string(),
+            category: "note".to_string(),
+            title: "B only".to_string(),
+            content: "shared word".to_string(),
+        })
+        .await
+        .unwrap();
+        // Same relative path edited in both projects, by one session each
+        hub.report_file(activity(1, "src/lib.rs")).await.unwrap();
+        assert!(hub.report_file(activity(3, "src/lib.rs")).await.unwrap().is_empty());
+
+        let a = hub.scope_for(Some(2), false).await;
+        assert_eq!(a, IntelScope::Project("aaa".into()));
+        let inbox = hub.get_messages_for(2, &a).await;
+        assert_eq!(inbox.len(), 1);
+        assert_eq!(inbox[0].message, "shared word from A");
+        assert!(hub.read_scratchpad(&a).await.is_empty());
+        assert_eq!(hub.search("shared", &[], &a).await.len(), 1);
+        assert!(hub.get_all_conflicts(&a).await.is_empty());
+
+        // A second session in project B makes a conflict there only
+        let conflicts = hub.report_file(activity(4, "src/lib.rs")).await.unwrap();
+        assert_eq!(conflicts[0].sessions, vec![3, 4]);
+        assert!(hub.get_all_conflicts(&a).await.is_empty());
+        assert_eq!(hub.get_all_conflicts(&IntelScope::Project("bbb".into())).await.len(), 1);
+        assert_eq!(hub.active_conflict_count().await, 1);
+
+        // The cross-project and desktop views see everything
+        assert_eq!(hub.scope_for(Some(2), true).await, IntelScope::All);
+        assert_eq!(hub.get_messages_for(2, &IntelScope::All).await.len(), 2);
+        assert_eq!(hub.read_scratchpad(&IntelScope::All).await.len(), 1);
+        assert_eq!(hub.search("shared", &[], &IntelScope::All).await.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn unregistered_callers_only_see_their_own_intel() {
+        let hub = IntelHub::new();
+        hub.set_session_project(1, "aaa".to_string(), "/work/aaa").await;
+        hub.add_broadcast(broadcast("info", "registered word")).await.unwrap();
+        let mut unregistered = broadcast("info", "unregistered word");
+        unregistered.session_id = 9;
+        hub.add_broadcast(unregistered).await.unwrap();
+
+        let own = hub.scope_for(Some(9), false).await;
+        assert_eq!(own, IntelScope::Session(9));
+        let found = hub.search("word", &[], &own).await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].session_id, 9);
+        assert!(hub.get_messages_for(9, &own).await.is_empty());
+
+        let anonymous = hub.scope_for(None, false).await;
+        assert!(hub.search("word", &[], &anonymous).await.is_empty());
+
+        // Asking for the cross-project view still works
+        let all = hub.scope_for(Some(9), true).await;
+        assert_eq!(hub.get_messages_for(9, &all).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn conflicts_are_announced_once_until_someone_joins() {
+        let hub = IntelHub::new();
+        hub.report_file(activity(1, "a.rs")).await.unwrap();
+
+        let conflicts = hub.report_file(activity(2, "a.rs")).await.unwrap();
+        let announced = hub.announce_new_conflicts(&conflicts).await;
+        assert_eq!(announced.len(), 1);
+        assert_eq!(announced[0].sessions, vec![1, 2]);
+
+        // Further edits by the same sessions are not news
+        let conflicts = hub.report_file(activity(1, "a.rs")).await.unwrap();
+        assert!(hub.announce_new_conflicts(&conflicts).await.is_empty());
+
+        // A third session joining is
+        let conflicts = hub.report_file(activity(3, "a.rs")).await.unwrap();
+        assert_eq!(hub.announce_new_conflicts(&conflicts).await[0].sessions, vec![1, 2, 3]);
+
+        // Once resolved, the next conflict on the file is announced again
+        hub.clear_file_activity().await;
+        hub.report_file(activity(1, "a.rs")).await.unwrap();
+        let conflicts = hub.report_file(activity(2, "a.rs")).await.unwrap();
+        assert_eq!(hub.announce_new_conflicts(&conflicts).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn acknowledged_conflict_stays_quiet_until_a_new_edit() {
+        let hub = IntelHub::new();
+        assert!(hub.acknowledge_conflict("a.rs", 1).await.is_err());
+
+        hub.report_file(activity(1, "a.rs")).await.unwrap();
+        assert_eq!(hub.report_file(activity(2, "a.rs")).await.unwrap().len(), 1);
+        assert!(hub.acknowledge_conflict("a.rs", 3).await.is_err());
+
+        hub.acknowledge_conflict("a.rs", 1).await.unwrap();
+        // Session 1's own edits no longer warn; session 2 hasn't acked yet
+        assert!(hub.report_file(activity(1, "a.rs")).await.unwrap().is_empty());
+        assert!(!hub.get_all_conflicts(&IntelScope::All).await[0].acknowledged);
+
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        hub.acknowledge_conflict("a.rs", 2).await.unwrap();
+        assert!(hub.get_all_conflicts(&IntelScope::All).await[0].acknowledged);
+
+        // A later edit from session 2 re-raises the warning for session 1
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+        assert!(hub.report_file(activity(2, "a.rs")).await.unwrap().is_empty());
+        let conflicts = hub.report_file(activity(1, "a.rs")).await.unwrap();
+        assert_eq!(conflicts.len(), 1);
+        assert!(!conflicts[0].acknowledged);
+        assert!(!hub.get_all_conflicts(&IntelScope::All).await[0].acknowledged);
+    }
 }
@@ -11,6 +11,10 @@ pub enum MarketplaceError {
     ParseError(String),
     /// Failed to clone repository.
     CloneError(String),
+    /// Repository URL is not an https or ssh git remote.
+    InvalidUrl(String),
+    /// Plugin manifest is missing or malformed.
+    InvalidManifest(String),
     /// Plugin not found.
     PluginNotFound(String),
     /// Marketplace source not found.
@@ -37,6 +41,8 @@ impl fmt::Display for MarketplaceError {
             Self::FetchError(msg) => write!(f, "Failed to fetch marketplace: {msg}"),
             Self::ParseError(msg) => write!(f, "Failed to parse marketplace catalog: {msg}"),
             Self::CloneError(msg) => write!(f, "Failed to clone repository: {msg}"),
+            Self::InvalidUrl(url) => write!(f, "Not an https or ssh git URL: {url}"),
+            Self::InvalidManifest(msg) => write!(f, "Invalid plugin manifest: {msg}"),
             Self::PluginNotFound(id) => write!(f, "Plugin not found: {id}"),
             Self::SourceNotFound(id) => write!(f, "Marketplace source not found: {id}"),
             Self::AlreadyInstalled(id) => write!(f, "Plugin already installed: {id}"),
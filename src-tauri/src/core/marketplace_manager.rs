@@ -300,6 +300,28 @@ impl MarketplaceManager {
         all_plugins
     }
 
+    /// Searches available plugins by keyword, best matches first.
+    ///
+    /// Matching is case-insensitive and by substring; every word of `query`
+    /// must appear in the name, ID, tags or description. An empty query
+    /// returns everything in `category`. Enabled sources that haven't been
+    /// fetched yet are fetched first.
+    pub async fn search(&self, query: &str, category: Option<PluginCategory>) -> Vec<MarketplaceSearchResult> {
+        let unfetched: Vec<String> = self.get_sources()
+            .into_iter()
+            .filter(|s| s.is_enabled && !self.available_plugins.contains_key(&s.id))
+            .map(|s| s.id)
+            .collect();
+
+        for source_id in unfetched {
+            if let Err(e) = self.fetch_marketplace(&source_id).await {
+                log::warn!("Failed to load marketplace {} for search: {}", source_id, e);
+            }
+        }
+
+        rank_plugins(self.get_available_plugins(), query, category)
+    }
+
     // ========== Plugin Installation ==========
 
     /// Gets the installation directory for a plugin based on scope.
@@ -642,6 +664,238 @@ impl MarketplaceManager {
         Ok(installed_plugin)
     }
 
+    /// Checks out a plugin from a Git URL and reads its manifest without
+    /// installing it, so the UI can show what would be added.
+    pub async fn preview_from_git(&self, url: &str, git_ref: Option<&str>) -> MarketplaceResult<GitPluginPreview> {
+        validate_git_url(url)?;
+        let staging_dir = Self::git_staging_dir()?;
+        let preview = Self::fetch_git_plugin(url, git_ref, &staging_dir).await;
+        let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+        preview
+    }
+
+    /// Installs a plugin straight from a Git repository into
+    /// ~/.chorus/plugins/, at `git_ref` (branch, tag or commit) or the
+    /// default branch.
+    ///
+    /// Only https and ssh remotes are accepted. The repository must contain
+    /// a `.claude-plugin/plugin.json` manifest; its `name` becomes the
+    /// install directory. Nothing is left behind if any step fails.
+    pub async fn install_from_git(&self, url: &str, git_ref: Option<&str>) -> MarketplaceResult<InstalledPlugin> {
+        validate_git_url(url)?;
+        let staging_dir = Self::git_staging_dir()?;
+        let result = self.install_from_staging(url, git_ref, &staging_dir).await;
+        if result.is_err() {
+            let _ = tokio::fs::remove_dir_all(&staging_dir).await;
+        }
+        result
+    }
+
+    async fn install_from_staging(
+        &self,
+        url: &str,
+        git_ref: Option<&str>,
+        staging_dir: &Path,
+    ) -> MarketplaceResult<InstalledPlugin> {
+        let preview = Self::fetch_git_plugin(url, git_ref, staging_dir).await?;
+
+        let install_base = self.get_install_dir(InstallScope::User, None)?;
+        let plugin_dir = install_base.join(&preview.name);
+        let plugin_path = plugin_dir.to_string_lossy().to_string();
+        if plugin_dir.exists() || self.installed_plugins.read().unwrap().iter().any(|p| p.path == plugin_path) {
+            return Err(MarketplaceError::AlreadyInstalled(preview.name));
+        }
+
+        // Chorus-specific metadata, as for marketplace installs
+        let chorus_metadata = serde_json::json!({
+            "display_name": preview.name,
+            "repository_url": url,
+            "git_ref": git_ref,
+            "commit": preview.commit,
+        });
+        let metadata_path = staging_dir.join(".claude-plugin").join("chorus-metadata.json");
+        tokio::fs::write(&metadata_path, serde_json::to_string_pretty(&chorus_metadata)?).await?;
+
+        tokio::fs::create_dir_all(&install_base).await?;
+        tokio::fs::rename(staging_dir, &plugin_dir).await?;
+
+        let installed_plugin = InstalledPlugin {
+            id: Self::generate_plugin_id(),
+            name: preview.name,
+            version: preview.version,
+            source: InstalledPluginSource::Git {
+                repository_url: url.to_string(),
+            },
+            install_scope: InstallScope::User,
+            path: plugin_path,
+            installed_at: Self::now_iso8601(),
+            updated_at: None,
+            skills: preview.skills,
+            commands: preview.commands,
+            mcp_servers: preview.mcp_servers,
+            agents: preview.agents,
+            hooks: preview.hooks,
+            is_enabled: true,
+        };
+
+        self.installed_plugins.write().unwrap().push(installed_plugin.clone());
+        log::info!("Installed plugin {} from {}", installed_plugin.name, url);
+
+        Ok(installed_plugin)
+    }
+
+    /// A fresh directory under ~/.chorus/plugins/repos/ to check out into.
+    /// It sits one level below the plugin scan so a half-finished checkout
+    /// is never picked up as a plugin.
+    fn git_staging_dir() -> MarketplaceResult<PathBuf> {
+        Self::get_repos_cache_dir()
+            .map(|dir| dir.join(format!("git-install-{}", Self::generate_plugin_id())))
+            .ok_or_else(|| MarketplaceError::InvalidPath("Cannot determine home directory".to_string()))
+    }
+
+    /// Checks out `url` at `git_ref` into `target_dir` and validates the
+    /// plugin manifest there. The caller removes `target_dir` on failure.
+    async fn fetch_git_plugin(
+        url: &str,
+        git_ref: Option<&str>,
+        target_dir: &Path,
+    ) -> MarketplaceResult<GitPluginPreview> {
+        if let Some(r) = git_ref {
+            if r.is_empty() || r.starts_with('-') {
+                return Err(MarketplaceError::CloneError(format!("Invalid git ref: {}", r)));
+            }
+        }
+
+        // init + fetch rather than clone so `git_ref` can also be a commit
+        tokio::fs::create_dir_all(target_dir).await?;
+        Self::run_git(target_dir, &["init", "-q"]).await?;
+        Self::run_git(target_dir, &["remote", "add", "origin", url]).await?;
+        Self::run_git(target_dir, &["fetch", "--depth", "1", "origin", git_ref.unwrap_or("HEAD")]).await?;
+        Self::run_git(target_dir, &["checkout", "-q", "FETCH_HEAD"]).await?;
+        let commit = Self::run_git(target_dir, &["rev-parse", "HEAD"]).await?;
+
+        let manifest_path = target_dir.join(".claude-plugin").join("plugin.json");
+        let content = tokio::fs::read_to_string(&manifest_path).await.map_err(|_| {
+            MarketplaceError::InvalidManifest("repository has no .claude-plugin/plugin.json".to_string())
+        })?;
+        let manifest: GitPluginManifest = serde_json::from_str(&content)
+            .map_err(|e| MarketplaceError::InvalidManifest(e.to_string()))?;
+        validate_plugin_name(&manifest.name)?;
+
+        let (skills, commands, mcp_servers, agents, hooks) = Self::discover_plugin_components(target_dir);
+
+        Ok(GitPluginPreview {
+            name: manifest.name,
+            version: manifest.version.unwrap_or_else(|| "0.0.0".to_string()),
+            description: manifest.description.unwrap_or_default(),
+            repository_url: url.to_string(),
+            git_ref: git_ref.map(String::from),
+            commit,
+            skills,
+            commands,
+            mcp_servers,
+            agents,
+            hooks,
+        })
+    }
+
+    /// Runs git in `dir` without prompting for credentials, returning
+    /// trimmed stdout.
+    async fn run_git(dir: &Path, args: &[&str]) -> MarketplaceResult<String> {
+        let output = tokio::time::timeout(
+            GIT_INSTALL_TIMEOUT,
+            Command::new("git")
+                .args(args)
+                .current_dir(dir)
+                .env("GIT_TERMINAL_PROMPT", "0")
+                .kill_on_drop(true)
+                .hide_console_window()
+                .output(),
+        )
+        .await
+        .map_err(|_| MarketplaceError::CloneError(format!(
+            "git {} timed out after {}s",
+            args[0],
+            GIT_INSTALL_TIMEOUT.as_secs()
+        )))?
+        .map_err(|e| MarketplaceError::CloneError(format!("Failed to run git: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(MarketplaceError::CloneError(format!(
+                "git {} failed: {}",
+                args[0],
+                stderr.trim()
+            )));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// Finds installed plugins with a newer version available: the catalog
+    /// version for marketplace installs, the highest semver tag on the
+    /// remote for Git installs. Local installs are never reported.
+    ///
+    /// Catalogs that aren't loaded yet are fetched first. Failures are
+    /// collected as warnings so one unreachable source doesn't hide updates
+    /// from the others.
+    pub async fn check_updates(&self) -> UpdateCheckResult {
+        let installed = self.get_installed_plugins();
+        let mut result = UpdateCheckResult::default();
+
+        let mut unloaded: Vec<&str> = installed
+            .iter()
+            .filter_map(|p| match &p.source {
+                InstalledPluginSource::Marketplace { marketplace_id, .. } => Some(marketplace_id.as_str()),
+                _ => None,
+            })
+            .filter(|id| !self.available_plugins.contains_key(*id))
+            .collect();
+        unloaded.sort_unstable();
+        unloaded.dedup();
+
+        for marketplace_id in unloaded {
+            if let Err(e) = self.fetch_marketplace(marketplace_id).await {
+                result.warnings.push(format!("Couldn't load marketplace {}: {}", marketplace_id, e));
+            }
+        }
+
+        for plugin in &installed {
+            let latest = match &plugin.source {
+                InstalledPluginSource::Marketplace { marketplace_id, plugin_id } => self
+                    .available_plugins
+                    .get(marketplace_id)
+                    .and_then(|plugins| plugins.iter().find(|p| &p.id == plugin_id).map(|p| p.version.clone())),
+                InstalledPluginSource::Git { repository_url } => match Self::latest_git_tag(repository_url).await {
+                    Ok(tag) => tag,
+                    Err(e) => {
+                        result.warnings.push(format!("Couldn't check {} for updates: {}", plugin.name, e));
+                        None
+                    }
+                },
+                InstalledPluginSource::Local { .. } => None,
+            };
+
+            if let Some(latest) = latest.filter(|l| is_newer_version(l, &plugin.version)) {
+                result.updates.push(PluginUpdate {
+                    id: plugin.id.clone(),
+                    name: plugin.name.clone(),
+                    current: plugin.version.clone(),
+                    latest,
+                });
+            }
+        }
+
+        result
+    }
+
+    /// The highest semver tag on `url`, without its `v` prefix, or None if
+    /// the remote has no version tags.
+    async fn latest_git_tag(url: &str) -> MarketplaceResult<Option<String>> {
+        let output = Self::run_git(&std::env::temp_dir(), &["ls-remote", "--tags", "--refs", url]).await?;
+        Ok(latest_version_tag(&output).map(|v| v.to_string()))
+    }
+
     /// Uninstalls a plugin by ID.
     pub async fn uninstall_plugin(&self, installed_plugin_id: &str) -> MarketplaceResult<()> {
         // Extract the plugin path while holding the lock, then release it
@@ -792,6 +1046,168 @@ impl Default for MarketplaceManager {
     }
 }
 
+/// How long a single git step of `install_from_git` may take.
+const GIT_INSTALL_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// The fields of `.claude-plugin/plugin.json` needed to install a plugin.
+#[derive(serde::Deserialize)]
+struct GitPluginManifest {
+    name: String,
+    #[serde(default)]
+    version: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// Accepts `https://` and `ssh://` URLs and scp-style `user@host:path`
+/// remotes. Local paths, `file://`, plain `http://` and git's transport
+/// helpers (`ext::` and friends) are rejected.
+fn validate_git_url(url: &str) -> MarketplaceResult<()> {
+    let invalid = || MarketplaceError::InvalidUrl(url.to_string());
+
+    if url.starts_with('-') || url.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(invalid());
+    }
+
+    if let Some(rest) = url.strip_prefix("https://").or_else(|| url.strip_prefix("ssh://")) {
+        let host = rest.split(['/', ':']).next().unwrap_or_default();
+        let host = host.rsplit('@').next().unwrap_or_default();
+        return if host.is_empty() || host.starts_with('-') { Err(invalid()) } else { Ok(()) };
+    }
+
+    // scp-like syntax: user@host:path
+    match url.split_once(':') {
+        Some((user_host, path)) if !url.contains("://") && !path.is_empty() => {
+            match user_host.split_once('@') {
+                Some((user, host))
+                    if !user.is_empty() && !host.is_empty() && !host.contains('/') && !host.starts_with('-') =>
+                {
+                    Ok(())
+                }
+                _ => Err(invalid()),
+            }
+        }
+        _ => Err(invalid()),
+    }
+}
+
+/// The manifest name becomes a directory under ~/.chorus/plugins/, so it
+/// must be a single, plain path component.
+fn validate_plugin_name(name: &str) -> MarketplaceResult<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(MarketplaceError::InvalidManifest(format!(
+            "plugin name '{}' must use only letters, digits, '-', '_' and '.'",
+            name
+        )))
+    }
+}
+
+/// Parses `v1.2.3` or `1.2.3` as semver.
+fn parse_version(version: &str) -> Option<semver::Version> {
+    let trimmed = version.trim();
+    semver::Version::parse(trimmed.strip_prefix('v').unwrap_or(trimmed)).ok()
+}
+
+/// Whether `latest` is an update over `current`. Uses semver ordering when
+/// both parse, and otherwise treats any difference as an update.
+fn is_newer_version(latest: &str, current: &str) -> bool {
+    match (parse_version(latest), parse_version(current)) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => latest.trim() != current.trim(),
+    }
+}
+
+/// Picks the highest semver tag from `git ls-remote --tags` output
+/// (`<sha>\trefs/tags/<name>` lines). Tags that aren't versions are ignored.
+fn latest_version_tag(ls_remote: &str) -> Option<semver::Version> {
+    ls_remote
+        .lines()
+        .filter_map(|line| line.split_once("\trefs/tags/"))
+        .filter_map(|(_, tag)| parse_version(tag))
+        .max()
+}
+
+/// Longest description returned in search results, in characters.
+const SEARCH_DESCRIPTION_MAX_CHARS: usize = 160;
+
+/// Filters `plugins` to those matching every word of `query` (and
+/// `category`, if given), sorted by relevance and then name.
+fn rank_plugins(
+    plugins: Vec<MarketplacePlugin>,
+    query: &str,
+    category: Option<PluginCategory>,
+) -> Vec<MarketplaceSearchResult> {
+    let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+
+    let mut scored: Vec<(u32, MarketplacePlugin)> = plugins
+        .into_iter()
+        .filter(|p| category.is_none_or(|c| p.category == c))
+        .filter_map(|p| {
+            let score = terms
+                .iter()
+                .map(|t| term_score(&p, t))
+                .sum::<Option<u32>>()?;
+            Some((score, p))
+        })
+        .collect();
+
+    scored.sort_by(|(a_score, a), (b_score, b)| {
+        b_score
+            .cmp(a_score)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+
+    scored
+        .into_iter()
+        .map(|(_, p)| MarketplaceSearchResult {
+            id: p.id,
+            name: p.name,
+            description: shorten(&p.description, SEARCH_DESCRIPTION_MAX_CHARS),
+            category: p.category,
+            marketplace_id: p.marketplace_id,
+        })
+        .collect()
+}
+
+/// How well a single lowercase `term` matches `plugin`, or None if it
+/// doesn't appear anywhere. Name matches outrank tags, which outrank the
+/// description.
+fn term_score(plugin: &MarketplacePlugin, term: &str) -> Option<u32> {
+    let name = plugin.name.to_lowercase();
+    let id = plugin.id.to_lowercase();
+    let tags: Vec<String> = plugin.tags.iter().map(|t| t.to_lowercase()).collect();
+
+    if name == term || id == term {
+        Some(100)
+    } else if name.starts_with(term) {
+        Some(80)
+    } else if name.contains(term) || id.contains(term) {
+        Some(60)
+    } else if tags.iter().any(|t| t == term) {
+        Some(50)
+    } else if tags.iter().any(|t| t.contains(term)) {
+        Some(40)
+    } else if plugin.description.to_lowercase().contains(term) {
+        Some(20)
+    } else {
+        None
+    }
+}
+
+/// Cuts `text` to at most `max_chars` characters, ending with an ellipsis
+/// if anything was removed.
+fn shorten(text: &str, max_chars: usize) -> String {
+    match text.char_indices().nth(max_chars) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -830,6 +1246,250 @@ mod tests {
         assert!(!manager.get_sources()[0].is_enabled);
     }
 
+    const FIXTURE_CATALOG: &str = r#"{
+        "name": "Fixture",
+        "plugins": [
+            {
+                "name": "git-helper",
+                "description": "Commit message suggestions and branch cleanup",
+                "category": "development",
+                "tags": ["git", "vcs"]
+            },
+            {
+                "name": "Notes",
+                "description": "Quick notes that mention git now and then",
+                "category": "productivity",
+                "tags": ["writing"]
+            },
+            {
+                "name": "Changelog",
+                "description": "Builds release notes",
+                "category": "documentation",
+                "tags": ["git-history"]
+            },
+            {
+                "name": "Lint",
+                "description": "Runs linters",
+                "category": "development"
+            }
+        ]
+    }"#;
+
+    fn manager_with_fixture() -> MarketplaceManager {
+        let manager = MarketplaceManager::new();
+        // Keep search from fetching the built-in catalogs over the network
+        for source in manager.get_sources() {
+            manager.remove_source(&source.id).unwrap();
+        }
+        let source = manager.add_source(
+            "Fixture".to_string(),
+            "https://github.com/test/fixture".to_string(),
+            false,
+        );
+        let catalog: MarketplaceCatalog = serde_json::from_str(FIXTURE_CATALOG).unwrap();
+        let plugins = catalog.plugins
+            .into_iter()
+            .map(|p| p.into_marketplace_plugin(&source.id, &source.repository_url))
+            .collect();
+        manager.available_plugins.insert(source.id, plugins);
+        manager
+    }
+
+    #[tokio::test]
+    async fn test_search_ranks_name_over_tags_over_description() {
+        let manager = manager_with_fixture();
+
+        let names: Vec<String> = manager.search("GIT", None).await
+            .into_iter()
+            .map(|r| r.name)
+            .collect();
+        assert_eq!(names, vec!["git-helper", "Changelog", "Notes"]);
+
+        // Every word must match somewhere
+        let results = manager.search("git cleanup", None).await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].id, "git-helper");
+
+        assert!(manager.search("nonexistent", None).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_filters_by_category() {
+        let manager = manager_with_fixture();
+
+        let results = manager.search("", Some(PluginCategory::Development)).await;
+        let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["git-helper", "Lint"]);
+
+        let results = manager.search("git", Some(PluginCategory::Productivity)).await;
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].name, "Notes");
+    }
+
+    #[test]
+    fn test_validate_git_url() {
+        for ok in [
+            "https://github.com/owner/plugin",
+            "https://github.com/owner/plugin.git",
+            "ssh://git@github.com/owner/plugin.git",
+            "git@github.com:owner/plugin.git",
+        ] {
+            assert!(validate_git_url(ok).is_ok(), "{ok} should be accepted");
+        }
+        for bad in [
+            "http://github.com/owner/plugin",
+            "file:///tmp/plugin",
+            "/tmp/plugin",
+            "../plugin",
+            "ext::sh -c touch% /tmp/pwned",
+            "-uhttps://example.com",
+            "ssh://-oProxyCommand=x/repo",
+            "https:///path-only",
+            "github.com:owner/plugin",
+            "C:\\plugins\\x",
+        ] {
+            assert!(
+                matches!(validate_git_url(bad), Err(MarketplaceError::InvalidUrl(_))),
+                "{bad} should be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_plugin_name() {
+        assert!(validate_plugin_name("my-plugin_2.0").is_ok());
+        assert!(validate_plugin_name("").is_err());
+        assert!(validate_plugin_name("..").is_err());
+        assert!(validate_plugin_name("a/b").is_err());
+        assert!(validate_plugin_name("has space").is_err());
+    }
+
+    async fn plugin_repo(manifest: Option<&str>) -> tempfile::TempDir {
+        let repo = tempfile::tempdir().unwrap();
+        let dir = repo.path();
+        std::fs::create_dir_all(dir.join("commands")).unwrap();
+        std::fs::write(dir.join("commands").join("hello.md"), "Say hello").unwrap();
+        if let Some(manifest) = manifest {
+            std::fs::create_dir_all(dir.join(".claude-plugin")).unwrap();
+            std::fs::write(dir.join(".claude-plugin").join("plugin.json"), manifest).unwrap();
+        }
+        for args in [
+            &["init", "-q"][..],
+            &["add", "-A"],
+            &["-c", "user.name=t", "-c", "user.email=t@t", "commit", "-q", "-m", "init"],
+        ] {
+            let status = Command::new("git").args(args).current_dir(dir).status().await.unwrap();
+            assert!(status.success());
+        }
+        repo
+    }
+
+    #[tokio::test]
+    async fn test_fetch_git_plugin_reads_manifest() {
+        let repo = plugin_repo(Some(r#"{"name": "hello-plugin", "version": "1.2.0"}"#)).await;
+        let target = tempfile::tempdir().unwrap();
+        let checkout = target.path().join("checkout");
+
+        let url = repo.path().to_string_lossy().to_string();
+        let preview = MarketplaceManager::fetch_git_plugin(&url, None, &checkout).await.unwrap();
+
+        assert_eq!(preview.name, "hello-plugin");
+        assert_eq!(preview.version, "1.2.0");
+        assert_eq!(preview.commands, vec!["hello"]);
+        assert_eq!(preview.commit.len(), 40);
+        assert!(checkout.join("commands").join("hello.md").exists());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_git_plugin_rejects_missing_manifest_and_bad_ref() {
+        let repo = plugin_repo(None).await;
+        let target = tempfile::tempdir().unwrap();
+        let url = repo.path().to_string_lossy().to_string();
+
+        let err = MarketplaceManager::fetch_git_plugin(&url, None, &target.path().join("a")).await.unwrap_err();
+        assert!(matches!(err, MarketplaceError::InvalidManifest(_)), "got {err:?}");
+
+        let err = MarketplaceManager::fetch_git_plugin(&url, Some("no-such-branch"), &target.path().join("b"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, MarketplaceError::CloneError(_)), "got {err:?}");
+    }
+
+    #[test]
+    fn test_is_newer_version() {
+        assert!(is_newer_version("1.10.0", "1.9.0"));
+        assert!(is_newer_version("v2.0.0", "1.9.9"));
+        assert!(is_newer_version("1.0.0", "1.0.0-beta.1"));
+        assert!(!is_newer_version("1.0.0", "1.0.0"));
+        assert!(!is_newer_version("1.0.0", "1.2.0"));
+        // Not semver: any difference counts
+        assert!(is_newer_version("2024-06", "2024-05"));
+        assert!(!is_newer_version("nightly", "nightly"));
+    }
+
+    #[test]
+    fn test_latest_version_tag() {
+        let output = "aaa\trefs/tags/v1.2.0\nbbb\trefs/tags/v1.10.0\nccc\trefs/tags/latest\nddd\trefs/tags/1.9.3\n";
+        assert_eq!(latest_version_tag(output), Some(semver::Version::new(1, 10, 0)));
+        assert_eq!(latest_version_tag("aaa\trefs/tags/stable\n"), None);
+        assert_eq!(latest_version_tag(""), None);
+    }
+
+    fn installed_from_marketplace(marketplace_id: &str, plugin_id: &str, version: &str) -> InstalledPlugin {
+        InstalledPlugin {
+            id: format!("installed-{}", plugin_id),
+            name: plugin_id.to_string(),
+            version: version.to_string(),
+            source: InstalledPluginSource::Marketplace {
+                marketplace_id: marketplace_id.to_string(),
+                plugin_id: plugin_id.to_string(),
+            },
+            install_scope: InstallScope::User,
+            path: format!("/plugins/{}", plugin_id),
+            installed_at: "0Z".to_string(),
+            updated_at: None,
+            skills: Vec::new(),
+            commands: Vec::new(),
+            mcp_servers: Vec::new(),
+            agents: Vec::new(),
+            hooks: Vec::new(),
+            is_enabled: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_updates_reports_newer_catalog_versions_and_warnings() {
+        let manager = manager_with_fixture();
+        let source_id = manager.get_sources()[0].id.clone();
+        if let Some(mut plugins) = manager.available_plugins.get_mut(&source_id) {
+            plugins[0].version = "1.2.0".to_string(); // git-helper
+            plugins[3].version = "1.0.0".to_string(); // Lint
+        }
+
+        {
+            let mut installed = manager.installed_plugins.write().unwrap();
+            installed.push(installed_from_marketplace(&source_id, "git-helper", "1.0.0"));
+            installed.push(installed_from_marketplace(&source_id, "Lint", "1.0.0"));
+            // Its marketplace is unknown, so it can't be checked
+            installed.push(installed_from_marketplace("removed-source", "gone", "1.0.0"));
+        }
+
+        let result = manager.check_updates().await;
+
+        assert_eq!(result.updates.len(), 1);
+        assert_eq!(result.updates[0].id, "installed-git-helper");
+        assert_eq!(result.updates[0].current, "1.0.0");
+        assert_eq!(result.updates[0].latest, "1.2.0");
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("removed-source"));
+    }
+
+    #[test]
+    fn test_shorten_description() {
+        assert_eq!(shorten("short", 10), "short");
+        assert_eq!(shorten("héllo wörld", 6), "héllo…");
+    }
+
     #[test]
     fn test_marketplace_json_url() {
         let url = MarketplaceManager::get_marketplace_json_url("https://github.com/owner/repo");
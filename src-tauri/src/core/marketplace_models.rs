@@ -137,6 +137,21 @@ pub struct MarketplacePlugin {
     pub stars: Option<u64>,
 }
 
+/// A marketplace search hit: just what a result list needs to render.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketplaceSearchResult {
+    /// Plugin ID within its marketplace.
+    pub id: String,
+    /// Human-readable name.
+    pub name: String,
+    /// Description, shortened for list display.
+    pub description: String,
+    /// Category for filtering.
+    pub category: PluginCategory,
+    /// ID of the marketplace source this came from.
+    pub marketplace_id: String,
+}
+
 /// Source of an installed plugin.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "source", rename_all = "lowercase")]
@@ -194,6 +209,57 @@ pub struct InstalledPlugin {
     pub is_enabled: bool,
 }
 
+/// What installing a plugin from a Git URL would add, read from a
+/// temporary checkout before anything is installed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitPluginPreview {
+    /// Plugin name from its manifest; also the install directory name.
+    pub name: String,
+    /// Version from the manifest, "0.0.0" if absent.
+    pub version: String,
+    /// Description from the manifest.
+    pub description: String,
+    /// Repository URL the plugin was fetched from.
+    pub repository_url: String,
+    /// Branch, tag or commit requested, or None for the default branch.
+    pub git_ref: Option<String>,
+    /// Commit that was checked out.
+    pub commit: String,
+    /// IDs of skills provided by this plugin.
+    pub skills: Vec<String>,
+    /// Names of commands provided by this plugin.
+    pub commands: Vec<String>,
+    /// Names of MCP servers provided by this plugin.
+    pub mcp_servers: Vec<String>,
+    /// Names of agents provided by this plugin.
+    pub agents: Vec<String>,
+    /// Names of hooks provided by this plugin.
+    pub hooks: Vec<String>,
+}
+
+/// An installed plugin with a newer version available.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginUpdate {
+    /// ID of the installed plugin.
+    pub id: String,
+    /// Human-readable name.
+    pub name: String,
+    /// Installed version.
+    pub current: String,
+    /// Latest version in the catalog, or the newest tag for Git installs.
+    pub latest: String,
+}
+
+/// Result of `MarketplaceManager::check_updates`.
+///
+/// Sources that couldn't be reached are listed in `warnings`; `updates`
+/// still covers every plugin that could be checked.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateCheckResult {
+    pub updates: Vec<PluginUpdate>,
+    pub warnings: Vec<String>,
+}
+
 /// Session-specific marketplace plugin configuration.
 ///
 /// Tracks which marketplace plugins are enabled for a specific session.
@@ -6,14 +6,63 @@
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Arc, LazyLock};
 
+use dashmap::DashMap;
 use serde_json::{json, Value};
+use tokio::sync::Mutex;
 
 use super::mcp_manager::{McpServerConfig, McpServerType};
 use crate::commands::mcp::McpCustomServer;
 
+/// Per-directory locks serializing the read-modify-write of `.mcp.json`, so
+/// sessions starting together in one working dir don't drop each other's entries.
+static DIR_LOCKS: LazyLock<DashMap<PathBuf, Arc<Mutex<()>>>> = LazyLock::new(DashMap::new);
+
+/// Returns the lock guarding `.mcp.json` in `working_dir`.
+fn dir_lock(working_dir: &Path) -> Arc<Mutex<()>> {
+    let key = super::path_utils::normalize_path_buf(working_dir);
+    DIR_LOCKS.entry(key).or_default().clone()
+}
+
+/// Writes `content` to `path` via a sibling temp file and a rename, so readers
+/// never observe a half-written file.
+async fn write_atomic(path: &Path, content: &str) -> Result<(), String> {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, uuid::Uuid::new_v4()));
+
+    tokio::fs::write(&tmp_path, content)
+        .await
+        .map_err(|e| format!("Failed to write {:?}: {}", tmp_path, e))?;
+
+    if let Err(e) = tokio::fs::rename(&tmp_path, path).await {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(format!("Failed to move {:?} into place: {}", tmp_path, e));
+    }
+    Ok(())
+}
+
+/// Returns `env` with the session's `overrides` applied on top.
+fn overlay_env(
+    env: &HashMap<String, String>,
+    overrides: &HashMap<String, String>,
+) -> HashMap<String, String> {
+    let mut merged = env.clone();
+    merged.extend(overrides.iter().map(|(k, v)| (k.clone(), v.clone())));
+    merged
+}
+
 /// Converts an McpServerConfig to the JSON format expected by `.mcp.json`.
-fn server_config_to_json(config: &McpServerConfig) -> Value {
+///
+/// `env_overrides` are merged over a stdio server's env; remote servers
+/// have no process environment and are unaffected.
+fn server_config_to_json(
+    config: &McpServerConfig,
+    env_overrides: &HashMap<String, String>,
+) -> Value {
     match &config.server_type {
         McpServerType::Stdio { command, args, env } => {
             let mut obj = json!({
@@ -21,73 +70,115 @@ fn server_config_to_json(config: &McpServerConfig) -> Value {
                 "command": command,
                 "args": args,
             });
+            let env = overlay_env(env, env_overrides);
             if !env.is_empty() {
                 obj["env"] = json!(env);
             }
             obj
         }
-        McpServerType::Http { url } => {
-            json!({
+        McpServerType::Http { url, headers } => {
+            let mut obj = json!({
                 "type": "http",
-                "url": url
-            })
+                "url": url,
+            });
+            if !headers.is_empty() {
+                obj["headers"] = json!(headers);
+            }
+            obj
+        }
+        McpServerType::Sse { url, headers } => {
+            let mut obj = json!({
+                "type": "sse",
+                "url": url,
+            });
+            if !headers.is_empty() {
+                obj["headers"] = json!(headers);
+            }
+            obj
         }
     }
 }
 
 /// Converts a custom MCP server to the JSON format expected by `.mcp.json`.
-fn custom_server_to_json(server: &McpCustomServer) -> Value {
+fn custom_server_to_json(
+    server: &McpCustomServer,
+    env_overrides: &HashMap<String, String>,
+) -> Value {
     let mut obj = json!({
         "type": "stdio",
         "command": server.command,
         "args": server.args,
     });
-    if !server.env.is_empty() {
-        obj["env"] = json!(server.env);
+    let env = overlay_env(&server.env, env_overrides);
+    if !env.is_empty() {
+        obj["env"] = json!(env);
     }
     obj
 }
 
+/// Env vars Chorus sets on the chorus-status server. Session overrides can't
+/// set them on any server, since `CHORUS_SESSION_ID` also marks an entry as
+/// Chorus-managed.
+const RESERVED_ENV_VARS: [&str; 3] =
+    ["CHORUS_SESSION_ID", "CHORUS_STATUS_URL", "CHORUS_INSTANCE_ID"];
+
+/// File stem of the Chorus status MCP server binary.
+const CHORUS_MCP_SERVER_BINARY: &str = "chorus-mcp-server";
+
 /// Checks if a server entry should be removed when updating the MCP config.
 ///
 /// Removes:
 /// 1. The single "chorus-status" entry (will be replaced with updated config)
-/// 2. Legacy per-session "chorus-status-*" entries (cleanup from old approach)
-/// 3. Legacy "chorus-*" entries (cleanup from old approach)
-/// 4. Legacy "chorus" entry (bare entry without session ID)
+/// 2. The legacy bare "chorus" entry
+/// 3. Any other entry Chorus wrote itself: one that carries a `CHORUS_SESSION_ID`
+///    env var or runs the `chorus-mcp-server` binary (legacy "chorus-status-*"
+///    and "chorus-*" entries)
+///
+/// User servers are never matched by name alone, so e.g. "chorus-docs" survives.
 ///
 /// This follows the Swift pattern: ONE MCP entry per project, session ID in env vars.
 /// Each Claude instance spawns its own MCP server process with the env vars from when
 /// it read the config.
-fn should_remove_server(name: &str, _config: &Value, _session_id: u32) -> bool {
+fn should_remove_server(name: &str, config: &Value, _session_id: u32) -> bool {
     // Remove the single chorus-status entry (we'll add an updated one)
     if name == "chorus-status" {
         log::debug!("[MCP] should_remove_server('{}') = true (single chorus-status entry)", name);
         return true;
     }
 
-    // Remove legacy per-session entries (cleanup from old per-session approach)
-    if name.starts_with("chorus-status-") {
-        log::debug!("[MCP] should_remove_server('{}') = true (legacy per-session entry)", name);
-        return true;
-    }
-
-    // Remove legacy "chorus-{N}" entries
-    if name.starts_with("chorus-") && name != "chorus-status" {
-        log::debug!("[MCP] should_remove_server('{}') = true (legacy chorus-N entry)", name);
-        return true;
-    }
-
     // Remove the legacy bare "chorus" entry
     if name == "chorus" {
         log::debug!("[MCP] should_remove_server('{}') = true (legacy bare chorus entry)", name);
         return true;
     }
 
+    // Remove legacy per-session entries, identified by content rather than name
+    if is_chorus_managed(config) {
+        log::debug!("[MCP] should_remove_server('{}') = true (Chorus-managed entry)", name);
+        return true;
+    }
+
     log::debug!("[MCP] should_remove_server('{}') = false (keeping)", name);
     false
 }
 
+/// Whether a server entry was written by Chorus: it carries our session env
+/// var or points at the `chorus-mcp-server` binary.
+fn is_chorus_managed(config: &Value) -> bool {
+    let has_session_env = config
+        .get("env")
+        .and_then(|env| env.get("CHORUS_SESSION_ID"))
+        .is_some();
+
+    let runs_chorus_binary = config
+        .get("command")
+        .and_then(|c| c.as_str())
+        .and_then(|c| Path::new(c).file_stem())
+        .is_some_and(|stem| stem == CHORUS_MCP_SERVER_BINARY);
+
+    has_session_env || runs_chorus_binary
+}
+
 /// Merges new MCP servers with an existing `.mcp.json` file.
 ///
 /// This function preserves user-defined servers while removing all Chorus-related
@@ -147,6 +238,82 @@ fn merge_with_existing(
     Ok(json!({ "mcpServers": final_servers }))
 }
 
+/// Checks a `.mcp.json` document against the shape the Claude CLI accepts.
+///
+/// Every server must have a known `type` (a missing type means `stdio`), a
+/// non-empty `command` for stdio or `url` for http/sse, and string-valued
+/// `args`, `env` and `headers`. Returns every problem found, not just the first.
+pub fn validate_mcp_config(config: &Value) -> Result<(), Vec<String>> {
+    let Some(servers) = config.get("mcpServers").and_then(|s| s.as_object()) else {
+        return Err(vec!["`mcpServers` must be an object".to_string()]);
+    };
+
+    let mut problems = Vec::new();
+    for (name, server) in servers {
+        let Some(server) = server.as_object() else {
+            problems.push(format!("server '{}': entry must be an object", name));
+            continue;
+        };
+
+        let server_type = match server.get("type") {
+            None => "stdio",
+            Some(Value::String(t)) => t.as_str(),
+            Some(_) => {
+                problems.push(format!("server '{}': `type` must be a string", name));
+                continue;
+            }
+        };
+
+        let required = match server_type {
+            "stdio" => "command",
+            "http" | "sse" => "url",
+            other => {
+                problems.push(format!("server '{}': unknown type '{}'", name, other));
+                continue;
+            }
+        };
+        match server.get(required).and_then(|v| v.as_str()) {
+            Some(value) if !value.trim().is_empty() => {}
+            _ => problems.push(format!(
+                "server '{}': {} server requires a non-empty `{}` string",
+                name, server_type, required
+            )),
+        }
+
+        if let Some(args) = server.get("args") {
+            let all_strings = args
+                .as_array()
+                .is_some_and(|a| a.iter().all(|v| v.is_string()));
+            if !all_strings {
+                problems.push(format!("server '{}': `args` must be an array of strings", name));
+            }
+        }
+
+        for field in ["env", "headers"] {
+            let Some(map) = server.get(field) else { continue };
+            match map.as_object() {
+                Some(map) => {
+                    for (key, value) in map {
+                        if !value.is_string() {
+                            problems.push(format!(
+                                "server '{}': `{}.{}` must be a string",
+                                name, field, key
+                            ));
+                        }
+                    }
+                }
+                None => problems.push(format!("server '{}': `{}` must be an object", name, field)),
+            }
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
 /// Configuration for the Chorus status MCP server.
 #[derive(Debug, Clone)]
 pub struct ChorusStatusConfig {
@@ -158,65 +325,69 @@ pub struct ChorusStatusConfig {
     pub instance_id: String,
 }
 
-/// Writes a session-specific `.mcp.json` to the working directory.
-///
-/// This function:
-/// 1. Adds the Chorus status server for real-time status reporting
-/// 2. Adds enabled discovered servers from the project's .mcp.json
-/// 3. Adds enabled custom servers (user-defined, global)
-/// 4. Merges with any existing `.mcp.json` (preserving user servers)
-/// 5. Writes the final config to the working directory
-///
-/// # Arguments
-///
-/// * `working_dir` - Directory where `.mcp.json` will be written
-/// * `session_id` - Session identifier used for merging
-/// * `enabled_servers` - List of discovered MCP server configs enabled for this session
-/// * `custom_servers` - List of custom MCP servers that are enabled
-/// * `chorus_status` - Optional configuration for the Chorus status MCP server
-pub async fn write_session_mcp_config(
-    working_dir: &Path,
+/// Builds this session's servers (steps 1-3 of `write_session_mcp_config`),
+/// keyed by server name, before they are merged with the existing file.
+fn build_session_servers(
     session_id: u32,
     enabled_servers: &[McpServerConfig],
     custom_servers: &[McpCustomServer],
+    env_overrides: &HashMap<String, String>,
     chorus_status: Option<&ChorusStatusConfig>,
-) -> Result<(), String> {
+) -> HashMap<String, Value> {
     let mut mcp_servers: HashMap<String, Value> = HashMap::new();
 
+    let env_overrides: HashMap<String, String> = env_overrides
+        .iter()
+        .filter(|(key, _)| {
+            let reserved = RESERVED_ENV_VARS.contains(&key.as_str());
+            if reserved {
+                log::warn!(
+                    "Ignoring override of reserved env var {} for session {}",
+                    key,
+                    session_id
+                );
+            }
+            !reserved
+        })
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
     // Add enabled discovered servers from project .mcp.json
     // Skip any chorus-managed entries — they get re-discovered from our own .mcp.json
     // writes and would carry stale env vars. We'll add the correct chorus-status below.
     for server in enabled_servers {
-        if should_remove_server(&server.name, &Value::Null, session_id) {
+        let config = server_config_to_json(server, &env_overrides);
+        if should_remove_server(&server.name, &config, session_id) {
             log::info!(
                 "Skipping discovered server '{}' (Chorus-managed, will be replaced)",
                 server.name
             );
             continue;
         }
-        mcp_servers.insert(server.name.clone(), server_config_to_json(server));
+        mcp_servers.insert(server.name.clone(), config);
     }
 
     // Add enabled custom servers (user-defined, global)
     for server in custom_servers {
-        mcp_servers.insert(server.name.clone(), custom_server_to_json(server));
+        mcp_servers.insert(server.name.clone(), custom_server_to_json(server, &env_overrides));
     }
 
     // Add the Chorus status server LAST so it always wins over any re-discovered version.
     // All three env vars must be explicit here because Claude CLI only passes env vars
     // listed in .mcp.json to MCP server processes (shell env is NOT inherited).
     // The "skip discovered chorus servers" logic above prevents stale re-discovered
-    // entries from overwriting these fresh values.
+    // entries from overwriting these fresh values. Session overrides apply here too.
     if let Some(config) = chorus_status {
+        let reserved = HashMap::from([
+            ("CHORUS_SESSION_ID".to_string(), session_id.to_string()),
+            ("CHORUS_STATUS_URL".to_string(), config.status_url.clone()),
+            ("CHORUS_INSTANCE_ID".to_string(), config.instance_id.clone()),
+        ]);
         let chorus_server = json!({
             "type": "stdio",
             "command": config.binary_path.to_string_lossy(),
             "args": [],
-            "env": {
-                "CHORUS_SESSION_ID": session_id.to_string(),
-                "CHORUS_STATUS_URL": config.status_url,
-                "CHORUS_INSTANCE_ID": config.instance_id
-            }
+            "env": overlay_env(&env_overrides, &reserved)
         });
         mcp_servers.insert("chorus-status".to_string(), chorus_server);
         log::info!(
@@ -226,13 +397,78 @@ pub async fn write_session_mcp_config(
         );
     }
 
-    // Merge with existing .mcp.json if present (preserve user servers AND other sessions)
-    let mcp_path = working_dir.join(".mcp.json");
-    let final_config = merge_with_existing(&mcp_path, mcp_servers, session_id)?;
+    mcp_servers
+}
 
-    // Write the file
-    let content = serde_json::to_string_pretty(&final_config)
-        .map_err(|e| format!("Failed to serialize MCP config: {}", e))?;
+/// Merges `servers` into the `.mcp.json` at `mcp_path` and returns the
+/// resulting file content. Reads the existing file but never writes it; the
+/// caller must hold the directory lock.
+///
+/// Only the entries Chorus generates are validated; servers the user already
+/// has in the file are passed through unchanged, even if malformed.
+fn render_session_mcp_config(
+    mcp_path: &Path,
+    servers: HashMap<String, Value>,
+    session_id: u32,
+) -> Result<String, String> {
+    // Refuse to write entries the Claude CLI would silently ignore
+    if let Err(problems) = validate_mcp_config(&json!({ "mcpServers": &servers })) {
+        log::error!(
+            "[MCP] Refusing to write invalid .mcp.json for session {} at {:?}: {:?}",
+            session_id,
+            mcp_path,
+            problems
+        );
+        return Err(format!("Invalid MCP config: {}", problems.join("; ")));
+    }
+
+    let final_config = merge_with_existing(mcp_path, servers, session_id)?;
+    serde_json::to_string_pretty(&final_config)
+        .map_err(|e| format!("Failed to serialize MCP config: {}", e))
+}
+
+/// Writes a session-specific `.mcp.json` to the working directory.
+///
+/// This function:
+/// 1. Adds the Chorus status server for real-time status reporting
+/// 2. Adds enabled discovered servers from the project's .mcp.json
+/// 3. Adds enabled custom servers (user-defined, global)
+///    Both get the session's env overrides merged over their own env
+/// 4. Merges with any existing `.mcp.json` (preserving user servers)
+/// 5. Atomically writes the final config to the working directory
+///
+/// Concurrent calls for the same working directory are serialized.
+///
+/// # Arguments
+///
+/// * `working_dir` - Directory where `.mcp.json` will be written
+/// * `session_id` - Session identifier used for merging
+/// * `enabled_servers` - List of discovered MCP server configs enabled for this session
+/// * `custom_servers` - List of custom MCP servers that are enabled
+/// * `env_overrides` - Session env vars overlaid on each stdio server's env
+/// * `chorus_status` - Optional configuration for the Chorus status MCP server
+pub async fn write_session_mcp_config(
+    working_dir: &Path,
+    session_id: u32,
+    enabled_servers: &[McpServerConfig],
+    custom_servers: &[McpCustomServer],
+    env_overrides: &HashMap<String, String>,
+    chorus_status: Option<&ChorusStatusConfig>,
+) -> Result<(), String> {
+    let mcp_servers = build_session_servers(
+        session_id,
+        enabled_servers,
+        custom_servers,
+        env_overrides,
+        chorus_status,
+    );
+
+    // Merge with existing .mcp.json if present (preserve user servers AND other sessions).
+    // Hold the directory lock across read, merge and write.
+    let lock = dir_lock(working_dir);
+    let _guard = lock.lock().await;
+    let mcp_path = working_dir.join(".mcp.json");
+    let content = render_session_mcp_config(&mcp_path, mcp_servers, session_id)?;
 
     log::info!(
         "[MCP] Writing .mcp.json to {:?} ({} bytes)",
@@ -240,7 +476,7 @@ pub async fn write_session_mcp_config(
         content.len()
     );
 
-    tokio::fs::write(&mcp_path, &content)
+    write_atomic(&mcp_path, &content)
         .await
         .map_err(|e| format!("Failed to write .mcp.json to {:?}: {}", mcp_path, e))?;
 
@@ -264,6 +500,33 @@ pub async fn write_session_mcp_config(
     Ok(())
 }
 
+/// Returns the `.mcp.json` that `write_session_mcp_config` would write with
+/// the same arguments, without touching the file.
+///
+/// Runs the same build and merge against the real existing file, so the
+/// preview matches what a write would produce at this moment.
+pub async fn preview_session_mcp_config(
+    working_dir: &Path,
+    session_id: u32,
+    enabled_servers: &[McpServerConfig],
+    custom_servers: &[McpCustomServer],
+    env_overrides: &HashMap<String, String>,
+    chorus_status: Option<&ChorusStatusConfig>,
+) -> Result<String, String> {
+    let mcp_servers = build_session_servers(
+        session_id,
+        enabled_servers,
+        custom_servers,
+        env_overrides,
+        chorus_status,
+    );
+
+    // Lock so a concurrent write can't be observed half-merged
+    let lock = dir_lock(working_dir);
+    let _guard = lock.lock().await;
+    render_session_mcp_config(&working_dir.join(".mcp.json"), mcp_servers, session_id)
+}
+
 /// Removes Chorus server entries from `.mcp.json`.
 ///
 /// This should be called when a session is killed to clean up the config file.
@@ -278,6 +541,8 @@ pub async fn write_session_mcp_config(
 /// * `working_dir` - Directory containing the `.mcp.json` file
 /// * `session_id` - Session identifier (used for logging, cleanup removes all Chorus entries)
 pub async fn remove_session_mcp_config(working_dir: &Path, session_id: u32) -> Result<(), String> {
+    let lock = dir_lock(working_dir);
+    let _guard = lock.lock().await;
     let mcp_path = working_dir.join(".mcp.json");
     if !mcp_path.exists() {
         return Ok(());
@@ -298,9 +563,9 @@ pub async fn remove_session_mcp_config(working_dir: &Path, session_id: u32) -> R
 
         // Also clean up any legacy per-session entries that might exist
         let legacy_keys: Vec<String> = servers
-            .keys()
-            .filter(|k| k.starts_with("chorus-status-") || k.starts_with("chorus-") || *k == "chorus")
-            .cloned()
+            .iter()
+            .filter(|(k, v)| should_remove_server(k, v, session_id))
+            .map(|(k, _)| k.clone())
             .collect();
 
         for key in legacy_keys {
@@ -313,7 +578,7 @@ pub async fn remove_session_mcp_config(working_dir: &Path, session_id: u32) -> R
     let output = serde_json::to_string_pretty(&config)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
 
-    tokio::fs::write(&mcp_path, output)
+    write_atomic(&mcp_path, &output)
         .await
         .map_err(|e| format!("Failed to write .mcp.json: {}", e))?;
 
@@ -341,7 +606,7 @@ mod tests {
             },
         };
 
-        let json = server_config_to_json(&config);
+        let json = server_config_to_json(&config, &HashMap::new());
         assert_eq!(json["type"], "stdio");
         assert_eq!(json["command"], "/usr/bin/test");
         assert_eq!(json["args"][0], "--flag");
@@ -354,12 +619,310 @@ mod tests {
             name: "test".to_string(),
             server_type: McpServerType::Http {
                 url: "http://localhost:3000".to_string(),
+                headers: HashMap::new(),
             },
         };
 
-        let json = server_config_to_json(&config);
+        let json = server_config_to_json(&config, &HashMap::new());
         assert_eq!(json["type"], "http");
         assert_eq!(json["url"], "http://localhost:3000");
+        assert!(json.get("headers").is_none());
+    }
+
+    #[test]
+    fn test_server_config_to_json_sse() {
+        let config = McpServerConfig {
+            name: "test".to_string(),
+            server_type: McpServerType::Sse {
+                url: "http://localhost:3000/sse".to_string(),
+                headers: HashMap::from([("X-Api-Key".to_string(), "k".to_string())]),
+            },
+        };
+
+        let json = server_config_to_json(&config, &HashMap::new());
+        assert_eq!(json["type"], "sse");
+        assert_eq!(json["url"], "http://localhost:3000/sse");
+        assert_eq!(json["headers"]["X-Api-Key"], "k");
+    }
+
+    /// Discovers `entry` from a project `.mcp.json`, writes it back out as a
+    /// session config and returns what was written.
+    async fn round_trip(entry: Value) -> Value {
+        let project = tempdir().unwrap();
+        let project_config = json!({ "mcpServers": { "srv": entry } });
+        std::fs::write(project.path().join(".mcp.json"), project_config.to_string()).unwrap();
+
+        let manager = crate::core::mcp_manager::McpManager::new();
+        let servers = manager.get_project_servers(&project.path().to_string_lossy());
+        assert_eq!(servers.len(), 1);
+
+        let session_dir = tempdir().unwrap();
+        write_session_mcp_config(session_dir.path(), 1, &servers, &[], &HashMap::new(), None)
+            .await
+            .unwrap();
+        let written = std::fs::read_to_string(session_dir.path().join(".mcp.json")).unwrap();
+        let written: Value = serde_json::from_str(&written).unwrap();
+        written["mcpServers"]["srv"].clone()
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_stdio() {
+        let entry = json!({
+            "type": "stdio",
+            "command": "/usr/bin/srv",
+            "args": ["--port", "1"],
+            "env": { "KEY": "value" }
+        });
+        assert_eq!(round_trip(entry.clone()).await, entry);
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_http() {
+        let entry = json!({ "type": "http", "url": "http://localhost:3000/mcp" });
+        assert_eq!(round_trip(entry.clone()).await, entry);
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_http_with_headers() {
+        let entry = json!({
+            "type": "http",
+            "url": "https://api.example.com/mcp",
+            "headers": { "Authorization": "Bearer t" }
+        });
+        assert_eq!(round_trip(entry.clone()).await, entry);
+    }
+
+    #[tokio::test]
+    async fn test_rewrite_preserves_http_headers() {
+        let dir = tempdir().unwrap();
+        let server = McpServerConfig {
+            name: "api".to_string(),
+            server_type: McpServerType::Http {
+                url: "https://api.example.com/mcp".to_string(),
+                headers: HashMap::from([("Authorization".to_string(), "Bearer t".to_string())]),
+            },
+        };
+
+        write_session_mcp_config(dir.path(), 1, &[server], &[], &HashMap::new(), None)
+            .await
+            .unwrap();
+        // A second session rewriting the same directory must keep the headers.
+        write_session_mcp_config(dir.path(), 2, &[], &[], &HashMap::new(), None)
+            .await
+            .unwrap();
+
+        let written = std::fs::read_to_string(dir.path().join(".mcp.json")).unwrap();
+        let written: Value = serde_json::from_str(&written).unwrap();
+        assert_eq!(
+            written["mcpServers"]["api"]["headers"]["Authorization"],
+            "Bearer t"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_sse() {
+        let entry = json!({
+            "type": "sse",
+            "url": "http://localhost:3000/sse",
+            "headers": { "Authorization": "Bearer t" }
+        });
+        assert_eq!(round_trip(entry.clone()).await, entry);
+    }
+
+    #[test]
+    fn test_merge_preserves_sse_servers() {
+        let dir = tempdir().unwrap();
+        let mcp_path = dir.path().join(".mcp.json");
+        let sse = json!({ "type": "sse", "url": "http://localhost:3000/sse" });
+        std::fs::write(&mcp_path, json!({ "mcpServers": { "events": sse } }).to_string()).unwrap();
+
+        let merged = merge_with_existing(&mcp_path, HashMap::new(), 1).unwrap();
+        assert_eq!(merged["mcpServers"]["events"], sse);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_writes_keep_every_session_entry() {
+        let dir = tempdir().unwrap();
+        let http = |name: &str| McpServerConfig {
+            name: name.to_string(),
+            server_type: McpServerType::Http {
+                url: format!("http://localhost/{}", name),
+                headers: HashMap::new(),
+            },
+        };
+        let servers: Vec<_> = (0..8).map(|i| http(&format!("srv-{}", i))).collect();
+        let no_overrides = HashMap::new();
+
+        let writes = servers.iter().enumerate().map(|(i, server)| {
+            write_session_mcp_config(
+                dir.path(),
+                i as u32,
+                std::slice::from_ref(server),
+                &[],
+                &no_overrides,
+                None,
+            )
+        });
+        for result in futures_util::future::join_all(writes).await {
+            result.unwrap();
+        }
+
+        let written = std::fs::read_to_string(dir.path().join(".mcp.json")).unwrap();
+        let written: Value = serde_json::from_str(&written).unwrap();
+        let names = written["mcpServers"].as_object().unwrap();
+        assert_eq!(names.len(), 8);
+
+        // The temp files used for atomic replacement must not linger.
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn test_merge_keeps_user_server_named_chorus_prefix() {
+        let dir = tempdir().unwrap();
+        let mcp_path = dir.path().join(".mcp.json");
+        let existing = json!({
+            "mcpServers": {
+                "chorus-docs": {
+                    "type": "stdio",
+                    "command": "/usr/bin/chorus-docs",
+                    "args": []
+                },
+                "chorus-status-2": {
+                    "type": "stdio",
+                    "command": "/usr/bin/chorus-status-2",
+                    "args": [],
+                    "env": { "CHORUS_SESSION_ID": "2" }
+                },
+                "status-bridge": {
+                    "type": "stdio",
+                    "command": "/opt/Chorus.app/Contents/Resources/chorus-mcp-server",
+                    "args": []
+                }
+            }
+        });
+        std::fs::write(&mcp_path, existing.to_string()).unwrap();
+
+        let merged = merge_with_existing(&mcp_path, HashMap::new(), 3).unwrap();
+        let servers = merged["mcpServers"].as_object().unwrap();
+
+        assert!(servers.contains_key("chorus-docs"), "user server should survive");
+        assert!(!servers.contains_key("chorus-status-2"), "legacy entry should be removed");
+        assert!(!servers.contains_key("status-bridge"), "chorus-mcp-server entry should be removed");
+    }
+
+    #[tokio::test]
+    async fn test_remove_session_keeps_user_server_named_chorus_prefix() {
+        let dir = tempdir().unwrap();
+        let existing = json!({
+            "mcpServers": {
+                "chorus-docs": { "type": "http", "url": "http://localhost/docs" },
+                "chorus-status-2": {
+                    "type": "stdio",
+                    "command": "/usr/bin/chorus-status-2",
+                    "args": [],
+                    "env": { "CHORUS_SESSION_ID": "2" }
+                }
+            }
+        });
+        std::fs::write(dir.path().join(".mcp.json"), existing.to_string()).unwrap();
+
+        remove_session_mcp_config(dir.path(), 2).await.unwrap();
+
+        let written = std::fs::read_to_string(dir.path().join(".mcp.json")).unwrap();
+        let written: Value = serde_json::from_str(&written).unwrap();
+        let servers = written["mcpServers"].as_object().unwrap();
+        assert!(servers.contains_key("chorus-docs"));
+        assert!(!servers.contains_key("chorus-status-2"));
+    }
+
+    #[test]
+    fn test_validate_accepts_every_transport() {
+        let config = json!({
+            "mcpServers": {
+                "legacy": { "command": "/usr/bin/srv" },
+                "local": { "type": "stdio", "command": "npx", "args": ["srv"], "env": { "K": "v" } },
+                "remote": { "type": "http", "url": "https://x/mcp", "headers": { "Authorization": "t" } },
+                "events": { "type": "sse", "url": "https://x/sse" }
+            }
+        });
+        assert_eq!(validate_mcp_config(&config), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_reports_every_problem() {
+        let config = json!({
+            "mcpServers": {
+                "no-command": { "type": "stdio", "args": [] },
+                "bad-type": { "type": "websocket", "url": "ws://x" },
+                "no-url": { "type": "http" },
+                "bad-env": { "command": "srv", "env": { "PORT": 8080 } },
+                "bad-args": { "command": "srv", "args": ["--port", 1] },
+                "bad-headers": { "type": "sse", "url": "https://x", "headers": ["a"] }
+            }
+        });
+        let problems = validate_mcp_config(&config).unwrap_err();
+        assert_eq!(problems.len(), 6, "{:?}", problems);
+        for name in ["no-command", "bad-type", "no-url", "bad-env", "bad-args", "bad-headers"] {
+            assert!(
+                problems.iter().any(|p| p.contains(&format!("'{}'", name))),
+                "missing problem for {}: {:?}",
+                name,
+                problems
+            );
+        }
+    }
+
+    #[test]
+    fn test_validate_requires_servers_object() {
+        assert!(validate_mcp_config(&json!({ "mcpServers": [] })).is_err());
+        assert!(validate_mcp_config(&json!({})).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_write_refuses_invalid_custom_server() {
+        let dir = tempdir().unwrap();
+        let custom = McpCustomServer {
+            id: "1".to_string(),
+            name: "broken".to_string(),
+            command: "  ".to_string(),
+            args: vec![],
+            env: HashMap::new(),
+            working_directory: None,
+            is_enabled: true,
+            created_at: String::new(),
+        };
+
+        let result =
+            write_session_mcp_config(dir.path(), 1, &[], &[custom], &HashMap::new(), None).await;
+        assert!(result.is_err());
+        assert!(!dir.path().join(".mcp.json").exists());
+    }
+
+    #[tokio::test]
+    async fn test_write_passes_malformed_user_server_through() {
+        let dir = tempdir().unwrap();
+        let mcp_path = dir.path().join(".mcp.json");
+        let user_server = json!({ "type": "websocket", "port": 8080 });
+        std::fs::write(
+            &mcp_path,
+            serde_json::to_string(&json!({ "mcpServers": { "mine": user_server } })).unwrap(),
+        )
+        .unwrap();
+
+        let chorus = ChorusStatusConfig {
+            binary_path: PathBuf::from("/usr/bin/chorus-mcp-server"),
+            status_url: "http://127.0.0.1:9900/status".to_string(),
+            instance_id: "test".to_string(),
+        };
+        write_session_mcp_config(dir.path(), 1, &[], &[], &HashMap::new(), Some(&chorus))
+            .await
+            .unwrap();
+
+        let written: Value =
+            serde_json::from_str(&std::fs::read_to_string(&mcp_path).unwrap()).unwrap();
+        assert_eq!(written["mcpServers"]["mine"], user_server);
+        assert!(written["mcpServers"].get("chorus-status").is_some());
     }
 
     #[tokio::test]
@@ -370,6 +933,7 @@ mod tests {
             1,
             &[],
             &[],
+            &HashMap::new(),
             None, // No chorus-status config for this test
         )
         .await;
@@ -378,6 +942,101 @@ mod tests {
         assert!(dir.path().join(".mcp.json").exists());
     }
 
+    #[tokio::test]
+    async fn test_preview_matches_write_without_touching_file() {
+        let dir = tempdir().unwrap();
+        let existing = json!({
+            "mcpServers": {
+                "user": { "type": "stdio", "command": "user-srv" },
+                "chorus-status": {
+                    "type": "stdio",
+                    "command": "/old/chorus-mcp-server",
+                    "env": { "CHORUS_SESSION_ID": "7" }
+                }
+            }
+        });
+        let original = serde_json::to_string_pretty(&existing).unwrap();
+        std::fs::write(dir.path().join(".mcp.json"), &original).unwrap();
+        let server = McpServerConfig {
+            name: "docs".to_string(),
+            server_type: McpServerType::Http {
+                url: "https://docs.example.com/mcp".to_string(),
+                headers: HashMap::new(),
+            },
+        };
+        let servers = std::slice::from_ref(&server);
+        let no_overrides = HashMap::new();
+
+        let preview =
+            preview_session_mcp_config(dir.path(), 1, servers, &[], &no_overrides, None)
+                .await
+                .unwrap();
+        let on_disk = std::fs::read_to_string(dir.path().join(".mcp.json")).unwrap();
+        assert_eq!(on_disk, original);
+
+        write_session_mcp_config(dir.path(), 1, servers, &[], &no_overrides, None)
+            .await
+            .unwrap();
+        let written = std::fs::read_to_string(dir.path().join(".mcp.json")).unwrap();
+        assert_eq!(preview, written);
+
+        let preview: Value = serde_json::from_str(&preview).unwrap();
+        assert!(preview["mcpServers"]["user"].is_object());
+        assert!(preview["mcpServers"]["docs"].is_object());
+        assert!(preview["mcpServers"].get("chorus-status").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_session_env_overrides_are_per_session() {
+        let server = McpServerConfig {
+            name: "github".to_string(),
+            server_type: McpServerType::Stdio {
+                command: "gh-mcp".to_string(),
+                args: vec![],
+                env: HashMap::from([
+                    ("GITHUB_TOKEN".to_string(), "base".to_string()),
+                    ("LOG".to_string(), "info".to_string()),
+                ]),
+            },
+        };
+        let chorus = ChorusStatusConfig {
+            binary_path: PathBuf::from("/bin/chorus-mcp-server"),
+            status_url: "http://127.0.0.1:1/status".to_string(),
+            instance_id: "instance".to_string(),
+        };
+        let overrides = HashMap::from([
+            ("GITHUB_TOKEN".to_string(), "session-1".to_string()),
+            ("CHORUS_SESSION_ID".to_string(), "99".to_string()),
+        ]);
+
+        let first = tempdir().unwrap();
+        let second = tempdir().unwrap();
+        let servers = std::slice::from_ref(&server);
+        write_session_mcp_config(first.path(), 1, servers, &[], &overrides, Some(&chorus))
+            .await
+            .unwrap();
+        write_session_mcp_config(second.path(), 2, servers, &[], &HashMap::new(), Some(&chorus))
+            .await
+            .unwrap();
+
+        let read = |dir: &Path| -> Value {
+            serde_json::from_str(&std::fs::read_to_string(dir.join(".mcp.json")).unwrap()).unwrap()
+        };
+        let first = read(first.path());
+        let env = &first["mcpServers"]["github"]["env"];
+        assert_eq!(env["GITHUB_TOKEN"], "session-1");
+        assert_eq!(env["LOG"], "info");
+        // Reserved vars can't be overridden on any server
+        assert!(env.get("CHORUS_SESSION_ID").is_none());
+        let chorus_env = &first["mcpServers"]["chorus-status"]["env"];
+        assert_eq!(chorus_env["CHORUS_SESSION_ID"], "1");
+        assert_eq!(chorus_env["GITHUB_TOKEN"], "session-1");
+
+        let second = read(second.path());
+        assert_eq!(second["mcpServers"]["github"]["env"]["GITHUB_TOKEN"], "base");
+        assert!(second["mcpServers"]["chorus-status"]["env"].get("GITHUB_TOKEN").is_none());
+    }
+
     #[test]
     fn test_merge_preserves_user_servers_removes_all_chorus() {
         let dir = tempdir().unwrap();
@@ -1,12 +1,21 @@
 //! MCP (Model Context Protocol) server discovery and session state management.
 //!
 //! This module parses `.mcp.json` files at project roots to discover configured
-//! MCP servers, and tracks which servers are enabled per session.
+//! MCP servers, and tracks which servers are enabled per session. Projects can
+//! opt in to having their `.mcp.json` watched so external edits refresh the
+//! cache without a manual reload.
 
 use dashmap::DashMap;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+/// Quiet period after a `.mcp.json` change before it is re-read, so an
+/// editor's burst of writes results in a single refresh.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(300);
 
 /// Configuration for an MCP server as read from `.mcp.json`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,8 +29,18 @@ pub enum McpServerType {
         #[serde(default)]
         env: HashMap<String, String>,
     },
-    /// HTTP-based MCP server.
-    Http { url: String },
+    /// HTTP-based MCP server, with optional request headers (e.g. auth).
+    Http {
+        url: String,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        headers: HashMap<String, String>,
+    },
+    /// Server-Sent Events MCP server, with optional request headers.
+    Sse {
+        url: String,
+        #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+        headers: HashMap<String, String>,
+    },
 }
 
 /// A named MCP server configuration.
@@ -54,6 +73,19 @@ struct McpServerEntry {
     env: Option<HashMap<String, String>>,
     #[serde(default)]
     url: Option<String>,
+    #[serde(default)]
+    headers: Option<HashMap<String, String>>,
+}
+
+/// How widely a server is enabled across all tracked sessions.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpServerUsage {
+    pub name: String,
+    /// Number of sessions that have this server enabled.
+    pub session_count: usize,
+    /// Number of distinct projects with at least one such session.
+    pub project_count: usize,
 }
 
 /// Session-specific key for enabled servers lookup.
@@ -63,18 +95,25 @@ type SessionKey = (String, u32); // (project_path, session_id)
 ///
 /// Thread-safe via `DashMap` — can be accessed from multiple async tasks.
 pub struct McpManager {
-    /// Cached MCP servers per project path (normalized).
-    project_servers: DashMap<String, Vec<McpServerConfig>>,
+    /// Cached MCP servers per project path (normalized). Shared with the
+    /// watcher threads that refresh it.
+    project_servers: Arc<DashMap<String, Vec<McpServerConfig>>>,
     /// Enabled server names per (project_path, session_id).
     session_enabled: DashMap<SessionKey, Vec<String>>,
+    /// Env vars overlaid on every stdio server's env for a session.
+    session_env: DashMap<SessionKey, HashMap<String, String>>,
+    /// Active `.mcp.json` watchers per project path; dropping one stops it.
+    watchers: DashMap<String, RecommendedWatcher>,
 }
 
 impl McpManager {
     /// Creates a new MCP manager with empty caches.
     pub fn new() -> Self {
         Self {
-            project_servers: DashMap::new(),
+            project_servers: Arc::new(DashMap::new()),
             session_enabled: DashMap::new(),
+            session_env: DashMap::new(),
+            watchers: DashMap::new(),
         }
     }
 
@@ -112,7 +151,17 @@ impl McpManager {
                     }
                     "http" => {
                         let url = entry.url?;
-                        McpServerType::Http { url }
+                        McpServerType::Http {
+                            url,
+                            headers: entry.headers.unwrap_or_default(),
+                        }
+                    }
+                    "sse" => {
+                        let url = entry.url?;
+                        McpServerType::Sse {
+                            url,
+                            headers: entry.headers.unwrap_or_default(),
+                        }
                     }
                     other => {
                         log::warn!("Unknown MCP server type '{}' for server '{}'", other, name);
@@ -147,6 +196,71 @@ impl McpManager {
         servers
     }
 
+    /// Starts watching the project's `.mcp.json`. When it is created,
+    /// edited or removed, the cache is refreshed and `on_change` is called
+    /// with the new server list. Does nothing if already watching.
+    ///
+    /// The project directory is watched rather than the file itself so that
+    /// editors which save by replacing the file are still noticed.
+    pub fn watch_project<F>(&self, project_path: &str, on_change: F) -> Result<(), String>
+    where
+        F: Fn(&str, &[McpServerConfig]) + Send + 'static,
+    {
+        if self.is_watching(project_path) {
+            return Ok(());
+        }
+
+        let (tx, rx) = mpsc::channel::<()>();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let Ok(event) = res else {
+                return;
+            };
+            let touches_config = event
+                .paths
+                .iter()
+                .any(|p| p.file_name().is_some_and(|n| n == ".mcp.json"));
+            if touches_config && !event.kind.is_access() {
+                let _ = tx.send(());
+            }
+        })
+        .map_err(|e| format!("Failed to create watcher: {}", e))?;
+        watcher
+            .watch(Path::new(project_path), RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch {}: {}", project_path, e))?;
+
+        // Ends once the watcher, and with it `tx`, is dropped
+        let cache = Arc::clone(&self.project_servers);
+        let path = project_path.to_string();
+        std::thread::spawn(move || {
+            while rx.recv().is_ok() {
+                loop {
+                    match rx.recv_timeout(WATCH_DEBOUNCE) {
+                        Ok(()) => continue,
+                        Err(mpsc::RecvTimeoutError::Timeout) => break,
+                        Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+                let servers = Self::parse_mcp_config(&path);
+                cache.insert(path.clone(), servers.clone());
+                log::info!("Reloaded .mcp.json for {} ({} servers)", path, servers.len());
+                on_change(&path, &servers);
+            }
+        });
+
+        self.watchers.insert(project_path.to_string(), watcher);
+        Ok(())
+    }
+
+    /// Stops watching the project's `.mcp.json`. Returns whether it was watched.
+    pub fn unwatch_project(&self, project_path: &str) -> bool {
+        self.watchers.remove(project_path).is_some()
+    }
+
+    /// Whether the project's `.mcp.json` is being watched.
+    pub fn is_watching(&self, project_path: &str) -> bool {
+        self.watchers.contains_key(project_path)
+    }
+
     /// Gets the enabled server names for a session.
     ///
     /// If not explicitly set, returns all available servers as enabled by default.
@@ -170,16 +284,154 @@ impl McpManager {
         self.session_enabled.insert(key, enabled);
     }
 
-    /// Removes session-enabled state when a session is closed.
+    /// Gets the env var overrides for a session (empty if none were set).
+    pub fn get_session_env(&self, project_path: &str, session_id: u32) -> HashMap<String, String> {
+        let key = (project_path.to_string(), session_id);
+        self.session_env
+            .get(&key)
+            .map(|env| env.clone())
+            .unwrap_or_default()
+    }
+
+    /// Sets the env var overrides for a session. They are merged on top of
+    /// each server's own env the next time the session's `.mcp.json` is
+    /// written. An empty map clears them.
+    pub fn set_session_env(
+        &self,
+        project_path: &str,
+        session_id: u32,
+        env: HashMap<String, String>,
+    ) {
+        let key = (project_path.to_string(), session_id);
+        if env.is_empty() {
+            self.session_env.remove(&key);
+        } else {
+            self.session_env.insert(key, env);
+        }
+    }
+
+    /// Removes session-enabled state and env overrides when a session is closed.
     pub fn remove_session(&self, project_path: &str, session_id: u32) {
         let key = (project_path.to_string(), session_id);
         self.session_enabled.remove(&key);
+        self.session_env.remove(&key);
     }
 
     /// Counts enabled MCP servers for a session.
     pub fn get_enabled_count(&self, project_path: &str, session_id: u32) -> usize {
         self.get_session_enabled(project_path, session_id).len()
     }
+
+    /// Aggregates the explicitly-set enabled servers of every session across
+    /// all projects, sorted by server name.
+    ///
+    /// Sessions that never had their selection set are not counted, since
+    /// their "all enabled" default is implicit.
+    pub fn server_usage(&self) -> Vec<McpServerUsage> {
+        let mut usage: BTreeMap<String, (usize, HashSet<String>)> = BTreeMap::new();
+
+        for entry in self.session_enabled.iter() {
+            let (project_path, _) = entry.key();
+            for name in entry.value() {
+                let (sessions, projects) = usage.entry(name.clone()).or_default();
+                *sessions += 1;
+                projects.insert(project_path.clone());
+            }
+        }
+
+        usage
+            .into_iter()
+            .map(|(name, (session_count, projects))| McpServerUsage {
+                name,
+                session_count,
+                project_count: projects.len(),
+            })
+            .collect()
+    }
+}
+
+/// A custom MCP server field that failed validation.
+///
+/// `field` names the offending form field (`name`, `command`), or is `None`
+/// when saving failed for another reason.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, thiserror::Error)]
+#[serde(rename_all = "camelCase")]
+#[error("{message}")]
+pub struct McpServerValidationError {
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl McpServerValidationError {
+    fn invalid(field: &str, message: impl Into<String>) -> Self {
+        Self {
+            field: Some(field.to_string()),
+            message: message.into(),
+        }
+    }
+}
+
+impl From<String> for McpServerValidationError {
+    fn from(message: String) -> Self {
+        Self { field: None, message }
+    }
+}
+
+/// Validates a custom MCP server before it is saved.
+///
+/// `other_names` are the names of the other saved servers. Returns warnings
+/// for problems that may only be apparent here, such as a command that is
+/// not on the app's PATH (the user's shell may add more directories).
+pub fn validate_custom_server(
+    name: &str,
+    command: &str,
+    other_names: &[&str],
+) -> Result<Vec<String>, McpServerValidationError> {
+    let name = name.trim();
+    let command = command.trim();
+
+    if name.is_empty() {
+        return Err(McpServerValidationError::invalid("name", "Name is required"));
+    }
+    if other_names.iter().any(|other| other.trim() == name) {
+        return Err(McpServerValidationError::invalid(
+            "name",
+            format!("A custom MCP server named \"{}\" already exists", name),
+        ));
+    }
+    if command.is_empty() {
+        return Err(McpServerValidationError::invalid("command", "Command is required"));
+    }
+
+    let mut warnings = Vec::new();
+    if resolve_command(command).is_none() {
+        warnings.push(format!(
+            "Command \"{}\" was not found on PATH; the server may fail to start",
+            command
+        ));
+    }
+    Ok(warnings)
+}
+
+/// Resolves a command to an existing file, either as a path or via PATH.
+fn resolve_command(command: &str) -> Option<PathBuf> {
+    let path = Path::new(command);
+    if path.components().count() > 1 || path.is_absolute() {
+        return path.is_file().then(|| path.to_path_buf());
+    }
+
+    let extensions: &[&str] = if cfg!(windows) {
+        &["", ".exe", ".cmd", ".bat"]
+    } else {
+        &[""]
+    };
+    let dirs = std::env::var_os("PATH")?;
+    std::env::split_paths(&dirs).find_map(|dir| {
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{}{}", command, ext)))
+            .find(|candidate| candidate.is_file())
+    })
 }
 
 impl Default for McpManager {
@@ -198,4 +450,76 @@ mod tests {
         let servers = manager.get_project_servers("/nonexistent/path");
         assert!(servers.is_empty());
     }
+
+    #[test]
+    fn test_server_usage_counts_sessions_and_projects() {
+        let manager = McpManager::new();
+        manager.set_session_enabled("/a", 1, vec!["fs".into(), "git".into()]);
+        manager.set_session_enabled("/a", 2, vec!["fs".into()]);
+        manager.set_session_enabled("/b", 1, vec!["fs".into()]);
+
+        let usage = manager.server_usage();
+        assert_eq!(usage.len(), 2);
+        assert_eq!(usage[0].name, "fs");
+        assert_eq!(usage[0].session_count, 3);
+        assert_eq!(usage[0].project_count, 2);
+        assert_eq!(usage[1].name, "git");
+        assert_eq!(usage[1].session_count, 1);
+        assert_eq!(usage[1].project_count, 1);
+    }
+
+    #[test]
+    fn test_validate_custom_server() {
+        let err = validate_custom_server("  ", "node", &[]).unwrap_err();
+        assert_eq!(err.field.as_deref(), Some("name"));
+
+        let err = validate_custom_server("fs", "", &[]).unwrap_err();
+        assert_eq!(err.field.as_deref(), Some("command"));
+
+        let err = validate_custom_server("fs ", "node", &["github", "fs"]).unwrap_err();
+        assert_eq!(err.field.as_deref(), Some("name"));
+        assert!(err.message.contains("already exists"));
+
+        let warnings =
+            validate_custom_server("fs", "definitely-not-a-real-binary-42", &[]).unwrap();
+        assert_eq!(warnings.len(), 1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = dir.path().join("server.sh");
+        std::fs::write(&script, "#!/bin/sh\n").unwrap();
+        let warnings =
+            validate_custom_server("fs", &script.to_string_lossy(), &[]).unwrap();
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_watch_refreshes_cache_once_per_burst() {
+        let dir = tempfile::tempdir().unwrap();
+        let project = dir.path().to_string_lossy().to_string();
+        let manager = McpManager::new();
+        assert!(manager.get_project_servers(&project).is_empty());
+
+        let (tx, rx) = mpsc::channel();
+        manager
+            .watch_project(&project, move |_, servers| {
+                let _ = tx.send(servers.len());
+            })
+            .unwrap();
+        assert!(manager.is_watching(&project));
+
+        let config = dir.path().join(".mcp.json");
+        std::fs::write(&config, r#"{"mcpServers": {}}"#).unwrap();
+        std::fs::write(
+            &config,
+            r#"{"mcpServers": {"fs": {"type": "stdio", "command": "mcp-fs"}}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(rx.recv_timeout(Duration::from_secs(5)), Ok(1));
+        assert!(rx.recv_timeout(WATCH_DEBOUNCE * 3).is_err(), "burst should refresh once");
+        assert_eq!(manager.get_project_servers(&project)[0].name, "fs");
+
+        assert!(manager.unwatch_project(&project));
+        assert!(!manager.is_watching(&project));
+    }
 }
@@ -0,0 +1,79 @@
+//! mDNS/Bonjour advertising for the web access server.
+//!
+//! Announces `_chorus._tcp` on the LAN so the mobile client can find a
+//! running Chorus by name instead of typing its IP. The TXT record carries a
+//! hash of the instance ID so clients can tell multiple Chorus instances
+//! apart; the instance ID itself authenticates the status server and must
+//! not be multicast.
+
+use mdns_sd::{ServiceDaemon, ServiceInfo};
+use sha2::{Digest, Sha256};
+
+/// DNS-SD service type advertised for the web access server.
+pub const SERVICE_TYPE: &str = "_chorus._tcp.local.";
+
+/// A registered mDNS service; dropped or stopped to withdraw it.
+pub struct MdnsAdvertiser {
+    daemon: ServiceDaemon,
+    fullname: String,
+}
+
+impl MdnsAdvertiser {
+    /// Register `_chorus._tcp` on `port`, tagged with a hash of `instance_id`.
+    pub fn start(port: u16, instance_id: &str) -> Result<Self, String> {
+        let public_id = public_id(instance_id);
+        let short_id = &public_id[..8];
+        let instance_name = format!("Chorus-{}", short_id);
+        let host_name = format!("chorus-{}.local.", short_id);
+
+        let info = ServiceInfo::new(
+            SERVICE_TYPE,
+            &instance_name,
+            &host_name,
+            "",
+            port,
+            &[("instance_id", public_id.as_str()), ("path", "/ws")][..],
+        )
+        .map_err(|e| format!("Invalid mDNS service info: {}", e))?
+        .enable_addr_auto();
+        let fullname = info.get_fullname().to_string();
+
+        let daemon = ServiceDaemon::new().map_err(|e| format!("Failed to start mDNS daemon: {}", e))?;
+        daemon
+            .register(info)
+            .map_err(|e| format!("Failed to register mDNS service: {}", e))?;
+
+        log::info!("Advertising {} on port {} via mDNS", fullname, port);
+        Ok(Self { daemon, fullname })
+    }
+
+    /// Withdraw the service and stop the mDNS daemon.
+    pub fn stop(self) {
+        if let Err(e) = self.daemon.unregister(&self.fullname) {
+            log::warn!("Failed to unregister mDNS service {}: {}", self.fullname, e);
+        }
+        if let Err(e) = self.daemon.shutdown() {
+            log::warn!("Failed to shut down mDNS daemon: {}", e);
+        }
+        log::info!("Stopped advertising {}", self.fullname);
+    }
+}
+
+/// Non-secret stand-in for `instance_id`: the first 12 hex digits of its SHA-256.
+fn public_id(instance_id: &str) -> String {
+    hex::encode(&Sha256::digest(instance_id.as_bytes())[..6])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn public_id_is_stable_and_does_not_reveal_the_instance_id() {
+        let instance_id = "3f2a9c1e-0b7d-4e8a-9f61-2c5d8e7a4b10";
+        let id = public_id(instance_id);
+        assert_eq!(id, public_id(instance_id));
+        assert_eq!(id.len(), 12);
+        assert!(!instance_id.replace('-', "").contains(&id[..8]));
+    }
+}
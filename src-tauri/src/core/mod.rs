@@ -1,3 +1,4 @@
+pub mod ansi;
 pub mod error;
 pub mod event_bus;
 pub mod intel_hub;
@@ -12,20 +13,28 @@ pub mod plugin_config_writer;
 pub mod plugin_manager;
 pub mod process_manager;
 pub mod process_tree;
+pub mod scrollback_log;
 pub mod session_manager;
+pub mod session_recorder;
 pub mod status_server;
+pub mod store_debouncer;
 pub mod terminal_backend;
 pub mod tunnel_manager;
 pub mod web_access_server;
 pub mod web_dispatch;
+pub mod web_tls;
 pub mod windows_process;
 pub mod worktree_manager;
 pub mod xterm_backend;
 
+#[cfg(feature = "mdns")]
+pub mod mdns_advertiser;
+#[cfg(feature = "vte-backend")]
+pub mod screen_grid;
 #[cfg(feature = "vte-backend")]
 pub mod vte_backend;
 
-pub use error::PtyError;
+pub use error::{CliNotFound, PtyError};
 pub use event_bus::EventBus;
 pub use font_detector::{detect_available_fonts, is_font_available, AvailableFont};
 pub use marketplace_manager::MarketplaceManager;
@@ -35,8 +44,8 @@ pub use process_manager::ProcessManager;
 pub use session_manager::SessionManager;
 pub use status_server::StatusServer;
 pub use terminal_backend::{
-    BackendCapabilities, BackendType, SubscriptionHandle, TerminalBackend, TerminalConfig,
-    TerminalError, TerminalState,
+    BackendCapabilities, BackendType, ResizeGranularity, ScreenSnapshot, SubscriptionHandle,
+    TerminalBackend, TerminalConfig, TerminalError, TerminalState,
 };
 pub use tunnel_manager::TunnelManager;
 pub use web_access_server::WebAccessServer;
@@ -45,4 +54,4 @@ pub use xterm_backend::XtermPassthroughBackend;
 pub use process_tree::{ProcessError, ProcessInfo, SessionProcessTree};
 
 #[cfg(feature = "vte-backend")]
-pub use vte_backend::VteBackend;
+pub use vte_backend::{VteBackend, VteSessions};
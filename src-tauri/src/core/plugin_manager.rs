@@ -83,6 +83,9 @@ pub struct SkillConfig {
     pub context: Option<String>,
     /// Subagent type when context="fork".
     pub agent: Option<String>,
+    /// Names or IDs of other skills this one relies on.
+    #[serde(default)]
+    pub requires: Vec<String>,
 }
 
 /// The source/origin of a plugin bundle.
@@ -192,6 +195,8 @@ struct RawSkillEntry {
     command: Option<String>,
     #[serde(default)]
     args: Option<Vec<String>>,
+    #[serde(default)]
+    requires: Vec<String>,
 }
 
 /// Raw plugin entry from JSON.
@@ -227,6 +232,9 @@ struct PluginManifest {
     description: Option<String>,
     #[serde(default)]
     icon: Option<String>,
+    /// Skills every skill in this plugin relies on.
+    #[serde(default)]
+    requires: Vec<String>,
 }
 
 /// Parsed YAML frontmatter from a skill/command markdown file.
@@ -241,6 +249,7 @@ struct Frontmatter {
     model: Option<String>,
     context: Option<String>,
     agent: Option<String>,
+    requires: Vec<String>,
 }
 
 impl Frontmatter {
@@ -266,12 +275,22 @@ impl Frontmatter {
         let yaml_content = &after_first[..end_idx];
 
         // Parse line by line (simple key: value parsing)
+        let mut in_requires_list = false;
         for line in yaml_content.lines() {
             let line = line.trim();
             if line.is_empty() || line.starts_with('#') {
                 continue;
             }
 
+            // Block list items under `requires:`
+            if in_requires_list {
+                if let Some(item) = line.strip_prefix("- ") {
+                    fm.requires.push(unquote(item).to_string());
+                    continue;
+                }
+                in_requires_list = false;
+            }
+
             let Some((key, value)) = line.split_once(':') else {
                 continue;
             };
@@ -293,6 +312,17 @@ impl Frontmatter {
                 "model" => fm.model = Some(value.to_string()),
                 "context" => fm.context = Some(value.to_string()),
                 "agent" => fm.agent = Some(value.to_string()),
+                // Either `requires: [a, b]`, `requires: a, b` or a block list
+                "requires" => {
+                    let list = value.trim_start_matches('[').trim_end_matches(']');
+                    fm.requires = list
+                        .split(',')
+                        .map(unquote)
+                        .filter(|s| !s.is_empty())
+                        .map(String::from)
+                        .collect();
+                    in_requires_list = value.is_empty();
+                }
                 _ => {}
             }
         }
@@ -301,6 +331,11 @@ impl Frontmatter {
     }
 }
 
+/// Trims whitespace and surrounding quotes from a frontmatter value.
+fn unquote(value: &str) -> &str {
+    value.trim().trim_matches('"').trim_matches('\'')
+}
+
 /// Scans a skills directory for SKILL.md files in subdirectories.
 /// Pattern: `dir/*/SKILL.md`
 fn scan_skills_directory(dir: &Path, source: SkillSource) -> Vec<SkillConfig> {
@@ -356,6 +391,7 @@ fn scan_skills_directory(dir: &Path, source: SkillSource) -> Vec<SkillConfig> {
             model: fm.model,
             context: fm.context,
             agent: fm.agent,
+            requires: fm.requires,
         });
     }
 
@@ -419,6 +455,7 @@ fn scan_commands_directory(dir: &Path, source: SkillSource) -> Vec<SkillConfig>
             model: fm.model,
             context: fm.context,
             agent: fm.agent,
+            requires: fm.requires,
         });
     }
 
@@ -477,6 +514,17 @@ fn scan_plugins_directory(dir: &Path) -> Vec<(PluginConfig, Vec<SkillConfig>)> {
             plugin_skills.extend(scan_commands_directory(&commands_dir, source.clone()));
         }
 
+        // Plugin-level requirements apply to all of its skills
+        if let Some(m) = manifest.as_ref() {
+            for skill in &mut plugin_skills {
+                for req in &m.requires {
+                    if !skill.requires.contains(req) {
+                        skill.requires.push(req.clone());
+                    }
+                }
+            }
+        }
+
         let skill_ids: Vec<String> = plugin_skills.iter().map(|s| s.id.clone()).collect();
 
         let plugin = PluginConfig {
@@ -535,6 +583,103 @@ fn deduplicate_skills(skills: Vec<SkillConfig>) -> Vec<SkillConfig> {
     result
 }
 
+/// Why a set of enabled skills can't be satisfied.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SkillDependencyError {
+    /// A requirement doesn't match any discovered skill.
+    #[error("skill '{skill}' requires '{requirement}', which is not installed")]
+    NotFound { skill: String, requirement: String },
+
+    /// Requirements exist but aren't enabled, and auto-enabling is off.
+    #[error("skill '{skill}' requires skills that are not enabled: {}", missing.join(", "))]
+    NotEnabled { skill: String, missing: Vec<String> },
+
+    /// Skills require each other in a loop.
+    #[error("skill dependency cycle: {}", cycle.join(" -> "))]
+    Cycle { cycle: Vec<String> },
+}
+
+/// Expands `enabled` with the skills it requires, transitively.
+///
+/// Requirements name another skill by ID or name. Returns the effective set
+/// in order: the requested IDs first, then added dependencies. Enabled IDs
+/// that aren't in `skills` are kept as-is.
+fn resolve_skill_dependencies(
+    skills: &[SkillConfig],
+    enabled: Vec<String>,
+    auto_enable_deps: bool,
+) -> Result<Vec<String>, SkillDependencyError> {
+    let by_id: HashMap<&str, &SkillConfig> = skills.iter().map(|s| (s.id.as_str(), s)).collect();
+    let lookup = |reference: &str| -> Option<&SkillConfig> {
+        by_id
+            .get(reference)
+            .copied()
+            .or_else(|| skills.iter().find(|s| s.name == reference))
+    };
+
+    // Depth-first walk; `path` holds the skills currently being visited
+    fn visit<'a>(
+        skill: &'a SkillConfig,
+        lookup: &dyn Fn(&str) -> Option<&'a SkillConfig>,
+        path: &mut Vec<&'a str>,
+        done: &mut HashSet<&'a str>,
+        required: &mut Vec<&'a str>,
+    ) -> Result<(), SkillDependencyError> {
+        if done.contains(skill.id.as_str()) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|id| *id == skill.id) {
+            let mut cycle: Vec<String> = path[start..].iter().map(|id| id.to_string()).collect();
+            cycle.push(skill.id.clone());
+            return Err(SkillDependencyError::Cycle { cycle });
+        }
+
+        path.push(&skill.id);
+        for requirement in &skill.requires {
+            let dep = lookup(requirement).ok_or_else(|| SkillDependencyError::NotFound {
+                skill: skill.id.clone(),
+                requirement: requirement.clone(),
+            })?;
+            visit(dep, lookup, path, done, required)?;
+            required.push(&dep.id);
+        }
+        path.pop();
+        done.insert(&skill.id);
+        Ok(())
+    }
+
+    let mut done = HashSet::new();
+    let mut effective = enabled;
+    for id in effective.clone() {
+        let Some(skill) = by_id.get(id.as_str()).copied() else {
+            continue;
+        };
+
+        let mut required = Vec::new();
+        visit(skill, &lookup, &mut Vec::new(), &mut done, &mut required)?;
+
+        let missing: Vec<&str> = required
+            .into_iter()
+            .filter(|dep| !effective.iter().any(|e| e == dep))
+            .collect();
+        if missing.is_empty() {
+            continue;
+        }
+        if !auto_enable_deps {
+            let mut missing: Vec<String> = missing.into_iter().map(String::from).collect();
+            missing.dedup();
+            return Err(SkillDependencyError::NotEnabled { skill: id, missing });
+        }
+        for dep in missing {
+            if !effective.iter().any(|e| e == dep) {
+                effective.push(dep.to_string());
+            }
+        }
+    }
+
+    Ok(effective)
+}
+
 /// Session-specific key for enabled items lookup.
 type SessionKey = (String, u32); // (project_path, session_id)
 
@@ -636,6 +781,7 @@ impl PluginManager {
                     model: None,
                     context: None,
                     agent: None,
+                    requires: entry.requires,
                 })
             })
             .collect();
@@ -808,6 +954,12 @@ impl PluginManager {
         plugins
     }
 
+    /// Drops every cached discovery so newly installed user plugins show up
+    /// on the next lookup for any project.
+    pub fn invalidate_all(&self) {
+        self.project_plugins.clear();
+    }
+
     /// Gets the enabled skill IDs for a session.
     ///
     /// If not explicitly set, returns all available skills as enabled by default.
@@ -826,10 +978,24 @@ impl PluginManager {
             .collect()
     }
 
-    /// Sets the enabled skill IDs for a session.
-    pub fn set_session_skills(&self, project_path: &str, session_id: u32, enabled: Vec<String>) {
+    /// Sets the enabled skill IDs for a session and returns the effective set.
+    ///
+    /// With `auto_enable_deps`, skills required by enabled skills are added
+    /// transitively. Otherwise any missing requirement is an error. Cycles
+    /// are always rejected, and nothing is stored on error.
+    pub fn set_session_skills(
+        &self,
+        project_path: &str,
+        session_id: u32,
+        enabled: Vec<String>,
+        auto_enable_deps: bool,
+    ) -> Result<Vec<String>, SkillDependencyError> {
+        let skills = self.get_project_plugins(project_path).skills;
+        let effective = resolve_skill_dependencies(&skills, enabled, auto_enable_deps)?;
+
         let key = (project_path.to_string(), session_id);
-        self.session_enabled_skills.insert(key, enabled);
+        self.session_enabled_skills.insert(key, effective.clone());
+        Ok(effective)
     }
 
     /// Gets the enabled plugin IDs for a session.
@@ -892,4 +1058,88 @@ mod tests {
         assert!(plugins.skills.is_empty());
         assert!(plugins.plugins.is_empty());
     }
+
+    fn skill(id: &str, requires: &[&str]) -> SkillConfig {
+        SkillConfig {
+            id: format!("project:{}", id),
+            name: id.to_string(),
+            description: String::new(),
+            icon: None,
+            skill_type: SkillType::Prompt { prompt: String::new() },
+            plugin_id: None,
+            source: SkillSource::Project,
+            path: None,
+            argument_hint: None,
+            disable_model_invocation: false,
+            user_invocable: true,
+            allowed_tools: None,
+            model: None,
+            context: None,
+            agent: None,
+            requires: requires.iter().map(|r| r.to_string()).collect(),
+        }
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| format!("project:{}", id)).collect()
+    }
+
+    #[test]
+    fn test_dependency_chain_is_enabled_transitively() {
+        // deploy -> build -> lint, by name and by ID
+        let skills = vec![
+            skill("deploy", &["build"]),
+            skill("build", &["project:lint"]),
+            skill("lint", &[]),
+            skill("unrelated", &[]),
+        ];
+
+        let effective = resolve_skill_dependencies(&skills, ids(&["deploy"]), true).unwrap();
+        assert_eq!(effective, ids(&["deploy", "lint", "build"]));
+
+        let err = resolve_skill_dependencies(&skills, ids(&["deploy", "lint"]), false).unwrap_err();
+        assert_eq!(
+            err,
+            SkillDependencyError::NotEnabled {
+                skill: "project:deploy".to_string(),
+                missing: ids(&["build"]),
+            }
+        );
+
+        // Already satisfied: unchanged either way
+        let all = ids(&["deploy", "build", "lint"]);
+        assert_eq!(resolve_skill_dependencies(&skills, all.clone(), false).unwrap(), all);
+    }
+
+    #[test]
+    fn test_dependency_cycle_is_rejected() {
+        let skills = vec![
+            skill("a", &["b"]),
+            skill("b", &["c"]),
+            skill("c", &["a"]),
+        ];
+
+        let err = resolve_skill_dependencies(&skills, ids(&["a"]), true).unwrap_err();
+        assert_eq!(
+            err,
+            SkillDependencyError::Cycle { cycle: ids(&["a", "b", "c", "a"]) }
+        );
+    }
+
+    #[test]
+    fn test_unknown_requirement_is_reported() {
+        let skills = vec![skill("a", &["missing"])];
+        let err = resolve_skill_dependencies(&skills, ids(&["a"]), true).unwrap_err();
+        assert!(matches!(err, SkillDependencyError::NotFound { requirement, .. } if requirement == "missing"));
+    }
+
+    #[test]
+    fn test_frontmatter_requires() {
+        let inline = Frontmatter::parse("---\nname: x\nrequires: [a, \"b\"]\n---\nbody");
+        assert_eq!(inline.requires, vec!["a", "b"]);
+
+        let block = Frontmatter::parse("---\nrequires:\n  - a\n  - b\nmodel: opus\n---\n");
+        assert_eq!(block.requires, vec!["a", "b"]);
+        assert_eq!(block.model.as_deref(), Some("opus"));
+    }
 }
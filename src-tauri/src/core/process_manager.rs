@@ -1,18 +1,24 @@
 use std::collections::HashMap;
 use std::io::{Read, Write};
-use std::sync::atomic::{AtomicU32, Ordering};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 
 use dashmap::DashMap;
 use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
-use tauri::{AppHandle, Emitter, Manager};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
 use tokio::sync::Notify;
 
 #[cfg(unix)]
 use libc;
 
-use super::error::PtyError;
+use super::error::{PtyError, PtyErrorCode};
+use super::scrollback_log::{self, ScrollbackLog};
+use super::session_recorder::AsciicastRecorder;
+use super::terminal_backend::{BackendType, ScreenSnapshot};
 
 /// Stateful UTF-8 decoder that handles split multi-byte sequences.
 ///
@@ -76,6 +82,323 @@ impl Utf8Decoder {
     }
 }
 
+/// Default per-session scrollback retained for `get_session_output`, in bytes.
+pub const DEFAULT_SCROLLBACK_BYTES: usize = 100_000;
+/// Smallest scrollback limit accepted by `set_scrollback_limit`.
+pub const MIN_SCROLLBACK_BYTES: usize = 4 * 1024;
+/// Largest scrollback limit accepted by `set_scrollback_limit`.
+pub const MAX_SCROLLBACK_BYTES: usize = 16 * 1024 * 1024;
+
+/// Default cap on bytes returned by one `get_session_output_since` call.
+pub const DEFAULT_OUTPUT_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Default time PTY output is held to merge chunks into one event, in ms.
+pub const DEFAULT_COALESCE_WINDOW_MS: u64 = 4;
+/// Largest window accepted by `set_output_coalesce_window`, in ms.
+pub const MAX_COALESCE_WINDOW_MS: u64 = 100;
+/// Coalesced output is flushed as soon as it reaches this many bytes.
+const COALESCE_MAX_FLUSH_BYTES: usize = 16 * 1024;
+
+/// Merges bursts of small PTY output chunks into fewer, larger events.
+///
+/// The first chunk of a batch starts the window; the batch is flushed when
+/// the window ends or it reaches `COALESCE_MAX_FLUSH_BYTES`, whichever comes
+/// first. A zero window passes every chunk straight through.
+pub(crate) struct OutputCoalescer {
+    pending: String,
+    /// When the current batch must be flushed; None while it's empty.
+    deadline: Option<Instant>,
+}
+
+impl OutputCoalescer {
+    pub fn new() -> Self {
+        Self {
+            pending: String::new(),
+            deadline: None,
+        }
+    }
+
+    /// Adds output, returning a batch if it's due for flushing.
+    pub fn push(&mut self, text: &str, window: Duration, now: Instant) -> Option<String> {
+        self.pending.push_str(text);
+        let deadline = *self.deadline.get_or_insert(now + window);
+        if self.pending.len() >= COALESCE_MAX_FLUSH_BYTES || now >= deadline {
+            return self.take();
+        }
+        None
+    }
+
+    /// When the pending batch is due, if there is one.
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// Takes the pending batch, if any.
+    pub fn take(&mut self) -> Option<String> {
+        self.deadline = None;
+        (!self.pending.is_empty()).then(|| std::mem::take(&mut self.pending))
+    }
+}
+
+/// Cap on output held back while a session's output is paused. Past this the
+/// oldest held output is dropped (it's still in scrollback, up to its limit).
+const MAX_HELD_OUTPUT_BYTES: usize = MAX_SCROLLBACK_BYTES;
+
+/// Output produced while a session's output events are paused, delivered in
+/// order on resume.
+pub(crate) struct HeldOutput {
+    data: String,
+    dropped: usize,
+}
+
+impl HeldOutput {
+    pub fn new() -> Self {
+        Self {
+            data: String::new(),
+            dropped: 0,
+        }
+    }
+
+    pub fn push(&mut self, text: &str) {
+        self.data.push_str(text);
+        if self.data.len() > MAX_HELD_OUTPUT_BYTES {
+            let mut drain_to = self.data.len() - MAX_HELD_OUTPUT_BYTES;
+            while !self.data.is_char_boundary(drain_to) {
+                drain_to += 1;
+            }
+            self.data.drain(..drain_to);
+            self.dropped += drain_to;
+        }
+    }
+
+    /// Takes everything held, split on char boundaries into chunks of at
+    /// most `max_bytes` so resuming doesn't produce one giant event.
+    pub fn drain(&mut self, max_bytes: usize) -> Vec<String> {
+        if self.dropped > 0 {
+            log::warn!("Dropped {} bytes of output held while paused", self.dropped);
+            self.dropped = 0;
+        }
+        let data = std::mem::take(&mut self.data);
+        let mut chunks = Vec::new();
+        let mut rest = data.as_str();
+        while !rest.is_empty() {
+            let mut end = max_bytes.max(4).min(rest.len());
+            while !rest.is_char_boundary(end) {
+                end -= 1;
+            }
+            let (chunk, tail) = rest.split_at(end);
+            chunks.push(chunk.to_string());
+            rest = tail;
+        }
+        chunks
+    }
+}
+
+/// Bounded buffer of a session's recent output.
+///
+/// Once the buffer grows past `limit` bytes the oldest output is dropped,
+/// trimming down to 80% of the limit so trimming doesn't run on every chunk.
+#[derive(Debug)]
+pub(crate) struct ScrollbackBuffer {
+    data: String,
+    limit: usize,
+    /// Total bytes dropped from the front since the session started.
+    dropped: u64,
+}
+
+impl ScrollbackBuffer {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            data: String::new(),
+            limit,
+            dropped: 0,
+        }
+    }
+
+    /// Appends output, dropping the oldest bytes if over the limit.
+    pub fn push(&mut self, text: &str) {
+        self.data.push_str(text);
+        if self.data.len() > self.limit {
+            self.trim_to(self.limit / 5 * 4);
+        }
+    }
+
+    /// Changes the limit, trimming immediately if the buffer is now too large.
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        if self.data.len() > limit {
+            self.trim_to(limit);
+        }
+    }
+
+    /// Drops oldest bytes so at most `keep` remain, on a char boundary.
+    fn trim_to(&mut self, keep: usize) {
+        let mut drain_to = self.data.len().saturating_sub(keep);
+        while !self.data.is_char_boundary(drain_to) {
+            drain_to += 1;
+        }
+        self.data.drain(..drain_to);
+        self.dropped += drain_to as u64;
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.data
+    }
+
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Reads up to `max_bytes` of output starting at absolute stream `offset`.
+    ///
+    /// Offsets count every byte the session has produced, including bytes
+    /// already dropped, so they stay valid as the buffer is trimmed. An offset
+    /// that has fallen out of the buffer resumes at the oldest retained byte.
+    pub fn read_since(&self, offset: u64, max_bytes: usize) -> SessionOutputChunk {
+        let end = self.dropped + self.data.len() as u64;
+        let start = offset.clamp(self.dropped, end);
+        let from = (start - self.dropped) as usize;
+        let mut to = from.saturating_add(max_bytes.max(1)).min(self.data.len());
+        while !self.data.is_char_boundary(to) {
+            to -= 1;
+        }
+        // A single char wider than max_bytes must still make progress.
+        if to == from && from < self.data.len() {
+            to = from + self.data[from..].chars().next().map_or(0, char::len_utf8);
+        }
+        SessionOutputChunk {
+            data: self.data[from..to].to_string(),
+            offset: start,
+            next_offset: self.dropped + to as u64,
+            has_more: to < self.data.len(),
+            truncated: offset < self.dropped,
+        }
+    }
+
+    pub fn info(&self) -> SessionOutputInfo {
+        SessionOutputInfo {
+            buffered_bytes: self.data.len(),
+            limit_bytes: self.limit,
+            dropped_bytes: self.dropped,
+        }
+    }
+}
+
+/// A slice of session output returned by `get_session_output_since`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionOutputChunk {
+    pub data: String,
+    /// Absolute stream offset of the first byte of `data`.
+    pub offset: u64,
+    /// Offset to pass to the next call to continue after `data`.
+    pub next_offset: u64,
+    /// Whether more buffered output follows `next_offset`.
+    pub has_more: bool,
+    /// True if the requested offset had already been dropped from scrollback.
+    pub truncated: bool,
+}
+
+impl SessionOutputChunk {
+    /// Strips ANSI escapes from `data`. Offsets still refer to the raw
+    /// stream so paging keeps working. An escape sequence cut off at the end
+    /// of the chunk is left for the next read, unless it fills the whole
+    /// chunk and holding it back would stall paging.
+    pub fn strip_ansi(mut self) -> Self {
+        let (data, pending) = super::ansi::strip_ansi_partial(&self.data);
+        if let Some(at) = pending.filter(|&at| at > 0 || !self.has_more) {
+            self.next_offset = self.offset + at as u64;
+        }
+        self.data = data;
+        self
+    }
+}
+
+/// How much scrollback a session currently holds.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionOutputInfo {
+    /// Bytes available from `get_session_output`.
+    pub buffered_bytes: usize,
+    /// Configured scrollback limit in bytes.
+    pub limit_bytes: usize,
+    /// Bytes of older output already discarded.
+    pub dropped_bytes: u64,
+}
+
+/// Event emitted (via `app.emit` and the `EventBus`) when a session's shell exits.
+pub const SESSION_EXITED_EVENT: &str = "session:exited";
+
+/// Event emitted (via `app.emit` and the `EventBus`) once a session's shell
+/// has been restarted in place by `restart_session`.
+pub const SESSION_RESTARTED_EVENT: &str = "session:restarted";
+
+/// Payload of `SESSION_RESTARTED_EVENT`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionRestartedPayload {
+    pub session_id: u32,
+    /// PID of the new shell process.
+    pub pid: i32,
+}
+
+/// Why a session's shell exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExitReason {
+    /// Terminated by `kill_session`.
+    Killed,
+    /// Exited on its own with status 0.
+    Exited,
+    /// Exited on its own with a non-zero status or a signal.
+    Crashed,
+}
+
+/// Payload of `SESSION_EXITED_EVENT`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionExitedPayload {
+    pub session_id: u32,
+    pub exit_code: u32,
+    /// Signal name if the process was terminated by a signal.
+    pub signal: Option<String>,
+    pub reason: ExitReason,
+}
+
+impl SessionExitedPayload {
+    fn new(session_id: u32, status: &portable_pty::ExitStatus, killed: bool) -> Self {
+        let reason = if killed {
+            ExitReason::Killed
+        } else if status.success() {
+            ExitReason::Exited
+        } else {
+            ExitReason::Crashed
+        };
+        Self {
+            session_id,
+            exit_code: status.exit_code(),
+            signal: status.signal().map(String::from),
+            reason,
+        }
+    }
+}
+
+/// Size, cursor and screen mode of a session's terminal.
+///
+/// `rows`/`cols` are the last size applied via `resize_pty` (24x80 until the
+/// first resize). Cursor fields are only available when a grid-capable
+/// backend (`vte-backend`) is compiled in.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTerminalState {
+    pub rows: u16,
+    pub cols: u16,
+    pub cursor_row: Option<u16>,
+    pub cursor_col: Option<u16>,
+    /// Whether a full-screen program has switched to the alternate screen.
+    pub alt_screen: bool,
+}
+
 /// A single PTY session with its associated resources.
 struct PtySession {
     /// Writer half of the PTY master — used for stdin.
@@ -93,13 +416,90 @@ struct PtySession {
     shutdown: Arc<Notify>,
     /// Handle to the dedicated reader OS thread.
     reader_handle: Mutex<Option<JoinHandle<()>>>,
-    /// Buffer for session output, capped at 100KB.
-    output_buffer: Arc<Mutex<String>>,
+    /// Recent session output, capped by a configurable scrollback limit.
+    output_buffer: Arc<Mutex<ScrollbackBuffer>>,
+    /// Last PTY size as `(rows, cols)`, updated on every resize.
+    size: Mutex<(u16, u16)>,
+    /// Active asciicast recording, shared with the event emitter task.
+    recorder: Arc<Mutex<Option<AsciicastRecorder>>>,
+    /// On-disk copy of the scrollback while persistence is enabled.
+    scrollback_log: Arc<Mutex<Option<ScrollbackLog>>>,
+    /// Set by `kill_session` so the exit watcher can tell a kill from a crash.
+    killed: Arc<AtomicBool>,
+    /// Whether output events are paused; the emitter holds output meanwhile.
+    output_paused: tokio::sync::watch::Sender<bool>,
+    /// Backend used to interpret this session's output for screen/state queries.
+    backend: BackendType,
+    /// Working directory and caller env the shell was spawned with, reused by
+    /// `restart_session`.
+    spawn_cwd: Option<String>,
+    spawn_env: HashMap<String, String>,
+}
+
+/// Tags each shell spawned for a session ID, so the waiter of a shell that
+/// `restart_session` replaced can tell and stay quiet about its exit.
+#[derive(Default)]
+struct ShellGenerations {
+    next: AtomicU64,
+    current: DashMap<u32, u64>,
+}
+
+impl ShellGenerations {
+    /// Records a new shell for `id`, superseding any earlier one.
+    fn begin(&self, id: u32) -> u64 {
+        let generation = self.next.fetch_add(1, Ordering::Relaxed);
+        self.current.insert(id, generation);
+        generation
+    }
+
+    /// Ends `generation` of `id`. False if a newer shell has replaced it.
+    fn finish(&self, id: u32, generation: u64) -> bool {
+        self.current
+            .remove_if(&id, |_, g| *g == generation)
+            .is_some()
+    }
+}
+
+/// Reaps `child` and passes its exit to `report`, unless a newer shell has
+/// taken over `id` in the meantime (the old one was killed by a restart).
+fn wait_for_exit(
+    mut child: Box<dyn portable_pty::Child + Send + Sync>,
+    id: u32,
+    generation: u64,
+    generations: &ShellGenerations,
+    killed: &AtomicBool,
+    report: impl FnOnce(&SessionExitedPayload),
+) {
+    let status = match child.wait() {
+        Ok(status) => status,
+        Err(e) => {
+            log::warn!("PTY session {id}: failed to wait for child: {e}");
+            generations.finish(id, generation);
+            return;
+        }
+    };
+    if !generations.finish(id, generation) {
+        log::debug!("PTY session {id}: replaced shell exited, not reporting");
+        return;
+    }
+    report(&SessionExitedPayload::new(
+        id,
+        &status,
+        killed.load(Ordering::SeqCst),
+    ));
 }
 
 struct Inner {
     sessions: DashMap<u32, PtySession>,
     next_id: AtomicU32,
+    /// Current shell generation per session ID, shared with waiter threads.
+    shell_generations: Arc<ShellGenerations>,
+    /// Output coalescing window in ms, shared with every session's emitter.
+    coalesce_window_ms: Arc<AtomicU64>,
+    /// Backend given to sessions spawned without an explicit choice.
+    default_backend: Mutex<BackendType>,
+    /// Where session scrollback is persisted; None while persistence is off.
+    scrollback_dir: Mutex<Option<PathBuf>>,
     /// Tracks last spawn time on Windows to prevent rapid consecutive spawns
     /// that may cause terminal spawning loops (Bug #76).
     #[cfg(windows)]
@@ -130,12 +530,38 @@ impl ProcessManager {
             inner: Arc::new(Inner {
                 sessions: DashMap::new(),
                 next_id: AtomicU32::new(1),
+                shell_generations: Arc::new(ShellGenerations::default()),
+                coalesce_window_ms: Arc::new(AtomicU64::new(DEFAULT_COALESCE_WINDOW_MS)),
+                default_backend: Mutex::new(BackendType::platform_default()),
+                scrollback_dir: Mutex::new(None),
                 #[cfg(windows)]
                 last_spawn_time: Mutex::new(std::time::Instant::now()),
             }),
         }
     }
 
+    /// Ensures new session IDs start above `max_id`, so sessions restored
+    /// from disk (and their persisted scrollback) never share an ID with a
+    /// freshly spawned shell.
+    pub fn reserve_ids_through(&self, max_id: u32) {
+        self.inner
+            .next_id
+            .fetch_max(max_id.saturating_add(1), Ordering::Relaxed);
+    }
+
+    /// Reserves the next session ID without spawning anything.
+    ///
+    /// Sessions owned by other backends use this so their IDs (and
+    /// `pty-output-{id}` event names) never collide with ours.
+    pub fn allocate_session_id(&self) -> Result<u32, PtyError> {
+        self.inner
+            .next_id
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                current.checked_add(1)
+            })
+            .map_err(|_| PtyError::id_overflow())
+    }
+
     /// Spawns a login shell in a new PTY and returns its session ID.
     ///
     /// Uses `$SHELL` (falling back to `/bin/sh`) with `-l` for a login environment.
@@ -143,30 +569,35 @@ impl ProcessManager {
     /// leader so `kill_session` can signal the entire process group.
     /// A dedicated OS thread reads PTY output into a bounded 1024-slot channel
     /// (~4 MB of 4 KB chunks), and a tokio task drains it into Tauri events
-    /// named `pty-output-{id}`. If the channel fills, output is dropped and a
-    /// log message is emitted to make the loss visible.
+    /// named `pty-output-{id}`, coalescing bursts within the output coalesce
+    /// window into a single event. If the channel fills, output is dropped and
+    /// a log message is emitted to make the loss visible.
     ///
     /// # Environment Variables
     /// - `CHORUS_SESSION_ID` is automatically set to the session ID
     /// - Additional env vars can be passed via the `env` parameter (e.g., `CHORUS_PROJECT_HASH`)
+    /// - The session's custom env from `SessionManager`, if it has one, is
+    ///   applied last (see `spawn_session_shell`)
     ///
     /// # Windows Debouncing
     /// On Windows, rapid consecutive spawn calls (within 500ms) are rejected to prevent
     /// terminal spawning loops (Bug #76).
-    pub fn spawn_shell(
+    pub fn spawn_shell<R: Runtime>(
         &self,
-        app_handle: AppHandle,
+        app_handle: AppHandle<R>,
         cwd: Option<String>,
         env: Option<HashMap<String, String>>,
+        backend: Option<BackendType>,
     ) -> Result<u32, PtyError> {
+        let backend = self.resolve_backend(backend)?;
+
         // Windows spawn debounce: prevent rapid consecutive spawns (Bug #76)
         #[cfg(windows)]
         {
-            let mut last = self
-                .inner
-                .last_spawn_time
-                .lock()
-                .map_err(|e| PtyError::spawn_failed(format!("Spawn time lock poisoned: {e}")))?;
+            let mut last =
+                self.inner.last_spawn_time.lock().map_err(|e| {
+                    PtyError::spawn_failed(format!("Spawn time lock poisoned: {e}"))
+                })?;
             let elapsed = last.elapsed();
             if elapsed < std::time::Duration::from_millis(500) {
                 log::warn!(
@@ -180,14 +611,70 @@ impl ProcessManager {
             *last = std::time::Instant::now();
         }
 
-        let id = self
-            .inner
-            .next_id
-            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
-                current.checked_add(1)
-            })
-            .map_err(|_| PtyError::id_overflow())?;
+        let id = self.allocate_session_id()?;
+        let generation = self.inner.shell_generations.begin(id);
+        self.spawn_with_id(
+            id,
+            generation,
+            app_handle,
+            cwd,
+            env.unwrap_or_default(),
+            backend,
+        )?;
+        Ok(id)
+    }
 
+    /// Spawns the shell of a session that exists before its PTY, e.g. one
+    /// made by `create_session_auto` or restored as `Stopped`, under the
+    /// session's own ID so its custom env applies from the first spawn.
+    /// Fails if the session already has a running shell. Returns the PID.
+    pub fn spawn_session_shell<R: Runtime>(
+        &self,
+        app_handle: AppHandle<R>,
+        session_id: u32,
+        cwd: Option<String>,
+        env: Option<HashMap<String, String>>,
+        backend: Option<BackendType>,
+    ) -> Result<i32, PtyError> {
+        if self.inner.sessions.contains_key(&session_id) {
+            return Err(PtyError::spawn_failed(format!(
+                "Session {session_id} already has a running shell"
+            )));
+        }
+        let backend = self.resolve_backend(backend)?;
+        // Never hand this ID out again to a new shell
+        self.reserve_ids_through(session_id);
+        let generation = self.inner.shell_generations.begin(session_id);
+        self.spawn_with_id(
+            session_id,
+            generation,
+            app_handle,
+            cwd,
+            env.unwrap_or_default(),
+            backend,
+        )
+    }
+
+    /// The backend to spawn with: `requested` if it is compiled in, else the default.
+    fn resolve_backend(&self, requested: Option<BackendType>) -> Result<BackendType, PtyError> {
+        match requested {
+            Some(requested) => requested.ensure_available().map_err(PtyError::spawn_failed),
+            None => Ok(self.default_backend()),
+        }
+    }
+
+    /// Spawns the shell for an already allocated session ID and registers it
+    /// as `generation` of that ID (from `ShellGenerations::begin`).
+    /// Shared by `spawn_shell` and `restart_session`.
+    fn spawn_with_id<R: Runtime>(
+        &self,
+        id: u32,
+        generation: u64,
+        app_handle: AppHandle<R>,
+        cwd: Option<String>,
+        env: HashMap<String, String>,
+        backend: BackendType,
+    ) -> Result<i32, PtyError> {
         let pty_system = native_pty_system();
 
         let pair = pty_system
@@ -212,10 +699,14 @@ impl ProcessManager {
         // Inject CHORUS_SESSION_ID automatically (used by MCP status server)
         cmd.env("CHORUS_SESSION_ID", id.to_string());
 
-        // Apply any additional environment variables from caller
-        if let Some(envs) = env {
-            for (key, value) in envs {
-                cmd.env(&key, &value);
+        // Apply any additional environment variables from caller, then the
+        // session's custom env (see `set_session_env`), which wins
+        for (key, value) in &env {
+            cmd.env(key, value);
+        }
+        if let Some(sm) = app_handle.try_state::<super::SessionManager>() {
+            for (key, value) in sm.get_env(id).unwrap_or_default() {
+                cmd.env(key, value);
             }
         }
 
@@ -233,6 +724,25 @@ impl ProcessManager {
             .map(|pid| pid as i32)
             .ok_or_else(|| PtyError::spawn_failed("Could not obtain child PID"))?;
 
+        // Dedicated OS thread that reaps the child and reports how it exited.
+        let killed = Arc::new(AtomicBool::new(false));
+        let killed_clone = killed.clone();
+        let exit_app = app_handle.clone();
+        let generations = self.inner.shell_generations.clone();
+        std::thread::Builder::new()
+            .name(format!("pty-waiter-{id}"))
+            .spawn(move || {
+                wait_for_exit(
+                    child,
+                    id,
+                    generation,
+                    &generations,
+                    &killed_clone,
+                    |payload| report_session_exit(&exit_app, payload),
+                );
+            })
+            .map_err(|e| PtyError::spawn_failed(format!("Failed to spawn waiter thread: {e}")))?;
+
         // Capture process group ID before moving master into Mutex (Unix only).
         // portable-pty calls setsid() on spawn, so PGID == child PID.
         // Using the API is safer than assuming the identity holds.
@@ -253,9 +763,14 @@ impl ProcessManager {
 
         let shutdown = Arc::new(Notify::new());
         let shutdown_clone = shutdown.clone();
+        let (output_paused, mut paused_rx) = tokio::sync::watch::channel(false);
 
-        let output_buffer = Arc::new(Mutex::new(String::new()));
+        let output_buffer = Arc::new(Mutex::new(ScrollbackBuffer::new(DEFAULT_SCROLLBACK_BYTES)));
         let output_buffer_clone = output_buffer.clone();
+        let recorder: Arc<Mutex<Option<AsciicastRecorder>>> = Arc::new(Mutex::new(None));
+        let recorder_clone = recorder.clone();
+        let scrollback_log = Arc::new(Mutex::new(self.open_scrollback_log(id, "")));
+        let scrollback_log_clone = scrollback_log.clone();
 
         // Dedicated OS thread for reading PTY output.
         // Sends data through a bounded mpsc channel (~4 MB of 4 KB chunks) to a
@@ -305,41 +820,82 @@ impl ProcessManager {
         // Tokio task: drain the channel and emit Tauri events
         let event_name = format!("pty-output-{id}");
         let app = app_handle.clone();
+        let coalesce_window_ms = self.inner.coalesce_window_ms.clone();
         tokio::spawn(async move {
             let mut decoder = Utf8Decoder::new();
+            let mut coalescer = OutputCoalescer::new();
+            let mut held = HeldOutput::new();
+            let mut paused = false;
+            let mut pause_sender_gone = false;
+            let emit = |text: String| {
+                let _ = app.emit(&event_name, text.clone());
+                // Forward to event bus for WebSocket clients
+                if let Some(bus) = app.try_state::<std::sync::Arc<super::event_bus::EventBus>>() {
+                    bus.send(event_name.clone(), serde_json::Value::String(text));
+                }
+            };
+            // Scrollback and recording are updated at flush time so they stay
+            // in step with the emitted events; they keep filling while paused.
+            let flush = |text: String, paused: bool, held: &mut HeldOutput| {
+                if let Ok(mut buf) = output_buffer_clone.lock() {
+                    buf.push(&text);
+                    persist_output(&scrollback_log_clone, id, &text, &buf);
+                }
+                record_output(&recorder_clone, id, &text);
+                if paused {
+                    held.push(&text);
+                } else {
+                    emit(text);
+                }
+            };
             loop {
+                let deadline = coalescer.deadline();
                 tokio::select! {
                     data = rx.recv() => {
                         match data {
                             Some(bytes) => {
                                 let text = decoder.decode(&bytes);
                                 if !text.is_empty() {
-                                    if let Ok(mut buf) = output_buffer_clone.lock() {
-                                        buf.push_str(&text);
-                                        // Cap at 100KB
-                                        if buf.len() > 100_000 {
-                                            let mut drain_to = buf.len() - 80_000;
-                                            while !buf.is_char_boundary(drain_to) {
-                                                drain_to += 1;
-                                            }
-                                            buf.drain(..drain_to);
-                                        }
-                                    }
-                                    let _ = app.emit(&event_name, text.clone());
-                                    // Forward to event bus for WebSocket clients
-                                    if let Some(bus) = app.try_state::<std::sync::Arc<super::event_bus::EventBus>>() {
-                                        bus.send(event_name.clone(), serde_json::Value::String(text));
+                                    let window = Duration::from_millis(coalesce_window_ms.load(Ordering::Relaxed));
+                                    if let Some(batch) = coalescer.push(&text, window, Instant::now()) {
+                                        flush(batch, paused, &mut held);
                                     }
                                 }
                             }
                             None => break, // Channel closed
                         }
                     }
+                    _ = tokio::time::sleep_until(deadline.unwrap_or_else(Instant::now).into()), if deadline.is_some() => {
+                        if let Some(batch) = coalescer.take() {
+                            flush(batch, paused, &mut held);
+                        }
+                    }
+                    changed = paused_rx.changed(), if !pause_sender_gone => {
+                        if changed.is_err() {
+                            pause_sender_gone = true;
+                            continue;
+                        }
+                        paused = *paused_rx.borrow_and_update();
+                        if !paused {
+                            for chunk in held.drain(COALESCE_MAX_FLUSH_BYTES) {
+                                emit(chunk);
+                            }
+                        }
+                    }
                     _ = shutdown_clone.notified() => {
                         break;
                     }
                 }
             }
+            if let Some(batch) = coalescer.take() {
+                flush(batch, paused, &mut held);
+            }
+            // Nobody is left to resume: deliver what was held back
+            for chunk in held.drain(COALESCE_MAX_FLUSH_BYTES) {
+                emit(chunk);
+            }
+            // The session ended (or was killed) mid-capture: close the file cleanly.
+            finish_recording(&recorder_clone, id);
             log::debug!("PTY event emitter {id} exited");
         });
 
@@ -355,6 +911,14 @@ impl ProcessManager {
             shutdown,
             reader_handle: Mutex::new(Some(reader_handle)),
             output_buffer,
+            size: Mutex::new((24, 80)),
+            recorder,
+            scrollback_log,
+            killed,
+            output_paused,
+            backend,
+            spawn_cwd: cwd,
+            spawn_env: env,
         };
 
         self.inner.sessions.insert(id, session);
@@ -363,7 +927,62 @@ impl ProcessManager {
         #[cfg(windows)]
         log::info!("Spawned PTY session {id} (pid={child_pid}, shell={shell})");
 
-        Ok(id)
+        Ok(child_pid)
+    }
+
+    /// Kills a session's shell and spawns a fresh one under the same ID.
+    ///
+    /// The new shell gets the original working directory, backend and caller
+    /// env, overlaid like every spawn with the session's current custom env
+    /// from `SessionManager`, so edits made since the last spawn take effect. The terminal size is
+    /// carried over. Output keeps flowing on `pty-output-{id}`; scrollback
+    /// starts empty. Fails if the session has no shell to restart (e.g. one
+    /// restored as `Stopped` after an app restart). Returns the new shell's PID.
+    pub async fn restart_session<R: Runtime>(
+        &self,
+        app_handle: AppHandle<R>,
+        session_id: u32,
+    ) -> Result<i32, PtyError> {
+        let (cwd, env, backend, (rows, cols)) = {
+            let session = self
+                .inner
+                .sessions
+                .get(&session_id)
+                .ok_or_else(|| PtyError {
+                    code: PtyErrorCode::SessionNotFound,
+                    message: format!(
+                    "Session {session_id} has no running shell to restart; launch it again instead"
+                ),
+                })?;
+            let size = session.size.lock().map(|s| *s).unwrap_or((24, 80));
+            (
+                session.spawn_cwd.clone(),
+                session.spawn_env.clone(),
+                session.backend,
+                size,
+            )
+        };
+
+        // Supersede the old shell before killing it: kill_session waits until
+        // it is reaped, so its waiter must already see it was replaced.
+        let generation = self.inner.shell_generations.begin(session_id);
+
+        // The shell may have exited (and been removed) since the lookup above
+        match self.kill_session(session_id).await {
+            Ok(()) => {}
+            Err(PtyError {
+                code: PtyErrorCode::SessionNotFound,
+                ..
+            }) => {}
+            Err(e) => return Err(e),
+        }
+
+        let pid = self.spawn_with_id(session_id, generation, app_handle, cwd, env, backend)?;
+        if (rows, cols) != (24, 80) {
+            self.resize_pty(session_id, rows, cols)?;
+        }
+        log::info!("Restarted PTY session {session_id} (pid={pid})");
+        Ok(pid)
     }
 
     /// Writes raw bytes to a session's PTY stdin and flushes immediately.
@@ -418,6 +1037,18 @@ impl ProcessManager {
             })
             .map_err(|e| PtyError::resize_failed(format!("Resize failed: {e}")))?;
 
+        if let Ok(mut size) = session.size.lock() {
+            *size = (rows, cols);
+        }
+
+        if let Ok(mut recorder) = session.recorder.lock() {
+            if let Some(rec) = recorder.as_mut() {
+                if let Err(e) = rec.resize(cols, rows) {
+                    log::warn!("Session {session_id}: failed to record resize: {e}");
+                }
+            }
+        }
+
         Ok(())
     }
 
@@ -443,6 +1074,7 @@ impl ProcessManager {
             .1;
 
         let pid = session.child_pid;
+        session.killed.store(true, Ordering::SeqCst);
 
         #[cfg(unix)]
         {
@@ -484,8 +1116,8 @@ impl ProcessManager {
 
         #[cfg(windows)]
         {
-            use std::process::Command;
             use super::windows_process::StdCommandExt;
+            use std::process::Command;
             // Use taskkill to terminate process tree
             let result = Command::new("taskkill")
                 .args(["/PID", &pid.to_string(), "/T", "/F"])
@@ -499,6 +1131,7 @@ impl ProcessManager {
 
         // Signal the tokio event emitter to shut down
         session.shutdown.notify_one();
+        finish_recording(&session.recorder, session_id);
 
         // Drop the master and writer first — this closes the PTY fd,
         // which causes the reader thread to get EOF and exit.
@@ -521,6 +1154,63 @@ impl ProcessManager {
         Ok(())
     }
 
+    /// Starts teeing a session's output into an asciicast v2 file at `path`.
+    ///
+    /// The header uses the session's current terminal size; later resizes are
+    /// recorded as `"r"` events. Fails if the session is already recording.
+    pub fn start_recording(&self, session_id: u32, path: &Path) -> Result<(), PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?;
+
+        let mut recorder = session
+            .recorder
+            .lock()
+            .map_err(|e| PtyError::recording_failed(format!("Recorder lock poisoned: {e}")))?;
+        if let Some(active) = recorder.as_ref() {
+            return Err(PtyError::recording_failed(format!(
+                "Session {session_id} is already recording to {}",
+                active.path().display()
+            )));
+        }
+
+        let (rows, cols) = session.size.lock().map(|s| *s).unwrap_or((24, 80));
+        let title = format!("Chorus session {session_id}");
+        let rec = AsciicastRecorder::create(path, cols, rows, Some(&title)).map_err(|e| {
+            PtyError::recording_failed(format!("Failed to create {}: {e}", path.display()))
+        })?;
+        *recorder = Some(rec);
+
+        log::info!("Session {session_id}: recording to {}", path.display());
+        Ok(())
+    }
+
+    /// Stops a session's recording, flushing and closing the file.
+    ///
+    /// Returns the path of the finished cast file, or None if the session
+    /// wasn't recording.
+    pub fn stop_recording(&self, session_id: u32) -> Result<Option<PathBuf>, PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?;
+
+        let rec = session
+            .recorder
+            .lock()
+            .map_err(|e| PtyError::recording_failed(format!("Recorder lock poisoned: {e}")))?
+            .take();
+        match rec {
+            Some(rec) => rec.finish().map(Some).map_err(|e| {
+                PtyError::recording_failed(format!("Failed to finish recording: {e}"))
+            }),
+            None => Ok(None),
+        }
+    }
+
     /// Returns the child PID for a specific session.
     ///
     /// Returns None if the session doesn't exist.
@@ -544,8 +1234,265 @@ impl ProcessManager {
 
     /// Returns the buffered output for a session, or None if session doesn't exist.
     pub fn get_session_output(&self, session_id: u32) -> Option<String> {
+        match self.inner.sessions.get(&session_id) {
+            Some(session) => session
+                .output_buffer
+                .lock()
+                .ok()
+                .map(|buf| buf.as_str().to_string()),
+            // Not running: fall back to scrollback persisted by an earlier run
+            None => self
+                .scrollback_dir()
+                .and_then(|dir| scrollback_log::read(&scrollback_log::log_path(&dir, session_id))),
+        }
+    }
+
+    /// Returns up to `max_bytes` of output after the absolute byte `offset`,
+    /// so clients can page through scrollback instead of fetching it all at
+    /// once. Returns None if the session doesn't exist.
+    pub fn get_session_output_since(
+        &self,
+        session_id: u32,
+        offset: u64,
+        max_bytes: usize,
+    ) -> Option<SessionOutputChunk> {
         self.inner.sessions.get(&session_id).and_then(|session| {
-            session.output_buffer.lock().ok().map(|buf| buf.clone())
+            session
+                .output_buffer
+                .lock()
+                .ok()
+                .map(|buf| buf.read_since(offset, max_bytes))
+        })
+    }
+
+    /// Returns how much scrollback a session holds, or None if it doesn't exist.
+    pub fn get_session_output_info(&self, session_id: u32) -> Option<SessionOutputInfo> {
+        self.inner
+            .sessions
+            .get(&session_id)
+            .and_then(|session| session.output_buffer.lock().ok().map(|buf| buf.info()))
+    }
+
+    /// Sets how many bytes of scrollback a session retains, dropping the
+    /// oldest output if the buffer already exceeds it. The limit is clamped
+    /// to `MIN_SCROLLBACK_BYTES..=MAX_SCROLLBACK_BYTES`; returns the applied limit.
+    pub fn set_scrollback_limit(&self, session_id: u32, limit: usize) -> Result<usize, PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?;
+        let limit = limit.clamp(MIN_SCROLLBACK_BYTES, MAX_SCROLLBACK_BYTES);
+        if let Ok(mut buf) = session.output_buffer.lock() {
+            buf.set_limit(limit);
+        }
+        Ok(limit)
+    }
+
+    /// Pauses or resumes a session's output events.
+    ///
+    /// While paused, output still reaches the scrollback buffer and any
+    /// recording but isn't emitted; on resume the held output is emitted in
+    /// order. Input and resizing are unaffected.
+    pub fn set_output_paused(&self, session_id: u32, paused: bool) -> Result<(), PtyError> {
+        let session = self
+            .inner
+            .sessions
+            .get(&session_id)
+            .ok_or_else(|| PtyError::session_not_found(session_id))?;
+        session.output_paused.send_replace(paused);
+        Ok(())
+    }
+
+    /// Sets how long PTY output is held to merge chunks into one event, for
+    /// all sessions. Clamped to `0..=MAX_COALESCE_WINDOW_MS`; 0 emits every
+    /// chunk as it arrives. Returns the applied window in ms.
+    pub fn set_output_coalesce_window(&self, window_ms: u64) -> u64 {
+        let window_ms = window_ms.min(MAX_COALESCE_WINDOW_MS);
+        self.inner
+            .coalesce_window_ms
+            .store(window_ms, Ordering::Relaxed);
+        window_ms
+    }
+
+    /// Returns the backend new sessions get when none is requested.
+    pub fn default_backend(&self) -> BackendType {
+        self.inner
+            .default_backend
+            .lock()
+            .map(|b| *b)
+            .unwrap_or_else(|_| BackendType::platform_default())
+    }
+
+    /// Sets the backend for sessions spawned without an explicit choice.
+    /// Existing sessions keep the backend they were spawned with.
+    pub fn set_default_backend(&self, backend: BackendType) -> Result<(), String> {
+        let backend = backend.ensure_available()?;
+        let mut current = self
+            .inner
+            .default_backend
+            .lock()
+            .map_err(|e| format!("Backend lock poisoned: {e}"))?;
+        *current = backend;
+        Ok(())
+    }
+
+    /// Directory scrollback is persisted to, or None while persistence is off.
+    pub fn scrollback_dir(&self) -> Option<PathBuf> {
+        self.inner
+            .scrollback_dir
+            .lock()
+            .ok()
+            .and_then(|d| d.clone())
+    }
+
+    /// Turns scrollback persistence on (writing to `~/.chorus/sessions/`) or off.
+    ///
+    /// Enabling seeds a log for every running session from its current
+    /// scrollback; disabling stops writing and deletes the running sessions'
+    /// logs. Logs of sessions that aren't running are left for
+    /// `remove_persisted_scrollback`.
+    pub fn set_scrollback_persistence(&self, enabled: bool) -> Result<(), String> {
+        let dir = if enabled {
+            Some(
+                scrollback_log::default_dir()
+                    .ok_or("No home directory to persist scrollback to")?,
+            )
+        } else {
+            None
+        };
+        self.set_scrollback_dir(dir)
+    }
+
+    /// Persists scrollback to `dir`, or stops persisting it when None.
+    fn set_scrollback_dir(&self, dir: Option<PathBuf>) -> Result<(), String> {
+        let enabled = dir.is_some();
+        *self
+            .inner
+            .scrollback_dir
+            .lock()
+            .map_err(|e| format!("Scrollback dir lock poisoned: {e}"))? = dir;
+
+        for session in self.inner.sessions.iter() {
+            let Ok(mut persisted) = session.scrollback_log.lock() else {
+                continue;
+            };
+            if enabled {
+                if persisted.is_none() {
+                    let retained = session
+                        .output_buffer
+                        .lock()
+                        .map(|buf| buf.as_str().to_string())
+                        .unwrap_or_default();
+                    *persisted = self.open_scrollback_log(*session.key(), &retained);
+                }
+            } else if let Some(old) = persisted.take() {
+                if let Err(e) = old.discard() {
+                    log::warn!(
+                        "Session {}: failed to delete scrollback log: {e}",
+                        session.key()
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Deletes a session's persisted scrollback, e.g. when the session is removed.
+    pub fn remove_persisted_scrollback(&self, session_id: u32) {
+        // Stop a running session from recreating the file
+        if let Some(session) = self.inner.sessions.get(&session_id) {
+            if let Ok(mut persisted) = session.scrollback_log.lock() {
+                persisted.take();
+            }
+        }
+        let Some(dir) = self.scrollback_dir().or_else(scrollback_log::default_dir) else {
+            return;
+        };
+        if let Err(e) = scrollback_log::remove(&scrollback_log::log_path(&dir, session_id)) {
+            log::warn!("Session {session_id}: failed to delete scrollback log: {e}");
+        }
+    }
+
+    /// Opens a fresh scrollback log holding `retained` if persistence is on.
+    fn open_scrollback_log(&self, session_id: u32, retained: &str) -> Option<ScrollbackLog> {
+        let path = scrollback_log::log_path(&self.scrollback_dir()?, session_id);
+        ScrollbackLog::create(&path, retained)
+            .inspect_err(|e| {
+                log::warn!(
+                    "Session {session_id}: cannot persist scrollback to {}: {e}",
+                    path.display()
+                )
+            })
+            .ok()
+    }
+
+    /// Returns the backend a session was spawned with, or None if it doesn't exist.
+    #[cfg(feature = "vte-backend")]
+    pub fn session_backend(&self, session_id: u32) -> Option<BackendType> {
+        self.inner.sessions.get(&session_id).map(|s| s.backend)
+    }
+
+    /// Renders the currently visible screen of a session at `rows` x `cols`.
+    ///
+    /// For sessions on the VTE backend the buffered output is replayed onto a
+    /// cell grid, giving text plus minimal styling. Otherwise the last `rows`
+    /// lines are returned with escape sequences stripped. Because the buffer
+    /// is capped, the grid may miss state set before the oldest retained byte.
+    /// Returns None if the session doesn't exist.
+    pub fn get_session_screen(
+        &self,
+        session_id: u32,
+        rows: u16,
+        cols: u16,
+    ) -> Option<ScreenSnapshot> {
+        let output = self.get_session_output(session_id)?;
+
+        #[cfg(feature = "vte-backend")]
+        if self.session_backend(session_id)? == BackendType::VteParser {
+            return Some(super::screen_grid::render_screen(&output, rows, cols));
+        }
+
+        Some(ScreenSnapshot::from_tail(&output, rows, cols))
+    }
+
+    /// Returns the session's terminal size, cursor position and alt-screen flag.
+    ///
+    /// For sessions on the VTE backend the buffered output is replayed onto a
+    /// grid of the current size to recover the cursor; otherwise the cursor
+    /// is unknown and alt-screen is inferred from the last mode switch in the
+    /// buffer. Returns None if the session doesn't exist.
+    pub fn get_terminal_state(&self, session_id: u32) -> Option<SessionTerminalState> {
+        let (rows, cols) = self
+            .inner
+            .sessions
+            .get(&session_id)?
+            .size
+            .lock()
+            .map(|size| *size)
+            .unwrap_or((24, 80));
+        let output = self.get_session_output(session_id)?;
+
+        #[cfg(feature = "vte-backend")]
+        if self.session_backend(session_id)? == BackendType::VteParser {
+            let mut screen = super::screen_grid::ScreenGrid::new(rows, cols);
+            screen.feed(output.as_bytes());
+            let (cursor_row, cursor_col) = screen.cursor();
+            return Some(SessionTerminalState {
+                rows,
+                cols,
+                cursor_row: Some(cursor_row),
+                cursor_col: Some(cursor_col),
+                alt_screen: screen.is_alt_screen(),
+            });
+        }
+
+        Some(SessionTerminalState {
+            rows,
+            cols,
+            cursor_row: None,
+            cursor_col: None,
+            alt_screen: super::ansi::ends_in_alt_screen(&output),
         })
     }
 
@@ -573,3 +1520,371 @@ impl ProcessManager {
         Ok(count)
     }
 }
+
+/// Emits `SESSION_EXITED_EVENT` and reflects the exit in the session's status.
+///
+/// Killed sessions keep their status; the caller that killed them owns it.
+fn report_session_exit<R: Runtime>(app: &AppHandle<R>, payload: &SessionExitedPayload) {
+    let id = payload.session_id;
+    match payload.reason {
+        ExitReason::Killed => log::debug!("PTY session {id} exited after kill"),
+        ExitReason::Exited => log::info!("PTY session {id} exited cleanly"),
+        ExitReason::Crashed => log::warn!(
+            "PTY session {id} exited unexpectedly (code={}, signal={:?})",
+            payload.exit_code,
+            payload.signal
+        ),
+    }
+
+    let status = match payload.reason {
+        ExitReason::Killed => None,
+        ExitReason::Exited => Some(super::session_manager::SessionStatus::Done),
+        ExitReason::Crashed => Some(super::session_manager::SessionStatus::Error),
+    };
+    if let (Some(status), Some(sm)) = (status, app.try_state::<super::SessionManager>()) {
+        sm.update_status(id, status);
+    }
+
+    let _ = app.emit(SESSION_EXITED_EVENT, payload);
+    if let Some(bus) = app.try_state::<Arc<super::event_bus::EventBus>>() {
+        match serde_json::to_value(payload) {
+            Ok(v) => bus.send(SESSION_EXITED_EVENT.to_string(), v),
+            Err(e) => log::error!("Failed to serialize session exit payload: {e}"),
+        }
+    }
+}
+
+/// Appends output to a session's recording, abandoning the recording if the
+/// write fails so a full disk doesn't produce an error per chunk.
+fn record_output(recorder: &Mutex<Option<AsciicastRecorder>>, session_id: u32, text: &str) {
+    let Ok(mut recorder) = recorder.lock() else {
+        return;
+    };
+    if let Some(rec) = recorder.as_mut() {
+        if let Err(e) = rec.output(text) {
+            log::warn!("Session {session_id}: recording write failed, stopping: {e}");
+            *recorder = None;
+        }
+    }
+}
+
+/// Appends flushed output to a session's persisted scrollback, compacting
+/// it to `buf` as needed. Stops persisting the session if the write fails.
+fn persist_output(
+    persisted: &Mutex<Option<ScrollbackLog>>,
+    session_id: u32,
+    text: &str,
+    buf: &ScrollbackBuffer,
+) {
+    let Ok(mut persisted) = persisted.lock() else {
+        return;
+    };
+    if let Some(file) = persisted.as_mut() {
+        if let Err(e) = file.append(text, buf.as_str(), buf.limit()) {
+            log::warn!("Session {session_id}: scrollback persistence failed, stopping: {e}");
+            *persisted = None;
+        }
+    }
+}
+
+/// Flushes and closes a session's recording if one is active.
+fn finish_recording(recorder: &Mutex<Option<AsciicastRecorder>>, session_id: u32) {
+    let rec = recorder.lock().ok().and_then(|mut r| r.take());
+    if let Some(rec) = rec {
+        match rec.finish() {
+            Ok(path) => log::info!(
+                "Session {session_id}: recording saved to {}",
+                path.display()
+            ),
+            Err(e) => log::warn!("Session {session_id}: failed to finish recording: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_backend_only_accepts_compiled_in_backends() {
+        let pm = ProcessManager::new();
+        assert_eq!(pm.default_backend(), BackendType::platform_default());
+
+        pm.set_default_backend(BackendType::XtermPassthrough)
+            .unwrap();
+        assert_eq!(pm.default_backend(), BackendType::XtermPassthrough);
+
+        let vte = pm.set_default_backend(BackendType::VteParser);
+        assert_eq!(vte.is_ok(), cfg!(feature = "vte-backend"));
+        if vte.is_err() {
+            assert_eq!(pm.default_backend(), BackendType::XtermPassthrough);
+        }
+    }
+
+    #[test]
+    fn held_output_is_delivered_in_order_on_resume() {
+        let mut held = HeldOutput::new();
+        held.push("first ");
+        held.push("second ");
+        held.push("été");
+
+        let chunks = held.drain(5);
+        assert!(chunks.iter().all(|c| c.len() <= 5));
+        assert_eq!(chunks.concat(), "first second été");
+        assert!(held.drain(5).is_empty());
+    }
+
+    #[test]
+    fn held_output_drops_oldest_past_cap() {
+        let mut held = HeldOutput::new();
+        held.push("old");
+        held.push(&"n".repeat(MAX_HELD_OUTPUT_BYTES));
+
+        let all = held.drain(usize::MAX).concat();
+        assert_eq!(all.len(), MAX_HELD_OUTPUT_BYTES);
+        assert!(!all.contains('o'));
+    }
+
+    #[test]
+    fn coalescer_merges_chunks_until_the_window_ends() {
+        let window = Duration::from_millis(4);
+        let start = Instant::now();
+        let mut c = OutputCoalescer::new();
+
+        assert_eq!(c.push("a", window, start), None);
+        assert_eq!(c.push("b", window, start + Duration::from_millis(1)), None);
+        assert_eq!(c.deadline(), Some(start + window));
+        assert_eq!(c.push("c", window, start + window), Some("abc".to_string()));
+        assert_eq!(c.deadline(), None);
+
+        // A timer flush takes whatever is pending
+        c.push("d", window, start + window);
+        assert_eq!(c.take(), Some("d".to_string()));
+        assert_eq!(c.take(), None);
+    }
+
+    #[test]
+    fn coalescer_flushes_large_batches_and_passes_through_without_window() {
+        let start = Instant::now();
+        let mut c = OutputCoalescer::new();
+        let big = "x".repeat(COALESCE_MAX_FLUSH_BYTES);
+        assert_eq!(
+            c.push(&big, Duration::from_secs(1), start).map(|b| b.len()),
+            Some(big.len())
+        );
+
+        assert_eq!(
+            c.push("prompt$ ", Duration::ZERO, start),
+            Some("prompt$ ".to_string())
+        );
+    }
+
+    #[test]
+    fn scrollback_drops_oldest_output_past_limit() {
+        let mut buf = ScrollbackBuffer::new(100);
+        buf.push(&"a".repeat(60));
+        buf.push(&"b".repeat(60));
+
+        let info = buf.info();
+        assert_eq!(info.buffered_bytes, 80);
+        assert_eq!(info.dropped_bytes, 40);
+        assert!(buf.as_str().ends_with(&"b".repeat(60)));
+    }
+
+    #[test]
+    fn scrollback_trims_on_char_boundary() {
+        let mut buf = ScrollbackBuffer::new(10);
+        buf.push("ééééééé"); // 14 bytes
+
+        assert!(buf.as_str().len() <= 8);
+        assert!(buf.as_str().chars().all(|c| c == 'é'));
+    }
+
+    #[test]
+    fn read_since_pages_with_absolute_offsets() {
+        let mut buf = ScrollbackBuffer::new(100);
+        buf.push(&"a".repeat(60));
+        buf.push(&"b".repeat(60)); // drops the first 40 bytes
+
+        let first = buf.read_since(0, 30);
+        assert!(first.truncated);
+        assert_eq!(first.offset, 40);
+        assert_eq!(first.data, "a".repeat(20) + &"b".repeat(10));
+        assert!(first.has_more);
+
+        let rest = buf.read_since(first.next_offset, 1000);
+        assert!(!rest.truncated);
+        assert_eq!(rest.data, "b".repeat(50));
+        assert_eq!(rest.next_offset, 120);
+        assert!(!rest.has_more);
+
+        buf.push("tail");
+        assert_eq!(buf.read_since(rest.next_offset, 1000).data, "tail");
+    }
+
+    #[test]
+    fn read_since_never_splits_a_char() {
+        let mut buf = ScrollbackBuffer::new(100);
+        buf.push("aé");
+
+        let chunk = buf.read_since(0, 2);
+        assert_eq!(chunk.data, "a");
+        let chunk = buf.read_since(chunk.next_offset, 1);
+        assert_eq!(chunk.data, "é");
+        assert_eq!(chunk.next_offset, 3);
+    }
+
+    #[test]
+    fn stripped_chunks_keep_split_escapes_whole() {
+        let mut buf = ScrollbackBuffer::new(100);
+        buf.push("red \x1b[31mtext\x1b[0m");
+
+        let first = buf.read_since(0, 7).strip_ansi();
+        assert_eq!(first.data, "red ");
+        assert_eq!(first.next_offset, 4);
+        assert!(first.has_more);
+
+        let rest = buf.read_since(first.next_offset, 1000).strip_ansi();
+        assert_eq!(rest.data, "text");
+        assert!(!rest.has_more);
+    }
+
+    #[tokio::test]
+    async fn restarted_session_gets_no_exit_event_from_its_old_shell() {
+        use tauri::Listener;
+
+        let app = tauri::test::mock_app();
+        let (tx, rx) = std::sync::mpsc::channel();
+        app.listen(SESSION_EXITED_EVENT, move |event| {
+            let _ = tx.send(event.payload().to_string());
+        });
+        let pm = ProcessManager::new();
+        let id = pm
+            .spawn_shell(app.handle().clone(), None, None, None)
+            .unwrap();
+
+        pm.restart_session(app.handle().clone(), id).await.unwrap();
+        // The old shell's waiter reports from its own thread; give it time
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(
+            rx.try_recv().is_err(),
+            "the replaced shell's exit must not be reported"
+        );
+
+        pm.kill_session(id).await.unwrap();
+        let payload = rx
+            .recv_timeout(Duration::from_secs(5))
+            .expect("new shell's exit is reported");
+        let payload: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(payload["sessionId"], id);
+        assert_eq!(payload["reason"], "killed");
+    }
+
+    #[tokio::test]
+    async fn persisted_scrollback_is_read_back_after_a_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let app = tauri::test::mock_app();
+        let pm = ProcessManager::new();
+        pm.set_scrollback_dir(Some(dir.path().to_path_buf()))
+            .unwrap();
+        let id = pm
+            .spawn_shell(app.handle().clone(), None, None, None)
+            .unwrap();
+
+        pm.write_stdin(id, "echo chorus-scrollback-marker\n")
+            .unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !pm
+            .get_session_output(id)
+            .is_some_and(|out| out.contains("chorus-scrollback-marker"))
+        {
+            assert!(Instant::now() < deadline, "shell output never arrived");
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        pm.kill_session(id).await.unwrap();
+
+        // The next run has no in-memory buffer for the session, only its log
+        let next_run = ProcessManager::new();
+        next_run
+            .set_scrollback_dir(Some(dir.path().to_path_buf()))
+            .unwrap();
+        let restored = next_run.get_session_output(id).expect("log is read back");
+        assert!(restored.contains("chorus-scrollback-marker"));
+    }
+
+    #[tokio::test]
+    async fn session_env_reaches_the_first_shell() {
+        use crate::core::session_manager::{AiMode, SessionManager};
+
+        let app = tauri::test::mock_app();
+        app.manage(SessionManager::new());
+        let pm = ProcessManager::new();
+        let sm = app.state::<SessionManager>();
+        let session = sm.create_session_auto(
+            || pm.allocate_session_id().ok(),
+            AiMode::Plain,
+            "/tmp".to_string(),
+        );
+        let id = session.unwrap().id;
+        let env = [("CHORUS_TEST_VAR".to_string(), "from-session".to_string())];
+        sm.set_env(id, env.into_iter().collect());
+
+        pm.spawn_session_shell(app.handle().clone(), id, None, None, None)
+            .unwrap();
+        // The echoed command line shows `$CHORUS_TEST_VAR`; only the shell's
+        // output has the value
+        pm.write_stdin(id, "echo chorus-env-$CHORUS_TEST_VAR\n")
+            .unwrap();
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !pm
+            .get_session_output(id)
+            .is_some_and(|out| out.contains("chorus-env-from-session"))
+        {
+            assert!(Instant::now() < deadline, "shell never saw the session env");
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+
+        // The ID is taken, so a second shell for it is refused
+        assert!(pm
+            .spawn_session_shell(app.handle().clone(), id, None, None, None)
+            .is_err());
+        pm.kill_session(id).await.unwrap();
+    }
+
+    #[test]
+    fn exit_reason_distinguishes_kills_from_crashes() {
+        let clean = portable_pty::ExitStatus::with_exit_code(0);
+        let failed = portable_pty::ExitStatus::with_exit_code(139);
+        let signaled = portable_pty::ExitStatus::with_signal("Segmentation fault");
+
+        assert_eq!(
+            SessionExitedPayload::new(1, &clean, false).reason,
+            ExitReason::Exited
+        );
+        assert_eq!(
+            SessionExitedPayload::new(1, &failed, false).reason,
+            ExitReason::Crashed
+        );
+        assert_eq!(
+            SessionExitedPayload::new(1, &failed, true).reason,
+            ExitReason::Killed
+        );
+
+        let payload = SessionExitedPayload::new(7, &signaled, false);
+        assert_eq!(payload.reason, ExitReason::Crashed);
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(json["sessionId"], 7);
+        assert_eq!(json["reason"], "crashed");
+        assert_eq!(json["signal"], "Segmentation fault");
+    }
+
+    #[test]
+    fn lowering_limit_trims_immediately() {
+        let mut buf = ScrollbackBuffer::new(1000);
+        buf.push(&"x".repeat(500));
+        buf.set_limit(200);
+
+        assert_eq!(buf.info().buffered_bytes, 200);
+        assert_eq!(buf.info().limit_bytes, 200);
+    }
+}
@@ -0,0 +1,542 @@
+//! Minimal VT screen model for rendering a session's visible screen.
+//!
+//! Replays PTY output through the `vte` parser (same parser as Alacritty)
+//! into a fixed-size cell grid. Only the sequences needed for a readable
+//! snapshot are handled: printing, cursor movement, erase, scrolling, SGR
+//! bold/inverse/colors and the alternate screen. Everything else is ignored,
+//! so the result is an approximation of what xterm.js shows, not a replica.
+
+use vte::{Parser, Perform};
+
+use super::terminal_backend::{ScreenLine, ScreenSnapshot, ScreenSource, StyleSpan};
+
+/// Graphic rendition applied to printed characters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Pen {
+    bold: bool,
+    inverse: bool,
+    fg: Option<u8>,
+    bg: Option<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cell {
+    ch: char,
+    pen: Pen,
+}
+
+impl Default for Cell {
+    fn default() -> Self {
+        Self {
+            ch: ' ',
+            pen: Pen::default(),
+        }
+    }
+}
+
+/// Grid state driven by the VTE parser.
+struct Grid {
+    rows: u16,
+    cols: u16,
+    cells: Vec<Vec<Cell>>,
+    cursor_row: u16,
+    cursor_col: u16,
+    /// Set after printing in the last column; the next print wraps first.
+    wrap_pending: bool,
+    pen: Pen,
+    /// Primary screen contents and cursor, saved while the alternate screen is active.
+    saved_primary: Option<(Vec<Vec<Cell>>, u16, u16)>,
+    /// Cursor saved by DECSC / CSI s.
+    saved_cursor: (u16, u16),
+}
+
+impl Grid {
+    fn new(rows: u16, cols: u16) -> Self {
+        let rows = rows.max(1);
+        let cols = cols.max(1);
+        Self {
+            rows,
+            cols,
+            cells: vec![vec![Cell::default(); cols as usize]; rows as usize],
+            cursor_row: 0,
+            cursor_col: 0,
+            wrap_pending: false,
+            pen: Pen::default(),
+            saved_primary: None,
+            saved_cursor: (0, 0),
+        }
+    }
+
+    /// Resizes the screen in place. Rows are dropped from the top when
+    /// shrinking so the cursor line stays visible, as terminals do.
+    fn resize(&mut self, rows: u16, cols: u16) {
+        let rows = rows.max(1);
+        let cols = cols.max(1);
+
+        let drop_top = self.cursor_row.saturating_sub(rows - 1);
+        resize_cells(&mut self.cells, rows, cols, drop_top);
+        if let Some((cells, row, col)) = self.saved_primary.as_mut() {
+            let saved_drop = row.saturating_sub(rows - 1);
+            resize_cells(cells, rows, cols, saved_drop);
+            *row -= saved_drop;
+            *col = (*col).min(cols - 1);
+        }
+
+        self.rows = rows;
+        self.cols = cols;
+        self.cursor_row -= drop_top;
+        self.cursor_col = self.cursor_col.min(cols - 1);
+        self.saved_cursor = (self.saved_cursor.0.min(rows - 1), self.saved_cursor.1.min(cols - 1));
+        self.wrap_pending = false;
+    }
+
+    fn blank_row(&self) -> Vec<Cell> {
+        vec![Cell::default(); self.cols as usize]
+    }
+
+    /// Moves the cursor down one row, scrolling the screen up at the bottom.
+    fn line_feed(&mut self) {
+        if self.cursor_row + 1 >= self.rows {
+            self.cells.remove(0);
+            let blank = self.blank_row();
+            self.cells.push(blank);
+        } else {
+            self.cursor_row += 1;
+        }
+    }
+
+    fn move_to(&mut self, row: u16, col: u16) {
+        self.cursor_row = row.min(self.rows - 1);
+        self.cursor_col = col.min(self.cols - 1);
+        self.wrap_pending = false;
+    }
+
+    /// Erases cells `[from, to)` on `row`.
+    fn erase_cells(&mut self, row: u16, from: u16, to: u16) {
+        let line = &mut self.cells[row as usize];
+        let to = (to as usize).min(line.len());
+        for cell in line.iter_mut().take(to).skip(from as usize) {
+            *cell = Cell::default();
+        }
+    }
+
+    fn erase_in_line(&mut self, mode: u16) {
+        let (row, col, cols) = (self.cursor_row, self.cursor_col, self.cols);
+        match mode {
+            0 => self.erase_cells(row, col, cols),
+            1 => self.erase_cells(row, 0, col + 1),
+            _ => self.erase_cells(row, 0, cols),
+        }
+    }
+
+    fn erase_in_display(&mut self, mode: u16) {
+        let row = self.cursor_row;
+        match mode {
+            0 => {
+                self.erase_in_line(0);
+                for r in row + 1..self.rows {
+                    self.erase_cells(r, 0, self.cols);
+                }
+            }
+            1 => {
+                self.erase_in_line(1);
+                for r in 0..row {
+                    self.erase_cells(r, 0, self.cols);
+                }
+            }
+            _ => {
+                for r in 0..self.rows {
+                    self.erase_cells(r, 0, self.cols);
+                }
+            }
+        }
+    }
+
+    fn set_alt_screen(&mut self, enable: bool) {
+        if enable && self.saved_primary.is_none() {
+            let blank = vec![self.blank_row(); self.rows as usize];
+            let primary = std::mem::replace(&mut self.cells, blank);
+            self.saved_primary = Some((primary, self.cursor_row, self.cursor_col));
+            self.move_to(0, 0);
+        } else if !enable {
+            if let Some((primary, row, col)) = self.saved_primary.take() {
+                self.cells = primary;
+                self.move_to(row, col);
+            }
+        }
+    }
+
+    fn apply_sgr(&mut self, params: &[Vec<u16>]) {
+        if params.is_empty() {
+            self.pen = Pen::default();
+            return;
+        }
+
+        let mut i = 0;
+        while i < params.len() {
+            let p = &params[i];
+            match p.first().copied().unwrap_or(0) {
+                0 => self.pen = Pen::default(),
+                1 => self.pen.bold = true,
+                22 => self.pen.bold = false,
+                7 => self.pen.inverse = true,
+                27 => self.pen.inverse = false,
+                n @ 30..=37 => self.pen.fg = Some((n - 30) as u8),
+                39 => self.pen.fg = None,
+                n @ 40..=47 => self.pen.bg = Some((n - 40) as u8),
+                49 => self.pen.bg = None,
+                n @ 90..=97 => self.pen.fg = Some((n - 90 + 8) as u8),
+                n @ 100..=107 => self.pen.bg = Some((n - 100 + 8) as u8),
+                n @ (38 | 48) => {
+                    // Extended color: either colon subparams (38:5:n) or
+                    // semicolon-separated params (38;5;n / 38;2;r;g;b)
+                    let (kind, rest, consumed) = if p.len() > 1 {
+                        (p[1], p[2..].to_vec(), 0)
+                    } else {
+                        let kind = params.get(i + 1).and_then(|v| v.first().copied()).unwrap_or(0);
+                        let count = if kind == 5 { 1 } else if kind == 2 { 3 } else { 0 };
+                        let rest = params
+                            .iter()
+                            .skip(i + 2)
+                            .take(count)
+                            .filter_map(|v| v.first().copied())
+                            .collect();
+                        (kind, rest, 1 + count)
+                    };
+                    // Truecolor has no palette index; fall back to the default color
+                    let color = if kind == 5 {
+                        rest.first().map(|&c| c.min(255) as u8)
+                    } else {
+                        None
+                    };
+                    if n == 38 {
+                        self.pen.fg = color;
+                    } else {
+                        self.pen.bg = color;
+                    }
+                    i += consumed;
+                }
+                _ => {}
+            }
+            i += 1;
+        }
+    }
+
+    fn render_line(&self, row: &[Cell]) -> ScreenLine {
+        // Trim trailing blank, unstyled cells
+        let len = row
+            .iter()
+            .rposition(|c| *c != Cell::default())
+            .map(|i| i + 1)
+            .unwrap_or(0);
+        let row = &row[..len];
+
+        let text = row.iter().map(|c| c.ch).collect();
+        let mut spans: Vec<StyleSpan> = Vec::new();
+        for (i, cell) in row.iter().enumerate() {
+            if cell.pen == Pen::default() {
+                continue;
+            }
+            match spans.last_mut() {
+                Some(span)
+                    if (span.start + span.len) as usize == i
+                        && span.bold == cell.pen.bold
+                        && span.inverse == cell.pen.inverse
+                        && span.fg == cell.pen.fg
+                        && span.bg == cell.pen.bg =>
+                {
+                    span.len += 1;
+                }
+                _ => spans.push(StyleSpan {
+                    start: i as u16,
+                    len: 1,
+                    bold: cell.pen.bold,
+                    inverse: cell.pen.inverse,
+                    fg: cell.pen.fg,
+                    bg: cell.pen.bg,
+                }),
+            }
+        }
+
+        ScreenLine { text, spans }
+    }
+}
+
+impl Perform for Grid {
+    fn print(&mut self, c: char) {
+        if self.wrap_pending {
+            self.cursor_col = 0;
+            self.line_feed();
+            self.wrap_pending = false;
+        }
+
+        let (row, col) = (self.cursor_row as usize, self.cursor_col as usize);
+        self.cells[row][col] = Cell { ch: c, pen: self.pen };
+
+        if self.cursor_col + 1 >= self.cols {
+            self.wrap_pending = true;
+        } else {
+            self.cursor_col += 1;
+        }
+    }
+
+    fn execute(&mut self, byte: u8) {
+        match byte {
+            // Carriage return
+            0x0D => {
+                self.cursor_col = 0;
+                self.wrap_pending = false;
+            }
+            // Line feed, vertical tab, form feed
+            0x0A..=0x0C => {
+                self.line_feed();
+                self.wrap_pending = false;
+            }
+            // Backspace
+            0x08 => {
+                self.cursor_col = self.cursor_col.saturating_sub(1);
+                self.wrap_pending = false;
+            }
+            // Tab
+            0x09 => {
+                let next = ((self.cursor_col / 8) + 1) * 8;
+                self.cursor_col = next.min(self.cols - 1);
+            }
+            _ => {}
+        }
+    }
+
+    fn hook(&mut self, _params: &vte::Params, _intermediates: &[u8], _ignore: bool, _action: char) {}
+
+    fn put(&mut self, _byte: u8) {}
+
+    fn unhook(&mut self) {}
+
+    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
+
+    fn csi_dispatch(
+        &mut self,
+        params: &vte::Params,
+        intermediates: &[u8],
+        _ignore: bool,
+        action: char,
+    ) {
+        let params: Vec<Vec<u16>> = params.iter().map(|p| p.to_vec()).collect();
+        // First value of a parameter, treating 0/missing as `default`
+        let param = |idx: usize, default: u16| -> u16 {
+            params
+                .get(idx)
+                .and_then(|p| p.first().copied())
+                .filter(|&v| v != 0)
+                .unwrap_or(default)
+        };
+        let private = intermediates.first() == Some(&b'?');
+
+        match action {
+            'A' => {
+                let row = self.cursor_row.saturating_sub(param(0, 1));
+                self.move_to(row, self.cursor_col);
+            }
+            'B' | 'e' => {
+                let row = self.cursor_row.saturating_add(param(0, 1));
+                self.move_to(row, self.cursor_col);
+            }
+            'C' | 'a' => {
+                let col = self.cursor_col.saturating_add(param(0, 1));
+                self.move_to(self.cursor_row, col);
+            }
+            'D' => {
+                let col = self.cursor_col.saturating_sub(param(0, 1));
+                self.move_to(self.cursor_row, col);
+            }
+            'E' => {
+                let row = self.cursor_row.saturating_add(param(0, 1));
+                self.move_to(row, 0);
+            }
+            'F' => {
+                let row = self.cursor_row.saturating_sub(param(0, 1));
+                self.move_to(row, 0);
+            }
+            'G' | '`' => self.move_to(self.cursor_row, param(0, 1) - 1),
+            'd' => self.move_to(param(0, 1) - 1, self.cursor_col),
+            'H' | 'f' => self.move_to(param(0, 1) - 1, param(1, 1) - 1),
+            'J' => self.erase_in_display(params.first().and_then(|p| p.first().copied()).unwrap_or(0)),
+            'K' => self.erase_in_line(params.first().and_then(|p| p.first().copied()).unwrap_or(0)),
+            'X' => {
+                let (row, col) = (self.cursor_row, self.cursor_col);
+                self.erase_cells(row, col, col.saturating_add(param(0, 1)));
+            }
+            'm' if !private => self.apply_sgr(&params),
+            's' if !private => self.saved_cursor = (self.cursor_row, self.cursor_col),
+            'u' if !private => {
+                let (row, col) = self.saved_cursor;
+                self.move_to(row, col);
+            }
+            'h' | 'l' if private => {
+                let enable = action == 'h';
+                for p in &params {
+                    if let Some(47 | 1047 | 1049) = p.first() {
+                        self.set_alt_screen(enable);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn esc_dispatch(&mut self, intermediates: &[u8], _ignore: bool, byte: u8) {
+        if !intermediates.is_empty() {
+            return;
+        }
+        match byte {
+            // DECSC / DECRC
+            b'7' => self.saved_cursor = (self.cursor_row, self.cursor_col),
+            b'8' => {
+                let (row, col) = self.saved_cursor;
+                self.move_to(row, col);
+            }
+            // IND / NEL
+            b'D' => self.line_feed(),
+            b'E' => {
+                self.line_feed();
+                self.cursor_col = 0;
+            }
+            // RI - reverse index, scrolling down at the top
+            b'M' => {
+                if self.cursor_row == 0 {
+                    self.cells.pop();
+                    let blank = self.blank_row();
+                    self.cells.insert(0, blank);
+                } else {
+                    self.cursor_row -= 1;
+                }
+            }
+            // RIS - full reset
+            b'c' => *self = Grid::new(self.rows, self.cols),
+            _ => {}
+        }
+    }
+}
+
+/// Drops `drop_top` rows, then pads or truncates `cells` to `rows` x `cols`.
+fn resize_cells(cells: &mut Vec<Vec<Cell>>, rows: u16, cols: u16, drop_top: u16) {
+    cells.drain(..(drop_top as usize).min(cells.len()));
+    cells.resize(rows as usize, vec![Cell::default(); cols as usize]);
+    for row in cells.iter_mut() {
+        row.resize(cols as usize, Cell::default());
+    }
+}
+
+/// A fixed-size screen fed with raw PTY bytes.
+pub struct ScreenGrid {
+    parser: Parser,
+    grid: Grid,
+}
+
+impl ScreenGrid {
+    /// Creates a blank screen. Zero dimensions are clamped to 1.
+    pub fn new(rows: u16, cols: u16) -> Self {
+        Self {
+            parser: Parser::new(),
+            grid: Grid::new(rows, cols),
+        }
+    }
+
+    /// Parses `bytes` and applies them to the screen.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.parser.advance(&mut self.grid, bytes);
+    }
+
+    /// Changes the screen size, keeping existing content where it fits.
+    pub fn resize(&mut self, rows: u16, cols: u16) {
+        self.grid.resize(rows, cols);
+    }
+
+    /// Returns the cursor position as `(row, col)`, 0-indexed.
+    pub fn cursor(&self) -> (u16, u16) {
+        (self.grid.cursor_row, self.grid.cursor_col)
+    }
+
+    /// Whether the alternate screen buffer is active (full-screen TUIs).
+    pub fn is_alt_screen(&self) -> bool {
+        self.grid.saved_primary.is_some()
+    }
+
+    /// Renders the visible rows as text plus style spans.
+    pub fn snapshot(&self) -> ScreenSnapshot {
+        ScreenSnapshot {
+            rows: self.grid.rows,
+            cols: self.grid.cols,
+            lines: self.grid.cells.iter().map(|row| self.grid.render_line(row)).collect(),
+            source: ScreenSource::Grid,
+        }
+    }
+}
+
+/// Replays `output` onto a `rows` x `cols` screen and returns what is visible.
+pub fn render_screen(output: &str, rows: u16, cols: u16) -> ScreenSnapshot {
+    let mut screen = ScreenGrid::new(rows, cols);
+    screen.feed(output.as_bytes());
+    screen.snapshot()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(snapshot: &ScreenSnapshot) -> Vec<String> {
+        snapshot.lines.iter().map(|l| l.text.clone()).collect()
+    }
+
+    #[test]
+    fn scrolls_and_keeps_last_rows() {
+        let snap = render_screen("one\r\ntwo\r\nthree\r\nfour", 3, 10);
+        assert_eq!(texts(&snap), vec!["two", "three", "four"]);
+    }
+
+    #[test]
+    fn clear_screen_and_cursor_position() {
+        let mut screen = ScreenGrid::new(3, 10);
+        screen.feed(b"junk\x1b[2J\x1b[2;3Hhi");
+        assert_eq!(texts(&screen.snapshot()), vec!["", "  hi", ""]);
+        assert_eq!(screen.cursor(), (1, 4));
+    }
+
+    #[test]
+    fn sgr_produces_spans() {
+        let snap = render_screen("a\x1b[1;31mbc\x1b[0md", 1, 10);
+        let line = &snap.lines[0];
+        assert_eq!(line.text, "abcd");
+        assert_eq!(
+            line.spans,
+            vec![StyleSpan {
+                start: 1,
+                len: 2,
+                bold: true,
+                inverse: false,
+                fg: Some(1),
+                bg: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn resize_keeps_cursor_line_visible() {
+        let mut screen = ScreenGrid::new(3, 10);
+        screen.feed(b"one\r\ntwo\r\nthree");
+        screen.resize(2, 4);
+        assert_eq!(texts(&screen.snapshot()), vec!["two", "thre"]);
+        assert_eq!(screen.cursor(), (1, 3));
+    }
+
+    #[test]
+    fn alt_screen_restores_primary() {
+        let mut screen = ScreenGrid::new(2, 10);
+        screen.feed(b"shell\x1b[?1049hvim");
+        assert!(screen.is_alt_screen());
+        assert_eq!(texts(&screen.snapshot()), vec!["vim", ""]);
+        screen.feed(b"\x1b[?1049l");
+        assert!(!screen.is_alt_screen());
+        assert_eq!(texts(&screen.snapshot()), vec!["shell", ""]);
+    }
+}
@@ -0,0 +1,153 @@
+//! Persists session scrollback to `~/.chorus/sessions/<id>.log`.
+//!
+//! Unlike asciicast recording this keeps no timing, only the most recent
+//! output, so a transcript survives a crash and can be shown again when the
+//! session is restored. Output is appended as it is flushed; once the file
+//! grows to twice the session's scrollback limit it is rewritten from the
+//! in-memory scrollback, so it stays bounded. Logs hold raw terminal output,
+//! so they are readable only by the current user.
+
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+/// Directory holding persisted scrollback, or None without a home directory.
+pub fn default_dir() -> Option<PathBuf> {
+    Some(dirs::home_dir()?.join(".chorus").join("sessions"))
+}
+
+/// Path of `session_id`'s log inside `dir`.
+pub fn log_path(dir: &Path, session_id: u32) -> PathBuf {
+    dir.join(format!("{session_id}.log"))
+}
+
+/// Appends one session's output to its log file, compacting it when it grows.
+pub struct ScrollbackLog {
+    file: File,
+    path: PathBuf,
+    /// Bytes in the file; compared against the limit to trigger compaction.
+    len: u64,
+}
+
+impl ScrollbackLog {
+    /// Starts a fresh log at `path` holding `retained`, replacing any older log.
+    pub fn create(path: &Path, retained: &str) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut file = create_private(path)?;
+        file.write_all(retained.as_bytes())?;
+        Ok(Self {
+            file,
+            path: path.to_path_buf(),
+            len: retained.len() as u64,
+        })
+    }
+
+    /// Appends `text`. Once the file exceeds twice `limit`, it is rewritten
+    /// to hold just `retained`, the session's current in-memory scrollback.
+    pub fn append(&mut self, text: &str, retained: &str, limit: usize) -> std::io::Result<()> {
+        self.file.write_all(text.as_bytes())?;
+        self.len += text.len() as u64;
+        if self.len > 2 * limit as u64 {
+            self.compact(retained)?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the file's contents with `retained` via a temp file and
+    /// rename, so a crash mid-compaction leaves the old log intact.
+    fn compact(&mut self, retained: &str) -> std::io::Result<()> {
+        let tmp = self.path.with_extension("log.tmp");
+        create_private(&tmp)?.write_all(retained.as_bytes())?;
+        std::fs::rename(&tmp, &self.path)?;
+        self.file = OpenOptions::new().append(true).open(&self.path)?;
+        self.len = retained.len() as u64;
+        Ok(())
+    }
+
+    /// Closes the log and deletes its file.
+    pub fn discard(self) -> std::io::Result<()> {
+        let path = self.path.clone();
+        drop(self);
+        remove(&path)
+    }
+}
+
+/// Creates (or truncates) `path` for writing, readable only by the current
+/// user where the OS supports it.
+fn create_private(path: &Path) -> std::io::Result<File> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)
+}
+
+/// Reads a persisted log, or None if there is none.
+pub fn read(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Deletes a persisted log; a log that doesn't exist is not an error.
+pub fn remove(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn appends_and_compacts_to_retained_output() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = log_path(dir.path(), 7);
+        let mut log = ScrollbackLog::create(&path, "seed\n").unwrap();
+
+        log.append("one\n", "seed\none\n", 8).unwrap();
+        assert_eq!(read(&path).unwrap(), "seed\none\n");
+
+        // Past twice the limit the file shrinks to what the buffer retains
+        log.append("two three\n", "three\n", 8).unwrap();
+        assert_eq!(read(&path).unwrap(), "three\n");
+        log.append("four\n", "three\nfour\n", 8).unwrap();
+        assert_eq!(read(&path).unwrap(), "three\nfour\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn logs_are_private_to_the_user() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = log_path(dir.path(), 2);
+        let mut log = ScrollbackLog::create(&path, "secret").unwrap();
+        let mode = |p: &Path| std::fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&path), 0o600);
+
+        // Compaction replaces the file; the new one must be private too
+        log.append(&"x".repeat(20), "x", 4).unwrap();
+        assert_eq!(mode(&path), 0o600);
+    }
+
+    #[test]
+    fn discard_and_remove_delete_the_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = log_path(dir.path(), 1);
+        ScrollbackLog::create(&path, "x")
+            .unwrap()
+            .discard()
+            .unwrap();
+        assert!(read(&path).is_none());
+
+        // Removing a missing log is fine
+        remove(&path).unwrap();
+    }
+}
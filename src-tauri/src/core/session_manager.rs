@@ -1,19 +1,110 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
 use dashmap::DashMap;
 use dashmap::mapref::entry::Entry;
 use serde::{Deserialize, Serialize};
 
+use super::store_debouncer::DEFAULT_SAVE_DEBOUNCE;
+
+/// File name of the persisted session registry inside the app data dir.
+pub const SESSIONS_FILE: &str = "sessions.json";
+
+/// Maximum length of a session note, in characters. Longer notes are truncated.
+pub const MAX_SESSION_NOTE_CHARS: usize = 2000;
+
+/// Environment variables set by the process manager itself, which a
+/// session's custom env must not override.
+pub const RESERVED_SESSION_ENV_VARS: &[&str] = &["CHORUS_SESSION_ID"];
+
 /// Which AI backend a session is configured to use.
 ///
 /// `Plain` is a raw terminal with no AI agent attached, useful for
-/// manual shell work within a worktree.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// manual shell work within a worktree. New variants must only be appended
+/// under new names so stored sessions keep deserializing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AiMode {
     Claude,
     Gemini,
     Codex,
+    Aider,
     Plain,
 }
 
+impl AiMode {
+    /// Every mode, in the order the UI lists them.
+    pub const ALL: [AiMode; 5] = [
+        AiMode::Claude,
+        AiMode::Gemini,
+        AiMode::Codex,
+        AiMode::Aider,
+        AiMode::Plain,
+    ];
+
+    /// Display name for the mode.
+    pub fn label(&self) -> &'static str {
+        match self {
+            AiMode::Claude => "Claude Code",
+            AiMode::Gemini => "Gemini CLI",
+            AiMode::Codex => "Codex",
+            AiMode::Aider => "Aider",
+            AiMode::Plain => "Terminal",
+        }
+    }
+
+    /// CLI binary the mode launches, or `None` for a plain shell.
+    pub fn cli_command(&self) -> Option<&'static str> {
+        match self {
+            AiMode::Claude => Some("claude"),
+            AiMode::Gemini => Some("gemini"),
+            AiMode::Codex => Some("codex"),
+            AiMode::Aider => Some("aider"),
+            AiMode::Plain => None,
+        }
+    }
+
+    /// How to install the mode's CLI, shown when it is missing.
+    pub fn install_hint(&self) -> Option<&'static str> {
+        match self {
+            AiMode::Claude => Some("npm install -g @anthropic-ai/claude-code"),
+            AiMode::Gemini => Some("npm install -g @google/gemini-cli"),
+            AiMode::Codex => Some("npm install -g codex"),
+            AiMode::Aider => Some("python -m pip install aider-install && aider-install"),
+            AiMode::Plain => None,
+        }
+    }
+
+    /// Whether the mode's CLI reads a project `.mcp.json`. Other CLIs keep
+    /// MCP settings in their own config files, or have no MCP support.
+    pub fn uses_mcp_config(&self) -> bool {
+        matches!(self, AiMode::Claude)
+    }
+}
+
+/// Describes an `AiMode` for the UI's mode picker.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiModeInfo {
+    pub mode: AiMode,
+    pub label: &'static str,
+    pub command: Option<&'static str>,
+    pub install_hint: Option<&'static str>,
+    pub uses_mcp_config: bool,
+}
+
+impl From<AiMode> for AiModeInfo {
+    fn from(mode: AiMode) -> Self {
+        Self {
+            mode,
+            label: mode.label(),
+            command: mode.cli_command(),
+            install_hint: mode.install_hint(),
+            uses_mcp_config: mode.uses_mcp_config(),
+        }
+    }
+}
+
 /// Lifecycle state of a session, tracked for UI status indicators.
 ///
 /// Transitions are driven by the frontend; the backend does not enforce
@@ -27,6 +118,8 @@ pub enum SessionStatus {
     NeedsInput,
     Done,
     Error,
+    /// Restored from disk after a restart; its PTY is no longer attached.
+    Stopped,
 }
 
 /// Frontend-visible configuration and state for a single session.
@@ -45,6 +138,28 @@ pub struct SessionConfig {
     pub project_path: String,
     /// Auto-generated title from first user message.
     pub title: Option<String>,
+    /// Freeform user note, e.g. "waiting on review, do not merge".
+    pub note: Option<String>,
+    /// Explicit display position set by `reorder`. Unordered sessions sort
+    /// after ordered ones, by ID.
+    pub order: Option<i32>,
+    /// User-defined labels for grouping sessions across projects.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Extra environment variables for the session's shell, e.g. per-session
+    /// API keys or PATH tweaks. Applied when the shell is (re)spawned; changing
+    /// them does not affect an already running process.
+    #[serde(default)]
+    pub env: BTreeMap<String, String>,
+}
+
+/// Result of `SessionManager::update_status_bulk`.
+#[derive(Debug, Clone, Serialize)]
+pub struct BulkStatusUpdate {
+    /// Sessions whose status was updated, in the order requested.
+    pub updated: Vec<SessionConfig>,
+    /// Requested IDs that don't exist.
+    pub missing: Vec<u32>,
 }
 
 /// Thread-safe session registry backed by `DashMap` for lock-free concurrent reads.
@@ -53,7 +168,83 @@ pub struct SessionConfig {
 /// no exclusive access is needed, enabling safe concurrent access from
 /// multiple async command handlers.
 pub struct SessionManager {
-    sessions: DashMap<u32, SessionConfig>,
+    sessions: Arc<DashMap<u32, SessionConfig>>,
+    /// Saves the registry to its JSON file after mutations, if persisted.
+    store: Option<Arc<SessionStore>>,
+}
+
+/// Writes the session registry file off the caller's thread.
+///
+/// Mutations only mark the registry dirty; a background thread waits for the
+/// first one, lets `DEFAULT_SAVE_DEBOUNCE` pass so a burst of status updates
+/// collapses into one write, then saves the latest snapshot. `flush` writes
+/// synchronously and is used on exit.
+struct SessionStore {
+    path: PathBuf,
+    state: Mutex<StoreState>,
+    wake: Condvar,
+    /// Serializes writes so the file always ends up with the latest snapshot.
+    write: Mutex<()>,
+}
+
+#[derive(Default)]
+struct StoreState {
+    dirty: bool,
+    closed: bool,
+}
+
+impl SessionStore {
+    fn mark_dirty(&self) {
+        lock(&self.state).dirty = true;
+        self.wake.notify_one();
+    }
+
+    /// Background save loop; returns once the store is closed.
+    fn run(&self, sessions: &DashMap<u32, SessionConfig>) {
+        loop {
+            {
+                let mut state = lock(&self.state);
+                while !state.dirty && !state.closed {
+                    state = self.wake.wait(state).unwrap_or_else(|p| p.into_inner());
+                }
+                if state.closed {
+                    return;
+                }
+            }
+            std::thread::sleep(DEFAULT_SAVE_DEBOUNCE);
+            self.flush(sessions);
+        }
+    }
+
+    /// Writes the registry now if it changed since the last write. Failures
+    /// are logged; the in-memory registry stays authoritative.
+    fn flush(&self, sessions: &DashMap<u32, SessionConfig>) {
+        let _write = lock(&self.write);
+        {
+            let mut state = lock(&self.state);
+            if !state.dirty {
+                return;
+            }
+            state.dirty = false;
+        }
+        if let Err(e) = write_sessions_file(&self.path, &sorted_snapshot(sessions)) {
+            log::error!("Failed to save sessions to {:?}: {}", self.path, e);
+        }
+    }
+
+    fn close(&self) {
+        lock(&self.state).closed = true;
+        self.wake.notify_one();
+    }
+}
+
+impl Drop for SessionManager {
+    fn drop(&mut self) {
+        if let Some(store) = &self.store {
+            store.close();
+            store.flush(&self.sessions);
+        }
+    }
 }
 
 impl Default for SessionManager {
@@ -63,31 +254,122 @@ impl Default for SessionManager {
 }
 
 impl SessionManager {
-    /// Creates an empty session registry.
+    /// Creates an empty, in-memory-only session registry.
     pub fn new() -> Self {
         Self {
-            sessions: DashMap::new(),
+            sessions: Arc::new(DashMap::new()),
+            store: None,
+        }
+    }
+
+    /// Creates a registry persisted to `path`, restoring any sessions saved there.
+    ///
+    /// Restored sessions are marked `Stopped` since their PTYs did not survive
+    /// the restart. A missing file starts empty; an unreadable or corrupt one
+    /// is logged, moved aside to `<path>.corrupt`, and also starts empty.
+    pub fn load(path: PathBuf) -> Self {
+        let restored = match std::fs::read_to_string(&path) {
+            Ok(content) => match serde_json::from_str::<Vec<SessionConfig>>(&content) {
+                Ok(sessions) => sessions,
+                Err(e) => {
+                    log::error!("Corrupt session file {:?}, starting empty: {}", path, e);
+                    let _ = std::fs::rename(&path, path.with_extension("json.corrupt"));
+                    Vec::new()
+                }
+            },
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                log::error!("Failed to read session file {:?}, starting empty: {}", path, e);
+                Vec::new()
+            }
+        };
+
+        let sessions = Arc::new(DashMap::new());
+        for mut session in restored {
+            session.status = SessionStatus::Stopped;
+            sessions.insert(session.id, session);
+        }
+        log::info!("Restored {} session(s) from {:?}", sessions.len(), path);
+
+        let store = Arc::new(SessionStore {
+            path,
+            state: Mutex::new(StoreState::default()),
+            wake: Condvar::new(),
+            write: Mutex::new(()),
+        });
+        let (writer, snapshot_source) = (store.clone(), sessions.clone());
+        if let Err(e) = std::thread::Builder::new()
+            .name("session-store".to_string())
+            .spawn(move || writer.run(&snapshot_source))
+        {
+            log::error!("Failed to start session store writer, saving on exit only: {}", e);
+        }
+
+        Self {
+            sessions,
+            store: Some(store),
         }
     }
 
+    /// Schedules a save of the registry, if it is persisted.
+    fn persist(&self) {
+        if let Some(store) = &self.store {
+            store.mark_dirty();
+        }
+    }
+
+    /// Writes any pending registry changes to disk immediately.
+    pub fn flush(&self) {
+        if let Some(store) = &self.store {
+            store.flush(&self.sessions);
+        }
+    }
+
+    /// Applies `f` to a session and persists the change.
+    /// Returns `None` if the session does not exist.
+    fn modify<R>(&self, id: u32, f: impl FnOnce(&mut SessionConfig) -> R) -> Option<R> {
+        let result = self.sessions.get_mut(&id).map(|mut session| f(&mut session))?;
+        self.persist();
+        Some(result)
+    }
+
     /// Inserts a new session with `Idle` status and no branch assigned.
     /// Returns `Err` with the existing config if a session with this ID already exists.
-    pub fn create_session(&self, id: u32, mode: AiMode, project_path: String) -> Result<SessionConfig, SessionConfig> {
-        let config = SessionConfig {
-            id,
-            mode,
-            branch: None,
-            status: SessionStatus::Idle,
-            worktree_path: None,
-            project_path,
-            title: None,
-        };
-        match self.sessions.entry(id) {
-            Entry::Occupied(e) => Err(e.get().clone()),
+    pub fn create_session(&self, id: u32, mode: AiMode, project_path: String) -> Result<SessionConfig, Box<SessionConfig>> {
+        let result = match self.sessions.entry(id) {
+            Entry::Occupied(e) => Err(Box::new(e.get().clone())),
             Entry::Vacant(e) => {
+                let config = new_session_config(id, mode, project_path);
                 e.insert(config.clone());
                 Ok(config)
             }
+        };
+        if result.is_ok() {
+            self.persist();
+        }
+        result
+    }
+
+    /// Like `create_session`, but takes IDs from `allocate` until one is free.
+    ///
+    /// Session and PTY IDs share one space (`pty-output-{id}` is routed by
+    /// it), so callers pass `ProcessManager::allocate_session_id` to keep a
+    /// later `spawn_shell` from handing out the same ID. IDs already taken by
+    /// explicit creation are skipped. Returns `None` once `allocate` does.
+    pub fn create_session_auto(
+        &self,
+        mut allocate: impl FnMut() -> Option<u32>,
+        mode: AiMode,
+        project_path: String,
+    ) -> Option<SessionConfig> {
+        loop {
+            let id = allocate()?;
+            if let Entry::Vacant(e) = self.sessions.entry(id) {
+                let config = new_session_config(id, mode, project_path);
+                e.insert(config.clone());
+                self.persist();
+                return Some(config);
+            }
         }
     }
 
@@ -99,54 +381,143 @@ impl SessionManager {
     /// Updates the session's status in place. Returns `false` if the session
     /// does not exist (no error is raised).
     pub fn update_status(&self, id: u32, status: SessionStatus) -> bool {
-        if let Some(mut session) = self.sessions.get_mut(&id) {
-            session.status = status;
-            true
-        } else {
-            false
+        self.modify(id, |session| session.status = status).is_some()
+    }
+
+    /// Sets the same status on several sessions, persisting once.
+    /// Unknown IDs are skipped and reported in `missing`; duplicates are applied once.
+    pub fn update_status_bulk(&self, ids: &[u32], status: SessionStatus) -> BulkStatusUpdate {
+        let mut result = BulkStatusUpdate { updated: Vec::new(), missing: Vec::new() };
+        for &id in ids {
+            if result.updated.iter().any(|s| s.id == id) || result.missing.contains(&id) {
+                continue;
+            }
+            match self.sessions.get_mut(&id) {
+                Some(mut session) => {
+                    session.status = status.clone();
+                    result.updated.push(session.clone());
+                }
+                None => result.missing.push(id),
+            }
+        }
+        if !result.updated.is_empty() {
+            self.persist();
         }
+        result
     }
 
     /// Updates the session's title. Returns `false` if the session does not exist.
     pub fn update_title(&self, id: u32, title: String) -> bool {
-        if let Some(mut session) = self.sessions.get_mut(&id) {
-            session.title = Some(title);
-            true
-        } else {
-            false
+        self.modify(id, |session| session.title = Some(title)).is_some()
+    }
+
+    /// Sets or clears the session's note. Blank notes clear it, and notes
+    /// longer than `MAX_SESSION_NOTE_CHARS` are truncated.
+    /// Returns the updated config, or `None` if the session does not exist.
+    pub fn set_note(&self, id: u32, note: Option<String>) -> Option<SessionConfig> {
+        let note = note
+            .map(|n| n.trim().chars().take(MAX_SESSION_NOTE_CHARS).collect::<String>())
+            .filter(|n| !n.is_empty());
+        self.modify(id, |session| {
+            session.note = note;
+            session.clone()
+        })
+    }
+
+    /// Replaces the session's tags. Tags are trimmed, blanks dropped and
+    /// duplicates removed (first occurrence wins); an empty list clears them.
+    /// Returns the updated config, or `None` if the session does not exist.
+    pub fn set_tags(&self, id: u32, tags: Vec<String>) -> Option<SessionConfig> {
+        let mut cleaned: Vec<String> = Vec::with_capacity(tags.len());
+        for tag in tags {
+            let tag = tag.trim();
+            if !tag.is_empty() && !cleaned.iter().any(|t| t == tag) {
+                cleaned.push(tag.to_string());
+            }
         }
+        self.modify(id, |session| {
+            session.tags = cleaned;
+            session.clone()
+        })
+    }
+
+    /// Replaces the session's custom environment. Callers should check the
+    /// variables with `validate_session_env` first. Takes effect on the next
+    /// spawn or restart of the session's shell.
+    /// Returns the updated config, or `None` if the session does not exist.
+    pub fn set_env(&self, id: u32, env: BTreeMap<String, String>) -> Option<SessionConfig> {
+        self.modify(id, |session| {
+            session.env = env;
+            session.clone()
+        })
+    }
+
+    /// Returns the session's custom environment, or `None` if the session does not exist.
+    pub fn get_env(&self, id: u32) -> Option<BTreeMap<String, String>> {
+        self.sessions.get(&id).map(|s| s.env.clone())
+    }
+
+    /// Returns all sessions carrying `tag`, across projects, in display order.
+    pub fn get_sessions_by_tag(&self, tag: &str) -> Vec<SessionConfig> {
+        let tag = tag.trim();
+        let mut sessions: Vec<SessionConfig> = self.sessions
+            .iter()
+            .filter(|entry| entry.value().tags.iter().any(|t| t == tag))
+            .map(|entry| entry.value().clone())
+            .collect();
+        sort_for_display(&mut sessions);
+        sessions
     }
 
     /// Associates a branch (and optional worktree path) with an existing session.
     /// Returns the updated config, or `None` if the session does not exist.
     pub fn assign_branch(&self, id: u32, branch: String, worktree_path: Option<String>) -> Option<SessionConfig> {
-        if let Some(mut session) = self.sessions.get_mut(&id) {
+        self.modify(id, |session| {
             session.branch = Some(branch);
             session.worktree_path = worktree_path;
-            Some(session.clone())
-        } else {
-            None
-        }
+            session.clone()
+        })
     }
 
-    /// Returns a snapshot of all active sessions. Order is not guaranteed.
+    /// Returns a snapshot of all active sessions in display order.
     pub fn all_sessions(&self) -> Vec<SessionConfig> {
-        self.sessions.iter().map(|e| e.value().clone()).collect()
+        sorted_snapshot(&self.sessions)
+    }
+
+    /// Sets the display order to follow `ids`. Listed sessions get positions
+    /// in sequence; unknown IDs are ignored and unlisted sessions keep their
+    /// current position. Returns all sessions in the new order.
+    pub fn reorder(&self, ids: &[u32]) -> Vec<SessionConfig> {
+        let mut position = 0;
+        for id in ids {
+            if let Some(mut session) = self.sessions.get_mut(id) {
+                session.order = Some(position);
+                position += 1;
+            }
+        }
+        self.persist();
+        self.all_sessions()
     }
 
     /// Removes and returns a session. Returns `None` if not found.
     pub fn remove_session(&self, id: u32) -> Option<SessionConfig> {
-        self.sessions.remove(&id).map(|(_, v)| v)
+        let removed = self.sessions.remove(&id).map(|(_, v)| v);
+        if removed.is_some() {
+            self.persist();
+        }
+        removed
     }
 
-    /// Returns all sessions for a specific project path.
+    /// Returns all sessions for a specific project path, in display order.
     /// Performs an exact match on project paths.
     pub fn get_sessions_for_project(&self, project_path: &str) -> Vec<SessionConfig> {
-        self.sessions
+        let mut sessions: Vec<SessionConfig> = self.sessions
             .iter()
             .filter(|entry| entry.value().project_path == project_path)
             .map(|entry| entry.value().clone())
-            .collect()
+            .collect();
+        sort_for_display(&mut sessions);
+        sessions
     }
 
     /// Removes all sessions for a project. Returns the removed configs.
@@ -158,9 +529,276 @@ impl SessionManager {
             .map(|entry| *entry.key())
             .collect();
 
-        ids_to_remove
+        let removed: Vec<SessionConfig> = ids_to_remove
             .into_iter()
             .filter_map(|id| self.sessions.remove(&id).map(|(_, v)| v))
-            .collect()
+            .collect();
+        if !removed.is_empty() {
+            self.persist();
+        }
+        removed
+    }
+}
+
+/// Clones every session, in display order.
+fn sorted_snapshot(sessions: &DashMap<u32, SessionConfig>) -> Vec<SessionConfig> {
+    let mut sessions: Vec<SessionConfig> = sessions.iter().map(|e| e.value().clone()).collect();
+    sort_for_display(&mut sessions);
+    sessions
+}
+
+/// Locks `mutex`, recovering the guard if a previous holder panicked.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Atomically replaces `path` with the JSON-serialized `sessions`.
+fn write_sessions_file(path: &Path, sessions: &[SessionConfig]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(sessions)?;
+    let tmp_path = path.with_extension("json.tmp");
+    std::fs::write(&tmp_path, content)?;
+    std::fs::rename(&tmp_path, path)
+}
+
+/// Builds the initial config for a freshly created session.
+fn new_session_config(id: u32, mode: AiMode, project_path: String) -> SessionConfig {
+    SessionConfig {
+        id,
+        mode,
+        branch: None,
+        status: SessionStatus::Idle,
+        worktree_path: None,
+        project_path,
+        title: None,
+        note: None,
+        order: None,
+        tags: Vec::new(),
+        env: BTreeMap::new(),
+    }
+}
+
+/// Checks that every variable name can be passed to a child process and
+/// isn't one the process manager sets itself.
+pub fn validate_session_env(env: &BTreeMap<String, String>) -> Result<(), String> {
+    for (name, value) in env {
+        if name.is_empty() || name.contains('=') || name.contains('\0') {
+            return Err(format!("Invalid environment variable name '{name}'"));
+        }
+        if value.contains('\0') {
+            return Err(format!("Environment variable '{name}' contains a NUL byte"));
+        }
+        if RESERVED_SESSION_ENV_VARS.contains(&name.as_str()) {
+            return Err(format!("'{name}' is set by Chorus and cannot be overridden"));
+        }
+    }
+    Ok(())
+}
+
+/// Sorts sessions with an explicit `order` first (ascending), then the rest by ID.
+fn sort_for_display(sessions: &mut [SessionConfig]) {
+    sessions.sort_by_key(|s| (s.order.is_none(), s.order, s.id));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::process_manager::ProcessManager;
+    use std::sync::Arc;
+
+    #[test]
+    fn every_ai_mode_with_a_cli_has_an_install_hint() {
+        for mode in AiMode::ALL {
+            assert_eq!(mode.cli_command().is_some(), mode.install_hint().is_some());
+        }
+        assert_eq!(AiMode::Claude.cli_command(), Some("claude"));
+        assert_eq!(AiMode::Plain.cli_command(), None);
+        assert!(!AiMode::Aider.uses_mcp_config());
+    }
+
+    #[test]
+    fn ai_mode_serialization_is_backward_compatible() {
+        for (json, mode) in [
+            ("\"Claude\"", AiMode::Claude),
+            ("\"Gemini\"", AiMode::Gemini),
+            ("\"Codex\"", AiMode::Codex),
+            ("\"Plain\"", AiMode::Plain),
+        ] {
+            assert_eq!(serde_json::from_str::<AiMode>(json).unwrap(), mode);
+            assert_eq!(serde_json::to_string(&mode).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn session_env_is_validated_and_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SESSIONS_FILE);
+        let manager = SessionManager::load(path.clone());
+        manager.create_session(1, AiMode::Claude, "/p".to_string()).unwrap();
+
+        let mut env = BTreeMap::new();
+        env.insert("ANTHROPIC_API_KEY".to_string(), "sk-test".to_string());
+        assert!(validate_session_env(&env).is_ok());
+        manager.set_env(1, env.clone()).unwrap();
+        assert!(manager.set_env(2, env.clone()).is_none());
+        manager.flush();
+
+        let reloaded = SessionManager::load(path);
+        assert_eq!(reloaded.get_env(1), Some(env));
+
+        for bad in ["", "A=B", "CHORUS_SESSION_ID"] {
+            let env = BTreeMap::from([(bad.to_string(), "x".to_string())]);
+            assert!(validate_session_env(&env).is_err(), "{bad:?} should be rejected");
+        }
+    }
+
+    #[test]
+    fn bulk_status_update_skips_missing_ids() {
+        let manager = SessionManager::new();
+        manager.create_session(1, AiMode::Claude, "/p".to_string()).unwrap();
+        manager.create_session(2, AiMode::Claude, "/p".to_string()).unwrap();
+
+        let result = manager.update_status_bulk(&[2, 9, 1, 2], SessionStatus::Done);
+
+        let updated: Vec<u32> = result.updated.iter().map(|s| s.id).collect();
+        assert_eq!(updated, [2, 1]);
+        assert_eq!(result.missing, [9]);
+        assert!(manager.all_sessions().iter().all(|s| matches!(s.status, SessionStatus::Done)));
+    }
+
+    #[test]
+    fn concurrent_auto_creates_get_distinct_ids() {
+        let manager = Arc::new(SessionManager::new());
+        let process_manager = Arc::new(ProcessManager::new());
+        manager.create_session(2, AiMode::Plain, "/p".to_string()).unwrap();
+
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let manager = manager.clone();
+                let process_manager = process_manager.clone();
+                std::thread::spawn(move || {
+                    (0..25)
+                        .map(|_| {
+                            manager
+                                .create_session_auto(
+                                    || process_manager.allocate_session_id().ok(),
+                                    AiMode::Claude,
+                                    "/p".to_string(),
+                                )
+                                .unwrap()
+                                .id
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+
+        let mut ids: Vec<u32> = handles.into_iter().flat_map(|h| h.join().unwrap()).collect();
+        ids.sort();
+        ids.dedup();
+        assert_eq!(ids.len(), 200);
+        assert!(!ids.contains(&2), "explicitly created ID must be skipped");
+        assert_eq!(manager.all_sessions().len(), 201);
+    }
+
+    #[test]
+    fn sessions_survive_reload_as_stopped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.json");
+
+        let manager = SessionManager::load(path.clone());
+        manager.create_session(4, AiMode::Claude, "/p".to_string()).unwrap();
+        manager.assign_branch(4, "feature".to_string(), Some("/wt".to_string()));
+        manager.update_status(4, SessionStatus::Working);
+        manager.create_session(7, AiMode::Plain, "/p".to_string()).unwrap();
+        manager.remove_session(7);
+        drop(manager);
+
+        let restored = SessionManager::load(path);
+        let sessions = restored.all_sessions();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].branch.as_deref(), Some("feature"));
+        assert!(matches!(sessions[0].status, SessionStatus::Stopped));
+        let mut ids = 4..;
+        let next = restored
+            .create_session_auto(|| ids.next(), AiMode::Claude, "/p".to_string())
+            .unwrap();
+        assert_eq!(next.id, 5, "restored ID must be skipped");
+    }
+
+    #[test]
+    fn auto_created_ids_are_not_reused_by_spawned_shells() {
+        let manager = SessionManager::new();
+        let process_manager = ProcessManager::new();
+        let allocate = || process_manager.allocate_session_id().ok();
+
+        // spawnShell() then createSession(sessionId), interleaved with auto creation
+        let shell = process_manager.allocate_session_id().unwrap();
+        manager.create_session(shell, AiMode::Claude, "/p".to_string()).unwrap();
+        let auto = manager.create_session_auto(allocate, AiMode::Plain, "/p".to_string()).unwrap();
+        let next_shell = process_manager.allocate_session_id().unwrap();
+        manager.create_session(next_shell, AiMode::Claude, "/p".to_string()).unwrap();
+
+        let mut ids = vec![shell, auto.id, next_shell];
+        ids.sort_unstable();
+        ids.dedup();
+        assert_eq!(ids.len(), 3);
+    }
+
+    #[test]
+    fn filters_sessions_by_tag_across_projects() {
+        let manager = SessionManager::new();
+        manager.create_session(1, AiMode::Claude, "/a".to_string()).unwrap();
+        manager.create_session(2, AiMode::Claude, "/b".to_string()).unwrap();
+        manager.create_session(3, AiMode::Claude, "/b".to_string()).unwrap();
+
+        let tagged = manager
+            .set_tags(1, vec![" frontend ".into(), "frontend".into(), "".into(), "flaky".into()])
+            .unwrap();
+        assert_eq!(tagged.tags, vec!["frontend", "flaky"]);
+        manager.set_tags(3, vec!["frontend".into()]);
+        manager.set_tags(2, vec!["backend".into()]);
+
+        let ids: Vec<u32> = manager.get_sessions_by_tag("frontend").iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![1, 3]);
+
+        manager.set_tags(3, Vec::new());
+        let ids: Vec<u32> = manager.get_sessions_by_tag("frontend").iter().map(|s| s.id).collect();
+        assert_eq!(ids, vec![1]);
+        assert!(manager.set_tags(99, vec!["x".into()]).is_none());
+    }
+
+    #[test]
+    fn saves_happen_off_the_caller_thread() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(SESSIONS_FILE);
+        let manager = SessionManager::load(path.clone());
+
+        manager.create_session(1, AiMode::Claude, "/p".to_string()).unwrap();
+        for _ in 0..50 {
+            manager.update_status(1, SessionStatus::Working);
+        }
+        assert!(!path.exists(), "mutations must not write synchronously");
+
+        manager.flush();
+        assert_eq!(SessionManager::load(path.clone()).all_sessions().len(), 1);
+
+        // Without a flush, the background writer catches up on its own
+        manager.create_session(2, AiMode::Plain, "/p".to_string()).unwrap();
+        std::thread::sleep(DEFAULT_SAVE_DEBOUNCE * 4);
+        assert_eq!(SessionManager::load(path).all_sessions().len(), 2);
+    }
+
+    #[test]
+    fn corrupt_session_file_starts_empty() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("sessions.json");
+        std::fs::write(&path, "{ not json").unwrap();
+
+        let manager = SessionManager::load(path.clone());
+        assert!(manager.all_sessions().is_empty());
+        assert!(dir.path().join("sessions.json.corrupt").exists());
     }
 }
@@ -0,0 +1,168 @@
+//! Records PTY output to an asciinema v2 `.cast` file.
+//!
+//! The file is newline-delimited JSON: a header object with the terminal
+//! size and start time, followed by `[elapsed_secs, "o", data]` output events
+//! and `[elapsed_secs, "r", "COLSxROWS"]` resize events. See
+//! <https://docs.asciinema.org/manual/asciicast/v2/>.
+
+use std::fs::{File, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use std::time::Instant;
+
+use serde_json::json;
+
+/// Directory web clients' recordings are saved in: `~/.chorus/recordings`.
+pub fn recordings_dir() -> Option<PathBuf> {
+    Some(dirs::home_dir()?.join(".chorus").join("recordings"))
+}
+
+/// Resolves a client-supplied recording name inside `dir`. Anything but a
+/// plain file name (separators, `..`, absolute paths) is refused.
+pub fn recording_path(dir: &Path, file_name: &str) -> Result<PathBuf, String> {
+    let mut components = Path::new(file_name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(name)), None) => Ok(dir.join(name)),
+        _ => Err(format!("Recording name must be a plain file name, got {:?}", file_name)),
+    }
+}
+
+/// Tees session output into an asciicast v2 file.
+///
+/// Writes are buffered; `finish` flushes and closes the file. Dropping the
+/// recorder without calling `finish` still flushes on a best-effort basis.
+pub struct AsciicastRecorder {
+    writer: BufWriter<File>,
+    path: PathBuf,
+    started: Instant,
+}
+
+impl AsciicastRecorder {
+    /// Creates `path` and writes the header. Fails if `path` already exists,
+    /// so a recording never overwrites another file.
+    pub fn create(path: &Path, cols: u16, rows: u16, title: Option<&str>) -> std::io::Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        let mut writer = BufWriter::new(file);
+
+        let mut header = json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": chrono::Utc::now().timestamp(),
+        });
+        if let Some(title) = title {
+            header["title"] = json!(title);
+        }
+        writeln!(writer, "{header}")?;
+
+        Ok(Self {
+            writer,
+            path: path.to_path_buf(),
+            started: Instant::now(),
+        })
+    }
+
+    /// Path of the cast file being written.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Records a chunk of terminal output.
+    pub fn output(&mut self, data: &str) -> std::io::Result<()> {
+        self.event("o", data)
+    }
+
+    /// Records a terminal resize so playback reflows at the same point.
+    pub fn resize(&mut self, cols: u16, rows: u16) -> std::io::Result<()> {
+        self.event("r", &format!("{cols}x{rows}"))
+    }
+
+    /// Flushes buffered events and closes the file.
+    pub fn finish(mut self) -> std::io::Result<PathBuf> {
+        self.writer.flush()?;
+        Ok(self.path)
+    }
+
+    fn event(&mut self, kind: &str, data: &str) -> std::io::Result<()> {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        writeln!(self.writer, "{}", json!([elapsed, kind, data]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    #[test]
+    fn scripted_session_produces_parseable_cast() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("casts").join("session-1.cast");
+
+        let mut rec = AsciicastRecorder::create(&path, 80, 24, Some("demo")).unwrap();
+        rec.output("$ echo hi\r\n").unwrap();
+        rec.resize(120, 40).unwrap();
+        rec.output("hi \u{1b}[32m✓\u{1b}[0m\r\n\"quoted\"").unwrap();
+        let written = rec.finish().unwrap();
+        assert_eq!(written, path);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<Value> = content
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 4);
+
+        let header = &lines[0];
+        assert_eq!(header["version"], 2);
+        assert_eq!(header["width"], 80);
+        assert_eq!(header["height"], 24);
+        assert_eq!(header["title"], "demo");
+        assert!(header["timestamp"].is_i64());
+
+        assert_eq!(lines[1][1], "o");
+        assert_eq!(lines[1][2], "$ echo hi\r\n");
+        assert_eq!(lines[2][1], "r");
+        assert_eq!(lines[2][2], "120x40");
+        assert_eq!(lines[3][2], "hi \u{1b}[32m✓\u{1b}[0m\r\n\"quoted\"");
+
+        let times: Vec<f64> = lines[1..].iter().map(|e| e[0].as_f64().unwrap()).collect();
+        assert!(times.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    fn dropping_without_finish_still_flushes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("abandoned.cast");
+
+        let mut rec = AsciicastRecorder::create(&path, 80, 24, None).unwrap();
+        rec.output("partial").unwrap();
+        drop(rec);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(content.lines().count(), 2);
+        assert!(!content.lines().next().unwrap().contains("title"));
+    }
+
+    #[test]
+    fn refuses_to_overwrite_an_existing_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        std::fs::write(&path, "keep me").unwrap();
+
+        assert!(AsciicastRecorder::create(&path, 80, 24, None).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "keep me");
+    }
+
+    #[test]
+    fn recording_path_only_accepts_plain_file_names() {
+        let dir = Path::new("/home/me/.chorus/recordings");
+        assert_eq!(recording_path(dir, "demo.cast").unwrap(), dir.join("demo.cast"));
+        for bad in ["", "../demo.cast", "sub/demo.cast", "/etc/passwd", "./demo.cast", ".."] {
+            assert!(recording_path(dir, bad).is_err(), "{bad:?} should be refused");
+        }
+    }
+}
@@ -4,12 +4,16 @@
 //! status updates from the Rust MCP server. Provides real-time updates
 //! and eliminates race conditions.
 
+use std::collections::{HashMap, VecDeque};
 use std::net::TcpListener;
 use std::sync::Arc;
+use std::time::Instant;
 
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
     routing::{get, post},
     Json, Router,
 };
@@ -20,10 +24,30 @@ use tokio::sync::RwLock;
 
 use super::event_bus::EventBus;
 use super::intel_hub::{
-    BroadcastMessage, BroadcastRequest, FileActivityRequest, FileConflict, IntelHub,
-    ScratchpadEntry, ScratchpadWriteRequest,
+    BroadcastMessage, BroadcastRequest, ConflictAckRequest, FileActivityRequest, FileConflict,
+    IntelHub, IntelScope, ScratchpadEntry, ScratchpadWriteRequest, SearchKind, SearchResult,
 };
 
+/// Header carrying the instance shared secret on intel requests.
+///
+/// MCP servers receive the secret as `CHORUS_INSTANCE_ID`; any other local
+/// process that finds the port is rejected with 401.
+pub const INSTANCE_TOKEN_HEADER: &str = "x-chorus-instance";
+
+/// Response to a report meant for another Chorus instance.
+///
+/// Each running instance has its own status server and `instance_id`, and
+/// hands both to its sessions' MCP servers. A request that carries a
+/// different instance id (in the payload or the token header) reached the
+/// wrong instance, e.g. an MCP server outliving its app while a second
+/// instance took over the port. It is rejected with 421 Misdirected Request,
+/// never applied, so the MCP server can say so instead of mis-routing.
+pub const WRONG_INSTANCE_STATUS: StatusCode = StatusCode::MISDIRECTED_REQUEST;
+
+/// Event pushed once when a file conflict forms or another session joins it.
+/// The payload is the [`FileConflict`].
+pub const INTEL_CONFLICT_DETECTED_EVENT: &str = "intel:conflict";
+
 /// Status payload received from MCP server.
 #[derive(Debug, Deserialize)]
 pub struct StatusRequest {
@@ -36,6 +60,86 @@ pub struct StatusRequest {
     pub timestamp: String,
 }
 
+/// Ports the status server tries in order; MCP configs are written with
+/// whichever one it got, so any free port in the range works.
+const STATUS_PORT_RANGE_START: u16 = 9900;
+const STATUS_PORT_RANGE_END: u16 = 9999;
+
+/// Log entries kept per session for the MCP debug panel.
+const MAX_MCP_LOG_ENTRIES: usize = 200;
+/// Longer log messages are truncated to this many bytes.
+const MAX_MCP_LOG_MESSAGE_LEN: usize = 4_096;
+/// MCP (RFC 5424) log levels accepted on `/log`.
+const MCP_LOG_LEVELS: &[&str] = &[
+    "debug",
+    "info",
+    "notice",
+    "warning",
+    "error",
+    "critical",
+    "alert",
+    "emergency",
+];
+
+/// Log line forwarded by an MCP server.
+#[derive(Debug, Deserialize)]
+pub struct LogRequest {
+    pub session_id: u32,
+    pub instance_id: String,
+    pub level: String,
+    pub message: String,
+    pub logger: Option<String>,
+}
+
+/// A stored MCP log line, also emitted to the frontend as `mcp-log`.
+#[derive(Debug, Clone, Serialize)]
+pub struct McpLogEntry {
+    pub session_id: u32,
+    pub level: String,
+    pub logger: Option<String>,
+    pub message: String,
+    pub timestamp: String,
+}
+
+/// Recent MCP log lines keyed by session.
+type McpLogs = Arc<RwLock<HashMap<u32, VecDeque<McpLogEntry>>>>;
+
+/// Last raw MCP state reported by each session.
+type McpStates = Arc<RwLock<HashMap<u32, ReportedState>>>;
+
+/// A session's last status POST.
+#[derive(Debug, Clone)]
+struct ReportedState {
+    state: String,
+    /// RFC 3339 time the status arrived.
+    reported_at: String,
+}
+
+/// A session registered with the status server, for debugging sessions whose
+/// status never updates. `last_state` is None until its MCP server reports.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredStatusSession {
+    pub session_id: u32,
+    pub project_path: String,
+    pub last_state: Option<String>,
+    pub last_reported_at: Option<String>,
+}
+
+/// Raw MCP activity update, emitted as `session:status` on every status POST.
+///
+/// Unlike `session-status-changed`, `state` is the MCP state as reported
+/// (`idle`, `working`, ...) and `previous_state` lets clients animate the
+/// transition.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionActivityPayload {
+    pub session_id: u32,
+    pub state: String,
+    pub previous_state: Option<String>,
+    pub message: String,
+}
+
 /// Payload emitted to the frontend for status changes.
 #[derive(Debug, Clone, Serialize)]
 pub struct SessionStatusPayload {
@@ -54,6 +158,96 @@ struct ServerState {
     session_projects: Arc<RwLock<std::collections::HashMap<u32, String>>>,
     /// Inter-session intelligence hub
     intel_hub: Arc<IntelHub>,
+    /// When the server started, for the uptime metric.
+    started_at: Instant,
+    /// Recent log lines from each session's MCP server.
+    mcp_logs: McpLogs,
+    /// Last MCP state per session, for `session:status` transitions.
+    mcp_states: McpStates,
+}
+
+/// Counters served by `GET /metrics`.
+#[derive(Debug, Clone, Serialize)]
+pub struct StatusMetrics {
+    pub registered_sessions: usize,
+    pub broadcast_count: usize,
+    pub scratchpad_count: usize,
+    pub active_conflicts: usize,
+    pub uptime_secs: u64,
+}
+
+impl StatusMetrics {
+    /// Renders the metrics in the Prometheus text exposition format.
+    fn to_prometheus(&self) -> String {
+        let gauges = [
+            (
+                "registered_sessions",
+                "Sessions registered with the status server",
+                self.registered_sessions as u64,
+            ),
+            (
+                "broadcast_count",
+                "Broadcast messages held in memory",
+                self.broadcast_count as u64,
+            ),
+            (
+                "scratchpad_count",
+                "Scratchpad entries held in memory",
+                self.scratchpad_count as u64,
+            ),
+            (
+                "active_conflicts",
+                "Files currently edited by more than one session",
+                self.active_conflicts as u64,
+            ),
+            (
+                "uptime_seconds",
+                "Seconds since the status server started",
+                self.uptime_secs,
+            ),
+        ];
+        let mut out = String::new();
+        for (name, help, value) in gauges {
+            out.push_str(&format!(
+                "# HELP chorus_{name} {help}\n# TYPE chorus_{name} gauge\nchorus_{name} {value}\n"
+            ));
+        }
+        out
+    }
+}
+
+/// Query parameters for `GET /metrics`.
+#[derive(Debug, Deserialize)]
+struct MetricsQuery {
+    /// `prometheus` for text exposition format; JSON otherwise.
+    format: Option<String>,
+}
+
+/// Query parameters for `GET /search`.
+#[derive(Debug, Deserialize)]
+struct SearchQuery {
+    q: String,
+    /// Comma-separated `broadcast`/`scratchpad`; all kinds when absent.
+    kinds: Option<String>,
+    session_id: Option<u32>,
+    #[serde(default)]
+    cross_project: bool,
+}
+
+/// Identifies the caller of an intel read so results can be limited to its
+/// project. With `cross_project=true` every project is visible; without a
+/// `session_id` nothing is (see `IntelHub::scope_for`).
+#[derive(Debug, Default, Deserialize)]
+struct ScopeQuery {
+    session_id: Option<u32>,
+    #[serde(default)]
+    cross_project: bool,
+}
+
+impl ScopeQuery {
+    async fn resolve(&self, hub: &IntelHub) -> IntelScope {
+        hub.scope_for(self.session_id, self.cross_project).await
+    }
 }
 
 /// HTTP status server that receives status updates from MCP servers.
@@ -62,17 +256,14 @@ pub struct StatusServer {
     instance_id: String,
     session_projects: Arc<RwLock<std::collections::HashMap<u32, String>>>,
     intel_hub: Arc<IntelHub>,
+    mcp_logs: McpLogs,
+    mcp_states: McpStates,
 }
 
 impl StatusServer {
-    /// Find an available port in the given range.
-    fn find_available_port(range_start: u16, range_end: u16) -> Option<u16> {
-        for port in range_start..=range_end {
-            if TcpListener::bind(("127.0.0.1", port)).is_ok() {
-                return Some(port);
-            }
-        }
-        None
+    /// Bind the first free port in the given range and return the listener.
+    fn find_available_port(range_start: u16, range_end: u16) -> Option<TcpListener> {
+        (range_start..=range_end).find_map(|port| TcpListener::bind(("127.0.0.1", port)).ok())
     }
 
     /// Generate a stable hash for a project path.
@@ -88,36 +279,75 @@ impl StatusServer {
     ///
     /// Returns the server instance with the port it's listening on.
     pub async fn start(app_handle: AppHandle, instance_id: String) -> Option<Self> {
-        let port = Self::find_available_port(9900, 9999)?;
+        let Some(listener) =
+            Self::find_available_port(STATUS_PORT_RANGE_START, STATUS_PORT_RANGE_END)
+        else {
+            log::error!(
+                "[STATUS SERVER] No free port in {}-{}; status reporting is unavailable",
+                STATUS_PORT_RANGE_START,
+                STATUS_PORT_RANGE_END
+            );
+            return None;
+        };
+        let port = listener.local_addr().ok()?.port();
+        if port != STATUS_PORT_RANGE_START {
+            log::warn!(
+                "[STATUS SERVER] Port {} is in use; using port {} instead",
+                STATUS_PORT_RANGE_START,
+                port
+            );
+        }
         let session_projects = Arc::new(RwLock::new(std::collections::HashMap::new()));
         let intel_hub = IntelHub::new();
+        let mcp_logs: McpLogs = Arc::new(RwLock::new(HashMap::new()));
+        let mcp_states: McpStates = Arc::new(RwLock::new(HashMap::new()));
 
         let state = Arc::new(ServerState {
             app_handle,
             instance_id: instance_id.clone(),
             session_projects: session_projects.clone(),
             intel_hub: intel_hub.clone(),
+            started_at: Instant::now(),
+            mcp_logs: mcp_logs.clone(),
+            mcp_states: mcp_states.clone(),
         });
 
-        let app = Router::new()
-            .route("/status", post(handle_status))
+        let intel_routes = Router::new()
             .route("/broadcast", post(handle_broadcast))
             .route("/messages/{session_id}", get(handle_get_messages))
             .route("/scratchpad", post(handle_scratchpad_write))
             .route("/scratchpad", get(handle_scratchpad_read))
             .route("/file-activity", post(handle_file_activity))
+            .route("/search", get(handle_search))
+            .route("/conflicts", get(handle_conflicts))
+            .route("/conflicts/ack", post(handle_conflict_ack))
+            .route("/log", post(handle_log))
+            .route_layer(middleware::from_fn_with_state(
+                state.clone(),
+                require_instance_token,
+            ));
+
+        let app = Router::new()
+            .route("/status", post(handle_status))
+            .route("/metrics", get(handle_metrics))
+            .merge(intel_routes)
             .with_state(state);
 
         let addr = format!("127.0.0.1:{}", port);
-        let listener = match tokio::net::TcpListener::bind(&addr).await {
+        // Serve on the probed listener directly; rebinding would race other processes
+        let listener = match listener
+            .set_nonblocking(true)
+            .and_then(|()| tokio::net::TcpListener::from_std(listener))
+        {
             Ok(l) => l,
             Err(e) => {
-                log::error!("Failed to bind status server to {}: {}", addr, e);
+                log::error!("Failed to set up status server listener on {}: {}", addr, e);
                 return None;
             }
         };
 
         eprintln!("[STATUS SERVER] Started on http://{}", addr);
+        log::info!("[STATUS SERVER] Listening on port {}", port);
         eprintln!("[STATUS SERVER] Instance ID: {}", instance_id);
 
         // Spawn the server in the background
@@ -132,6 +362,8 @@ impl StatusServer {
             instance_id,
             session_projects,
             intel_hub,
+            mcp_logs,
+            mcp_states,
         })
     }
 
@@ -162,13 +394,21 @@ impl StatusServer {
 
     /// Register a session with its project path.
     /// This allows routing status updates to the correct project.
-    pub async fn register_session(&self, session_id: u32, project_path: &str) {
+    /// `working_dir` is where the session runs (its worktree, if it has one);
+    /// the file paths it reports are checked against it.
+    pub async fn register_session(&self, session_id: u32, project_path: &str, working_dir: &str) {
+        self.intel_hub
+            .set_session_project(
+                session_id,
+                Self::generate_project_hash(project_path),
+                working_dir,
+            )
+            .await;
         let mut projects = self.session_projects.write().await;
         projects.insert(session_id, project_path.to_string());
         eprintln!(
             "[STATUS SERVER] Registered session {} for project '{}'",
-            session_id,
-            project_path
+            session_id, project_path
         );
     }
 
@@ -178,6 +418,20 @@ impl StatusServer {
         if projects.remove(&session_id).is_some() {
             log::debug!("Unregistered session {}", session_id);
         }
+        drop(projects);
+        self.intel_hub.remove_session_project(session_id).await;
+        self.mcp_logs.write().await.remove(&session_id);
+        self.mcp_states.write().await.remove(&session_id);
+    }
+
+    /// Get the recent MCP log lines for a session, oldest first.
+    pub async fn mcp_logs(&self, session_id: u32) -> Vec<McpLogEntry> {
+        self.mcp_logs
+            .read()
+            .await
+            .get(&session_id)
+            .map(|logs| logs.iter().cloned().collect())
+            .unwrap_or_default()
     }
 
     /// Get list of registered session IDs (for debugging).
@@ -185,28 +439,65 @@ impl StatusServer {
         let projects = self.session_projects.read().await;
         projects.keys().copied().collect()
     }
+
+    /// Registered sessions with their last reported status, by session id.
+    pub async fn registered_session_details(&self) -> Vec<RegisteredStatusSession> {
+        let projects = self.session_projects.read().await;
+        let states = self.mcp_states.read().await;
+        let mut sessions: Vec<_> = projects
+            .iter()
+            .map(|(&session_id, project_path)| {
+                let reported = states.get(&session_id);
+                RegisteredStatusSession {
+                    session_id,
+                    project_path: project_path.clone(),
+                    last_state: reported.map(|r| r.state.clone()),
+                    last_reported_at: reported.map(|r| r.reported_at.clone()),
+                }
+            })
+            .collect();
+        sessions.sort_by_key(|s| s.session_id);
+        sessions
+    }
 }
 
 /// Handle incoming status POST requests.
+/// Records `payload` as the session's latest MCP state and returns the
+/// transition to emit as `session:status`.
+async fn record_activity(states: &McpStates, payload: &StatusRequest) -> SessionActivityPayload {
+    let reported = ReportedState {
+        state: payload.state.clone(),
+        reported_at: chrono::Utc::now().to_rfc3339(),
+    };
+    let previous_state = states
+        .write()
+        .await
+        .insert(payload.session_id, reported)
+        .map(|r| r.state);
+    SessionActivityPayload {
+        session_id: payload.session_id,
+        state: payload.state.clone(),
+        previous_state,
+        message: payload.message.clone(),
+    }
+}
+
 async fn handle_status(
     State(state): State<Arc<ServerState>>,
     Json(payload): Json<StatusRequest>,
 ) -> StatusCode {
     eprintln!(
         "[STATUS] Received: session_id={}, instance_id={}, state={}",
-        payload.session_id,
-        payload.instance_id,
-        payload.state
+        payload.session_id, payload.instance_id, payload.state
     );
 
     // Verify this request is for our instance
     if payload.instance_id != state.instance_id {
         eprintln!(
             "[STATUS] REJECTED - wrong instance: expected {}, got {}",
-            state.instance_id,
-            payload.instance_id
+            state.instance_id, payload.instance_id
         );
-        return StatusCode::FORBIDDEN;
+        return WRONG_INSTANCE_STATUS;
     }
 
     // Get the project path for this session
@@ -222,14 +513,20 @@ async fn handle_status(
     let project_path = match project_path {
         Some(p) => p,
         None => {
-            eprintln!(
-                "[STATUS] REJECTED - unknown session {}",
-                payload.session_id
-            );
+            eprintln!("[STATUS] REJECTED - unknown session {}", payload.session_id);
             return StatusCode::NOT_FOUND;
         }
     };
 
+    let activity = record_activity(&state.mcp_states, &payload).await;
+    let _ = state.app_handle.emit("session:status", &activity);
+    if let Some(bus) = state.app_handle.try_state::<std::sync::Arc<EventBus>>() {
+        match serde_json::to_value(&activity) {
+            Ok(v) => bus.send("session:status".to_string(), v),
+            Err(e) => log::error!("[STATUS] Failed to serialize activity payload: {}", e),
+        }
+    }
+
     // Map MCP state to session status string
     let status = match payload.state.as_str() {
         "idle" => "Idle",
@@ -245,9 +542,7 @@ async fn handle_status(
 
     eprintln!(
         "[STATUS] EMITTING: session={} status={} project={}",
-        payload.session_id,
-        status,
-        &project_path
+        payload.session_id, status, &project_path
     );
 
     let event_payload = SessionStatusPayload {
@@ -259,7 +554,10 @@ async fn handle_status(
     };
 
     // Emit Tauri event immediately - no polling delay!
-    if let Err(e) = state.app_handle.emit("session-status-changed", &event_payload) {
+    if let Err(e) = state
+        .app_handle
+        .emit("session-status-changed", &event_payload)
+    {
         eprintln!("[STATUS] EMIT FAILED: {}", e);
     } else {
         eprintln!("[STATUS] EMIT SUCCESS");
@@ -291,14 +589,22 @@ async fn handle_broadcast(
             message: "rejected: wrong instance".to_string(),
             metadata: None,
             timestamp: String::new(),
+            project_hash: None,
+            ttl_secs: None,
+            repeat_count: 1,
         };
-        return (StatusCode::FORBIDDEN, Json(empty));
+        return (WRONG_INSTANCE_STATUS, Json(empty));
     }
 
     let msg = match state.intel_hub.add_broadcast(payload).await {
         Ok(msg) => msg,
         Err(e) => {
             log::warn!("[INTEL] Broadcast validation failed: {}", e);
+            let status = if e.rate_limited {
+                StatusCode::TOO_MANY_REQUESTS
+            } else {
+                StatusCode::BAD_REQUEST
+            };
             let empty = BroadcastMessage {
                 id: String::new(),
                 session_id: 0,
@@ -307,8 +613,11 @@ async fn handle_broadcast(
                 message: format!("validation error: {}", e),
                 metadata: None,
                 timestamp: String::new(),
+                project_hash: None,
+                ttl_secs: None,
+                repeat_count: 1,
             };
-            return (StatusCode::BAD_REQUEST, Json(empty));
+            return (status, Json(empty));
         }
     };
 
@@ -326,12 +635,51 @@ async fn handle_broadcast(
     (StatusCode::OK, Json(msg))
 }
 
-/// Handle GET messages for a session.
+/// Checks the instance token in `headers`: `Ok` if it matches, otherwise
+/// 401 when it is missing and [`WRONG_INSTANCE_STATUS`] when it names
+/// another instance.
+fn check_instance_token(headers: &HeaderMap, expected: &str) -> Result<(), StatusCode> {
+    match headers
+        .get(INSTANCE_TOKEN_HEADER)
+        .and_then(|v| v.to_str().ok())
+    {
+        Some(token) if !expected.is_empty() && token == expected => Ok(()),
+        Some(token) if !token.is_empty() => Err(WRONG_INSTANCE_STATUS),
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
+/// Rejects intel requests without this instance's token header.
+async fn require_instance_token(
+    State(state): State<Arc<ServerState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if let Err(status) = check_instance_token(request.headers(), &state.instance_id) {
+        log::warn!(
+            "[INTEL] Rejected {} {} with {}: missing or foreign {} header",
+            request.method(),
+            request.uri().path(),
+            status,
+            INSTANCE_TOKEN_HEADER
+        );
+        return status.into_response();
+    }
+    next.run(request).await
+}
+
+/// Handle GET messages for a session, limited to its project unless
+/// `?cross_project=true`.
 async fn handle_get_messages(
     State(state): State<Arc<ServerState>>,
     Path(session_id): Path<u32>,
+    Query(scope): Query<ScopeQuery>,
 ) -> Json<Vec<BroadcastMessage>> {
-    let messages = state.intel_hub.get_messages_for(session_id).await;
+    let scope = state
+        .intel_hub
+        .scope_for(Some(session_id), scope.cross_project)
+        .await;
+    let messages = state.intel_hub.get_messages_for(session_id, &scope).await;
     Json(messages)
 }
 
@@ -348,8 +696,9 @@ async fn handle_scratchpad_write(
             title: String::new(),
             content: "rejected: wrong instance".to_string(),
             timestamp: String::new(),
+            project_hash: None,
         };
-        return (StatusCode::FORBIDDEN, Json(empty));
+        return (WRONG_INSTANCE_STATUS, Json(empty));
     }
 
     let entry = match state.intel_hub.write_scratchpad(payload).await {
@@ -363,6 +712,7 @@ async fn handle_scratchpad_write(
                 title: String::new(),
                 content: format!("validation error: {}", e),
                 timestamp: String::new(),
+                project_hash: None,
             };
             return (StatusCode::BAD_REQUEST, Json(empty));
         }
@@ -384,8 +734,10 @@ async fn handle_scratchpad_write(
 /// Handle scratchpad read GET.
 async fn handle_scratchpad_read(
     State(state): State<Arc<ServerState>>,
+    Query(scope): Query<ScopeQuery>,
 ) -> Json<Vec<ScratchpadEntry>> {
-    let entries = state.intel_hub.read_scratchpad().await;
+    let scope = scope.resolve(&state.intel_hub).await;
+    let entries = state.intel_hub.read_scratchpad(&scope).await;
     Json(entries)
 }
 
@@ -395,7 +747,7 @@ async fn handle_file_activity(
     Json(payload): Json<FileActivityRequest>,
 ) -> (StatusCode, Json<Vec<FileConflict>>) {
     if payload.instance_id != state.instance_id {
-        return (StatusCode::FORBIDDEN, Json(Vec::new()));
+        return (WRONG_INSTANCE_STATUS, Json(Vec::new()));
     }
 
     let session_id = payload.session_id;
@@ -423,15 +775,368 @@ async fn handle_file_activity(
             session_id,
             conflicts.len()
         );
+
+        // Push newly formed conflicts so clients can alert without polling
+        for conflict in state.intel_hub.announce_new_conflicts(&conflicts).await {
+            let _ = state
+                .app_handle
+                .emit(INTEL_CONFLICT_DETECTED_EVENT, &conflict);
+            if let Some(bus) = state.app_handle.try_state::<std::sync::Arc<EventBus>>() {
+                match serde_json::to_value(&conflict) {
+                    Ok(v) => bus.send(INTEL_CONFLICT_DETECTED_EVENT.to_string(), v),
+                    Err(e) => log::error!("[INTEL] Failed to serialize conflict: {}", e),
+                }
+            }
+        }
     }
 
     (StatusCode::OK, Json(conflicts))
 }
 
+/// Append `entry` to its session's log, dropping the oldest past the cap.
+fn push_mcp_log(logs: &mut HashMap<u32, VecDeque<McpLogEntry>>, entry: McpLogEntry) {
+    let session_logs = logs.entry(entry.session_id).or_default();
+    session_logs.push_back(entry);
+    if session_logs.len() > MAX_MCP_LOG_ENTRIES {
+        session_logs.pop_front();
+    }
+}
+
+/// Handle log POST from MCP servers.
+async fn handle_log(
+    State(state): State<Arc<ServerState>>,
+    Json(payload): Json<LogRequest>,
+) -> StatusCode {
+    if payload.instance_id != state.instance_id {
+        return WRONG_INSTANCE_STATUS;
+    }
+    if !MCP_LOG_LEVELS.contains(&payload.level.as_str()) {
+        return StatusCode::BAD_REQUEST;
+    }
+
+    let mut message = payload.message;
+    if message.len() > MAX_MCP_LOG_MESSAGE_LEN {
+        let mut cut = MAX_MCP_LOG_MESSAGE_LEN;
+        while !message.is_char_boundary(cut) {
+            cut -= 1;
+        }
+        message.truncate(cut);
+    }
+
+    let entry = McpLogEntry {
+        session_id: payload.session_id,
+        level: payload.level,
+        logger: payload.logger,
+        message,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+    };
+    push_mcp_log(&mut *state.mcp_logs.write().await, entry.clone());
+
+    let _ = state.app_handle.emit("mcp-log", &entry);
+
+    if let Some(bus) = state.app_handle.try_state::<std::sync::Arc<EventBus>>() {
+        match serde_json::to_value(&entry) {
+            Ok(v) => bus.send("mcp-log".to_string(), v),
+            Err(e) => log::error!("[STATUS] Failed to serialize MCP log entry: {}", e),
+        }
+    }
+
+    StatusCode::OK
+}
+
+/// Handle conflicts GET — all files currently edited by more than one session.
+async fn handle_conflicts(
+    State(state): State<Arc<ServerState>>,
+    Query(scope): Query<ScopeQuery>,
+) -> Json<Vec<FileConflict>> {
+    let scope = scope.resolve(&state.intel_hub).await;
+    Json(state.intel_hub.get_all_conflicts(&scope).await)
+}
+
+/// Handle conflict acknowledgment POST.
+async fn handle_conflict_ack(
+    State(state): State<Arc<ServerState>>,
+    Json(payload): Json<ConflictAckRequest>,
+) -> (StatusCode, String) {
+    if payload.instance_id != state.instance_id {
+        return (
+            WRONG_INSTANCE_STATUS,
+            "rejected: wrong instance".to_string(),
+        );
+    }
+
+    if let Err(e) = state
+        .intel_hub
+        .acknowledge_conflict(&payload.file_path, payload.session_id)
+        .await
+    {
+        return (StatusCode::BAD_REQUEST, format!("validation error: {}", e));
+    }
+
+    // Push the full list so the frontend can gray out acknowledged conflicts
+    let conflicts = state.intel_hub.get_all_conflicts(&IntelScope::All).await;
+    let _ = state.app_handle.emit("intel-conflict", &conflicts);
+    if let Some(bus) = state.app_handle.try_state::<std::sync::Arc<EventBus>>() {
+        match serde_json::to_value(&conflicts) {
+            Ok(v) => bus.send("intel-conflict".to_string(), v),
+            Err(e) => log::error!("[INTEL] Failed to serialize conflicts: {}", e),
+        }
+    }
+
+    (StatusCode::OK, String::new())
+}
+
+/// Handle search GET across broadcasts and scratchpad.
+async fn handle_search(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<SearchQuery>,
+) -> Result<Json<Vec<SearchResult>>, (StatusCode, String)> {
+    let kinds = query
+        .kinds
+        .as_deref()
+        .unwrap_or("")
+        .split(',')
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(str::parse::<SearchKind>)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let scope = state
+        .intel_hub
+        .scope_for(query.session_id, query.cross_project)
+        .await;
+    Ok(Json(state.intel_hub.search(&query.q, &kinds, &scope).await))
+}
+
+/// Handle metrics GET — JSON by default, Prometheus text with `?format=prometheus`.
+///
+/// Like every route here it is only reachable on loopback.
+async fn handle_metrics(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<MetricsQuery>,
+) -> Response {
+    let metrics = StatusMetrics {
+        registered_sessions: state.session_projects.read().await.len(),
+        broadcast_count: state.intel_hub.broadcast_count().await,
+        scratchpad_count: state.intel_hub.scratchpad_count().await,
+        active_conflicts: state.intel_hub.active_conflict_count().await,
+        uptime_secs: state.started_at.elapsed().as_secs(),
+    };
+
+    if query.format.as_deref() == Some("prometheus") {
+        (
+            [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+            metrics.to_prometheus(),
+        )
+            .into_response()
+    } else {
+        Json(metrics).into_response()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn prometheus_output_has_one_gauge_per_metric() {
+        let metrics = StatusMetrics {
+            registered_sessions: 3,
+            broadcast_count: 12,
+            scratchpad_count: 0,
+            active_conflicts: 1,
+            uptime_secs: 60,
+        };
+        let text = metrics.to_prometheus();
+
+        assert!(text
+            .contains("# TYPE chorus_registered_sessions gauge\nchorus_registered_sessions 3\n"));
+        assert!(text.contains("chorus_broadcast_count 12\n"));
+        assert!(text.contains("chorus_active_conflicts 1\n"));
+        assert!(text.contains("chorus_uptime_seconds 60\n"));
+        assert_eq!(text.lines().filter(|l| !l.starts_with('#')).count(), 5);
+    }
+
+    fn test_server() -> StatusServer {
+        StatusServer {
+            port: 0,
+            instance_id: "test".to_string(),
+            session_projects: Arc::new(RwLock::new(HashMap::new())),
+            intel_hub: IntelHub::new(),
+            mcp_logs: Arc::new(RwLock::new(HashMap::new())),
+            mcp_states: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    #[tokio::test]
+    async fn registered_sessions_report_their_last_status() {
+        let server = test_server();
+        server.register_session(2, "/tmp/b", "/tmp/b").await;
+        server.register_session(1, "/tmp/a", "/tmp/a").await;
+        server.mcp_states.write().await.insert(
+            2,
+            ReportedState {
+                state: "working".to_string(),
+                reported_at: "2026-01-01T00:00:00+00:00".to_string(),
+            },
+        );
+
+        let sessions = server.registered_session_details().await;
+        assert_eq!(
+            sessions.iter().map(|s| s.session_id).collect::<Vec<_>>(),
+            [1, 2]
+        );
+        assert_eq!(sessions[0].last_state, None);
+        assert_eq!(sessions[1].last_state.as_deref(), Some("working"));
+        let json = serde_json::to_value(&sessions[1]).unwrap();
+        assert_eq!(json["sessionId"], 2);
+        assert_eq!(json["lastState"], "working");
+
+        // Unregistering forgets the session and its status
+        server.unregister_session(2).await;
+        assert_eq!(server.registered_session_details().await.len(), 1);
+        assert!(server.mcp_states.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn activity_transitions_follow_each_sessions_reports() {
+        let server = test_server();
+        let report = |session_id, state: &str| StatusRequest {
+            session_id,
+            instance_id: "test".to_string(),
+            state: state.to_string(),
+            message: format!("{} is {}", session_id, state),
+            needs_input_prompt: None,
+            timestamp: String::new(),
+        };
+
+        let mut emitted = Vec::new();
+        for (session_id, state) in [
+            (1, "working"),
+            (2, "idle"),
+            (1, "needs_input"),
+            (1, "working"),
+            (2, "working"),
+            (1, "finished"),
+        ] {
+            emitted.push(record_activity(&server.mcp_states, &report(session_id, state)).await);
+        }
+
+        let transitions: Vec<_> = emitted
+            .iter()
+            .map(|a| (a.session_id, a.previous_state.as_deref(), a.state.as_str()))
+            .collect();
+        assert_eq!(
+            transitions,
+            [
+                (1, None, "working"),
+                (2, None, "idle"),
+                (1, Some("working"), "needs_input"),
+                (1, Some("needs_input"), "working"),
+                (2, Some("idle"), "working"),
+                (1, Some("working"), "finished"),
+            ]
+        );
+        assert_eq!(emitted[5].message, "1 is finished");
+        let json = serde_json::to_value(&emitted[2]).unwrap();
+        assert_eq!(json["sessionId"], 1);
+        assert_eq!(json["previousState"], "working");
+
+        // A re-registered session starts over without a previous state
+        server.unregister_session(1).await;
+        let restarted = record_activity(&server.mcp_states, &report(1, "idle")).await;
+        assert_eq!(restarted.previous_state, None);
+    }
+
+    #[tokio::test]
+    async fn worktree_sessions_report_files_in_their_worktree() {
+        let server = test_server();
+        server
+            .register_session(3, "/work/proj", "/data/worktrees/ab12/feature")
+            .await;
+        let report = |path: &str| FileActivityRequest {
+            session_id: 3,
+            instance_id: "test".to_string(),
+            file_path: path.to_string(),
+            action: "editing".to_string(),
+        };
+
+        let hub = server.intel_hub();
+        let in_worktree = hub
+            .report_file(report("/data/worktrees/ab12/feature/src/main.rs"))
+            .await;
+        assert!(in_worktree.is_ok());
+        assert!(hub.report_file(report("src/lib.rs")).await.is_ok());
+        assert!(hub.report_file(report("/etc/passwd")).await.is_err());
+        // Intel is still shared with the project's other sessions
+        assert_eq!(
+            hub.project_of(3).await,
+            Some(StatusServer::generate_project_hash("/work/proj"))
+        );
+    }
+
+    #[test]
+    fn port_scan_skips_ports_in_use() {
+        let taken = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let busy = taken.local_addr().unwrap().port();
+        let Some(end) = busy.checked_add(20) else {
+            return; // no room above an ephemeral port at the top of the range
+        };
+
+        let listener = StatusServer::find_available_port(busy, end).unwrap();
+        let port = listener.local_addr().unwrap().port();
+        assert!(port > busy && port <= end);
+
+        assert!(StatusServer::find_available_port(busy, busy).is_none());
+    }
+
+    #[test]
+    fn mcp_logs_are_capped_per_session() {
+        let mut logs = HashMap::new();
+        let entry = |session_id, message: String| McpLogEntry {
+            session_id,
+            level: "info".to_string(),
+            logger: None,
+            message,
+            timestamp: String::new(),
+        };
+        for i in 0..(MAX_MCP_LOG_ENTRIES + 5) {
+            push_mcp_log(&mut logs, entry(1, format!("line {}", i)));
+        }
+        push_mcp_log(&mut logs, entry(2, "other".to_string()));
+
+        let session_logs = &logs[&1];
+        assert_eq!(session_logs.len(), MAX_MCP_LOG_ENTRIES);
+        assert_eq!(session_logs.front().unwrap().message, "line 5");
+        assert_eq!(logs[&2].len(), 1);
+    }
+
+    #[test]
+    fn instance_token_must_match_exactly() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(
+            check_instance_token(&headers, "abc"),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+
+        // A foreign token is another instance's session, not an anonymous caller
+        headers.insert(INSTANCE_TOKEN_HEADER, "wrong".parse().unwrap());
+        assert_eq!(
+            check_instance_token(&headers, "abc"),
+            Err(WRONG_INSTANCE_STATUS)
+        );
+
+        headers.insert(INSTANCE_TOKEN_HEADER, "abc".parse().unwrap());
+        assert_eq!(check_instance_token(&headers, "abc"), Ok(()));
+
+        headers.insert(INSTANCE_TOKEN_HEADER, "".parse().unwrap());
+        assert_eq!(
+            check_instance_token(&headers, ""),
+            Err(StatusCode::UNAUTHORIZED)
+        );
+    }
+
     #[test]
     fn test_generate_project_hash() {
         let hash = StatusServer::generate_project_hash("/Users/test/project");
@@ -0,0 +1,127 @@
+//! Coalesces rapid `tauri-plugin-store` saves into a single disk write.
+//!
+//! Callers update the store in memory as usual (so reads see the new value
+//! immediately) and then call `StoreDebouncer::schedule` instead of
+//! `store.save()`. Each schedule restarts a short timer for that store; the
+//! save only runs once the store has been quiet for the whole window.
+//! `flush` writes every pending store right away and is called on app exit.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+
+/// Default quiet period before a pending store is written to disk.
+pub const DEFAULT_SAVE_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Persists a store by name. Errors are logged by the implementation.
+type SaveFn = dyn Fn(&str) + Send + Sync;
+
+/// Debounces saves per store name.
+pub struct StoreDebouncer {
+    window: Duration,
+    save: Arc<SaveFn>,
+    /// Store names with a save pending, mapped to the generation of the
+    /// latest schedule. A timer only saves if its generation is still current.
+    pending: Arc<DashMap<String, u64>>,
+    next_generation: AtomicU64,
+}
+
+impl StoreDebouncer {
+    /// Creates a debouncer that calls `save` once a store has been quiet for `window`.
+    pub fn new(window: Duration, save: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        Self {
+            window,
+            save: Arc::new(save),
+            pending: Arc::new(DashMap::new()),
+            next_generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Schedules a save of `store_name`, superseding any save already pending for it.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn schedule(&self, store_name: &str) {
+        let generation = self.next_generation.fetch_add(1, Ordering::Relaxed);
+        self.pending.insert(store_name.to_string(), generation);
+
+        let pending = self.pending.clone();
+        let save = self.save.clone();
+        let window = self.window;
+        let store_name = store_name.to_string();
+        tokio::spawn(async move {
+            tokio::time::sleep(window).await;
+            // Only the most recent schedule for this store gets to save; a
+            // flush in the meantime removes the entry and skips it as well.
+            if pending
+                .remove_if(&store_name, |_, current| *current == generation)
+                .is_some()
+            {
+                save(&store_name);
+            }
+        });
+    }
+
+    /// Immediately saves every store with a pending write.
+    pub fn flush(&self) {
+        let names: Vec<String> = self.pending.iter().map(|e| e.key().clone()).collect();
+        for name in names {
+            if self.pending.remove(&name).is_some() {
+                log::debug!("Flushing pending store save: {}", name);
+                (self.save)(&name);
+            }
+        }
+    }
+
+    /// Number of stores with a save still pending.
+    pub fn pending_count(&self) -> usize {
+        self.pending.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    fn recording_debouncer(window: Duration) -> (StoreDebouncer, Arc<Mutex<Vec<String>>>) {
+        let saved = Arc::new(Mutex::new(Vec::new()));
+        let sink = saved.clone();
+        let debouncer = StoreDebouncer::new(window, move |name| {
+            sink.lock().unwrap().push(name.to_string());
+        });
+        (debouncer, saved)
+    }
+
+    #[tokio::test]
+    async fn coalesces_rapid_schedules_into_one_save() {
+        let (debouncer, saved) = recording_debouncer(Duration::from_millis(100));
+
+        for _ in 0..5 {
+            debouncer.schedule("chorus-a.json");
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        debouncer.schedule("chorus-b.json");
+        assert!(saved.lock().unwrap().is_empty());
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        let mut saved = saved.lock().unwrap().clone();
+        saved.sort();
+        assert_eq!(saved, vec!["chorus-a.json", "chorus-b.json"]);
+        assert_eq!(debouncer.pending_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn flush_saves_pending_stores_exactly_once() {
+        let (debouncer, saved) = recording_debouncer(Duration::from_millis(100));
+
+        debouncer.schedule("chorus-a.json");
+        debouncer.flush();
+        assert_eq!(*saved.lock().unwrap(), vec!["chorus-a.json"]);
+
+        // The timer spawned before the flush must not save a second time.
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(saved.lock().unwrap().len(), 1);
+    }
+}
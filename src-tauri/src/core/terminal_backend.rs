@@ -107,10 +107,26 @@ pub struct BackendCapabilities {
     pub kitty_graphics: bool,
     /// Backend supports shell integration hooks.
     pub shell_integration: bool,
+    /// 24-bit color sequences reach the renderer intact.
+    pub true_color: bool,
+    /// Mouse reporting modes requested by programs reach the renderer.
+    pub mouse_reporting: bool,
+    /// Smallest resize step the backend honours.
+    pub resize_granularity: ResizeGranularity,
     /// Name of the backend implementation.
     pub backend_name: &'static str,
 }
 
+/// Unit in which a backend can be resized. Every current backend resizes by
+/// whole cells; a pixel-granular backend would add a variant here.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResizeGranularity {
+    /// Whole character cells (rows x cols).
+    #[default]
+    Cell,
+}
+
 /// Trait defining the terminal backend interface.
 ///
 /// Implementations must be Send + Sync to allow sharing across async contexts.
@@ -158,9 +174,97 @@ pub trait TerminalBackend: Send + Sync {
     fn capabilities(&self) -> BackendCapabilities;
 }
 
-/// Identifies the active backend type for the frontend.
+/// Where the contents of a `ScreenSnapshot` came from.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
 #[serde(rename_all = "kebab-case")]
+pub enum ScreenSource {
+    /// Rendered from a parsed cell grid.
+    #[cfg(feature = "vte-backend")]
+    Grid,
+    /// The last lines of raw output with escape sequences stripped.
+    Tail,
+}
+
+/// A run of identically-styled cells within a `ScreenLine`.
+///
+/// `start` and `len` are measured in characters. Colors are ANSI 256-color
+/// palette indices; `None` means the terminal default.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StyleSpan {
+    pub start: u16,
+    pub len: u16,
+    pub bold: bool,
+    pub inverse: bool,
+    pub fg: Option<u8>,
+    pub bg: Option<u8>,
+}
+
+/// One visible row of a `ScreenSnapshot`.
+///
+/// Unstyled text has no spans; trailing blank cells are trimmed.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ScreenLine {
+    pub text: String,
+    pub spans: Vec<StyleSpan>,
+}
+
+/// Largest `rows` or `cols` a screen snapshot can be requested at.
+pub const MAX_SCREEN_DIMENSION: u16 = 500;
+
+/// The visible screen of a session, sized to the requested rows/cols.
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenSnapshot {
+    pub rows: u16,
+    pub cols: u16,
+    pub lines: Vec<ScreenLine>,
+    pub source: ScreenSource,
+}
+
+impl ScreenSnapshot {
+    /// Checks requested snapshot dimensions: both must be between 1 and
+    /// `MAX_SCREEN_DIMENSION`.
+    pub fn check_size(rows: u16, cols: u16) -> Result<(), String> {
+        if rows == 0 || cols == 0 || rows > MAX_SCREEN_DIMENSION || cols > MAX_SCREEN_DIMENSION {
+            return Err("Invalid dimensions".to_string());
+        }
+        Ok(())
+    }
+
+    /// Builds a line-based snapshot from the tail of raw PTY output.
+    ///
+    /// Escape sequences are stripped, carriage-return overwrites keep only the
+    /// last segment of each line, and lines are truncated to `cols` characters.
+    /// Used for sessions not backed by a grid-capable backend.
+    pub fn from_tail(output: &str, rows: u16, cols: u16) -> Self {
+        let plain = super::ansi::strip_ansi(output);
+        let all: Vec<&str> = plain.lines().collect();
+        let start = all.len().saturating_sub(rows as usize);
+
+        let lines = all[start..]
+            .iter()
+            .map(|line| {
+                let visible = line.rsplit('\r').find(|seg| !seg.is_empty()).unwrap_or("");
+                ScreenLine {
+                    text: visible.chars().take(cols as usize).collect(),
+                    spans: Vec::new(),
+                }
+            })
+            .collect();
+
+        Self {
+            rows,
+            cols,
+            lines,
+            source: ScreenSource::Tail,
+        }
+    }
+}
+
+/// Identifies the active backend type for the frontend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum BackendType {
     /// xterm.js passthrough - raw PTY output sent directly to xterm.js.
     XtermPassthrough,
@@ -178,4 +282,66 @@ impl BackendType {
         }
         BackendType::XtermPassthrough
     }
+
+    /// Every backend type, whether or not it is compiled in.
+    pub const ALL: [BackendType; 2] = [BackendType::XtermPassthrough, BackendType::VteParser];
+
+    /// Whether this backend is compiled into the current build.
+    pub fn is_available(self) -> bool {
+        match self {
+            BackendType::XtermPassthrough => true,
+            BackendType::VteParser => cfg!(feature = "vte-backend"),
+        }
+    }
+
+    /// Backends that sessions can be spawned with in this build.
+    pub fn available() -> Vec<BackendType> {
+        Self::ALL.into_iter().filter(|b| b.is_available()).collect()
+    }
+
+    /// Returns an error naming the missing feature if this backend isn't compiled in.
+    pub fn ensure_available(self) -> Result<Self, String> {
+        if self.is_available() {
+            Ok(self)
+        } else {
+            Err(format!(
+                "Terminal backend '{}' is not available in this build (requires the vte-backend feature)",
+                self.name()
+            ))
+        }
+    }
+
+    /// Kebab-case name, matching the serialized form.
+    pub fn name(self) -> &'static str {
+        match self {
+            BackendType::XtermPassthrough => "xterm-passthrough",
+            BackendType::VteParser => "vte-parser",
+        }
+    }
+
+    /// Capabilities of this backend, matching what its `TerminalBackend` impl advertises.
+    pub fn capabilities(self) -> BackendCapabilities {
+        match self {
+            BackendType::XtermPassthrough => BackendCapabilities {
+                enhanced_state: false,
+                text_reflow: false,
+                kitty_graphics: false,
+                shell_integration: false,
+                true_color: true,
+                mouse_reporting: true,
+                resize_granularity: ResizeGranularity::Cell,
+                backend_name: "xterm-passthrough",
+            },
+            BackendType::VteParser => BackendCapabilities {
+                enhanced_state: true,
+                text_reflow: false,
+                kitty_graphics: false,
+                shell_integration: false,
+                true_color: true,
+                mouse_reporting: true,
+                resize_granularity: ResizeGranularity::Cell,
+                backend_name: "vte-parser",
+            },
+        }
+    }
 }
@@ -9,23 +9,30 @@
 //! ```text
 //! PTY Output → VTE Parser → State Update + Tauri Event → xterm.js (render)
 //! ```
+//!
+//! Output is also replayed onto a [`ScreenGrid`] so developers can dump the
+//! parsed screen and compare it against what xterm.js renders from the raw
+//! passthrough stream. [`VteSessions`] tracks backends spawned explicitly for
+//! that purpose, independent of `ProcessManager`.
 
 use std::io::{Read, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
 use std::thread::JoinHandle;
 
+use dashmap::DashMap;
 use portable_pty::{native_pty_system, CommandBuilder, MasterPty, PtySize};
 use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::Notify;
-use vte::{Parser, Perform};
+use vte::Perform;
 
 #[cfg(unix)]
 use libc;
 
+use super::screen_grid::ScreenGrid;
 use super::terminal_backend::{
-    BackendCapabilities, BackendType, CursorShape, SubscriptionHandle, TerminalBackend,
-    TerminalConfig, TerminalError, TerminalState,
+    BackendCapabilities, BackendType, CursorShape, ScreenSnapshot, SubscriptionHandle,
+    TerminalBackend, TerminalConfig, TerminalError, TerminalState,
 };
 
 /// Stateful UTF-8 decoder that handles split multi-byte sequences.
@@ -272,6 +279,8 @@ struct SessionState {
 pub struct VteBackend {
     session: Mutex<Option<SessionState>>,
     handler: RwLock<Option<VteHandler>>,
+    /// Screen model fed from the output loop.
+    grid: Arc<Mutex<ScreenGrid>>,
     session_id: Mutex<Option<u32>>,
     app_handle: Mutex<Option<AppHandle>>,
     initialized: AtomicBool,
@@ -288,6 +297,7 @@ impl VteBackend {
         Self {
             session: Mutex::new(None),
             handler: RwLock::new(None),
+            grid: Arc::new(Mutex::new(ScreenGrid::new(24, 80))),
             session_id: Mutex::new(None),
             app_handle: Mutex::new(None),
             initialized: AtomicBool::new(false),
@@ -297,6 +307,14 @@ impl VteBackend {
     pub fn backend_type() -> BackendType {
         BackendType::VteParser
     }
+
+    /// Returns the parsed screen, or None if the backend isn't initialized.
+    pub fn grid_dump(&self) -> Option<ScreenSnapshot> {
+        if !self.initialized.load(Ordering::Acquire) {
+            return None;
+        }
+        Some(self.grid.lock().unwrap().snapshot())
+    }
 }
 
 impl TerminalBackend for VteBackend {
@@ -304,6 +322,7 @@ impl TerminalBackend for VteBackend {
         // Initialize VTE handler
         let handler = VteHandler::new(config.rows, config.cols);
         *self.handler.write().unwrap() = Some(handler);
+        *self.grid.lock().unwrap() = ScreenGrid::new(config.rows, config.cols);
 
         // Set up PTY
         let pty_system = native_pty_system();
@@ -396,12 +415,12 @@ impl TerminalBackend for VteBackend {
         // Event loop: parse with VTE and emit to frontend
         let event_name = format!("pty-output-{session_id}");
         let app = config.app_handle.clone();
+        let grid = self.grid.clone();
 
         tokio::spawn(async move {
-            let mut parser = Parser::new();
             let mut decoder = Utf8Decoder::new();
             // Note: We can't easily share VteHandler with the async task due to lifetime constraints
-            // For now, just forward data to the frontend - state tracking happens on read
+            // For now, just forward data to the frontend - cursor state tracking happens on read
             loop {
                 tokio::select! {
                     data = rx.recv() => {
@@ -417,8 +436,8 @@ impl TerminalBackend for VteBackend {
                                     }
                                 }
 
-                                // Parse for state (in a real impl, we'd update shared state here)
-                                parser.advance(&mut DummyPerform, &bytes);
+                                // Parse onto the screen grid for dumps
+                                grid.lock().unwrap().feed(&bytes);
                             }
                             None => break,
                         }
@@ -499,6 +518,7 @@ impl TerminalBackend for VteBackend {
         if let Some(ref mut handler) = *self.handler.write().unwrap() {
             handler.resize(rows, cols);
         }
+        self.grid.lock().unwrap().resize(rows, cols);
 
         // Resize PTY
         let session_guard = self.session.lock().unwrap();
@@ -605,13 +625,7 @@ impl TerminalBackend for VteBackend {
     }
 
     fn capabilities(&self) -> BackendCapabilities {
-        BackendCapabilities {
-            enhanced_state: true,
-            text_reflow: false,
-            kitty_graphics: false,
-            shell_integration: false,
-            backend_name: "vte-parser",
-        }
+        Self::backend_type().capabilities()
     }
 }
 
@@ -623,16 +637,39 @@ impl Drop for VteBackend {
     }
 }
 
-/// Dummy Perform implementation for async parsing
-struct DummyPerform;
-
-impl Perform for DummyPerform {
-    fn print(&mut self, _c: char) {}
-    fn execute(&mut self, _byte: u8) {}
-    fn hook(&mut self, _params: &vte::Params, _intermediates: &[u8], _ignore: bool, _action: char) {}
-    fn put(&mut self, _byte: u8) {}
-    fn unhook(&mut self) {}
-    fn osc_dispatch(&mut self, _params: &[&[u8]], _bell_terminated: bool) {}
-    fn csi_dispatch(&mut self, _params: &vte::Params, _intermediates: &[u8], _ignore: bool, _action: char) {}
-    fn esc_dispatch(&mut self, _intermediates: &[u8], _ignore: bool, _byte: u8) {}
+/// Sessions spawned directly on the VTE backend for developer comparison.
+///
+/// These bypass `ProcessManager` entirely, so they only support the
+/// operations exposed here. IDs come from `ProcessManager::allocate_session_id`.
+#[derive(Default)]
+pub struct VteSessions {
+    sessions: DashMap<u32, Arc<VteBackend>>,
+}
+
+impl VteSessions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Initializes a new VTE backend for `config` and tracks it by session ID.
+    pub fn spawn(&self, config: TerminalConfig) -> Result<(), TerminalError> {
+        let session_id = config.session_id;
+        let backend = Arc::new(VteBackend::new());
+        backend.init(config)?;
+        self.sessions.insert(session_id, backend);
+        Ok(())
+    }
+
+    pub fn get(&self, session_id: u32) -> Option<Arc<VteBackend>> {
+        self.sessions.get(&session_id).map(|b| b.clone())
+    }
+
+    /// Stops tracking the session and shuts its backend down.
+    /// Returns false if no such session exists.
+    pub fn kill(&self, session_id: u32) -> Result<bool, TerminalError> {
+        match self.sessions.remove(&session_id) {
+            Some((_, backend)) => backend.shutdown().map(|_| true),
+            None => Ok(false),
+        }
+    }
 }
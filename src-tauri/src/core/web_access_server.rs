@@ -4,30 +4,41 @@
 //! mobile browsers. Provides token-based auth and a WebSocket protocol
 //! for invoking Tauri commands and subscribing to events.
 
-use std::collections::HashSet;
-use std::net::TcpListener;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::net::{IpAddr, SocketAddr, TcpListener};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 
 use axum::{
     extract::{
-        ws::{Message, WebSocket},
+        connect_info::{ConnectInfo, Connected},
+        ws::{close_code, CloseFrame, Message, WebSocket},
         State, WebSocketUpgrade,
     },
-    response::IntoResponse,
+    http::StatusCode,
+    response::{IntoResponse, Response},
     routing::get,
+    serve::IncomingStream,
     Router,
 };
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures_util::future::BoxFuture;
+use futures_util::stream::{SplitSink, SplitStream};
 use futures_util::{SinkExt, StreamExt};
+use ipnet::IpNet;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tauri::AppHandle;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::RwLock;
 use tower_http::services::ServeDir;
 
 
-use super::event_bus::EventBus;
+use super::event_bus::{EventBus, REPLAY_BUFFER_SIZE};
 use super::web_dispatch;
+use super::web_tls::{TlsIdentity, TlsListener};
 
 /// Token info with expiry tracking.
 struct TokenInfo {
@@ -35,11 +46,108 @@ struct TokenInfo {
     expires_at: std::time::Instant,
 }
 
+/// Web clients allowed at once unless changed from the desktop UI.
+pub const DEFAULT_MAX_CLIENTS: usize = 8;
+/// Upper bound accepted by `set_max_clients`.
+pub const MAX_CLIENTS_LIMIT: usize = 64;
+/// Reason sent to clients refused because the server is full.
+const TOO_MANY_CONNECTIONS: &str = "Too many connections";
+
+/// Inclusive range of ports the web access server may listen on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PortRange {
+    pub start: u16,
+    pub end: u16,
+}
+
+impl PortRange {
+    /// Range used unless the user picks another one.
+    pub const DEFAULT: PortRange = PortRange { start: 8800, end: 8899 };
+
+    /// Validated range; rejects empty ranges and port 0.
+    pub fn new(start: u16, end: u16) -> Result<Self, String> {
+        if start == 0 {
+            return Err("Port range must start above 0".to_string());
+        }
+        if start > end {
+            return Err(format!("Invalid port range {}-{}: start is after end", start, end));
+        }
+        Ok(Self { start, end })
+    }
+}
+
+impl Default for PortRange {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Addresses allowed to open a WebSocket. An empty list allows everyone.
+///
+/// Entries are CIDR ranges (`192.168.1.0/24`) or single addresses. This is
+/// mainly useful for direct LAN access: behind a tunnel every connection
+/// comes from the tunnel's local end, so the real client can't be told apart.
+#[derive(Debug, Clone, Default)]
+pub struct IpAllowlist {
+    nets: Vec<IpNet>,
+}
+
+impl IpAllowlist {
+    pub fn parse(entries: &[String]) -> Result<Self, String> {
+        let nets = entries
+            .iter()
+            .map(|entry| entry.trim())
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                entry
+                    .parse::<IpNet>()
+                    .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                    .map(|net| net.trunc())
+                    .map_err(|_| format!("Invalid IP address or CIDR range: {}", entry))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { nets })
+    }
+
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        let ip = ip.to_canonical();
+        self.nets.is_empty() || self.nets.iter().any(|net| net.contains(&ip))
+    }
+
+    pub fn entries(&self) -> Vec<String> {
+        self.nets.iter().map(|net| net.to_string()).collect()
+    }
+}
+
+/// Remote address of a connection, for both the plain and TLS listeners.
+#[derive(Debug, Clone, Copy)]
+struct ClientAddr(SocketAddr);
+
+impl Connected<IncomingStream<'_, tokio::net::TcpListener>> for ClientAddr {
+    fn connect_info(stream: IncomingStream<'_, tokio::net::TcpListener>) -> Self {
+        Self(*stream.remote_addr())
+    }
+}
+
+impl Connected<IncomingStream<'_, TlsListener>> for ClientAddr {
+    fn connect_info(stream: IncomingStream<'_, TlsListener>) -> Self {
+        Self(*stream.remote_addr())
+    }
+}
+
 /// Web access server state.
 pub struct WebAccessServer {
     port: u16,
+    port_range: PortRange,
+    /// Certificate fingerprint when serving HTTPS; None for plain HTTP.
+    tls_fingerprint: Option<String>,
     token: Arc<RwLock<Option<TokenInfo>>>,
     connected_clients: Arc<AtomicUsize>,
+    max_clients: Arc<AtomicUsize>,
+    allowlist: Arc<RwLock<IpAllowlist>>,
+    /// LAN announcement; None if advertising failed or was stopped.
+    #[cfg(feature = "mdns")]
+    mdns: std::sync::Mutex<Option<super::mdns_advertiser::MdnsAdvertiser>>,
 }
 
 /// Status returned to the frontend UI.
@@ -48,7 +156,15 @@ pub struct WebAccessServer {
 pub struct WebAccessStatus {
     pub running: bool,
     pub port: u16,
+    pub port_range: PortRange,
+    pub tls: bool,
+    /// SHA-256 fingerprint of the served certificate, to compare against
+    /// what the browser shows before trusting it.
+    pub cert_fingerprint: Option<String>,
     pub connected_clients: usize,
+    pub max_clients: usize,
+    /// Allowed client addresses; empty means everyone.
+    pub allowlist: Vec<String>,
     pub has_valid_token: bool,
 }
 
@@ -61,12 +177,92 @@ pub struct WebAccessTokenResult {
     pub expires_in_secs: u64,
 }
 
+/// QR code for opening the web client from a phone.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebAccessQr {
+    /// The URL encoded in the QR, including the token when one was given.
+    pub url: String,
+    /// SVG image, base64-encoded for use in a `data:image/svg+xml` URL.
+    pub svg_base64: String,
+}
+
+/// URL that opens the web client and authenticates with `token`.
+///
+/// The token rides in the fragment, which browsers never send to the server,
+/// matching what `transport.ts` reads on load.
+pub fn access_url(base_url: &str, token: Option<&str>) -> String {
+    let base = base_url.trim_end_matches('/');
+    match token {
+        Some(token) => format!("{}/#token={}", base, token),
+        None => base.to_string(),
+    }
+}
+
+/// Renders `url` as a QR code SVG, base64-encoded.
+pub fn render_access_qr(url: &str) -> Result<WebAccessQr, String> {
+    use base64::Engine;
+
+    let code = qrcode::QrCode::with_error_correction_level(url, qrcode::EcLevel::M)
+        .map_err(|e| format!("Failed to encode QR code: {}", e))?;
+    let svg = code
+        .render::<qrcode::render::svg::Color>()
+        .min_dimensions(200, 200)
+        .quiet_zone(true)
+        .build();
+
+    Ok(WebAccessQr {
+        url: url.to_string(),
+        svg_base64: base64::engine::general_purpose::STANDARD.encode(svg),
+    })
+}
+
+/// Runs a command invoked by a web client; `web_dispatch::dispatch` outside tests.
+type InvokeFn = Arc<dyn Fn(String, Value) -> BoxFuture<'static, Result<Value, String>> + Send + Sync>;
+
 /// Shared state for WebSocket handlers.
 struct WsState {
-    app_handle: AppHandle,
+    server_version: String,
+    invoke: InvokeFn,
     event_bus: Arc<EventBus>,
     token: Arc<RwLock<Option<TokenInfo>>>,
     connected_clients: Arc<AtomicUsize>,
+    max_clients: Arc<AtomicUsize>,
+    allowlist: Arc<RwLock<IpAllowlist>>,
+}
+
+/// Claims a client slot unless `max` are already taken.
+///
+/// Check and increment are one atomic step, so concurrent connections can't
+/// both take the last slot.
+fn try_acquire_client_slot(connected: &AtomicUsize, max: usize) -> bool {
+    connected
+        .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < max).then_some(n + 1))
+        .is_ok()
+}
+
+/// Serialized messages at least this large are sent gzipped; smaller control
+/// messages stay plain text since compressing them costs more than it saves.
+const COMPRESS_THRESHOLD: usize = 8 * 1024;
+
+/// Wraps a serialized `ServerMessage` in a WebSocket frame for a client that
+/// opted into compression with `SetCompression`.
+///
+/// Large payloads (scrollback, directory listings) go out as a binary frame
+/// holding the gzipped JSON; clients treat any binary frame as compressed.
+/// Falls back to text if compression fails or doesn't shrink the payload.
+fn encode_frame(json: String) -> Message {
+    if json.len() >= COMPRESS_THRESHOLD {
+        let mut encoder = GzEncoder::new(Vec::with_capacity(json.len() / 4), Compression::fast());
+        if encoder.write_all(json.as_bytes()).is_ok() {
+            if let Ok(gz) = encoder.finish() {
+                if gz.len() < json.len() {
+                    return Message::Binary(gz.into());
+                }
+            }
+        }
+    }
+    Message::Text(json.into())
 }
 
 // --- WebSocket Protocol Messages ---
@@ -74,15 +270,119 @@ struct WsState {
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type", rename_all = "PascalCase")]
 enum ClientMessage {
+    /// First message of the versioned handshake, answered with `ServerHello`
+    /// before the client sends `Auth`.
+    Hello {
+        #[serde(rename = "protocolVersion")]
+        protocol_version: u32,
+        #[serde(rename = "clientVersion", default)]
+        client_version: Option<String>,
+    },
     Auth { token: String },
     Invoke { id: u64, command: String, args: Value },
-    Subscribe { event: String },
+    /// Subscribes to `event`, or to every event with a given prefix when it
+    /// ends in `*` (e.g. `session:*`). With `replay > 0`, up to that many
+    /// recent buffered matching events are delivered first.
+    Subscribe {
+        event: String,
+        #[serde(default)]
+        replay: usize,
+    },
     Unsubscribe { event: String },
+    /// Replaces the set of sessions whose output is multiplexed into
+    /// `TAIL_EVENT` frames. An empty list stops tailing.
+    TailSessions {
+        #[serde(rename = "sessionIds")]
+        session_ids: Vec<u32>,
+    },
+    /// Turns gzipped binary frames on or off for this connection. Off until
+    /// the client asks; a client that fails to decode a frame turns it off.
+    SetCompression {
+        enabled: bool,
+    },
+}
+
+/// Event name for combined multi-session output frames.
+const TAIL_EVENT: &str = "session-tail";
+
+/// Payload of a `TAIL_EVENT` frame: one output delta tagged with its session.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TailedOutput {
+    session_id: u32,
+    data: Value,
+}
+
+/// Whether a subscription pattern covers `event`. A pattern ending in `*`
+/// matches every event starting with the text before it; anything else must
+/// match exactly.
+fn pattern_matches(pattern: &str, event: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => event.starts_with(prefix),
+        None => pattern == event,
+    }
+}
+
+/// Whether a live event should be forwarded: some subscription must match
+/// it, and that subscription's replay must not already have delivered it.
+fn should_forward(
+    subs: &HashSet<String>,
+    replayed_through: &HashMap<String, u64>,
+    event: &str,
+    seq: u64,
+) -> bool {
+    subs.iter().any(|pattern| {
+        pattern_matches(pattern, event)
+            && replayed_through.get(pattern).is_none_or(|&through| seq > through)
+    })
+}
+
+/// Extracts the session ID from a `pty-output-{id}` event name.
+fn pty_output_session_id(event: &str) -> Option<u32> {
+    event.strip_prefix("pty-output-")?.parse().ok()
+}
+
+/// WebSocket protocol version spoken by this server. Clients that skip
+/// `Hello` and open with `Auth` are treated as the unversioned protocol 1.
+const PROTOCOL_VERSION: u32 = 2;
+/// Oldest `Hello` protocol version still accepted.
+const MIN_PROTOCOL_VERSION: u32 = 2;
+
+/// Optional protocol features advertised in `ServerHello`, so clients can
+/// feature-detect instead of guessing from the version.
+const PROTOCOL_FEATURES: &[&str] = &[
+    "event-lagged",
+    "gzip-frames",
+    "subscribe-pattern",
+    "subscribe-replay",
+    "tail-sessions",
+];
+
+/// Ok if a client speaking `version` can talk to this server, otherwise the
+/// close reason to send it.
+fn check_protocol_version(version: u32) -> Result<(), String> {
+    if (MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION).contains(&version) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Unsupported protocol version {}; this Chorus speaks {}-{}",
+            version, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+        ))
+    }
 }
 
 #[derive(Debug, Serialize)]
 #[serde(tag = "type", rename_all = "PascalCase")]
 enum ServerMessage {
+    ServerHello {
+        #[serde(rename = "protocolVersion")]
+        protocol_version: u32,
+        #[serde(rename = "serverVersion")]
+        server_version: String,
+        /// Commands accepted by `Invoke`.
+        commands: &'static [&'static str],
+        features: &'static [&'static str],
+    },
     AuthResult {
         success: bool,
         #[serde(skip_serializing_if = "Option::is_none")]
@@ -99,21 +399,61 @@ enum ServerMessage {
         event: String,
         payload: Value,
     },
+    /// The client fell behind and `dropped` events were never delivered;
+    /// it should refetch any state it derives from events.
+    EventLagged {
+        dropped: u64,
+    },
+}
+
+/// The frame to send when the event receiver errors: a lag notice, or None
+/// once the bus has closed and forwarding should stop.
+fn lag_notice(err: RecvError) -> Option<ServerMessage> {
+    match err {
+        RecvError::Lagged(dropped) => Some(ServerMessage::EventLagged { dropped }),
+        RecvError::Closed => None,
+    }
 }
 
 impl WebAccessServer {
-    /// Try to start the server on a port in range 8800-8899.
+    /// Try to start the server on the first free port in `port_range`.
     /// Returns None if no port is available.
-    pub fn start(app_handle: AppHandle, event_bus: Arc<EventBus>) -> Option<Self> {
-        let port = Self::find_available_port(8800, 8899)?;
+    ///
+    /// With a `tls` identity the server speaks HTTPS/WSS instead of plain HTTP.
+    /// WebSocket connections from outside `allowlist` are refused.
+    ///
+    /// With the `mdns` feature the server is also advertised on the LAN,
+    /// tagged with `instance_id`; advertising failures are only logged.
+    pub fn start(
+        app_handle: AppHandle,
+        event_bus: Arc<EventBus>,
+        instance_id: &str,
+        port_range: PortRange,
+        tls: Option<&TlsIdentity>,
+        allowlist: IpAllowlist,
+    ) -> Option<Self> {
+        let Some(listener) = Self::find_available_port(port_range.start, port_range.end) else {
+            log::error!("No free port for web access in {}-{}", port_range.start, port_range.end);
+            return None;
+        };
+        let port = listener.local_addr().ok()?.port();
         let token: Arc<RwLock<Option<TokenInfo>>> = Arc::new(RwLock::new(None));
         let connected_clients = Arc::new(AtomicUsize::new(0));
+        let max_clients = Arc::new(AtomicUsize::new(DEFAULT_MAX_CLIENTS));
+        let allowlist = Arc::new(RwLock::new(allowlist));
 
+        let invoke_app = app_handle.clone();
         let ws_state = Arc::new(WsState {
-            app_handle: app_handle.clone(),
+            server_version: app_handle.package_info().version.to_string(),
+            invoke: Arc::new(move |command: String, args: Value| -> BoxFuture<'static, _> {
+                let app = invoke_app.clone();
+                Box::pin(async move { web_dispatch::dispatch(&app, &command, args).await })
+            }),
             event_bus,
             token: token.clone(),
             connected_clients: connected_clients.clone(),
+            max_clients: max_clients.clone(),
+            allowlist: allowlist.clone(),
         });
 
         // Resolve the dist directory for serving static files.
@@ -125,21 +465,14 @@ impl WebAccessServer {
             .fallback_service(ServeDir::new(&dist_dir).append_index_html_on_directories(true))
             .with_state(ws_state);
 
-        let addr = format!("0.0.0.0:{}", port);
-        log::info!("Starting web access server on {}", addr);
-
-        // We need to bind synchronously to confirm the port, then serve async.
-        let listener = match std::net::TcpListener::bind(&addr) {
-            Ok(l) => {
-                l.set_nonblocking(true).ok();
-                l
-            }
-            Err(e) => {
-                log::error!("Failed to bind web access server to {}: {}", addr, e);
-                return None;
-            }
-        };
+        log::info!("Starting web access server on 0.0.0.0:{}", port);
 
+        // Serve on the listener the port probe already bound, so nothing can
+        // take the port in between.
+        if let Err(e) = listener.set_nonblocking(true) {
+            log::error!("Failed to make web access listener non-blocking: {}", e);
+            return None;
+        }
         let tokio_listener = match tokio::net::TcpListener::from_std(listener) {
             Ok(l) => l,
             Err(e) => {
@@ -148,21 +481,73 @@ impl WebAccessServer {
             }
         };
 
-        tokio::spawn(async move {
-            if let Err(e) = axum::serve(tokio_listener, app).await {
-                log::error!("Web access server error: {}", e);
+        match tls {
+            Some(identity) => {
+                let tls_listener = match TlsListener::new(tokio_listener, identity) {
+                    Ok(l) => l,
+                    Err(e) => {
+                        log::error!("Failed to set up web access TLS: {}", e);
+                        return None;
+                    }
+                };
+                tokio::spawn(async move {
+                    let service = app.into_make_service_with_connect_info::<ClientAddr>();
+                    if let Err(e) = axum::serve(tls_listener, service).await {
+                        log::error!("Web access server error: {}", e);
+                    }
+                });
             }
-        });
+            None => {
+                tokio::spawn(async move {
+                    let service = app.into_make_service_with_connect_info::<ClientAddr>();
+                    if let Err(e) = axum::serve(tokio_listener, service).await {
+                        log::error!("Web access server error: {}", e);
+                    }
+                });
+            }
+        }
 
-        log::info!("Web access server started on port {}", port);
+        log::info!(
+            "Web access server started on port {} ({})",
+            port,
+            if tls.is_some() { "https" } else { "http" }
+        );
+
+        #[cfg(feature = "mdns")]
+        let mdns = match super::mdns_advertiser::MdnsAdvertiser::start(port, instance_id) {
+            Ok(advertiser) => Some(advertiser),
+            Err(e) => {
+                log::warn!("mDNS advertising unavailable: {}", e);
+                None
+            }
+        };
+        #[cfg(not(feature = "mdns"))]
+        let _ = instance_id;
 
         Some(Self {
             port,
+            port_range,
+            tls_fingerprint: tls.map(|identity| identity.fingerprint().to_string()),
             token,
             connected_clients,
+            max_clients,
+            allowlist,
+            #[cfg(feature = "mdns")]
+            mdns: std::sync::Mutex::new(mdns),
         })
     }
 
+    /// Stop announcing the server on the LAN. Safe to call more than once.
+    pub fn stop_advertising(&self) {
+        #[cfg(feature = "mdns")]
+        {
+            let advertiser = self.mdns.lock().unwrap_or_else(|e| e.into_inner()).take();
+            if let Some(advertiser) = advertiser {
+                advertiser.stop();
+            }
+        }
+    }
+
     /// Generate a new access token. Returns (url, token, expires_in_secs).
     pub async fn generate_token(&self) -> (String, String, u64) {
         let token = uuid::Uuid::new_v4().to_string();
@@ -179,10 +564,26 @@ impl WebAccessServer {
             .map(|ip| ip.to_string())
             .unwrap_or_else(|_| "0.0.0.0".to_string());
 
-        let url = format!("http://{}:{}", ip, self.port);
+        let scheme = if self.tls_fingerprint.is_some() { "https" } else { "http" };
+        let url = format!("{}://{}:{}", scheme, ip, self.port);
         (url, token, expires_in)
     }
 
+    /// Set how many web clients may be connected at once, clamped to
+    /// `1..=MAX_CLIENTS_LIMIT`. Already-connected clients are never dropped;
+    /// the new limit applies to later connections. Returns the applied limit.
+    pub fn set_max_clients(&self, max: usize) -> usize {
+        let max = max.clamp(1, MAX_CLIENTS_LIMIT);
+        self.max_clients.store(max, Ordering::Relaxed);
+        max
+    }
+
+    /// Replace the client address allowlist. Applies to new connections;
+    /// already-connected clients stay connected.
+    pub async fn set_allowlist(&self, allowlist: IpAllowlist) {
+        *self.allowlist.write().await = allowlist;
+    }
+
     /// Get current server status.
     pub async fn get_status(&self) -> WebAccessStatus {
         let has_valid_token = {
@@ -196,7 +597,12 @@ impl WebAccessServer {
         WebAccessStatus {
             running: true,
             port: self.port,
+            port_range: self.port_range,
+            tls: self.tls_fingerprint.is_some(),
+            cert_fingerprint: self.tls_fingerprint.clone(),
             connected_clients: self.connected_clients.load(Ordering::Relaxed),
+            max_clients: self.max_clients.load(Ordering::Relaxed),
+            allowlist: self.allowlist.read().await.entries(),
             has_valid_token,
         }
     }
@@ -209,13 +615,9 @@ impl WebAccessServer {
         log::info!("Web access token revoked");
     }
 
-    fn find_available_port(start: u16, end: u16) -> Option<u16> {
-        for port in start..=end {
-            if TcpListener::bind(("0.0.0.0", port)).is_ok() {
-                return Some(port);
-            }
-        }
-        None
+    /// Bind the first free port in `start..=end` and return the listener.
+    fn find_available_port(start: u16, end: u16) -> Option<TcpListener> {
+        (start..=end).find_map(|port| TcpListener::bind(("0.0.0.0", port)).ok())
     }
 
     /// Resolve the directory containing the built frontend assets (dist/).
@@ -254,75 +656,149 @@ impl WebAccessServer {
 /// WebSocket upgrade handler.
 async fn ws_handler(
     ws: WebSocketUpgrade,
+    ConnectInfo(ClientAddr(addr)): ConnectInfo<ClientAddr>,
     State(state): State<Arc<WsState>>,
-) -> impl IntoResponse {
+) -> Response {
+    if !state.allowlist.read().await.allows(addr.ip()) {
+        log::warn!("Refusing WebSocket client {}: not in the allowlist", addr.ip());
+        return StatusCode::FORBIDDEN.into_response();
+    }
     ws.on_upgrade(move |socket| handle_ws(socket, state))
 }
 
+/// Sends a close frame with `reason`.
+async fn send_close(sender: &mut SplitSink<WebSocket, Message>, code: u16, reason: String) {
+    let _ = sender
+        .send(Message::Close(Some(CloseFrame {
+            code,
+            reason: reason.into(),
+        })))
+        .await;
+}
+
+/// Tells a client the server is full and closes the socket.
+async fn reject_full(sender: &mut SplitSink<WebSocket, Message>) {
+    log::warn!("Refusing WebSocket client: {}", TOO_MANY_CONNECTIONS);
+    let msg = ServerMessage::AuthResult {
+        success: false,
+        error: Some(TOO_MANY_CONNECTIONS.to_string()),
+    };
+    let _ = sender.send(Message::Text(serde_json::to_string(&msg).unwrap().into())).await;
+    send_close(sender, close_code::AGAIN, TOO_MANY_CONNECTIONS.to_string()).await;
+}
+
+/// Waits up to the handshake timeout for the client's next text frame.
+async fn next_handshake_text(receiver: &mut SplitStream<WebSocket>) -> Option<String> {
+    match tokio::time::timeout(std::time::Duration::from_secs(10), receiver.next()).await {
+        Ok(Some(Ok(Message::Text(text)))) => Some(text.to_string()),
+        _ => None,
+    }
+}
+
 /// Handle an individual WebSocket connection.
 async fn handle_ws(socket: WebSocket, state: Arc<WsState>) {
     let (mut sender, mut receiver) = socket.split();
     let mut subscriptions: HashSet<String> = HashSet::new();
 
-    // First message must be Auth
-    let auth_timeout = tokio::time::timeout(
-        std::time::Duration::from_secs(10),
-        receiver.next(),
-    );
-
-    let authenticated = match auth_timeout.await {
-        Ok(Some(Ok(Message::Text(text)))) => {
-            match serde_json::from_str::<ClientMessage>(&text) {
-                Ok(ClientMessage::Auth { token }) => {
-                    let valid = {
-                        let guard = state.token.read().await;
-                        guard
-                            .as_ref()
-                            .map(|t| t.token == token && t.expires_at > std::time::Instant::now())
-                            .unwrap_or(false)
-                    };
+    // Refuse early when full, without waiting for (or counting) the auth step
+    if state.connected_clients.load(Ordering::Relaxed) >= state.max_clients.load(Ordering::Relaxed) {
+        reject_full(&mut sender).await;
+        return;
+    }
 
-                    if valid {
-                        let msg = ServerMessage::AuthResult { success: true, error: None };
-                        let _ = sender.send(Message::Text(serde_json::to_string(&msg).unwrap().into())).await;
-                        true
-                    } else {
-                        let msg = ServerMessage::AuthResult {
-                            success: false,
-                            error: Some("Invalid or expired token".to_string()),
-                        };
-                        let _ = sender.send(Message::Text(serde_json::to_string(&msg).unwrap().into())).await;
-                        false
-                    }
-                }
-                _ => {
-                    let msg = ServerMessage::AuthResult {
-                        success: false,
-                        error: Some("First message must be Auth".to_string()),
-                    };
-                    let _ = sender.send(Message::Text(serde_json::to_string(&msg).unwrap().into())).await;
-                    false
-                }
+    // Versioned clients open with Hello; older ones go straight to Auth
+    let Some(first) = next_handshake_text(&mut receiver).await else {
+        return;
+    };
+    let auth_text = match serde_json::from_str::<ClientMessage>(&first) {
+        Ok(ClientMessage::Hello { protocol_version, client_version }) => {
+            if let Err(reason) = check_protocol_version(protocol_version) {
+                log::warn!("Refusing WebSocket client: {}", reason);
+                send_close(&mut sender, close_code::PROTOCOL, reason).await;
+                return;
+            }
+            log::info!(
+                "WebSocket client {} speaks protocol {}",
+                client_version.as_deref().unwrap_or("(unknown version)"),
+                protocol_version
+            );
+            let hello = ServerMessage::ServerHello {
+                protocol_version: PROTOCOL_VERSION,
+                server_version: state.server_version.clone(),
+                commands: web_dispatch::SUPPORTED_COMMANDS,
+                features: PROTOCOL_FEATURES,
+            };
+            let _ = sender.send(Message::Text(serde_json::to_string(&hello).unwrap().into())).await;
+            let Some(text) = next_handshake_text(&mut receiver).await else {
+                return;
+            };
+            text
+        }
+        // Compatibility shim for the unversioned handshake; drop next release
+        _ => {
+            log::debug!("WebSocket client skipped Hello; using protocol 1");
+            first
+        }
+    };
+
+    let authenticated = match serde_json::from_str::<ClientMessage>(&auth_text) {
+        Ok(ClientMessage::Auth { token }) => {
+            let valid = {
+                let guard = state.token.read().await;
+                guard
+                    .as_ref()
+                    .map(|t| t.token == token && t.expires_at > std::time::Instant::now())
+                    .unwrap_or(false)
+            };
+
+            if valid {
+                let msg = ServerMessage::AuthResult { success: true, error: None };
+                let _ = sender.send(Message::Text(serde_json::to_string(&msg).unwrap().into())).await;
+                true
+            } else {
+                let msg = ServerMessage::AuthResult {
+                    success: false,
+                    error: Some("Invalid or expired token".to_string()),
+                };
+                let _ = sender.send(Message::Text(serde_json::to_string(&msg).unwrap().into())).await;
+                false
             }
         }
-        _ => false,
+        _ => {
+            let msg = ServerMessage::AuthResult {
+                success: false,
+                error: Some("Expected Auth after the handshake".to_string()),
+            };
+            let _ = sender.send(Message::Text(serde_json::to_string(&msg).unwrap().into())).await;
+            false
+        }
     };
 
     if !authenticated {
         return;
     }
 
-    // Track connected client
-    state.connected_clients.fetch_add(1, Ordering::Relaxed);
+    // Track connected client; another connection may have taken the last slot during auth
+    if !try_acquire_client_slot(&state.connected_clients, state.max_clients.load(Ordering::Relaxed)) {
+        reject_full(&mut sender).await;
+        return;
+    }
     log::info!("WebSocket client connected (total: {})", state.connected_clients.load(Ordering::Relaxed));
 
     // Split sender into a channel so we can send from multiple tasks
     let (tx, mut tx_rx) = tokio::sync::mpsc::channel::<String>(256);
+    let compress = Arc::new(AtomicBool::new(false));
+    let send_compressed = compress.clone();
 
     // Task: forward mpsc channel to WebSocket sender
     let send_task = tokio::spawn(async move {
         while let Some(msg) = tx_rx.recv().await {
-            if sender.send(Message::Text(msg.into())).await.is_err() {
+            let frame = if send_compressed.load(Ordering::Relaxed) {
+                encode_frame(msg)
+            } else {
+                Message::Text(msg.into())
+            };
+            if sender.send(frame).await.is_err() {
                 break;
             }
         }
@@ -333,38 +809,96 @@ async fn handle_ws(socket: WebSocket, state: Arc<WsState>) {
     let event_bus_rx = state.event_bus.subscribe();
     let subs = Arc::new(RwLock::new(subscriptions.clone()));
     let subs_clone = subs.clone();
+    let tailed: Arc<RwLock<HashSet<u32>>> = Arc::new(RwLock::new(HashSet::new()));
+    let tailed_clone = tailed.clone();
+    // Subscriptions that asked for replay are handed to the event task so the
+    // replayed frames and the live stream are ordered on one consumer.
+    let (replay_tx, mut replay_rx) = tokio::sync::mpsc::unbounded_channel::<(String, usize)>();
+    let replay_bus = state.event_bus.clone();
 
     let event_task = tokio::spawn(async move {
         let mut rx = event_bus_rx;
+        // Highest sequence already delivered by replay, per subscription
+        // pattern, so the same events still queued on `rx` aren't sent twice.
+        let mut replayed_through: HashMap<String, u64> = HashMap::new();
         loop {
-            match rx.recv().await {
+            let received = tokio::select! {
+                Some((event, n)) = replay_rx.recv() => {
+                    let replayed = replay_bus.recent_events(n, |e| pattern_matches(&event, e));
+                    if let Some(last) = replayed.last() {
+                        replayed_through.insert(event.clone(), last.seq);
+                    }
+                    subs_clone.write().await.insert(event);
+                    for bus_event in replayed {
+                        let msg = ServerMessage::Event {
+                            event: bus_event.event,
+                            payload: bus_event.payload,
+                        };
+                        if let Ok(json) = serde_json::to_string(&msg) {
+                            if event_tx.send(json).await.is_err() {
+                                return;
+                            }
+                        }
+                    }
+                    continue;
+                }
+                received = rx.recv() => received,
+            };
+            match received {
                 Ok(bus_event) => {
-                    let subscribed = {
-                        let guard = subs_clone.read().await;
-                        guard.contains(&bus_event.event)
+                    // Tailed output shares the bounded outbound channel with
+                    // regular subscriptions, so it gets the same backpressure.
+                    let tailed_session = match pty_output_session_id(&bus_event.event) {
+                        Some(id) if tailed_clone.read().await.contains(&id) => Some(id),
+                        _ => None,
                     };
+                    let subscribed = should_forward(
+                        &*subs_clone.read().await,
+                        &replayed_through,
+                        &bus_event.event,
+                        bus_event.seq,
+                    );
+
+                    let mut frames = Vec::with_capacity(2);
+                    if let Some(session_id) = tailed_session {
+                        frames.push(ServerMessage::Event {
+                            event: TAIL_EVENT.to_string(),
+                            payload: serde_json::to_value(TailedOutput {
+                                session_id,
+                                data: bus_event.payload.clone(),
+                            })
+                            .unwrap_or(Value::Null),
+                        });
+                    }
                     if subscribed {
-                        let msg = ServerMessage::Event {
+                        frames.push(ServerMessage::Event {
                             event: bus_event.event,
                             payload: bus_event.payload,
-                        };
+                        });
+                    }
+
+                    for msg in frames {
                         if let Ok(json) = serde_json::to_string(&msg) {
                             if event_tx.send(json).await.is_err() {
-                                break;
+                                return;
                             }
                         }
                     }
                 }
-                Err(tokio::sync::broadcast::error::RecvError::Lagged(n)) => {
-                    log::warn!("WebSocket client lagged, dropped {} events", n);
+                Err(err) => {
+                    let Some(notice) = lag_notice(err) else { break };
+                    log::warn!("WebSocket client lagged: {:?}", notice);
+                    if let Ok(json) = serde_json::to_string(&notice) {
+                        if event_tx.send(json).await.is_err() {
+                            return;
+                        }
+                    }
                 }
-                Err(_) => break,
             }
         }
     });
 
     // Main loop: process incoming messages
-    let app_handle = state.app_handle.clone();
     while let Some(Ok(msg)) = receiver.next().await {
         let Message::Text(text) = msg else { continue };
 
@@ -377,14 +911,14 @@ async fn handle_ws(socket: WebSocket, state: Arc<WsState>) {
         };
 
         match client_msg {
-            ClientMessage::Auth { .. } => {
-                // Already authenticated, ignore subsequent auth messages
+            ClientMessage::Hello { .. } | ClientMessage::Auth { .. } => {
+                // Handshake already done, ignore repeats
             }
             ClientMessage::Invoke { id, command, args } => {
-                let app = app_handle.clone();
+                let invocation = (state.invoke)(command, args);
                 let invoke_tx = tx.clone();
                 tokio::spawn(async move {
-                    let result = web_dispatch::dispatch(&app, &command, args).await;
+                    let result = invocation.await;
                     let msg = match result {
                         Ok(value) => ServerMessage::InvokeResult {
                             id,
@@ -402,14 +936,26 @@ async fn handle_ws(socket: WebSocket, state: Arc<WsState>) {
                     }
                 });
             }
-            ClientMessage::Subscribe { event } => {
-                subs.write().await.insert(event.clone());
-                subscriptions.insert(event);
+            ClientMessage::Subscribe { event, replay } => {
+                subscriptions.insert(event.clone());
+                if replay > 0 {
+                    let _ = replay_tx.send((event, replay.min(REPLAY_BUFFER_SIZE)));
+                } else {
+                    subs.write().await.insert(event);
+                }
             }
             ClientMessage::Unsubscribe { event } => {
                 subs.write().await.remove(&event);
                 subscriptions.remove(&event);
             }
+            ClientMessage::TailSessions { session_ids } => {
+                log::debug!("WebSocket client tailing sessions {:?}", session_ids);
+                *tailed.write().await = session_ids.into_iter().collect();
+            }
+            ClientMessage::SetCompression { enabled } => {
+                log::debug!("WebSocket client set compression to {}", enabled);
+                compress.store(enabled, Ordering::Relaxed);
+            }
         }
     }
 
@@ -419,3 +965,302 @@ async fn handle_ws(socket: WebSocket, state: Arc<WsState>) {
     event_task.abort();
     send_task.abort();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn handshake_accepts_only_supported_protocol_versions() {
+        assert!(check_protocol_version(PROTOCOL_VERSION).is_ok());
+        assert!(check_protocol_version(1).is_err());
+        let reason = check_protocol_version(PROTOCOL_VERSION + 1).unwrap_err();
+        assert!(reason.contains(&format!("{}", PROTOCOL_VERSION + 1)));
+
+        let hello: ClientMessage =
+            serde_json::from_str(r#"{"type":"Hello","protocolVersion":2,"clientVersion":"1.2.0"}"#).unwrap();
+        assert!(matches!(
+            hello,
+            ClientMessage::Hello { protocol_version: 2, client_version: Some(ref v) } if v == "1.2.0"
+        ));
+
+        let reply = serde_json::to_value(ServerMessage::ServerHello {
+            protocol_version: PROTOCOL_VERSION,
+            server_version: "1.2.0".to_string(),
+            commands: &["get_sessions"],
+            features: PROTOCOL_FEATURES,
+        })
+        .unwrap();
+        assert_eq!(reply["type"], "ServerHello");
+        assert_eq!(reply["protocolVersion"], PROTOCOL_VERSION);
+        assert_eq!(reply["commands"], serde_json::json!(["get_sessions"]));
+    }
+
+    #[tokio::test]
+    async fn lagging_client_is_told_how_many_events_it_missed() {
+        let bus = EventBus::with_capacity(4);
+        let mut rx = bus.subscribe();
+        for i in 0..10 {
+            bus.send("session-status-changed".to_string(), Value::from(i));
+        }
+
+        let err = rx.recv().await.unwrap_err();
+        let notice = lag_notice(err).expect("lag should produce a notice");
+        assert_eq!(
+            serde_json::to_value(&notice).unwrap(),
+            serde_json::json!({ "type": "EventLagged", "dropped": 6 })
+        );
+        // Delivery resumes with the oldest event still queued
+        assert_eq!(rx.recv().await.unwrap().payload, Value::from(6));
+
+        drop(bus);
+        rx.recv().await.unwrap();
+        rx.recv().await.unwrap();
+        rx.recv().await.unwrap();
+        assert!(lag_notice(rx.recv().await.unwrap_err()).is_none());
+    }
+
+    #[test]
+    fn allowlist_matches_ranges_and_single_addresses() {
+        let ip = |s: &str| s.parse::<IpAddr>().unwrap();
+        assert!(IpAllowlist::default().allows(ip("203.0.113.9")));
+
+        let list = IpAllowlist::parse(&["192.168.1.7/24".into(), " 10.0.0.5 ".into(), "".into()]).unwrap();
+        assert_eq!(list.entries(), vec!["192.168.1.0/24", "10.0.0.5/32"]);
+        assert!(list.allows(ip("192.168.1.200")));
+        assert!(list.allows(ip("10.0.0.5")));
+        assert!(list.allows(ip("::ffff:192.168.1.3")));
+        assert!(!list.allows(ip("10.0.0.6")));
+        assert!(!list.allows(ip("192.168.2.1")));
+
+        assert!(IpAllowlist::parse(&["192.168.1.0/33".into()]).is_err());
+        assert!(IpAllowlist::parse(&["not-an-ip".into()]).is_err());
+    }
+
+    #[test]
+    fn port_range_rejects_empty_and_zero_ranges() {
+        assert_eq!(PortRange::new(9000, 9000), Ok(PortRange { start: 9000, end: 9000 }));
+        assert!(PortRange::new(9001, 9000).is_err());
+        assert!(PortRange::new(0, 100).is_err());
+        assert_eq!(PortRange::default(), PortRange { start: 8800, end: 8899 });
+    }
+
+    #[test]
+    fn port_probe_keeps_the_port_bound() {
+        let first = WebAccessServer::find_available_port(48800, 48899).unwrap();
+        let first_port = first.local_addr().unwrap().port();
+        assert!((48800..=48899).contains(&first_port));
+
+        // The probed port stays held, so the next probe must skip it
+        let second = WebAccessServer::find_available_port(first_port, 48899).unwrap();
+        assert_ne!(second.local_addr().unwrap().port(), first_port);
+    }
+
+    #[test]
+    fn only_large_frames_are_gzipped() {
+        let small = r#"{"type":"InvokeResult","id":1,"result":null}"#.to_string();
+        assert!(matches!(encode_frame(small), Message::Text(_)));
+
+        let big = serde_json::to_string(&ServerMessage::Event {
+            event: "pty-output-1".to_string(),
+            payload: Value::String("$ ls -la\n".repeat(2000)),
+        })
+        .unwrap();
+        let Message::Binary(gz) = encode_frame(big.clone()) else {
+            panic!("expected a compressed binary frame");
+        };
+        assert!(gz.len() < big.len() / 4);
+
+        let mut decoded = String::new();
+        flate2::read::GzDecoder::new(&gz[..]).read_to_string(&mut decoded).unwrap();
+        assert_eq!(decoded, big);
+    }
+
+    #[test]
+    fn compression_is_toggled_by_the_client() {
+        let msg: ClientMessage =
+            serde_json::from_str(r#"{"type":"SetCompression","enabled":false}"#).unwrap();
+        assert!(matches!(
+            msg,
+            ClientMessage::SetCompression { enabled: false }
+        ));
+    }
+
+    #[test]
+    fn client_slots_refuse_past_the_limit_without_counting() {
+        let connected = AtomicUsize::new(0);
+        for _ in 0..DEFAULT_MAX_CLIENTS {
+            assert!(try_acquire_client_slot(&connected, DEFAULT_MAX_CLIENTS));
+        }
+
+        // The N+1th is refused and existing clients keep their slots
+        assert!(!try_acquire_client_slot(&connected, DEFAULT_MAX_CLIENTS));
+        assert_eq!(connected.load(Ordering::Relaxed), DEFAULT_MAX_CLIENTS);
+
+        // Lowering the limit never evicts anyone; a freed slot is reusable
+        assert!(!try_acquire_client_slot(&connected, 2));
+        connected.fetch_sub(1, Ordering::Relaxed);
+        assert!(try_acquire_client_slot(&connected, DEFAULT_MAX_CLIENTS));
+    }
+
+    type TestClient =
+        tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+    const TEST_TOKEN: &str = "test-token";
+
+    /// Serves `/ws` on a loopback port with a valid token and stubbed commands.
+    async fn serve_ws(event_bus: Arc<EventBus>, max_clients: usize) -> (SocketAddr, Arc<WsState>) {
+        let state = Arc::new(WsState {
+            server_version: "0.0.0".to_string(),
+            invoke: Arc::new(|_: String, _: Value| -> BoxFuture<'static, _> {
+                Box::pin(async { Ok(Value::Null) })
+            }),
+            event_bus,
+            token: Arc::new(RwLock::new(Some(TokenInfo {
+                token: TEST_TOKEN.to_string(),
+                expires_at: std::time::Instant::now() + std::time::Duration::from_secs(60),
+            }))),
+            connected_clients: Arc::new(AtomicUsize::new(0)),
+            max_clients: Arc::new(AtomicUsize::new(max_clients)),
+            allowlist: Arc::new(RwLock::new(IpAllowlist::default())),
+        });
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = Router::new().route("/ws", get(ws_handler)).with_state(state.clone());
+        tokio::spawn(async move {
+            let service = app.into_make_service_with_connect_info::<ClientAddr>();
+            axum::serve(listener, service).await.unwrap();
+        });
+        (addr, state)
+    }
+
+    async fn send_json(client: &mut TestClient, msg: Value) {
+        let text = msg.to_string();
+        client.send(tokio_tungstenite::tungstenite::Message::Text(text.into())).await.unwrap();
+    }
+
+    /// Next text frame from the server, parsed.
+    async fn next_json(client: &mut TestClient) -> Value {
+        loop {
+            let frame = tokio::time::timeout(std::time::Duration::from_secs(5), client.next())
+                .await
+                .expect("timed out waiting for a frame")
+                .expect("socket closed")
+                .unwrap();
+            if let tokio_tungstenite::tungstenite::Message::Text(text) = frame {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    /// Connects and authenticates, returning the client and its `AuthResult`.
+    async fn connect_client(addr: SocketAddr) -> (TestClient, Value) {
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+            .await
+            .unwrap();
+        send_json(&mut client, serde_json::json!({ "type": "Auth", "token": TEST_TOKEN })).await;
+        let result = next_json(&mut client).await;
+        (client, result)
+    }
+
+    #[tokio::test]
+    async fn clients_past_the_limit_are_turned_away() {
+        let (addr, state) = serve_ws(Arc::new(EventBus::new()), 2).await;
+        let (_first, result) = connect_client(addr).await;
+        assert_eq!(result["success"], true);
+        let (second, result) = connect_client(addr).await;
+        assert_eq!(result["success"], true);
+
+        let (mut third, result) = connect_client(addr).await;
+        assert_eq!(result["success"], false);
+        assert_eq!(result["error"], TOO_MANY_CONNECTIONS);
+        let frame = third.next().await.unwrap().unwrap();
+        let tokio_tungstenite::tungstenite::Message::Close(Some(close)) = &frame else {
+            panic!("expected a close frame, got {:?}", frame);
+        };
+        assert_eq!(u16::from(close.code), close_code::AGAIN);
+        assert_eq!(state.connected_clients.load(Ordering::Relaxed), 2);
+
+        // A disconnect frees its slot for the next client
+        drop(second);
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+        let (_fourth, result) = connect_client(addr).await;
+        assert_eq!(result["success"], true);
+    }
+
+    #[tokio::test]
+    async fn lagging_subscriber_gets_a_notice_over_the_socket() {
+        let bus = Arc::new(EventBus::with_capacity(4));
+        let (addr, _state) = serve_ws(bus.clone(), DEFAULT_MAX_CLIENTS).await;
+        let (mut client, _) = connect_client(addr).await;
+        send_json(&mut client, serde_json::json!({ "type": "Subscribe", "event": "session-status-changed" })).await;
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+
+        // The test runtime is single-threaded, so the connection's event task
+        // can't run between these sends and falls behind the bus
+        for i in 0..10 {
+            bus.send("session-status-changed".to_string(), Value::from(i));
+        }
+
+        assert_eq!(
+            next_json(&mut client).await,
+            serde_json::json!({ "type": "EventLagged", "dropped": 6 })
+        );
+        for i in 6..10 {
+            let event = next_json(&mut client).await;
+            assert_eq!(event["event"], "session-status-changed");
+            assert_eq!(event["payload"], i);
+        }
+    }
+
+    #[test]
+    fn qr_encodes_url_with_token_in_fragment() {
+        let url = access_url("https://abc.example.com/", Some("tok-123"));
+        assert_eq!(url, "https://abc.example.com/#token=tok-123");
+        assert_eq!(access_url("http://10.0.0.2:9800", None), "http://10.0.0.2:9800");
+
+        use base64::Engine;
+        let qr = render_access_qr(&url).unwrap();
+        assert_eq!(qr.url, url);
+        let svg = base64::engine::general_purpose::STANDARD
+            .decode(&qr.svg_base64)
+            .unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.contains("<svg"));
+    }
+
+    fn subs(patterns: &[&str]) -> HashSet<String> {
+        patterns.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn wildcard_subscription_matches_prefix() {
+        let subs = subs(&["session:*"]);
+        let none = HashMap::new();
+
+        assert!(should_forward(&subs, &none, "session:exited", 1));
+        assert!(should_forward(&subs, &none, "session:status", 2));
+        assert!(!should_forward(&subs, &none, "session-status-changed", 3));
+        assert!(!should_forward(&subs, &none, "pty-output-1", 4));
+    }
+
+    #[test]
+    fn exact_subscriptions_still_match_exactly() {
+        let subs = subs(&["session-status-changed"]);
+        let none = HashMap::new();
+
+        assert!(should_forward(&subs, &none, "session-status-changed", 1));
+        assert!(!should_forward(&subs, &none, "session-status-changed-v2", 2));
+        assert!(pattern_matches("*", "anything"));
+    }
+
+    #[test]
+    fn replayed_events_are_not_forwarded_twice() {
+        let subs = subs(&["session:*"]);
+        let replayed: HashMap<String, u64> = [("session:*".to_string(), 10)].into();
+
+        assert!(!should_forward(&subs, &replayed, "session:exited", 10));
+        assert!(should_forward(&subs, &replayed, "session:exited", 11));
+    }
+}
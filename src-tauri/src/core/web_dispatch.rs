@@ -4,100 +4,173 @@
 //! This avoids going through Tauri's IPC layer, directly accessing
 //! the managed state via `AppHandle`.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
 use std::sync::Arc;
 
 use serde_json::Value;
 use tauri::Manager;
+use tauri_plugin_store::StoreExt;
 
+use crate::core::ansi::strip_ansi;
 use crate::core::event_bus::EventBus;
 use crate::core::mcp_config_writer;
 use crate::core::mcp_manager::McpManager;
 use crate::core::plugin_manager::PluginManager;
-use crate::core::process_manager::ProcessManager;
-use crate::core::session_manager::{AiMode, SessionManager, SessionStatus};
+use crate::core::process_manager::{ProcessManager, DEFAULT_OUTPUT_CHUNK_BYTES};
+use crate::core::session_manager::{
+    validate_session_env, AiMode, AiModeInfo, SessionManager, SessionStatus,
+};
+use crate::core::session_recorder;
 use crate::core::status_server::StatusServer;
+use crate::core::terminal_backend::{BackendType, ScreenSnapshot};
 use crate::core::worktree_manager::WorktreeManager;
 
-/// Dispatch a command by name, extracting args from the JSON value.
-pub async fn dispatch(
-    app: &tauri::AppHandle,
-    command: &str,
-    args: Value,
-) -> Result<Value, String> {
-    match command {
-        // === Terminal commands ===
-        "spawn_shell" => cmd_spawn_shell(app, args).await,
-        "write_stdin" => cmd_write_stdin(app, args).await,
-        "resize_pty" => cmd_resize_pty(app, args).await,
-        "kill_session" => cmd_kill_session(app, args).await,
-        "kill_all_sessions" => cmd_kill_all_sessions(app).await,
-        "check_cli_available" => cmd_check_cli_available(args).await,
-        "get_backend_info" => cmd_get_backend_info(),
-        "get_status_server_info" => cmd_get_status_server_info(app),
-
-        // === Session commands ===
-        "get_sessions" => cmd_get_sessions(app),
-        "create_session" => cmd_create_session(app, args),
-        "update_session_status" => cmd_update_session_status(app, args),
-        "update_session_title" => cmd_update_session_title(app, args),
-        "assign_session_branch" => cmd_assign_session_branch(app, args),
-        "remove_session" => cmd_remove_session(app, args),
-        "get_sessions_for_project" => cmd_get_sessions_for_project(app, args),
-        "remove_sessions_for_project" => cmd_remove_sessions_for_project(app, args).await,
-
-        // === Worktree commands ===
-        "prepare_session_worktree" => cmd_prepare_session_worktree(app, args).await,
-        "cleanup_session_worktree" => cmd_cleanup_session_worktree(app, args).await,
-
-        // === MCP commands ===
-        "get_project_mcp_servers" => cmd_get_project_mcp_servers(app, args),
-        "get_session_mcp_servers" => cmd_get_session_mcp_servers(app, args),
-        "set_session_mcp_servers" => cmd_set_session_mcp_servers(app, args),
-        "get_session_mcp_count" => cmd_get_session_mcp_count(app, args),
-        "write_session_mcp_config" => cmd_write_session_mcp_config(app, args).await,
-        "remove_session_mcp_config" => cmd_remove_session_mcp_config(args).await,
-        "generate_project_hash" => cmd_generate_project_hash(args),
-
-        // === Git commands ===
-        "git_current_branch" => {
-            let repo_path = get_str(&args, "repoPath")?;
-            let git = crate::git::Git::new(&repo_path);
-            let branch = git.current_branch().await.map_err(|e| e.to_string())?;
-            Ok(Value::String(branch))
+/// Defines `dispatch` and `SUPPORTED_COMMANDS` from one table of
+/// `"name" => handler` arms, so the advertised list can't drift from what
+/// `dispatch` actually handles. `app`, `command` and `args` name the
+/// parameters the handlers use.
+macro_rules! commands {
+    ($app:ident, $command:ident, $args:ident; $($name:literal => $handler:expr),* $(,)?) => {
+        /// Every command `dispatch` handles, advertised to web clients in the
+        /// protocol handshake and by `list_commands`.
+        pub const SUPPORTED_COMMANDS: &[&str] = &[$($name),*];
+
+        /// Dispatch a command by name, extracting args from the JSON value.
+        pub async fn dispatch(
+            $app: &tauri::AppHandle,
+            $command: &str,
+            $args: Value,
+        ) -> Result<Value, String> {
+            match $command {
+                $($name => $handler,)*
+                _ => Err(format!(
+                    "Command '{}' not yet supported via web access (list_commands returns the supported ones)",
+                    $command
+                )),
+            }
         }
-        "git_branches" => cmd_git_branches(args).await,
-        "git_worktree_list" => cmd_git_worktree_list(args).await,
-
-        // === Plugin commands ===
-        "get_project_plugins" => cmd_get_project_plugins(app, args),
-        "get_session_skills" => cmd_get_session_skills(app, args),
-        "set_session_skills" => cmd_set_session_skills(app, args),
-
-        // === ClaudeMd commands ===
-        "check_claude_md" => cmd_check_claude_md(args).await,
-        "read_claude_md" => cmd_read_claude_md(args).await,
-
-        // === Font commands ===
-        "get_available_fonts" => cmd_get_available_fonts(),
-        "check_font_available" => cmd_check_font_available(args),
-
-        // === Explorer commands ===
-        "read_directory" => cmd_read_directory(args).await,
-        "read_file_content" => cmd_read_file_content(args).await,
-
-        // === Mobile push commands ===
-        "push_session_to_mobile" => cmd_push_session_to_mobile(app, args),
-        "get_session_output" => cmd_get_session_output(app, args),
-
-        // === Store proxy commands (for mobile browser) ===
-        "store_get" => cmd_store_get(app, args).await,
-        "store_set" => cmd_store_set(app, args).await,
+    };
+}
 
-        // === Unsupported ===
-        _ => Err(format!("Command '{}' not yet supported via web access", command)),
-    }
+commands! { app, command, args;
+    // === Connection ===
+    "ping" => cmd_ping(args),
+    "list_commands" => Ok(serde_json::json!(SUPPORTED_COMMANDS)),
+
+    // === Terminal commands ===
+    "spawn_shell" => cmd_spawn_shell(app, args).await,
+    "write_stdin" => cmd_write_stdin(app, args).await,
+    "resize_pty" => cmd_resize_pty(app, args).await,
+    "kill_session" => cmd_kill_session(app, args).await,
+    "restart_session" => cmd_restart_session(app, args).await,
+    "kill_all_sessions" => cmd_kill_all_sessions(app).await,
+    "check_cli_available" => cmd_check_cli_available(args).await,
+    "check_session_cli" => cmd_check_session_cli(args).await,
+    "get_backend_info" => cmd_get_backend_info(app),
+    "set_default_backend" => cmd_set_default_backend(app, args),
+    "get_status_server_info" => cmd_get_status_server_info(app),
+    "get_registered_status_sessions" => cmd_get_registered_status_sessions(app).await,
+    "get_mcp_logs" => cmd_get_mcp_logs(app, args).await,
+
+    // === Session commands ===
+    "get_sessions" => cmd_get_sessions(app),
+    "get_ai_modes" => cmd_get_ai_modes(),
+    "create_session" => cmd_create_session(app, args),
+    "create_session_auto" => cmd_create_session_auto(app, args),
+    "update_session_status" => cmd_update_session_status(app, args),
+    "update_sessions_status" => cmd_update_sessions_status(app, args),
+    "update_session_title" => cmd_update_session_title(app, args),
+    "set_session_note" => cmd_set_session_note(app, args),
+    "set_session_tags" => cmd_set_session_tags(app, args),
+    "get_session_env" => cmd_get_session_env(app, args),
+    "set_session_env" => cmd_set_session_env(app, args),
+    "get_sessions_by_tag" => cmd_get_sessions_by_tag(app, args),
+    "assign_session_branch" => cmd_assign_session_branch(app, args),
+    "verify_session_branch" => cmd_verify_session_branch(app, args).await,
+    "reorder_sessions" => cmd_reorder_sessions(app, args),
+    "remove_session" => cmd_remove_session(app, args),
+    "get_sessions_for_project" => cmd_get_sessions_for_project(app, args),
+    "remove_sessions_for_project" => cmd_remove_sessions_for_project(app, args).await,
+
+    // === Worktree commands ===
+    "prepare_session_worktree" => cmd_prepare_session_worktree(app, args).await,
+    "cleanup_session_worktree" => cmd_cleanup_session_worktree(app, args).await,
+    "cancel_worktree_prepare" => cmd_cancel_worktree_prepare(app, args).await,
+    "list_worktrees_with_sessions" => cmd_list_worktrees_with_sessions(app, args).await,
+    "worktree_disk_usage" => cmd_worktree_disk_usage(app, args).await,
+
+    // === MCP commands ===
+    "get_project_mcp_servers" => cmd_get_project_mcp_servers(app, args),
+    "get_session_mcp_servers" => cmd_get_session_mcp_servers(app, args),
+    "set_session_mcp_servers" => cmd_set_session_mcp_servers(app, args),
+    "get_session_mcp_env" => cmd_get_session_mcp_env(app, args),
+    "set_session_mcp_env" => cmd_set_session_mcp_env(app, args),
+    "get_session_mcp_count" => cmd_get_session_mcp_count(app, args),
+    "get_mcp_server_usage" => cmd_get_mcp_server_usage(app).await,
+    "write_session_mcp_config" => cmd_write_session_mcp_config(app, args).await,
+    "preview_session_mcp_config" => cmd_preview_session_mcp_config(app, args).await,
+    "remove_session_mcp_config" => cmd_remove_session_mcp_config(args).await,
+    "generate_project_hash" => cmd_generate_project_hash(args),
+
+    // === Git commands ===
+    "git_current_branch" => {
+        let repo_path = get_str(&args, "repoPath")?;
+        let git = crate::git::Git::new(&repo_path);
+        let branch = git.current_branch().await.map_err(|e| e.to_string())?;
+        Ok(Value::String(branch))
+    },
+    "git_branches" => cmd_git_branches(args).await,
+    "git_worktree_list" => cmd_git_worktree_list(args).await,
+    "git_log" => cmd_git_log(args).await,
+    "git_diff" => cmd_git_diff(args).await,
+    "git_stage" => cmd_git_stage(args).await,
+    "git_commit" => cmd_git_commit(args).await,
+    "git_remotes" => cmd_git_remotes(args).await,
+    "git_fetch" => cmd_git_fetch(args).await,
+    "git_stash_save" => cmd_git_stash_save(args).await,
+    "git_stash_list" => cmd_git_stash_list(args).await,
+    "git_stash_pop" => cmd_git_stash_pop(args).await,
+
+    // === Plugin commands ===
+    "get_project_plugins" => cmd_get_project_plugins(app, args),
+    "get_session_skills" => cmd_get_session_skills(app, args),
+    "set_session_skills" => cmd_set_session_skills(app, args),
+
+    // === ClaudeMd commands ===
+    "check_claude_md" => cmd_check_claude_md(args).await,
+    "read_claude_md" => cmd_read_claude_md(args).await,
+
+    // === Font commands ===
+    "get_available_fonts" => cmd_get_available_fonts(),
+    "check_font_available" => cmd_check_font_available(args),
+
+    // === Explorer commands ===
+    "read_directory" => cmd_read_directory(args).await,
+    "read_file_content" => cmd_read_file_content(args).await,
+
+    // === Mobile push commands ===
+    "push_session_to_mobile" => cmd_push_session_to_mobile(app, args),
+    "get_session_output" => cmd_get_session_output(app, args),
+    "get_session_screen" => cmd_get_session_screen(app, args),
+    "get_session_terminal_state" => cmd_get_session_terminal_state(app, args),
+    "start_recording" => cmd_start_recording(app, args),
+    "stop_recording" => cmd_stop_recording(app, args),
+    "get_session_output_since" => cmd_get_session_output_since(app, args),
+    "get_session_output_info" => cmd_get_session_output_info(app, args),
+    "set_scrollback_limit" => cmd_set_scrollback_limit(app, args),
+    "set_scrollback_persistence" => cmd_set_scrollback_persistence(app, args),
+    "get_scrollback_persistence" => cmd_get_scrollback_persistence(app),
+    "set_output_coalesce_window" => cmd_set_output_coalesce_window(app, args),
+    "pause_session_output" => cmd_set_session_output_paused(app, args, true),
+    "resume_session_output" => cmd_set_session_output_paused(app, args, false),
+    "get_known_event_names" => cmd_get_known_event_names(app),
+
+    // === Store proxy commands (for mobile browser) ===
+    "store_get" => cmd_store_get(app, args).await,
+    "store_set" => cmd_store_set(app, args).await,
+    "store_delete" => cmd_store_delete(app, args).await,
+    "store_keys" => cmd_store_keys(app, args).await,
 }
 
 // ============================================================================
@@ -109,6 +182,12 @@ async fn cmd_spawn_shell(app: &tauri::AppHandle, args: Value) -> Result<Value, S
     let env: Option<HashMap<String, String>> = args
         .get("env")
         .and_then(|v| serde_json::from_value(v.clone()).ok());
+    let backend: Option<BackendType> = match args.get("backend") {
+        Some(v) if !v.is_null() => {
+            Some(serde_json::from_value(v.clone()).map_err(|e| format!("Invalid backend: {e}"))?)
+        }
+        _ => None,
+    };
 
     let canonical_cwd = if let Some(ref dir) = cwd {
         let canonical = crate::core::path_utils::normalize_path_buf(Path::new(dir));
@@ -122,9 +201,15 @@ async fn cmd_spawn_shell(app: &tauri::AppHandle, args: Value) -> Result<Value, S
 
     let pm = app.state::<ProcessManager>();
     let pm = pm.inner().clone();
-    let id = pm
-        .spawn_shell(app.clone(), canonical_cwd, env)
-        .map_err(|e| e.to_string())?;
+    let id = match args.get("sessionId") {
+        Some(v) if !v.is_null() => {
+            let id = get_u32(&args, "sessionId")?;
+            pm.spawn_session_shell(app.clone(), id, canonical_cwd, env, backend)
+                .map(|_| id)
+        }
+        _ => pm.spawn_shell(app.clone(), canonical_cwd, env, backend),
+    }
+    .map_err(|e| e.to_string())?;
     Ok(serde_json::to_value(id).unwrap())
 }
 
@@ -162,6 +247,14 @@ async fn cmd_kill_session(app: &tauri::AppHandle, args: Value) -> Result<Value,
     Ok(Value::Null)
 }
 
+async fn cmd_restart_session(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let pid = crate::commands::terminal::restart_session_internal(app, session_id)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(serde_json::to_value(pid).unwrap())
+}
+
 async fn cmd_kill_all_sessions(app: &tauri::AppHandle) -> Result<Value, String> {
     let pm = app.state::<ProcessManager>();
     let pm = pm.inner().clone();
@@ -178,8 +271,31 @@ async fn cmd_check_cli_available(args: Value) -> Result<Value, String> {
     Ok(serde_json::to_value(available).unwrap())
 }
 
-fn cmd_get_backend_info() -> Result<Value, String> {
-    let info = crate::commands::terminal::get_backend_info();
+async fn cmd_check_session_cli(args: Value) -> Result<Value, String> {
+    let mode: AiMode = serde_json::from_value(
+        args.get("mode").cloned().unwrap_or(Value::String("Claude".into())),
+    )
+    .map_err(|e| e.to_string())?;
+    crate::commands::terminal::check_session_cli(mode)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(Value::Null)
+}
+
+fn cmd_get_backend_info(app: &tauri::AppHandle) -> Result<Value, String> {
+    let pm = app.state::<ProcessManager>();
+    let info = crate::commands::terminal::backend_info(&pm);
+    serde_json::to_value(info).map_err(|e| e.to_string())
+}
+
+fn cmd_set_default_backend(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let backend: BackendType = serde_json::from_value(
+        args.get("backend").cloned().ok_or("Missing 'backend'")?,
+    )
+    .map_err(|e| format!("Invalid backend: {e}"))?;
+    let pm = app.state::<ProcessManager>();
+    pm.set_default_backend(backend)?;
+    let info = crate::commands::terminal::backend_info(&pm);
     serde_json::to_value(info).map_err(|e| e.to_string())
 }
 
@@ -193,6 +309,19 @@ fn cmd_get_status_server_info(app: &tauri::AppHandle) -> Result<Value, String> {
     serde_json::to_value(info).map_err(|e| e.to_string())
 }
 
+async fn cmd_get_registered_status_sessions(app: &tauri::AppHandle) -> Result<Value, String> {
+    let ss = app.state::<Arc<StatusServer>>();
+    let sessions = ss.registered_session_details().await;
+    serde_json::to_value(sessions).map_err(|e| e.to_string())
+}
+
+async fn cmd_get_mcp_logs(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let ss = app.state::<Arc<StatusServer>>();
+    let logs = ss.mcp_logs(session_id).await;
+    serde_json::to_value(logs).map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // Session commands
 // ============================================================================
@@ -203,6 +332,11 @@ fn cmd_get_sessions(app: &tauri::AppHandle) -> Result<Value, String> {
     serde_json::to_value(sessions).map_err(|e| e.to_string())
 }
 
+fn cmd_get_ai_modes() -> Result<Value, String> {
+    let modes: Vec<AiModeInfo> = AiMode::ALL.into_iter().map(AiModeInfo::from).collect();
+    serde_json::to_value(modes).map_err(|e| e.to_string())
+}
+
 fn cmd_create_session(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
     let id = get_u32(&args, "id")?;
     let mode: AiMode = serde_json::from_value(
@@ -211,11 +345,36 @@ fn cmd_create_session(app: &tauri::AppHandle, args: Value) -> Result<Value, Stri
     .map_err(|e| e.to_string())?;
     let project_path = get_str(&args, "projectPath")?;
     let canonical = crate::core::path_utils::normalize_path(&project_path);
+    let env = get_env_arg(&args)?;
+    if let Some(ref env) = env {
+        validate_session_env(env)?;
+    }
 
     let sm = app.state::<SessionManager>();
-    let session = sm
+    let mut session = sm
         .create_session(id, mode, canonical)
         .map_err(|existing| format!("Session {} already exists", existing.id))?;
+    if let Some(env) = env.filter(|env| !env.is_empty()) {
+        session = sm
+            .set_env(id, env)
+            .ok_or_else(|| format!("Session {} not found", id))?;
+    }
+    serde_json::to_value(session).map_err(|e| e.to_string())
+}
+
+fn cmd_create_session_auto(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let mode: AiMode = serde_json::from_value(
+        args.get("mode").cloned().unwrap_or(Value::String("Claude".into())),
+    )
+    .map_err(|e| e.to_string())?;
+    let project_path = get_str(&args, "projectPath")?;
+    let canonical = crate::core::path_utils::normalize_path(&project_path);
+
+    let sm = app.state::<SessionManager>();
+    let pm = app.state::<ProcessManager>();
+    let session = sm
+        .create_session_auto(|| pm.allocate_session_id().ok(), mode, canonical)
+        .ok_or_else(|| "No free session ID available".to_string())?;
     serde_json::to_value(session).map_err(|e| e.to_string())
 }
 
@@ -231,6 +390,21 @@ fn cmd_update_session_status(app: &tauri::AppHandle, args: Value) -> Result<Valu
     Ok(serde_json::to_value(updated).unwrap())
 }
 
+fn cmd_update_sessions_status(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_ids: Vec<u32> = args
+        .get("sessionIds")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .ok_or_else(|| "Missing or invalid 'sessionIds' argument".to_string())?;
+    let status: SessionStatus = serde_json::from_value(
+        args.get("status").cloned().ok_or("Missing 'status'")?,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let sm = app.state::<SessionManager>();
+    let result = sm.update_status_bulk(&session_ids, status);
+    serde_json::to_value(result).map_err(|e| e.to_string())
+}
+
 fn cmd_update_session_title(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
     let session_id = get_u32(&args, "sessionId")?;
     let title = get_str(&args, "title")?;
@@ -240,6 +414,69 @@ fn cmd_update_session_title(app: &tauri::AppHandle, args: Value) -> Result<Value
     Ok(serde_json::to_value(updated).unwrap())
 }
 
+fn cmd_set_session_note(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let note = args.get("note").and_then(|v| v.as_str()).map(String::from);
+
+    let sm = app.state::<SessionManager>();
+    let updated = sm
+        .set_note(session_id, note)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    serde_json::to_value(updated).map_err(|e| e.to_string())
+}
+
+fn cmd_set_session_tags(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let tags: Vec<String> = args
+        .get("tags")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .ok_or_else(|| "Missing or invalid 'tags' argument".to_string())?;
+
+    let sm = app.state::<SessionManager>();
+    let updated = sm
+        .set_tags(session_id, tags)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    serde_json::to_value(updated).map_err(|e| e.to_string())
+}
+
+/// Reads an optional `env` object of string values from the arguments.
+fn get_env_arg(args: &Value) -> Result<Option<BTreeMap<String, String>>, String> {
+    match args.get("env") {
+        Some(v) if !v.is_null() => serde_json::from_value(v.clone())
+            .map(Some)
+            .map_err(|e| format!("Invalid 'env' argument: {e}")),
+        _ => Ok(None),
+    }
+}
+
+fn cmd_get_session_env(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let sm = app.state::<SessionManager>();
+    let env = sm
+        .get_env(session_id)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    serde_json::to_value(env).map_err(|e| e.to_string())
+}
+
+fn cmd_set_session_env(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let env = get_env_arg(&args)?.ok_or("Missing 'env' argument")?;
+    validate_session_env(&env)?;
+
+    let sm = app.state::<SessionManager>();
+    let updated = sm
+        .set_env(session_id, env)
+        .ok_or_else(|| format!("Session {} not found", session_id))?;
+    serde_json::to_value(updated).map_err(|e| e.to_string())
+}
+
+fn cmd_get_sessions_by_tag(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let tag = get_str(&args, "tag")?;
+    let sm = app.state::<SessionManager>();
+    let sessions = sm.get_sessions_by_tag(&tag);
+    serde_json::to_value(sessions).map_err(|e| e.to_string())
+}
+
 fn cmd_assign_session_branch(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
     let session_id = get_u32(&args, "sessionId")?;
     let branch = get_str(&args, "branch")?;
@@ -252,10 +489,39 @@ fn cmd_assign_session_branch(app: &tauri::AppHandle, args: Value) -> Result<Valu
     serde_json::to_value(session).map_err(|e| e.to_string())
 }
 
+async fn cmd_verify_session_branch(
+    app: &tauri::AppHandle,
+    args: Value,
+) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let sm = app.state::<SessionManager>();
+    let result = crate::commands::session::verify_session_branch(sm, session_id).await?;
+    serde_json::to_value(result).map_err(|e| e.to_string())
+}
+
+fn cmd_reorder_sessions(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let ids: Vec<u32> = args
+        .get("ids")
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .ok_or_else(|| "Missing or invalid 'ids' argument".to_string())?;
+
+    let sm = app.state::<SessionManager>();
+    let sessions = sm.reorder(&ids);
+    serde_json::to_value(sessions).map_err(|e| e.to_string())
+}
+
 fn cmd_remove_session(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
     let session_id = get_u32(&args, "sessionId")?;
     let sm = app.state::<SessionManager>();
     let removed = sm.remove_session(session_id);
+
+    if let Some(session) = &removed {
+        app.state::<ProcessManager>().remove_persisted_scrollback(session.id);
+        if sm.get_sessions_for_project(&session.project_path).is_empty() {
+            app.state::<McpManager>().unwatch_project(&session.project_path);
+        }
+    }
+
     serde_json::to_value(removed).map_err(|e| e.to_string())
 }
 
@@ -300,11 +566,27 @@ async fn cmd_remove_sessions_for_project(
         if let Err(e) = pm.kill_session(session.id).await {
             log::warn!("Failed to kill PTY for session {}: {}", session.id, e);
         }
+        pm.remove_persisted_scrollback(session.id);
     }
 
+    mcp.unwatch_project(&canonical);
+
     serde_json::to_value(removed).map_err(|e| e.to_string())
 }
 
+// ============================================================================
+// Connection commands
+// ============================================================================
+
+/// Echoes `args.nonce` with the server's clock (ms since the epoch), so
+/// clients can measure round-trip time and check liveness. Stateless.
+fn cmd_ping(args: Value) -> Result<Value, String> {
+    Ok(serde_json::json!({
+        "nonce": args.get("nonce").cloned().unwrap_or(Value::Null),
+        "serverTime": chrono::Utc::now().timestamp_millis(),
+    }))
+}
+
 // ============================================================================
 // Worktree commands
 // ============================================================================
@@ -315,17 +597,30 @@ async fn cmd_prepare_session_worktree(
 ) -> Result<Value, String> {
     let project_path = get_str(&args, "projectPath")?;
     let branch = args.get("branch").and_then(|v| v.as_str()).map(String::from);
+    let prepare_handle = args.get("prepareHandle").and_then(|v| v.as_str()).map(String::from);
 
     let wm = app.state::<WorktreeManager>();
     // Delegate to the Tauri command handler directly
     let result = crate::commands::worktree::prepare_session_worktree(
-        wm, project_path, branch,
+        wm, project_path, branch, prepare_handle,
     )
     .await?;
 
     serde_json::to_value(result).map_err(|e| e.to_string())
 }
 
+async fn cmd_cancel_worktree_prepare(
+    app: &tauri::AppHandle,
+    args: Value,
+) -> Result<Value, String> {
+    let handle = get_str(&args, "handle")?;
+
+    let wm = app.state::<WorktreeManager>();
+    let result = crate::commands::worktree::cancel_worktree_prepare(wm, handle).await?;
+
+    Ok(serde_json::to_value(result).unwrap())
+}
+
 async fn cmd_cleanup_session_worktree(
     app: &tauri::AppHandle,
     args: Value,
@@ -342,6 +637,27 @@ async fn cmd_cleanup_session_worktree(
     Ok(serde_json::to_value(result).unwrap())
 }
 
+async fn cmd_list_worktrees_with_sessions(
+    app: &tauri::AppHandle,
+    args: Value,
+) -> Result<Value, String> {
+    let project_path = get_str(&args, "projectPath")?;
+
+    let sm = app.state::<SessionManager>();
+    let result = crate::commands::worktree::list_worktrees_with_sessions(sm, project_path).await?;
+
+    serde_json::to_value(result).map_err(|e| e.to_string())
+}
+
+async fn cmd_worktree_disk_usage(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let repo_path = get_str(&args, "repoPath")?;
+
+    let wm = app.state::<WorktreeManager>();
+    let result = crate::commands::worktree::worktree_disk_usage(wm, repo_path).await?;
+
+    serde_json::to_value(result).map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // MCP commands
 // ============================================================================
@@ -376,6 +692,27 @@ fn cmd_set_session_mcp_servers(app: &tauri::AppHandle, args: Value) -> Result<Va
     Ok(Value::Null)
 }
 
+fn cmd_get_session_mcp_env(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let project_path = get_str(&args, "projectPath")?;
+    let session_id = get_u32(&args, "sessionId")?;
+    let canonical = crate::core::path_utils::normalize_path(&project_path);
+    let mcp = app.state::<McpManager>();
+    serde_json::to_value(mcp.get_session_env(&canonical, session_id)).map_err(|e| e.to_string())
+}
+
+fn cmd_set_session_mcp_env(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let project_path = get_str(&args, "projectPath")?;
+    let session_id = get_u32(&args, "sessionId")?;
+    let env: HashMap<String, String> = serde_json::from_value(
+        args.get("env").cloned().unwrap_or(Value::Object(Default::default())),
+    )
+    .map_err(|e| e.to_string())?;
+    let canonical = crate::core::path_utils::normalize_path(&project_path);
+    let mcp = app.state::<McpManager>();
+    mcp.set_session_env(&canonical, session_id, env);
+    Ok(Value::Null)
+}
+
 fn cmd_get_session_mcp_count(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
     let project_path = get_str(&args, "projectPath")?;
     let session_id = get_u32(&args, "sessionId")?;
@@ -385,6 +722,12 @@ fn cmd_get_session_mcp_count(app: &tauri::AppHandle, args: Value) -> Result<Valu
     Ok(serde_json::to_value(count).unwrap())
 }
 
+async fn cmd_get_mcp_server_usage(app: &tauri::AppHandle) -> Result<Value, String> {
+    let mcp = app.state::<McpManager>();
+    let usage = crate::commands::mcp::get_mcp_server_usage(app.clone(), mcp).await?;
+    serde_json::to_value(usage).map_err(|e| e.to_string())
+}
+
 async fn cmd_write_session_mcp_config(
     app: &tauri::AppHandle,
     args: Value,
@@ -402,8 +745,14 @@ async fn cmd_write_session_mcp_config(
     // Use the Tauri command directly (it uses AppHandle + State)
     // We need to replicate the logic here since we can't call tauri commands directly.
     let canonical = crate::core::path_utils::normalize_path(&project_path);
+
+    let sm = app.state::<SessionManager>();
+    if sm.get_session(session_id).is_some_and(|s| !s.mode.uses_mcp_config()) {
+        return Ok(Value::Null);
+    }
     let ss = app.state::<Arc<StatusServer>>();
-    ss.register_session(session_id, &canonical).await;
+    let canonical_working_dir = crate::core::path_utils::normalize_path(&working_dir);
+    ss.register_session(session_id, &canonical, &canonical_working_dir).await;
 
     let mcp_state = app.state::<McpManager>();
     let all_discovered = mcp_state.get_project_servers(&canonical);
@@ -416,7 +765,8 @@ async fn cmd_write_session_mcp_config(
         Path::new(&working_dir),
         session_id,
         &enabled_discovered,
-        &[],  // No custom servers in web context
+        &[], // No custom servers in web context
+        &mcp_state.get_session_env(&canonical, session_id),
         None, // No chorus-status binary path in web context
     )
     .await?;
@@ -424,6 +774,46 @@ async fn cmd_write_session_mcp_config(
     Ok(Value::Null)
 }
 
+async fn cmd_preview_session_mcp_config(
+    app: &tauri::AppHandle,
+    args: Value,
+) -> Result<Value, String> {
+    let working_dir = get_str(&args, "workingDir")?;
+    let session_id = get_u32(&args, "sessionId")?;
+    let project_path = get_str(&args, "projectPath")?;
+    let enabled_server_names: Vec<String> = serde_json::from_value(
+        args.get("enabledServerNames")
+            .cloned()
+            .unwrap_or(Value::Array(vec![])),
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Mirrors cmd_write_session_mcp_config's inputs
+    let sm = app.state::<SessionManager>();
+    if sm.get_session(session_id).is_some_and(|s| !s.mode.uses_mcp_config()) {
+        return Ok(Value::Null);
+    }
+    let canonical = crate::core::path_utils::normalize_path(&project_path);
+    let mcp_state = app.state::<McpManager>();
+    let enabled_discovered: Vec<_> = mcp_state
+        .get_project_servers(&canonical)
+        .into_iter()
+        .filter(|s| enabled_server_names.contains(&s.name))
+        .collect();
+
+    let content = mcp_config_writer::preview_session_mcp_config(
+        Path::new(&working_dir),
+        session_id,
+        &enabled_discovered,
+        &[],
+        &mcp_state.get_session_env(&canonical, session_id),
+        None,
+    )
+    .await?;
+
+    Ok(Value::String(content))
+}
+
 async fn cmd_remove_session_mcp_config(args: Value) -> Result<Value, String> {
     let working_dir = get_str(&args, "workingDir")?;
     let session_id = get_u32(&args, "sessionId")?;
@@ -456,6 +846,105 @@ async fn cmd_git_worktree_list(args: Value) -> Result<Value, String> {
     serde_json::to_value(worktrees).map_err(|e| e.to_string())
 }
 
+async fn cmd_git_log(args: Value) -> Result<Value, String> {
+    let repo_path = get_str(&args, "repoPath")?;
+    let limit = args
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .map_or(50, |v| v as usize);
+    let branch = args.get("branch").and_then(|v| v.as_str());
+    let git = crate::git::Git::new(&repo_path);
+    let commits = git.log(limit, branch).await.map_err(|e| e.to_string())?;
+    serde_json::to_value(commits).map_err(|e| e.to_string())
+}
+
+async fn cmd_git_diff(args: Value) -> Result<Value, String> {
+    let repo_path = get_str(&args, "repoPath")?;
+    let path = args.get("path").and_then(|v| v.as_str());
+    let staged = args.get("staged").and_then(|v| v.as_bool()).unwrap_or(false);
+    let max_bytes = args
+        .get("maxBytes")
+        .and_then(|v| v.as_u64())
+        .map(|v| v as usize);
+    let git = crate::git::Git::new(&repo_path);
+    let diff = git
+        .diff(path, staged, max_bytes)
+        .await
+        .map_err(|e| e.to_string())?;
+    serde_json::to_value(diff).map_err(|e| e.to_string())
+}
+
+/// Stages the given `paths`, or every change when `paths` is omitted.
+async fn cmd_git_stage(args: Value) -> Result<Value, String> {
+    let repo_path = get_str(&args, "repoPath")?;
+    let git = crate::git::Git::new(&repo_path);
+    match args.get("paths").and_then(|v| v.as_array()) {
+        Some(paths) => {
+            let paths: Vec<String> = paths
+                .iter()
+                .filter_map(|p| p.as_str().map(String::from))
+                .collect();
+            git.stage_files(&paths).await
+        }
+        None => git.stage_all().await,
+    }
+    .map_err(|e| e.to_string())?;
+    Ok(Value::Null)
+}
+
+async fn cmd_git_commit(args: Value) -> Result<Value, String> {
+    let repo_path = get_str(&args, "repoPath")?;
+    let message = get_str(&args, "message")?;
+    if message.trim().is_empty() {
+        return Err("Commit message must not be empty".to_string());
+    }
+    let git = crate::git::Git::new(&repo_path);
+    let hash = git.create_commit(&message).await.map_err(|e| e.to_string())?;
+    Ok(Value::String(hash))
+}
+
+/// Lists the repository's remotes; empty when none are configured.
+async fn cmd_git_remotes(args: Value) -> Result<Value, String> {
+    let repo_path = get_str(&args, "repoPath")?;
+    let git = crate::git::Git::new(&repo_path);
+    let remotes = git.list_remotes().await.map_err(|e| e.to_string())?;
+    serde_json::to_value(remotes).map_err(|e| e.to_string())
+}
+
+/// Fetches `remote` (or the upstream's remote) and returns the updated refs.
+async fn cmd_git_fetch(args: Value) -> Result<Value, String> {
+    let repo_path = get_str(&args, "repoPath")?;
+    let remote = args.get("remote").and_then(|v| v.as_str());
+    let git = crate::git::Git::new(&repo_path);
+    let summary = git.fetch(remote).await.map_err(|e| e.to_string())?;
+    serde_json::to_value(summary).map_err(|e| e.to_string())
+}
+
+async fn cmd_git_stash_save(args: Value) -> Result<Value, String> {
+    let repo_path = get_str(&args, "repoPath")?;
+    let message = args.get("message").and_then(|v| v.as_str());
+    let git = crate::git::Git::new(&repo_path);
+    git.stash_save(message).await.map_err(|e| e.to_string())?;
+    Ok(Value::Null)
+}
+
+async fn cmd_git_stash_list(args: Value) -> Result<Value, String> {
+    let repo_path = get_str(&args, "repoPath")?;
+    let git = crate::git::Git::new(&repo_path);
+    let stashes = git.stash_list().await.map_err(|e| e.to_string())?;
+    serde_json::to_value(stashes).map_err(|e| e.to_string())
+}
+
+/// Pops stash `index` (default 0). Conflicts come back as an error naming
+/// the conflicted files; the stash entry is kept in that case.
+async fn cmd_git_stash_pop(args: Value) -> Result<Value, String> {
+    let repo_path = get_str(&args, "repoPath")?;
+    let index = args.get("index").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+    let git = crate::git::Git::new(&repo_path);
+    git.stash_pop(index).await.map_err(|e| e.to_string())?;
+    Ok(Value::Null)
+}
+
 // ============================================================================
 // Plugin commands
 // ============================================================================
@@ -484,10 +973,13 @@ fn cmd_set_session_skills(app: &tauri::AppHandle, args: Value) -> Result<Value,
         args.get("enabled").cloned().unwrap_or(Value::Array(vec![])),
     )
     .map_err(|e| e.to_string())?;
+    let auto_enable_deps = args.get("autoEnableDeps").and_then(|v| v.as_bool()).unwrap_or(true);
     let canonical = crate::core::path_utils::normalize_path(&project_path);
     let pm = app.state::<PluginManager>();
-    pm.set_session_skills(&canonical, session_id, enabled);
-    Ok(Value::Null)
+    let effective = pm
+        .set_session_skills(&canonical, session_id, enabled, auto_enable_deps)
+        .map_err(|e| e.to_string())?;
+    serde_json::to_value(effective).map_err(|e| e.to_string())
 }
 
 // ============================================================================
@@ -590,11 +1082,123 @@ fn cmd_push_session_to_mobile(app: &tauri::AppHandle, args: Value) -> Result<Val
     Ok(Value::Null)
 }
 
+fn cmd_get_known_event_names(app: &tauri::AppHandle) -> Result<Value, String> {
+    let names = crate::commands::web_access::get_known_event_names(app.clone());
+    serde_json::to_value(names).map_err(|e| e.to_string())
+}
+
 fn cmd_get_session_output(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
     let session_id = get_u32(&args, "sessionId")?;
+    let strip = args.get("stripAnsi").and_then(|v| v.as_bool()).unwrap_or(false);
     let pm = app.state::<ProcessManager>();
     let buffer = pm.get_session_output(session_id).unwrap_or_default();
-    Ok(Value::String(buffer))
+    Ok(Value::String(if strip { strip_ansi(&buffer) } else { buffer }))
+}
+
+fn cmd_get_session_screen(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let rows = get_u16(&args, "rows")?;
+    let cols = get_u16(&args, "cols")?;
+    ScreenSnapshot::check_size(rows, cols)?;
+    let pm = app.state::<ProcessManager>();
+    let screen = pm.get_session_screen(session_id, rows, cols);
+    serde_json::to_value(screen).map_err(|e| e.to_string())
+}
+
+fn cmd_get_session_terminal_state(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let pm = app.state::<ProcessManager>();
+    let state = pm.get_terminal_state(session_id);
+    serde_json::to_value(state).map_err(|e| e.to_string())
+}
+
+/// Web clients may only name the file; it is created under the recordings
+/// directory and never overwrites an existing one.
+fn cmd_start_recording(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let file_name = get_str(&args, "path")?;
+    let dir = session_recorder::recordings_dir()
+        .ok_or_else(|| "No home directory to save recordings in".to_string())?;
+    let path = session_recorder::recording_path(&dir, &file_name)?;
+    let pm = app.state::<ProcessManager>();
+    pm.start_recording(session_id, &path)
+        .map_err(|e| e.to_string())?;
+    Ok(Value::Null)
+}
+
+fn cmd_stop_recording(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let pm = app.state::<ProcessManager>();
+    let path = pm.stop_recording(session_id).map_err(|e| e.to_string())?;
+    Ok(path.map_or(Value::Null, |p| Value::String(p.to_string_lossy().into_owned())))
+}
+
+fn cmd_get_session_output_since(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let offset = args
+        .get("offset")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Missing or invalid 'offset' argument".to_string())?;
+    let max_bytes = args
+        .get("maxBytes")
+        .and_then(|v| v.as_u64())
+        .map_or(DEFAULT_OUTPUT_CHUNK_BYTES, |v| v as usize);
+    let strip = args.get("stripAnsi").and_then(|v| v.as_bool()).unwrap_or(false);
+    let pm = app.state::<ProcessManager>();
+    let chunk = pm
+        .get_session_output_since(session_id, offset, max_bytes)
+        .map(|c| if strip { c.strip_ansi() } else { c });
+    serde_json::to_value(chunk).map_err(|e| e.to_string())
+}
+
+fn cmd_get_session_output_info(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let pm = app.state::<ProcessManager>();
+    let info = pm.get_session_output_info(session_id);
+    serde_json::to_value(info).map_err(|e| e.to_string())
+}
+
+fn cmd_set_scrollback_limit(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let limit = args
+        .get("limit")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Missing or invalid 'limit' argument".to_string())?;
+    let pm = app.state::<ProcessManager>();
+    let applied = pm
+        .set_scrollback_limit(session_id, limit as usize)
+        .map_err(|e| e.to_string())?;
+    Ok(serde_json::json!(applied))
+}
+
+fn cmd_set_scrollback_persistence(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let enabled = args
+        .get("enabled")
+        .and_then(|v| v.as_bool())
+        .ok_or_else(|| "Missing or invalid 'enabled' argument".to_string())?;
+    crate::commands::terminal::save_scrollback_persistence(app, enabled)?;
+    Ok(Value::Null)
+}
+
+fn cmd_get_scrollback_persistence(app: &tauri::AppHandle) -> Result<Value, String> {
+    let enabled = app.state::<ProcessManager>().scrollback_dir().is_some();
+    Ok(Value::Bool(enabled))
+}
+
+fn cmd_set_output_coalesce_window(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+    let window_ms = args
+        .get("windowMs")
+        .and_then(|v| v.as_u64())
+        .ok_or_else(|| "Missing or invalid 'windowMs' argument".to_string())?;
+    let pm = app.state::<ProcessManager>();
+    Ok(serde_json::json!(pm.set_output_coalesce_window(window_ms)))
+}
+
+fn cmd_set_session_output_paused(app: &tauri::AppHandle, args: Value, paused: bool) -> Result<Value, String> {
+    let session_id = get_u32(&args, "sessionId")?;
+    let pm = app.state::<ProcessManager>();
+    pm.set_output_paused(session_id, paused).map_err(|e| e.to_string())?;
+    Ok(Value::Null)
 }
 
 // ============================================================================
@@ -602,56 +1206,151 @@ fn cmd_get_session_output(app: &tauri::AppHandle, args: Value) -> Result<Value,
 // tauri-plugin-store JSON files that the desktop Zustand uses.
 // ============================================================================
 
-/// Resolve the app data dir (same location tauri-plugin-store uses).
-fn store_file_path(app: &tauri::AppHandle, file_name: &str) -> Result<std::path::PathBuf, String> {
-    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?;
-    Ok(dir.join(file_name))
+/// Opens a store through tauri-plugin-store, so mobile reads and writes share
+/// the desktop's in-memory copy instead of racing it on disk. The plugin
+/// resolves `file_name` against the app data dir.
+fn open_store<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    file_name: &str,
+) -> Result<Arc<tauri_plugin_store::Store<R>>, String> {
+    app.store(file_name)
+        .map_err(|e| format!("Failed to open store {}: {}", file_name, e))
 }
 
-/// Read a key from a store JSON file.  Returns `Value::Null` if missing.
-async fn cmd_store_get(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+/// Read a key from a store.  Returns `Value::Null` if missing.
+async fn cmd_store_get<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    args: Value,
+) -> Result<Value, String> {
     let file_name = get_str(&args, "fileName")?;
     let key = get_str(&args, "key")?;
 
-    let path = store_file_path(app, &file_name)?;
-    if !path.exists() {
-        return Ok(Value::Null);
-    }
-
-    let content = tokio::fs::read_to_string(&path)
-        .await
-        .map_err(|e| format!("Failed to read store file: {}", e))?;
-    let store: serde_json::Map<String, Value> =
-        serde_json::from_str(&content).map_err(|e| format!("Failed to parse store: {}", e))?;
-
-    Ok(store.get(&key).cloned().unwrap_or(Value::Null))
+    let store = open_store(app, &file_name)?;
+    Ok(store.get(&key).unwrap_or(Value::Null))
 }
 
-/// Write a key into a store JSON file (read-modify-write).
-async fn cmd_store_set(app: &tauri::AppHandle, args: Value) -> Result<Value, String> {
+/// Write a key into a store and save it.
+async fn cmd_store_set<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    args: Value,
+) -> Result<Value, String> {
     let file_name = get_str(&args, "fileName")?;
     let key = get_str(&args, "key")?;
     let value = args.get("value").cloned().ok_or("Missing 'value' argument")?;
 
-    let path = store_file_path(app, &file_name)?;
+    let store = open_store(app, &file_name)?;
+    store.set(key, value);
+    store
+        .save()
+        .map_err(|e| format!("Failed to save store {}: {}", file_name, e))?;
 
-    // Read existing store or start empty
-    let mut store: serde_json::Map<String, Value> = if path.exists() {
-        let content = tokio::fs::read_to_string(&path)
-            .await
-            .unwrap_or_else(|_| "{}".to_string());
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        serde_json::Map::new()
-    };
+    Ok(Value::Null)
+}
 
-    store.insert(key, value);
+/// Remove a key from a store. Missing keys are a no-op and save nothing.
+async fn cmd_store_delete<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    args: Value,
+) -> Result<Value, String> {
+    let file_name = get_str(&args, "fileName")?;
+    let key = get_str(&args, "key")?;
 
-    let content = serde_json::to_string_pretty(&store)
-        .map_err(|e| format!("Failed to serialize store: {}", e))?;
-    tokio::fs::write(&path, content)
-        .await
-        .map_err(|e| format!("Failed to write store file: {}", e))?;
+    let store = open_store(app, &file_name)?;
+    if store.delete(&key) {
+        store
+            .save()
+            .map_err(|e| format!("Failed to save store {}: {}", file_name, e))?;
+    }
 
     Ok(Value::Null)
 }
+
+/// List the keys in a store (empty if the file doesn't exist).
+async fn cmd_store_keys<R: tauri::Runtime>(
+    app: &tauri::AppHandle<R>,
+    args: Value,
+) -> Result<Value, String> {
+    let file_name = get_str(&args, "fileName")?;
+
+    let store = open_store(app, &file_name)?;
+    Ok(Value::Array(store.keys().into_iter().map(Value::String).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ping_echoes_the_nonce_with_the_server_time() {
+        let reply = cmd_ping(serde_json::json!({ "nonce": "abc" })).unwrap();
+        assert_eq!(reply["nonce"], "abc");
+        assert!(reply["serverTime"].as_i64().unwrap() > 0);
+
+        assert_eq!(cmd_ping(serde_json::json!({})).unwrap()["nonce"], Value::Null);
+    }
+
+    #[test]
+    fn backend_info_serializes_capabilities_for_clients() {
+        let info = crate::commands::terminal::backend_info(&ProcessManager::new());
+        let json = serde_json::to_value(info).unwrap();
+
+        assert_eq!(json["backendType"], serde_json::json!(BackendType::platform_default()));
+        assert!(json["availableBackends"]
+            .as_array()
+            .unwrap()
+            .contains(&Value::from("xterm-passthrough")));
+        let caps = json["capabilities"].as_object().unwrap();
+        let mut keys: Vec<&str> = caps.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        assert_eq!(
+            keys,
+            [
+                "backendName",
+                "enhancedState",
+                "kittyGraphics",
+                "mouseReporting",
+                "resizeGranularity",
+                "shellIntegration",
+                "textReflow",
+                "trueColor",
+            ]
+        );
+        assert_eq!(caps["resizeGranularity"], "cell");
+    }
+
+    #[tokio::test]
+    async fn mobile_store_writes_share_the_desktop_store() {
+        let app = tauri::test::mock_builder()
+            .plugin(tauri_plugin_store::Builder::default().build())
+            .build(tauri::test::mock_context(tauri::test::noop_assets()))
+            .unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("settings.json");
+        let args = |key: &str, value: Value| {
+            serde_json::json!({ "fileName": path.to_string_lossy(), "key": key, "value": value })
+        };
+
+        // The desktop has the store open with a change it hasn't saved yet
+        let desktop = app.store(&path).unwrap();
+        desktop.set("theme", "dark");
+
+        cmd_store_set(app.handle(), args("fontSize", Value::from(14))).await.unwrap();
+        assert_eq!(desktop.get("fontSize"), Some(Value::from(14)));
+        let saved: serde_json::Map<String, Value> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["theme"], "dark");
+        assert_eq!(saved["fontSize"], 14);
+
+        cmd_store_delete(app.handle(), args("theme", Value::Null)).await.unwrap();
+        cmd_store_delete(app.handle(), args("missing", Value::Null)).await.unwrap();
+        assert!(!desktop.has("theme"));
+        assert_eq!(
+            cmd_store_keys(app.handle(), args("", Value::Null)).await.unwrap(),
+            serde_json::json!(["fontSize"])
+        );
+        assert_eq!(
+            cmd_store_get(app.handle(), args("fontSize", Value::Null)).await.unwrap(),
+            Value::from(14)
+        );
+    }
+}
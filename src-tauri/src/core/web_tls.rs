@@ -0,0 +1,191 @@
+//! Optional TLS for the web access server.
+//!
+//! On first use a self-signed certificate is generated with `rcgen` and kept
+//! under `~/.chorus/tls/`, so browsers only ask the user to trust it once.
+//! The SHA-256 fingerprint is shown in the desktop UI so users can check it
+//! against what their browser reports before trusting it.
+
+use std::io;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::serve::Listener;
+use sha2::{Digest, Sha256};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::rustls::crypto::ring;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+const CERT_FILE: &str = "web-access-cert.pem";
+const KEY_FILE: &str = "web-access-key.pem";
+
+/// Connections that haven't finished the TLS handshake by then are dropped.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Certificate and key the web access server presents.
+pub struct TlsIdentity {
+    cert: CertificateDer<'static>,
+    key: PrivateKeyDer<'static>,
+    fingerprint: String,
+}
+
+impl TlsIdentity {
+    /// Load the certificate from `dir`, generating one on first use.
+    ///
+    /// The certificate covers `localhost` and the LAN IP at generation time.
+    /// Delete the files to regenerate it after the machine's IP changes.
+    pub fn load_or_create(dir: &Path) -> Result<Self, String> {
+        let cert_path = dir.join(CERT_FILE);
+        let key_path = dir.join(KEY_FILE);
+
+        if !cert_path.exists() || !key_path.exists() {
+            let mut names = vec!["localhost".to_string()];
+            if let Ok(ip) = local_ip_address::local_ip() {
+                names.push(ip.to_string());
+            }
+            let generated = rcgen::generate_simple_self_signed(names)
+                .map_err(|e| format!("Failed to generate TLS certificate: {}", e))?;
+
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            std::fs::write(&cert_path, generated.cert.pem())
+                .map_err(|e| format!("Failed to write {}: {}", cert_path.display(), e))?;
+            write_private(&key_path, generated.key_pair.serialize_pem().as_bytes())
+                .map_err(|e| format!("Failed to write {}: {}", key_path.display(), e))?;
+            log::info!("Generated web access TLS certificate in {}", dir.display());
+        }
+
+        let cert = CertificateDer::from_pem_file(&cert_path)
+            .map_err(|e| format!("Failed to read {}: {}", cert_path.display(), e))?;
+        let key = PrivateKeyDer::from_pem_file(&key_path)
+            .map_err(|e| format!("Failed to read {}: {}", key_path.display(), e))?;
+        let fingerprint = fingerprint(&cert);
+
+        Ok(Self { cert, key, fingerprint })
+    }
+
+    /// SHA-256 fingerprint of the certificate, as colon-separated hex.
+    pub fn fingerprint(&self) -> &str {
+        &self.fingerprint
+    }
+
+    fn acceptor(&self) -> Result<TlsAcceptor, String> {
+        let config = ServerConfig::builder_with_provider(Arc::new(ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .and_then(|b| b.with_no_client_auth().with_single_cert(vec![self.cert.clone()], self.key.clone_key()))
+            .map_err(|e| format!("Invalid TLS configuration: {}", e))?;
+        Ok(TlsAcceptor::from(Arc::new(config)))
+    }
+}
+
+/// Formats the SHA-256 of a DER certificate as `AB:CD:...`.
+fn fingerprint(cert: &CertificateDer<'_>) -> String {
+    Sha256::digest(cert.as_ref())
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+/// Writes a file readable only by the current user where the OS supports it.
+fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+        std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)?
+            .write_all(contents)
+    }
+    #[cfg(not(unix))]
+    {
+        std::fs::write(path, contents)
+    }
+}
+
+/// A listener that hands axum connections after the TLS handshake.
+///
+/// Handshakes run on their own tasks so one slow client can't hold up the
+/// accept loop.
+pub struct TlsListener {
+    local_addr: SocketAddr,
+    accepted: mpsc::Receiver<(TlsStream<TcpStream>, SocketAddr)>,
+}
+
+impl TlsListener {
+    pub fn new(listener: TcpListener, identity: &TlsIdentity) -> Result<Self, String> {
+        let acceptor = identity.acceptor()?;
+        let local_addr = listener.local_addr().map_err(|e| e.to_string())?;
+        let (tx, accepted) = mpsc::channel(32);
+
+        tokio::spawn(async move {
+            while !tx.is_closed() {
+                let (stream, addr) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        log::warn!("Web access accept failed: {}", e);
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                };
+                let acceptor = acceptor.clone();
+                let tx = tx.clone();
+                tokio::spawn(async move {
+                    match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                        Ok(Ok(tls)) => {
+                            let _ = tx.send((tls, addr)).await;
+                        }
+                        Ok(Err(e)) => log::debug!("TLS handshake with {} failed: {}", addr, e),
+                        Err(_) => log::debug!("TLS handshake with {} timed out", addr),
+                    }
+                });
+            }
+        });
+
+        Ok(Self { local_addr, accepted })
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.accepted.recv().await {
+            Some(conn) => conn,
+            // The accept loop only ends once this receiver is gone
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Ok(self.local_addr)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn certificate_is_generated_once_and_reused() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = TlsIdentity::load_or_create(dir.path()).unwrap();
+        let second = TlsIdentity::load_or_create(dir.path()).unwrap();
+
+        assert_eq!(first.fingerprint(), second.fingerprint());
+        // 32 bytes as two hex digits each, colon-separated
+        assert_eq!(first.fingerprint().len(), 32 * 3 - 1);
+        assert!(first.acceptor().is_ok());
+    }
+}
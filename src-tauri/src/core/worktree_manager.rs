@@ -1,7 +1,14 @@
 use std::collections::HashSet;
+use std::future::Future;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
 
+use dashmap::DashMap;
+use serde::Serialize;
+use futures_util::future::{abortable, AbortHandle};
 use sha2::{Digest, Sha256};
+use tokio::sync::Notify;
 
 use crate::git::{Git, GitError, WorktreeInfo};
 
@@ -51,13 +58,85 @@ fn sanitize_branch(branch: &str) -> String {
     sanitized
 }
 
+/// Upper bound on filesystem entries visited by one `disk_usage` walk, so a
+/// pathological worktree (e.g. a huge `node_modules`) can't stall the app.
+const MAX_DISK_USAGE_ENTRIES: usize = 500_000;
+
+/// Sums the sizes of regular files under `root`, visiting at most
+/// `max_entries` entries. Symlinks are never followed or counted, and the
+/// top-level `.git` (shared objects live in the main repo) is skipped.
+/// Returns the byte total and whether the walk stopped at the bound.
+fn dir_size(root: &Path, max_entries: usize) -> (u64, bool) {
+    let mut total = 0u64;
+    let mut visited = 0usize;
+    let mut stack = vec![root.to_path_buf()];
+
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            visited += 1;
+            if visited > max_entries {
+                return (total, true);
+            }
+            if dir == root && entry.file_name() == ".git" {
+                continue;
+            }
+            let Ok(meta) = entry.path().symlink_metadata() else {
+                continue;
+            };
+            if meta.is_dir() {
+                stack.push(entry.path());
+            } else if meta.is_file() {
+                total += meta.len();
+            }
+        }
+    }
+
+    (total, false)
+}
+
+/// Outcome of `WorktreeManager::prune_orphaned`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OrphanPruneResult {
+    /// Worktree paths that were removed.
+    pub removed: Vec<String>,
+    /// Orphaned worktrees left in place, with the reason (e.g. uncommitted changes).
+    pub skipped: Vec<SkippedWorktree>,
+}
+
+/// An orphaned worktree `prune_orphaned` did not remove.
+#[derive(Debug, Clone, Serialize)]
+pub struct SkippedWorktree {
+    pub path: String,
+    pub reason: String,
+}
+
+/// Bookkeeping for an in-flight, cancellable worktree preparation.
+struct PendingPrepare {
+    abort: AbortHandle,
+    repo_path: PathBuf,
+    wt_path: PathBuf,
+    /// Whether the worktree directory already existed when the prepare started.
+    /// Pre-existing worktrees are never removed on cancel.
+    preexisting: bool,
+    /// Signalled once the prepare future has stopped running.
+    finished: Arc<Notify>,
+}
+
 /// Manages Chorus-owned git worktrees under a deterministic, repo-specific
 /// directory inside XDG data dirs.
 ///
 /// Worktree paths are derived from a SHA-256 hash of the canonical repo path
 /// (truncated to 16 hex chars) so that different repos never collide, and a
 /// sanitized branch name so each branch gets its own subdirectory.
-pub struct WorktreeManager;
+///
+/// Also tracks in-flight preparations by a caller-supplied handle so they
+/// can be aborted via `cancel_prepare`.
+pub struct WorktreeManager {
+    pending: DashMap<String, PendingPrepare>,
+}
 
 impl Default for WorktreeManager {
     fn default() -> Self {
@@ -66,10 +145,12 @@ impl Default for WorktreeManager {
 }
 
 impl WorktreeManager {
-    /// Creates a new stateless manager. All path computation is pure and
-    /// deterministic from the repo path and branch name.
+    /// Creates a new manager with no in-flight preparations. All path
+    /// computation is pure and deterministic from the repo path and branch name.
     pub fn new() -> Self {
-        Self
+        Self {
+            pending: DashMap::new(),
+        }
     }
 
     /// Compute the worktree path for a given repo + branch
@@ -149,6 +230,108 @@ impl WorktreeManager {
             .collect())
     }
 
+    /// Returns the bytes used by files in a worktree's checkout.
+    ///
+    /// The worktree's `.git` entry is skipped because its objects are shared
+    /// with the main repo, and symlinks are ignored to avoid double-counting
+    /// or loops. The walk is bounded; if the bound is hit the partial total
+    /// is returned. A missing path reports 0.
+    pub async fn disk_usage(&self, worktree_path: &Path) -> u64 {
+        let root = worktree_path.to_path_buf();
+        let (bytes, truncated) =
+            tokio::task::spawn_blocking(move || dir_size(&root, MAX_DISK_USAGE_ENTRIES))
+                .await
+                .unwrap_or((0, false));
+        if truncated {
+            log::warn!(
+                "Disk usage walk of {} stopped after {} entries",
+                worktree_path.display(),
+                MAX_DISK_USAGE_ENTRIES
+            );
+        }
+        bytes
+    }
+
+    /// Lists Chorus-managed worktrees that no known session references.
+    ///
+    /// Cross-references `list_managed` against `known_session_paths` after
+    /// normalizing both sides. Worktrees created outside Chorus are never
+    /// reported, nor are worktrees a `run_cancellable` preparation is still
+    /// creating, since no session references them yet. Returns an empty list
+    /// if the repository (or its `.git`) no longer exists, since there is
+    /// nothing git can clean up there.
+    pub async fn list_orphaned(
+        &self,
+        repo_path: &Path,
+        known_session_paths: &[PathBuf],
+    ) -> Result<Vec<WorktreeInfo>, GitError> {
+        if !tokio::fs::try_exists(repo_path.join(".git")).await.unwrap_or(false) {
+            log::info!(
+                "Skipping orphan scan: no git repository at {}",
+                repo_path.display()
+            );
+            return Ok(Vec::new());
+        }
+
+        let in_flight: Vec<PathBuf> = self.pending.iter().map(|p| p.wt_path.clone()).collect();
+        let known: HashSet<PathBuf> = known_session_paths
+            .iter()
+            .chain(&in_flight)
+            .map(|p| crate::core::path_utils::normalize_path_buf(p))
+            .collect();
+
+        Ok(self
+            .list_managed(repo_path)
+            .await?
+            .into_iter()
+            .filter(|wt| {
+                !known.contains(&crate::core::path_utils::normalize_path_buf(Path::new(&wt.path)))
+            })
+            .collect())
+    }
+
+    /// Removes the worktrees `list_orphaned` reports and returns what was reclaimed.
+    ///
+    /// Removal is not forced, so git refuses worktrees with uncommitted
+    /// changes; those are returned in `skipped` rather than failing the whole
+    /// prune. Stale refs whose directories are already gone are cleared by
+    /// `git worktree prune` first.
+    pub async fn prune_orphaned(
+        &self,
+        repo_path: &Path,
+        known_session_paths: &[PathBuf],
+    ) -> Result<OrphanPruneResult, GitError> {
+        let mut result = OrphanPruneResult::default();
+        if !tokio::fs::try_exists(repo_path.join(".git")).await.unwrap_or(false) {
+            return Ok(result);
+        }
+
+        let git = Git::new(repo_path);
+        git.worktree_prune().await?;
+
+        for wt in self.list_orphaned(repo_path, known_session_paths).await? {
+            let wt_path = PathBuf::from(&wt.path);
+            match git.worktree_remove(&wt_path, false).await {
+                Ok(()) => {
+                    log::info!("Removed orphaned worktree {}", wt.path);
+                    if let Some(parent) = wt_path.parent() {
+                        let _ = tokio::fs::remove_dir(parent).await; // only succeeds if empty
+                    }
+                    result.removed.push(wt.path);
+                }
+                Err(e) => {
+                    log::warn!("Keeping orphaned worktree {}: {}", wt.path, e);
+                    result.skipped.push(SkippedWorktree {
+                        path: wt.path,
+                        reason: e.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
     /// Prunes stale git worktree refs and removes orphaned directories.
     ///
     /// First runs `git worktree prune`, then scans the managed directory for
@@ -206,4 +389,216 @@ impl WorktreeManager {
 
         Ok(())
     }
+
+    /// Runs a worktree preparation for `branch` so that it can be aborted with
+    /// `cancel_prepare(handle)`.
+    ///
+    /// Returns `None` if the preparation was cancelled before completing.
+    /// Dropping the future kills any in-flight git subprocess because all
+    /// git commands are spawned with `kill_on_drop(true)`.
+    pub async fn run_cancellable<F, T>(
+        &self,
+        handle: String,
+        repo_path: &Path,
+        branch: &str,
+        fut: F,
+    ) -> Option<T>
+    where
+        F: Future<Output = T>,
+    {
+        let wt_path = self.worktree_path(repo_path, branch).await;
+        let preexisting = tokio::fs::try_exists(&wt_path).await.unwrap_or(true);
+        let finished = Arc::new(Notify::new());
+        let (fut, abort) = abortable(fut);
+
+        self.pending.insert(
+            handle.clone(),
+            PendingPrepare {
+                abort,
+                repo_path: repo_path.to_path_buf(),
+                wt_path,
+                preexisting,
+                finished: finished.clone(),
+            },
+        );
+
+        let result = fut.await;
+
+        // Only drop our own entry; a later prepare may have reused the handle
+        self.pending
+            .remove_if(&handle, |_, p| Arc::ptr_eq(&p.finished, &finished));
+        finished.notify_one();
+
+        result.ok()
+    }
+
+    /// Aborts the preparation registered under `handle` and removes any
+    /// partially-created worktree.
+    ///
+    /// Returns `None` if no preparation with this handle is in flight,
+    /// otherwise whether cleanup succeeded. Branch creation and main-repo
+    /// checkouts performed before the abort are left as-is.
+    pub async fn cancel_prepare(&self, handle: &str) -> Option<bool> {
+        let (_, pending) = self.pending.remove(handle)?;
+        pending.abort.abort();
+
+        // Wait for the prepare future to actually stop before touching disk
+        if tokio::time::timeout(Duration::from_secs(5), pending.finished.notified())
+            .await
+            .is_err()
+        {
+            log::warn!("Worktree prepare {} did not stop within 5s", handle);
+        }
+
+        if pending.preexisting {
+            return Some(true);
+        }
+
+        Some(self.cleanup_partial(&pending.repo_path, &pending.wt_path).await)
+    }
+
+    /// Removes a worktree left behind by an aborted preparation. Handles both
+    /// the case where git registered the worktree and the case where only the
+    /// directory was created.
+    async fn cleanup_partial(&self, repo_path: &Path, wt_path: &Path) -> bool {
+        let git = Git::new(repo_path);
+        let target = crate::core::path_utils::normalize_path_buf(wt_path);
+        let registered = git
+            .worktree_list()
+            .await
+            .map(|list| {
+                list.iter().any(|wt| {
+                    crate::core::path_utils::normalize_path_buf(Path::new(&wt.path)) == target
+                })
+            })
+            .unwrap_or(false);
+
+        if registered {
+            return match self.remove(repo_path, wt_path).await {
+                Ok(()) => true,
+                Err(e) => {
+                    log::warn!("Failed to remove cancelled worktree {}: {}", wt_path.display(), e);
+                    false
+                }
+            };
+        }
+
+        if tokio::fs::try_exists(wt_path).await.unwrap_or(false) {
+            if let Err(e) = tokio::fs::remove_dir_all(wt_path).await {
+                log::warn!("Failed to delete cancelled worktree dir {}: {}", wt_path.display(), e);
+                return false;
+            }
+        }
+        if let Some(parent) = wt_path.parent() {
+            let _ = tokio::fs::remove_dir(parent).await; // only succeeds if empty
+        }
+
+        git.worktree_prune().await.is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dir_size_skips_git_and_symlinks() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(root.join("a.txt"), vec![0u8; 100]).unwrap();
+        std::fs::create_dir_all(root.join("src/nested")).unwrap();
+        std::fs::write(root.join("src/nested/b.rs"), vec![0u8; 50]).unwrap();
+        // Worktrees have a `.git` file pointing at the main repo.
+        std::fs::write(root.join(".git"), vec![0u8; 1000]).unwrap();
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(root.join("a.txt"), root.join("link.txt")).unwrap();
+            std::os::unix::fs::symlink(root, root.join("src/loop")).unwrap();
+        }
+
+        assert_eq!(dir_size(root, MAX_DISK_USAGE_ENTRIES), (150, false));
+    }
+
+    #[test]
+    fn dir_size_stops_at_entry_bound() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..10 {
+            std::fs::write(dir.path().join(format!("f{i}")), b"x").unwrap();
+        }
+
+        let (bytes, truncated) = dir_size(dir.path(), 4);
+        assert!(truncated);
+        assert_eq!(bytes, 4);
+    }
+
+    /// Points the managed worktree directory at a temp dir shared by every
+    /// test in this module, so tests never touch the real data dir.
+    fn isolate_data_dir() {
+        static DATA_DIR: std::sync::OnceLock<tempfile::TempDir> = std::sync::OnceLock::new();
+        let dir = DATA_DIR.get_or_init(|| tempfile::tempdir().unwrap());
+        std::env::set_var("XDG_DATA_HOME", dir.path().canonicalize().unwrap());
+    }
+
+    /// A repo with one commit and a `feature` branch that isn't checked out.
+    async fn temp_repo() -> (tempfile::TempDir, PathBuf) {
+        isolate_data_dir();
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path().canonicalize().unwrap();
+        let git = Git::new(&repo);
+        git.run(&["init", "-q"]).await.unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        git.run(&["config", "user.email", "test@example.com"]).await.unwrap();
+        std::fs::write(repo.join("a.txt"), "hello\n").unwrap();
+        git.run(&["add", "a.txt"]).await.unwrap();
+        git.run(&["commit", "-q", "-m", "init"]).await.unwrap();
+        git.run(&["branch", "feature"]).await.unwrap();
+        (dir, repo)
+    }
+
+    #[tokio::test]
+    async fn prune_orphaned_removes_unreferenced_worktrees() {
+        let (_dir, repo) = temp_repo().await;
+        let manager = WorktreeManager::new();
+        let wt_path = manager.create("feature", &repo).await.unwrap();
+
+        let known = [wt_path.clone()];
+        assert!(manager.list_orphaned(&repo, &known).await.unwrap().is_empty());
+
+        let orphans = manager.list_orphaned(&repo, &[]).await.unwrap();
+        assert_eq!(orphans.len(), 1);
+
+        let result = manager.prune_orphaned(&repo, &[]).await.unwrap();
+        assert_eq!(result.removed.len(), 1);
+        assert!(result.skipped.is_empty());
+        assert!(!wt_path.exists());
+    }
+
+    #[tokio::test]
+    async fn prune_orphaned_keeps_worktrees_still_being_prepared() {
+        let (_dir, repo) = temp_repo().await;
+        let manager = WorktreeManager::new();
+        let (created_tx, created_rx) = tokio::sync::oneshot::channel();
+        let (release_tx, release_rx) = tokio::sync::oneshot::channel::<()>();
+
+        let prepare = manager.run_cancellable("h1".to_string(), &repo, "feature", async {
+            let wt_path = manager.create("feature", &repo).await.unwrap();
+            created_tx.send(wt_path.clone()).unwrap();
+            let _ = release_rx.await;
+            wt_path
+        });
+        let prune = async {
+            let wt_path = created_rx.await.unwrap();
+            let result = manager.prune_orphaned(&repo, &[]).await.unwrap();
+            release_tx.send(()).unwrap();
+            (wt_path, result)
+        };
+
+        let (prepared, (wt_path, result)) = tokio::join!(prepare, prune);
+        assert_eq!(prepared, Some(wt_path.clone()));
+        assert!(result.removed.is_empty(), "{:?}", result.removed);
+        assert!(wt_path.exists());
+
+        // Once the preparation has finished the worktree is an ordinary orphan
+        assert_eq!(manager.list_orphaned(&repo, &[]).await.unwrap().len(), 1);
+    }
 }
@@ -448,13 +448,7 @@ impl TerminalBackend for XtermPassthroughBackend {
     }
 
     fn capabilities(&self) -> BackendCapabilities {
-        BackendCapabilities {
-            enhanced_state: false,
-            text_reflow: false,
-            kitty_graphics: false,
-            shell_integration: false,
-            backend_name: "xterm-passthrough",
-        }
+        Self::backend_type().capabilities()
     }
 }
 
@@ -50,6 +50,22 @@ pub enum GitError {
     /// The specified worktree path does not exist in git's worktree list.
     #[error("worktree not found: {0}")]
     WorktreeNotFound(String),
+
+    /// A commit was requested but the index has no staged changes.
+    #[error("nothing to commit: no changes are staged")]
+    NothingToCommit,
+
+    /// A stash was requested but the working tree has no changes.
+    #[error("nothing to stash: the working tree is clean")]
+    NothingToStash,
+
+    /// Popping a stash left merge conflicts; the stash entry was kept.
+    #[error("stash applied with conflicts in {}; the stash entry was kept", paths.join(", "))]
+    StashConflict { paths: Vec<String> },
+
+    /// Git has no author identity for this repository.
+    #[error("git user.name and user.email are not configured; set them with `git config --global user.name \"Your Name\"` and `git config --global user.email you@example.com`")]
+    IdentityNotConfigured,
 }
 
 /// Serializes the error as its `Display` string so the frontend receives a
@@ -3,5 +3,5 @@ pub mod ops;
 pub mod runner;
 
 pub use error::GitError;
-pub use ops::{BranchInfo, CommitInfo, FileChange, FileChangeStatus, GitUserConfig, RemoteInfo, WorkingChange, WorktreeInfo};
+pub use ops::{BranchInfo, CommitInfo, DiffOutput, FetchSummary, FileChange, FileChangeStatus, GitUserConfig, RemoteInfo, StashEntry, WorkingChange, WorktreeInfo};
 pub use runner::Git;
@@ -1,9 +1,17 @@
 use serde::Serialize;
 use std::path::Path;
+use std::time::Duration;
 
 use super::error::GitError;
 use super::runner::Git;
 
+/// Upper bound on commits returned by `Git::log`, so history queries sent
+/// over the web tunnel stay small even for huge repositories.
+pub const MAX_LOG_LIMIT: usize = 500;
+
+/// How long `Git::fetch` may take before it is abandoned.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(60);
+
 /// A local or remote branch returned by `list_branches`.
 ///
 /// Remote branches have `is_remote = true` and names like `origin/main`.
@@ -44,6 +52,17 @@ pub struct CommitInfo {
     pub summary: String,
 }
 
+/// Unified diff text returned by `Git::diff`.
+///
+/// When a `max_bytes` cap was given and the diff exceeded it, `diff` is cut
+/// at a line boundary and `truncated` is set; `total_bytes` is the full size.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffOutput {
+    pub diff: String,
+    pub truncated: bool,
+    pub total_bytes: usize,
+}
+
 /// Represents a file changed in a commit.
 #[derive(Debug, Clone, Serialize)]
 pub struct FileChange {
@@ -94,6 +113,56 @@ pub struct RemoteInfo {
     pub url: String,
 }
 
+/// A stash entry returned by `Git::stash_list`, newest first.
+///
+/// `branch` is the branch the stash was made on, or `None` if HEAD was
+/// detached. `message` is the user's message, or git's `<hash> <subject>`
+/// default when none was given.
+#[derive(Debug, Clone, Serialize)]
+pub struct StashEntry {
+    pub index: usize,
+    pub branch: Option<String>,
+    pub message: String,
+}
+
+/// How a ref changed during `Git::fetch`, from the flag column of git's
+/// fetch report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FetchRefStatus {
+    /// A ref that didn't exist locally before (`*`).
+    New,
+    /// A fast-forward update (` `).
+    Updated,
+    /// A forced, non-fast-forward update (`+`).
+    Forced,
+    /// A tag that moved (`t`).
+    Tag,
+    /// A ref removed by pruning (`-`).
+    Pruned,
+    /// An update git refused to apply (`!`).
+    Rejected,
+}
+
+/// A single ref updated by `Git::fetch`.
+///
+/// `summary` is git's own description, e.g. `a863abb..e890325` or
+/// `[new branch]`. `remote_ref` is `(none)` for pruned refs.
+#[derive(Debug, Clone, Serialize)]
+pub struct FetchedRef {
+    pub status: FetchRefStatus,
+    pub summary: String,
+    pub remote_ref: String,
+    pub local_ref: String,
+}
+
+/// Result of `Git::fetch`: every ref that changed. Empty when the local
+/// copy was already up to date or the repository has no remotes.
+#[derive(Debug, Clone, Serialize)]
+pub struct FetchSummary {
+    pub updated_refs: Vec<FetchedRef>,
+}
+
 impl Git {
     /// Lists all local and remote branches, excluding `HEAD` pointer entries.
     ///
@@ -285,11 +354,33 @@ impl Git {
         Ok(())
     }
 
+    /// Stages every change in the working tree, including untracked and
+    /// deleted files (`git add -A`).
+    pub async fn stage_all(&self) -> Result<(), GitError> {
+        self.run(&["add", "-A"]).await?;
+        Ok(())
+    }
+
     /// Creates a commit with the staged changes.
     ///
-    /// Returns the hash of the created commit.
+    /// Returns the hash of the created commit. Fails with `NothingToCommit`
+    /// if nothing is staged, and with `IdentityNotConfigured` if git can't
+    /// resolve user.name/user.email from its normal config lookup.
     pub async fn create_commit(&self, message: &str) -> Result<String, GitError> {
-        self.run(&["commit", "-m", message]).await?;
+        // `diff --cached --quiet` exits 1 when the index differs from HEAD
+        match self.run(&["diff", "--cached", "--quiet"]).await {
+            Ok(_) => return Err(GitError::NothingToCommit),
+            Err(GitError::CommandFailed { code: 1, .. }) => {}
+            Err(e) => return Err(e),
+        }
+
+        match self.run(&["commit", "-m", message]).await {
+            Ok(_) => {}
+            Err(GitError::CommandFailed { ref stderr, .. }) if is_identity_error(stderr) => {
+                return Err(GitError::IdentityNotConfigured);
+            }
+            Err(e) => return Err(e),
+        }
         // Get the hash of the commit we just created
         let output = self.run(&["rev-parse", "HEAD"]).await?;
         Ok(output.trimmed().to_string())
@@ -495,6 +586,116 @@ impl Git {
         Ok(commits)
     }
 
+    /// Returns up to `limit` commits reachable from `branch` (or HEAD), newest first.
+    ///
+    /// `limit` is capped at `MAX_LOG_LIMIT`. Fields are separated by the ASCII
+    /// unit separator so author names and subjects may contain any printable
+    /// character. A repository with no commits yet yields an empty list.
+    pub async fn log(
+        &self,
+        limit: usize,
+        branch: Option<&str>,
+    ) -> Result<Vec<CommitInfo>, GitError> {
+        if let Some(b) = branch {
+            if b.is_empty() || b.starts_with('-') {
+                return Err(GitError::ParseError {
+                    message: format!("invalid branch name: {:?}", b),
+                });
+            }
+        }
+
+        let count_str = format!("-{}", limit.clamp(1, MAX_LOG_LIMIT));
+        let mut args = vec![
+            "log",
+            "--format=%H%x1f%h%x1f%P%x1f%an%x1f%ae%x1f%at%x1f%s",
+            &count_str,
+        ];
+        if let Some(b) = branch {
+            args.push(b);
+        }
+        args.push("--");
+
+        let output = match self.run(&args).await {
+            Ok(output) => output,
+            // Git returns: "fatal: your current branch 'main' does not have any commits yet"
+            Err(GitError::CommandFailed { stderr, .. })
+                if branch.is_none() && stderr.contains("does not have any commits") =>
+            {
+                return Ok(Vec::new());
+            }
+            Err(e) => return Err(e),
+        };
+
+        let mut commits = Vec::new();
+        for line in output.lines() {
+            let parts: Vec<&str> = line.splitn(7, '\x1f').collect();
+            if parts.len() < 7 {
+                continue;
+            }
+
+            commits.push(CommitInfo {
+                hash: parts[0].to_string(),
+                short_hash: parts[1].to_string(),
+                parent_hashes: parts[2].split_whitespace().map(String::from).collect(),
+                author_name: parts[3].to_string(),
+                author_email: parts[4].to_string(),
+                timestamp: parts[5].parse::<i64>().unwrap_or(0),
+                summary: parts[6].to_string(),
+            });
+        }
+
+        Ok(commits)
+    }
+
+    /// Returns the unified diff of the working tree (or the index when
+    /// `staged`), optionally limited to one `path` relative to the repo.
+    ///
+    /// Binary files show git's "Binary files ... differ" line rather than
+    /// their contents. External diff drivers and color are disabled so the
+    /// output is plain text. If `max_bytes` is given, longer diffs are cut at
+    /// the last line boundary that fits.
+    pub async fn diff(
+        &self,
+        path: Option<&str>,
+        staged: bool,
+        max_bytes: Option<usize>,
+    ) -> Result<DiffOutput, GitError> {
+        let mut args = vec!["diff", "--no-color", "--no-ext-diff", "--no-textconv"];
+        if staged {
+            args.push("--cached");
+        }
+        args.push("--");
+        if let Some(p) = path {
+            args.push(p);
+        }
+
+        let output = self.run(&args).await?;
+        let mut diff = output.stdout;
+        let total_bytes = diff.len();
+
+        let truncated = match max_bytes {
+            Some(max) if total_bytes > max => {
+                let mut cut = max;
+                while !diff.is_char_boundary(cut) {
+                    cut -= 1;
+                }
+                // Prefer ending on a complete line
+                if let Some(nl) = diff[..cut].rfind('\n') {
+                    cut = nl + 1;
+                }
+                diff.truncate(cut);
+                true
+            }
+            _ => false,
+        };
+
+        Ok(DiffOutput {
+            diff,
+            truncated,
+            total_bytes,
+        })
+    }
+
     /// Checks out a branch by name.
     ///
     /// For local branches, uses `git checkout <name>`.
@@ -728,6 +929,31 @@ impl Git {
         }
     }
 
+    /// Fetches from `remote`, or from the current branch's upstream remote
+    /// (git's default) when `None`, and reports the refs that changed.
+    ///
+    /// Gives up after `FETCH_TIMEOUT` so an unreachable remote can't tie up
+    /// the caller. A repository without remotes fetches nothing. A remote
+    /// that is empty or starts with `-` is rejected, since git would parse it
+    /// as an option (e.g. `--upload-pack=<cmd>`).
+    pub async fn fetch(&self, remote: Option<&str>) -> Result<FetchSummary, GitError> {
+        let mut args = vec!["fetch"];
+        if let Some(r) = remote {
+            if r.is_empty() || r.starts_with('-') {
+                return Err(GitError::ParseError {
+                    message: format!("invalid remote name: {:?}", r),
+                });
+            }
+            args.push(r);
+        }
+
+        // git writes the ref report to stderr
+        let output = self.run_with_timeout(&args, FETCH_TIMEOUT).await?;
+        Ok(FetchSummary {
+            updated_refs: parse_fetch_output(&output.stderr),
+        })
+    }
+
     /// Updates the URL of an existing remote.
     pub async fn set_remote_url(&self, name: &str, url: &str) -> Result<(), GitError> {
         self.run(&["remote", "set-url", name, url]).await?;
@@ -770,4 +996,386 @@ impl Git {
         self.run(&["checkout", "--detach"]).await?;
         Ok(())
     }
+
+    /// Stashes all changes, including untracked files, so a worktree can be
+    /// cleaned up without losing work. Fails with `NothingToStash` if the
+    /// working tree is clean.
+    pub async fn stash_save(&self, message: Option<&str>) -> Result<(), GitError> {
+        let mut args = vec!["stash", "push", "--include-untracked"];
+        if let Some(m) = message {
+            args.push("-m");
+            args.push(m);
+        }
+
+        let output = self.run(&args).await?;
+        // git exits 0 even when there was nothing to stash
+        if output.trimmed().starts_with("No local changes to save") {
+            return Err(GitError::NothingToStash);
+        }
+        Ok(())
+    }
+
+    /// Lists stash entries, newest (index 0) first.
+    pub async fn stash_list(&self) -> Result<Vec<StashEntry>, GitError> {
+        let output = self.run(&["stash", "list", "--format=%gd%x00%gs"]).await?;
+        Ok(output.lines().into_iter().filter_map(parse_stash_entry).collect())
+    }
+
+    /// Applies stash `index` and drops it.
+    ///
+    /// If the changes conflict with the working tree, the conflicted files
+    /// are left with merge markers, the stash is kept, and `StashConflict`
+    /// lists the affected paths.
+    pub async fn stash_pop(&self, index: usize) -> Result<(), GitError> {
+        let stash_ref = format!("stash@{{{}}}", index);
+        match self.run(&["stash", "pop", &stash_ref]).await {
+            Ok(_) => Ok(()),
+            Err(e @ GitError::CommandFailed { .. }) => {
+                let conflicted = self
+                    .run(&["diff", "--name-only", "--diff-filter=U"])
+                    .await?;
+                let paths: Vec<String> = conflicted.lines().iter().map(|l| l.to_string()).collect();
+                if paths.is_empty() {
+                    Err(e)
+                } else {
+                    Err(GitError::StashConflict { paths })
+                }
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Parses a `%gd%x00%gs` stash line, e.g. `stash@{1}\0On main: message` or
+/// `stash@{0}\0WIP on main: abc1234 subject`.
+fn parse_stash_entry(line: &str) -> Option<StashEntry> {
+    let (selector, subject) = line.split_once('\0')?;
+    let index = selector
+        .strip_prefix("stash@{")?
+        .strip_suffix('}')?
+        .parse()
+        .ok()?;
+
+    let rest = subject
+        .strip_prefix("WIP on ")
+        .or_else(|| subject.strip_prefix("On "))
+        .unwrap_or(subject);
+    let (branch, message) = rest.split_once(": ").unwrap_or(("", rest));
+    let branch = match branch {
+        "" | "(no branch)" => None,
+        b => Some(b.to_string()),
+    };
+
+    Some(StashEntry {
+        index,
+        branch,
+        message: message.to_string(),
+    })
+}
+
+/// Parses the ref lines of `git fetch` stderr, e.g.
+/// ` + e890325...a863abb master     -> origin/master  (forced update)`.
+/// The `From <url>` headers and anything else unrecognized are skipped.
+fn parse_fetch_output(stderr: &str) -> Vec<FetchedRef> {
+    stderr
+        .lines()
+        .filter_map(|line| {
+            let mut chars = line.strip_prefix(' ')?.chars();
+            let status = match chars.next()? {
+                '*' => FetchRefStatus::New,
+                ' ' => FetchRefStatus::Updated,
+                '+' => FetchRefStatus::Forced,
+                't' => FetchRefStatus::Tag,
+                '-' => FetchRefStatus::Pruned,
+                '!' => FetchRefStatus::Rejected,
+                _ => return None,
+            };
+            let rest = chars.as_str().trim_start();
+
+            // The summary is either a bracketed phrase or a single token
+            let (summary, rest) = if rest.starts_with('[') {
+                let end = rest.find(']')? + 1;
+                rest.split_at(end)
+            } else {
+                rest.split_at(rest.find(' ')?)
+            };
+            let (remote_ref, local) = rest.split_once(" -> ")?;
+            let local_ref = local.split_whitespace().next()?;
+
+            Some(FetchedRef {
+                status,
+                summary: summary.to_string(),
+                remote_ref: remote_ref.trim().to_string(),
+                local_ref: local_ref.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Whether `git commit` stderr reports a missing author identity.
+fn is_identity_error(stderr: &str) -> bool {
+    stderr.contains("Please tell me who you are")
+        || stderr.contains("unable to auto-detect email address")
+        || stderr.contains("empty ident name")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn temp_repo() -> (tempfile::TempDir, Git) {
+        let dir = tempfile::tempdir().unwrap();
+        let git = Git::new(dir.path());
+        git.run(&["init", "-q"]).await.unwrap();
+        git.run(&["config", "user.name", "Test"]).await.unwrap();
+        git.run(&["config", "user.email", "test@example.com"]).await.unwrap();
+        (dir, git)
+    }
+
+    #[tokio::test]
+    async fn commit_fails_clearly_when_nothing_staged() {
+        let (_dir, git) = temp_repo().await;
+        assert!(git.log(10, None).await.unwrap().is_empty());
+
+        let err = git.create_commit("empty").await.unwrap_err();
+        assert!(matches!(err, GitError::NothingToCommit), "got {err:?}");
+    }
+
+    #[tokio::test]
+    async fn stage_all_then_commit_returns_new_hash() {
+        let (dir, git) = temp_repo().await;
+        std::fs::write(dir.path().join("a.txt"), "hello\n").unwrap();
+
+        git.stage_all().await.unwrap();
+        let hash = git.create_commit("Add a.txt").await.unwrap();
+
+        let head = git.run(&["rev-parse", "HEAD"]).await.unwrap();
+        assert_eq!(hash, head.trimmed());
+        assert_eq!(hash.len(), 40);
+        let log = git.log(10, None).await.unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].summary, "Add a.txt");
+
+        // Nothing left to commit afterwards
+        assert!(matches!(
+            git.create_commit("again").await,
+            Err(GitError::NothingToCommit)
+        ));
+    }
+
+    #[tokio::test]
+    async fn repo_without_remotes_lists_and_fetches_nothing() {
+        let (_dir, git) = temp_repo().await;
+        assert!(git.list_remotes().await.unwrap().is_empty());
+        assert!(git.fetch(None).await.unwrap().updated_refs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn fetch_rejects_option_like_remotes() {
+        let (dir, git) = temp_repo().await;
+        let marker = dir.path().join("pwned");
+        let upload_pack = format!("--upload-pack=touch {}", marker.display());
+        for remote in ["", "-v", upload_pack.as_str()] {
+            assert!(matches!(
+                git.fetch(Some(remote)).await,
+                Err(GitError::ParseError { .. })
+            ));
+        }
+        assert!(!marker.exists());
+    }
+
+    #[tokio::test]
+    async fn fetch_reports_updated_refs() {
+        let (upstream_dir, upstream) = temp_repo().await;
+        std::fs::write(upstream_dir.path().join("a.txt"), "one\n").unwrap();
+        upstream.stage_all().await.unwrap();
+        upstream.create_commit("one").await.unwrap();
+
+        let clone_dir = tempfile::tempdir().unwrap();
+        let git = Git::new(clone_dir.path());
+        git.run(&["clone", "-q", &upstream_dir.path().to_string_lossy(), "."])
+            .await
+            .unwrap();
+        let remotes = git.list_remotes().await.unwrap();
+        assert_eq!(remotes.len(), 1);
+        assert_eq!(remotes[0].name, "origin");
+
+        upstream.run(&["branch", "feature"]).await.unwrap();
+        let summary = git.fetch(Some("origin")).await.unwrap();
+        assert_eq!(summary.updated_refs.len(), 1);
+        let fetched = &summary.updated_refs[0];
+        assert_eq!(fetched.status, FetchRefStatus::New);
+        assert_eq!(fetched.local_ref, "origin/feature");
+
+        // Nothing new the second time
+        assert!(git.fetch(None).await.unwrap().updated_refs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn log_returns_structured_commits_newest_first() {
+        let (dir, git) = temp_repo().await;
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        git.stage_all().await.unwrap();
+        let first = git.create_commit("First: a | b").await.unwrap();
+        git.run(&["checkout", "-q", "-b", "feature"]).await.unwrap();
+        std::fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        git.stage_all().await.unwrap();
+        let second = git.create_commit("Second").await.unwrap();
+        git.run(&["checkout", "-q", "-"]).await.unwrap();
+
+        let log = git.log(10, Some("feature")).await.unwrap();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].hash, second);
+        assert!(second.starts_with(&log[0].short_hash));
+        assert_eq!(log[0].parent_hashes, vec![first.clone()]);
+        assert_eq!(log[0].summary, "Second");
+        assert_eq!(log[1].hash, first);
+        assert!(log[1].parent_hashes.is_empty());
+        assert_eq!(log[1].summary, "First: a | b");
+        assert_eq!(log[1].author_name, "Test");
+        assert_eq!(log[1].author_email, "test@example.com");
+        assert!(log[1].timestamp > 0);
+
+        // HEAD is back on the original branch, and the limit applies
+        assert_eq!(git.log(10, None).await.unwrap().len(), 1);
+        assert_eq!(git.log(1, Some("feature")).await.unwrap()[0].hash, second);
+        assert!(matches!(
+            git.log(10, Some("--all")).await,
+            Err(GitError::ParseError { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn diff_covers_one_file_or_the_whole_worktree() {
+        let (dir, git) = temp_repo().await;
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "one\n").unwrap();
+        git.stage_all().await.unwrap();
+        git.create_commit("one").await.unwrap();
+        assert!(git.diff(None, false, None).await.unwrap().diff.is_empty());
+
+        std::fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "two\n").unwrap();
+
+        let all = git.diff(None, false, None).await.unwrap();
+        assert!(all.diff.contains("+++ b/a.txt"));
+        assert!(all.diff.contains("+++ b/b.txt"));
+        assert!(!all.truncated);
+        assert_eq!(all.total_bytes, all.diff.len());
+
+        let one = git.diff(Some("a.txt"), false, None).await.unwrap();
+        assert!(one.diff.contains("-one\n+two\n"));
+        assert!(!one.diff.contains("b.txt"));
+
+        let capped = git.diff(None, false, Some(20)).await.unwrap();
+        assert!(capped.truncated);
+        assert_eq!(capped.total_bytes, all.total_bytes);
+        assert!(capped.diff.len() <= 20);
+        assert!(all.diff.starts_with(&capped.diff));
+
+        // Only what's staged shows with `staged`
+        assert!(git.diff(None, true, None).await.unwrap().diff.is_empty());
+        git.run(&["add", "b.txt"]).await.unwrap();
+        let staged = git.diff(None, true, None).await.unwrap();
+        assert!(staged.diff.contains("+++ b/b.txt"));
+        assert!(!staged.diff.contains("a.txt"));
+    }
+
+    #[tokio::test]
+    async fn stash_save_list_and_pop_round_trip() {
+        let (dir, git) = temp_repo().await;
+        std::fs::write(dir.path().join("a.txt"), "one\n").unwrap();
+        git.stage_all().await.unwrap();
+        git.create_commit("one").await.unwrap();
+
+        assert!(matches!(
+            git.stash_save(None).await,
+            Err(GitError::NothingToStash)
+        ));
+
+        std::fs::write(dir.path().join("a.txt"), "two\n").unwrap();
+        std::fs::write(dir.path().join("new.txt"), "untracked\n").unwrap();
+        git.stash_save(Some("before cleanup")).await.unwrap();
+        assert!(!dir.path().join("new.txt").exists());
+
+        let stashes = git.stash_list().await.unwrap();
+        assert_eq!(stashes.len(), 1);
+        assert_eq!(stashes[0].index, 0);
+        assert_eq!(stashes[0].message, "before cleanup");
+        assert!(stashes[0].branch.is_some());
+
+        git.stash_pop(0).await.unwrap();
+        assert_eq!(std::fs::read_to_string(dir.path().join("a.txt")).unwrap(), "two\n");
+        assert!(dir.path().join("new.txt").exists());
+        assert!(git.stash_list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn stash_pop_reports_conflicts() {
+        let (dir, git) = temp_repo().await;
+        let file = dir.path().join("a.txt");
+        std::fs::write(&file, "one\n").unwrap();
+        git.stage_all().await.unwrap();
+        git.create_commit("one").await.unwrap();
+
+        std::fs::write(&file, "stashed\n").unwrap();
+        git.stash_save(None).await.unwrap();
+        std::fs::write(&file, "committed\n").unwrap();
+        git.stage_all().await.unwrap();
+        git.create_commit("two").await.unwrap();
+
+        match git.stash_pop(0).await {
+            Err(GitError::StashConflict { paths }) => assert_eq!(paths, vec!["a.txt"]),
+            other => panic!("expected StashConflict, got {other:?}"),
+        }
+        assert_eq!(git.stash_list().await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn parses_stash_entries() {
+        let wip = parse_stash_entry("stash@{0}\0WIP on main: abc1234 Fix it").unwrap();
+        assert_eq!(wip.index, 0);
+        assert_eq!(wip.branch.as_deref(), Some("main"));
+        assert_eq!(wip.message, "abc1234 Fix it");
+
+        let named = parse_stash_entry("stash@{12}\0On feature/x: keep: this").unwrap();
+        assert_eq!(named.index, 12);
+        assert_eq!(named.branch.as_deref(), Some("feature/x"));
+        assert_eq!(named.message, "keep: this");
+
+        let detached = parse_stash_entry("stash@{1}\0WIP on (no branch): abc1234 x").unwrap();
+        assert_eq!(detached.branch, None);
+
+        assert!(parse_stash_entry("garbage").is_none());
+    }
+
+    #[test]
+    fn parses_fetch_report() {
+        let stderr = "From /tmp/upstream\n\
+            \x20  a863abb..e890325  master     -> origin/master\n\
+            \x20* [new tag]         v2         -> v2\n\
+            \x20- [deleted]         (none)     -> origin/feat\n\
+            \x20+ e890325...a863abb main       -> origin/main  (forced update)\n";
+        let refs = parse_fetch_output(stderr);
+
+        assert_eq!(refs.len(), 4);
+        assert_eq!(refs[0].status, FetchRefStatus::Updated);
+        assert_eq!(refs[0].summary, "a863abb..e890325");
+        assert_eq!(refs[0].remote_ref, "master");
+        assert_eq!(refs[0].local_ref, "origin/master");
+        assert_eq!(refs[1].status, FetchRefStatus::New);
+        assert_eq!(refs[1].summary, "[new tag]");
+        assert_eq!(refs[2].status, FetchRefStatus::Pruned);
+        assert_eq!(refs[2].remote_ref, "(none)");
+        assert_eq!(refs[3].status, FetchRefStatus::Forced);
+        assert_eq!(refs[3].local_ref, "origin/main");
+    }
+
+    #[test]
+    fn recognizes_missing_identity() {
+        assert!(is_identity_error(
+            "Author identity unknown\n\n*** Please tell me who you are.\n"
+        ));
+        assert!(!is_identity_error("error: pathspec 'x' did not match"));
+    }
 }
@@ -5,6 +5,9 @@ use tokio::time::{timeout, Duration};
 use super::error::GitError;
 use crate::core::windows_process::TokioCommandExt;
 
+/// How long a git subprocess may run before it is killed.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Captured stdout/stderr from a completed git subprocess.
 ///
 /// Provides convenience methods for common parsing patterns: `lines()` splits
@@ -52,6 +55,12 @@ impl Git {
     /// other I/O failures, and `CommandFailed` for non-zero exit codes.
     /// Both stdout and stderr are decoded as UTF-8 (returns `InvalidUtf8` on failure).
     pub async fn run(&self, args: &[&str]) -> Result<GitOutput, GitError> {
+        self.run_with_timeout(args, DEFAULT_TIMEOUT).await
+    }
+
+    /// Like `run`, but kills the subprocess after `limit` instead of the
+    /// default 30 seconds. Used for network operations such as fetch.
+    pub async fn run_with_timeout(&self, args: &[&str], limit: Duration) -> Result<GitOutput, GitError> {
         let mut cmd = Command::new("git");
         cmd.arg("-C")
             .arg(&self.repo_path)
@@ -63,11 +72,11 @@ impl Git {
 
         let command_str = format!("git -C {} {}", self.repo_path.display(), args.join(" "));
 
-        let output = timeout(Duration::from_secs(30), cmd.output())
+        let output = timeout(limit, cmd.output())
             .await
             .map_err(|_| GitError::CommandFailed {
                 code: -1,
-                stderr: format!("Command timed out after 30s: {}", command_str),
+                stderr: format!("Command timed out after {}s: {}", limit.as_secs(), command_str),
                 command: command_str.clone(),
             })?
             .map_err(|source| {
@@ -5,22 +5,24 @@ mod git;
 use std::sync::Arc;
 
 use tauri::Manager;
+use tauri_plugin_store::StoreExt;
 
 use core::event_bus::EventBus;
 use core::marketplace_manager::MarketplaceManager;
 use core::mcp_manager::McpManager;
 use core::plugin_manager::PluginManager;
 use core::status_server::StatusServer;
+use core::store_debouncer::{StoreDebouncer, DEFAULT_SAVE_DEBOUNCE};
 use core::tunnel_manager::TunnelManager;
 use core::web_access_server::WebAccessServer;
 use core::ProcessManager;
-use core::session_manager::SessionManager;
+use core::session_manager::{SessionManager, SESSIONS_FILE};
 use core::worktree_manager::WorktreeManager;
 
 /// Entry point for the Tauri application.
 ///
 /// Registers plugins (store, dialog), injects shared state (ProcessManager,
-/// SessionManager, WorktreeManager), verifies git availability at startup
+/// SessionManager restored from disk, WorktreeManager), verifies git availability at startup
 /// (non-fatal -- logs an error but does not abort), and mounts all IPC
 /// command handlers for the terminal, git, and session subsystems.
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -73,7 +75,6 @@ pub fn run() {
         .manage(McpManager::new())
         .manage(PluginManager::new())
         .manage(ProcessManager::new())
-        .manage(SessionManager::new())
         .manage(TunnelManager::new())
         .manage(WorktreeManager::new())
         .setup(|app| {
@@ -82,6 +83,39 @@ pub fn run() {
             let instance_id = uuid::Uuid::new_v4().to_string();
             log::info!("Chorus instance ID: {}", instance_id);
 
+            // Restore the session list saved by the previous run
+            let session_manager = match app.path().app_data_dir() {
+                Ok(dir) => SessionManager::load(dir.join(SESSIONS_FILE)),
+                Err(e) => {
+                    log::error!("No app data dir, sessions will not be persisted: {e}");
+                    SessionManager::new()
+                }
+            };
+            // Keep new PTY ids clear of restored sessions and their persisted scrollback
+            let max_restored_id = session_manager.all_sessions().iter().map(|s| s.id).max();
+            app.state::<ProcessManager>()
+                .reserve_ids_through(max_restored_id.unwrap_or(0));
+            app.manage(session_manager);
+            // Resume persisting scrollback if it was left on
+            commands::terminal::load_scrollback_persistence(app.handle());
+
+            // Sessions spawned directly on the VTE backend (developer comparison aid)
+            #[cfg(feature = "vte-backend")]
+            app.manage(core::VteSessions::new());
+
+            // Debounced persistence for per-project defaults, flushed on exit
+            let store_handle = app.handle().clone();
+            app.manage(StoreDebouncer::new(DEFAULT_SAVE_DEBOUNCE, move |store_name| {
+                match store_handle.store(store_name) {
+                    Ok(store) => {
+                        if let Err(e) = store.save() {
+                            log::error!("Failed to save store {}: {}", store_name, e);
+                        }
+                    }
+                    Err(e) => log::error!("Failed to open store {}: {}", store_name, e),
+                }
+            }));
+
             // Verify git is available at startup (non-blocking with timeout)
             tauri::async_runtime::spawn(async {
                 match tokio::time::timeout(
@@ -100,8 +134,9 @@ pub fn run() {
             // IMPORTANT: This must be done synchronously so the server is ready
             // before any commands try to use it
             let app_handle = app.handle().clone();
+            let status_instance_id = instance_id.clone();
             let server = tauri::async_runtime::block_on(async {
-                StatusServer::start(app_handle, instance_id).await
+                StatusServer::start(app_handle, status_instance_id).await
             });
 
             match server {
@@ -127,8 +162,18 @@ pub fn run() {
             // Start the web access server for mobile browser connections
             let web_app_handle = app.handle().clone();
             let web_event_bus = event_bus.clone();
+            let port_range = commands::web_access::load_port_range(app.handle());
+            let tls = commands::web_access::load_tls_identity(app.handle());
+            let allowlist = commands::web_access::load_allowlist(app.handle());
             tauri::async_runtime::spawn(async move {
-                match WebAccessServer::start(web_app_handle.clone(), web_event_bus) {
+                match WebAccessServer::start(
+                    web_app_handle.clone(),
+                    web_event_bus,
+                    &instance_id,
+                    port_range,
+                    tls.as_ref(),
+                    allowlist,
+                ) {
                     Some(server) => {
                         log::info!("Web access server started on port {}", server.get_status().await.port);
                         web_app_handle.manage(server);
@@ -147,9 +192,32 @@ pub fn run() {
             commands::terminal::write_stdin,
             commands::terminal::resize_pty,
             commands::terminal::kill_session,
+            commands::terminal::restart_session,
             commands::terminal::kill_all_sessions,
             commands::terminal::check_cli_available,
+            commands::terminal::check_session_cli,
             commands::terminal::get_backend_info,
+            commands::terminal::set_default_backend,
+            commands::terminal::get_session_screen,
+            commands::terminal::get_session_terminal_state,
+            commands::terminal::get_session_output_since,
+            commands::terminal::start_recording,
+            commands::terminal::stop_recording,
+            commands::terminal::get_session_output_info,
+            commands::terminal::set_scrollback_limit,
+            commands::terminal::set_scrollback_persistence,
+            commands::terminal::get_scrollback_persistence,
+            commands::terminal::set_output_coalesce_window,
+            commands::terminal::pause_session_output,
+            commands::terminal::resume_session_output,
+            #[cfg(feature = "vte-backend")]
+            commands::terminal::spawn_vte_session,
+            #[cfg(feature = "vte-backend")]
+            commands::terminal::write_vte_session,
+            #[cfg(feature = "vte-backend")]
+            commands::terminal::kill_vte_session,
+            #[cfg(feature = "vte-backend")]
+            commands::terminal::get_backend_grid_dump,
             commands::terminal::get_session_process_tree,
             commands::terminal::get_all_process_trees,
             commands::terminal::kill_process,
@@ -158,6 +226,8 @@ pub fn run() {
             commands::git::git_current_branch,
             commands::git::git_uncommitted_count,
             commands::git::git_worktree_list,
+            commands::git::git_log,
+            commands::git::git_diff,
             commands::git::git_worktree_add,
             commands::git::git_worktree_remove,
             commands::git::git_commit_log,
@@ -181,36 +251,64 @@ pub fn run() {
             commands::git::git_clean_files,
             commands::git::git_create_commit,
             commands::git::git_push,
+            commands::git::git_fetch,
+            commands::git::git_stash_save,
+            commands::git::git_stash_list,
+            commands::git::git_stash_pop,
             // Session commands (new)
             commands::session::get_sessions,
+            commands::session::get_ai_modes,
             commands::session::create_session,
             commands::session::update_session_status,
+            commands::session::update_sessions_status,
             commands::session::update_session_title,
+            commands::session::set_session_note,
+            commands::session::set_session_tags,
+            commands::session::get_session_env,
+            commands::session::set_session_env,
+            commands::session::get_sessions_by_tag,
             commands::session::assign_session_branch,
+            commands::session::verify_session_branch,
+            commands::session::reorder_sessions,
             commands::session::remove_session,
             commands::session::get_sessions_for_project,
             commands::session::remove_sessions_for_project,
             // Worktree commands
             commands::worktree::prepare_session_worktree,
             commands::worktree::cleanup_session_worktree,
+            commands::worktree::cancel_worktree_prepare,
+            commands::worktree::list_worktrees_with_sessions,
+            commands::worktree::list_orphaned_worktrees,
+            commands::worktree::prune_orphaned_worktrees,
+            commands::worktree::worktree_disk_usage,
             // MCP commands
             commands::mcp::get_project_mcp_servers,
             commands::mcp::refresh_project_mcp_servers,
+            commands::mcp::watch_project_mcp_servers,
+            commands::mcp::unwatch_project_mcp_servers,
             commands::mcp::get_session_mcp_servers,
             commands::mcp::set_session_mcp_servers,
+            commands::mcp::get_session_mcp_env,
+            commands::mcp::set_session_mcp_env,
             commands::mcp::get_session_mcp_count,
+            commands::mcp::get_mcp_server_usage,
             commands::mcp::save_project_mcp_defaults,
             commands::mcp::load_project_mcp_defaults,
             commands::mcp::add_mcp_project,
             commands::mcp::remove_mcp_project,
             commands::mcp::remove_session_status,
             commands::mcp::write_session_mcp_config,
+            commands::mcp::preview_session_mcp_config,
+            commands::mcp::get_chorus_mcp_server_path,
+            commands::mcp::set_chorus_mcp_server_path,
             commands::mcp::remove_session_mcp_config,
             commands::mcp::generate_project_hash,
             commands::mcp::get_custom_mcp_servers,
             commands::mcp::save_custom_mcp_server,
             commands::mcp::delete_custom_mcp_server,
             commands::mcp::get_status_server_info,
+            commands::mcp::get_registered_status_sessions,
+            commands::mcp::get_mcp_logs,
             // Plugin commands
             commands::plugin::get_project_plugins,
             commands::plugin::refresh_project_plugins,
@@ -239,8 +337,12 @@ pub fn run() {
             commands::marketplace::refresh_marketplace,
             commands::marketplace::refresh_all_marketplaces,
             commands::marketplace::get_available_plugins,
+            commands::marketplace::search_marketplace_plugins,
             commands::marketplace::get_installed_plugins,
             commands::marketplace::install_marketplace_plugin,
+            commands::marketplace::preview_git_plugin,
+            commands::marketplace::install_plugin_from_git,
+            commands::marketplace::check_plugin_updates,
             commands::marketplace::uninstall_plugin,
             commands::marketplace::is_marketplace_plugin_installed,
             commands::marketplace::get_session_marketplace_config,
@@ -261,19 +363,47 @@ pub fn run() {
             commands::intel::get_intel_broadcasts,
             commands::intel::get_intel_conflicts,
             commands::intel::get_intel_scratchpad,
+            commands::intel::get_intel_digest,
             commands::intel::write_intel_scratchpad,
             commands::intel::clear_intel_scratchpad,
+            commands::intel::clear_intel_broadcasts,
+            commands::intel::clear_intel_conflicts,
+            commands::intel::clear_all_intel,
+            commands::intel::export_intel_markdown,
             // Web access commands
             commands::web_access::generate_web_access_token,
             commands::web_access::get_web_access_status,
+            commands::web_access::get_web_access_qr,
+            commands::web_access::set_web_access_max_clients,
+            commands::web_access::set_web_access_port_range,
+            commands::web_access::set_web_access_tls,
+            commands::web_access::set_web_access_allowlist,
             commands::web_access::revoke_web_access,
             commands::web_access::start_web_tunnel,
             commands::web_access::stop_web_tunnel,
             commands::web_access::get_web_tunnel_status,
             commands::web_access::push_session_to_mobile,
+            commands::web_access::get_known_event_names,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running Chorus");
+        .build(tauri::generate_context!())
+        .expect("error while building Chorus")
+        .run(|app, event| {
+            if let tauri::RunEvent::Exit = event {
+                // Make sure the last toggles of project defaults reach disk
+                let debouncer = app.state::<StoreDebouncer>();
+                if debouncer.pending_count() > 0 {
+                    log::info!("Flushing {} pending store save(s)", debouncer.pending_count());
+                    debouncer.flush();
+                }
+                // Same for the session registry's debounced save
+                app.state::<SessionManager>().flush();
+
+                // Withdraw the LAN announcement so clients don't find a dead instance
+                if let Some(server) = app.try_state::<WebAccessServer>() {
+                    server.stop_advertising();
+                }
+            }
+        });
 }
 
 async fn verify_git_available() -> Result<String, String> {
@@ -8,7 +8,7 @@ import {
 } from "lucide-react";
 import { useEffect, useRef, useState } from "react";
 import { useMcpStore } from "@/stores/useMcpStore";
-import type { McpCustomServer } from "@/lib/mcp";
+import type { McpCustomServer, McpServerValidationError } from "@/lib/mcp";
 
 interface McpServerEditorModalProps {
   /** Existing server to edit, or undefined to create a new one. */
@@ -181,16 +181,16 @@ export function McpServerEditorModal({
         createdAt: server?.createdAt ?? new Date().toISOString(),
       };
 
-      if (isEditing) {
-        await updateCustomServer(serverData);
-      } else {
-        await addCustomServer(serverData);
-      }
+      const warnings = isEditing
+        ? await updateCustomServer(serverData)
+        : await addCustomServer(serverData);
+      warnings.forEach((warning) => console.warn(`MCP server "${serverData.name}": ${warning}`));
 
       onSaved?.();
       onClose();
     } catch (err) {
-      setError(String(err));
+      const validation = err as Partial<McpServerValidationError>;
+      setError(typeof validation?.message === "string" ? validation.message : String(err));
     } finally {
       setSaving(false);
     }
@@ -5,9 +5,9 @@ import { useCallback, useEffect, useRef, useState } from "react";
 import "@xterm/xterm/css/xterm.css";
 
 import { TerminalSpecialKeys } from "@/components/mobile/TerminalSpecialKeys";
-import { onPtyOutput, resizePty, writeStdin } from "@/lib/terminal";
+import { onPtyOutput, resizePty, streamSessionOutput, writeStdin } from "@/lib/terminal";
 import { DEFAULT_THEME, toXtermTheme } from "@/lib/terminalTheme";
-import { listen, onWsStatusChange, type WsConnectionStatus } from "@/lib/transport";
+import { listen, onEventsLagged, onWsStatusChange, type WsConnectionStatus } from "@/lib/transport";
 
 interface PushedSession {
   sessionId: number;
@@ -165,6 +165,23 @@ export function MobileTerminalView() {
         if (!disposed) console.error("PTY listener failed:", err);
       });
 
+    // Output events were dropped: redraw from the backend's scrollback
+    const unlistenLag = onEventsLagged(() => {
+      if (disposed) return;
+      unlisten?.();
+      unlisten = null;
+      term.reset();
+      streamSessionOutput(session.sessionId, (data) => {
+        if (!disposed) term.write(data);
+      })
+        .then((fn) => {
+          if (disposed) { fn(); } else { unlisten = fn; }
+        })
+        .catch((err) => {
+          if (!disposed) console.error("Failed to resync terminal output:", err);
+        });
+    });
+
     // Resize on container size change
     const resizeObserver = new ResizeObserver(() => {
       requestAnimationFrame(() => {
@@ -178,6 +195,7 @@ export function MobileTerminalView() {
     return () => {
       disposed = true;
       unlisten?.();
+      unlistenLag();
       dataDisposable.dispose();
       resizeDisposable.dispose();
       resizeObserver.disconnect();
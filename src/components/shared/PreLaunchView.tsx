@@ -16,7 +16,7 @@ export function PreLaunchView({ onSelectProject }: PreLaunchViewProps) {
         <h1 className="text-2xl font-bold text-foreground">Welcome to Chorus</h1>
         <p className="max-w-md text-center text-sm text-muted-foreground">
           Multi-session AI orchestrator for Linux. Open a project to get started with Claude,
-          Gemini, or Codex sessions.
+          Gemini, Codex, or Aider sessions.
         </p>
       </div>
 
@@ -11,6 +11,7 @@ const STATUS_DEFS: {
   { key: "NeedsInput", label: "Needs Input", colorClass: "bg-yellow-300" },
   { key: "Done", label: "Done", colorClass: "bg-green-500" },
   { key: "Error", label: "Error", colorClass: "bg-red-400" },
+  { key: "Stopped", label: "Stopped", colorClass: "bg-muted-foreground/50" },
   { key: "Timeout", label: "Timeout", colorClass: "bg-red-400" },
 ];
 
@@ -28,6 +29,7 @@ export function StatusLegend() {
       NeedsInput: 0,
       Done: 0,
       Error: 0,
+      Stopped: 0,
       Timeout: 0,
     },
   );
@@ -47,7 +47,9 @@ export function IntelligenceSection() {
   const scratchpad = useIntelStore((s) => s.scratchpad);
 
   const totalCount = broadcasts.length + conflicts.length + scratchpad.length;
-  const hasConflicts = conflicts.length > 0;
+  // Acknowledged conflicts stay listed but don't raise the alert
+  const openConflictCount = conflicts.filter((c) => !c.acknowledged).length;
+  const hasConflicts = openConflictCount > 0;
   const prevConflictCount = useRef(conflicts.length);
 
   // Auto-switch to Conflicts tab when new conflicts arrive
@@ -85,7 +87,7 @@ export function IntelligenceSection() {
         )}
         {hasConflicts && (
           <span className="bg-orange-500/20 text-orange-500 text-[9px] px-1 rounded-full font-bold animate-pulse">
-            {conflicts.length}!
+            {openConflictCount}!
           </span>
         )}
       </div>
@@ -178,15 +180,29 @@ function ConflictsContent({ conflicts }: { conflicts: FileConflict[] }) {
       {conflicts.map((conflict) => (
         <div
           key={`${conflict.file_path}-${conflict.sessions.join(",")}`}
-          className="flex items-start gap-1.5 rounded px-1 py-0.5 text-foreground bg-orange-500/5 hover:bg-orange-500/10"
+          className={`flex items-start gap-1.5 rounded px-1 py-0.5 text-foreground ${
+            conflict.acknowledged
+              ? "opacity-50 hover:bg-accent/50"
+              : "bg-orange-500/5 hover:bg-orange-500/10"
+          }`}
         >
-          <FileWarning size={10} className="shrink-0 mt-px text-orange-500" />
+          <FileWarning
+            size={10}
+            className={`shrink-0 mt-px ${
+              conflict.acknowledged ? "text-muted-foreground" : "text-orange-500"
+            }`}
+          />
           <div className="min-w-0 flex-1">
-            <div className="text-[10px] font-medium text-orange-400 truncate">
+            <div
+              className={`text-[10px] font-medium truncate ${
+                conflict.acknowledged ? "text-muted-foreground" : "text-orange-400"
+              }`}
+            >
               {conflict.file_path}
             </div>
             <div className="text-[9px] text-muted-foreground">
               {conflict.sessions.map((s) => `#${s}`).join(", ")}
+              {conflict.acknowledged && " · acknowledged"}
             </div>
           </div>
         </div>
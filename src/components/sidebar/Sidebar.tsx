@@ -88,6 +88,7 @@ const STATUS_DOT_CLASS: Record<BackendSessionStatus, string> = {
   NeedsInput: "bg-yellow-500",
   Done: "bg-green-500",
   Error: "bg-destructive",
+  Stopped: "bg-muted-foreground/50",
   Timeout: "bg-destructive",
 };
 
@@ -98,6 +99,7 @@ const STATUS_LABEL: Record<BackendSessionStatus, string> = {
   NeedsInput: "Needs Input",
   Done: "Done",
   Error: "Error",
+  Stopped: "Stopped",
   Timeout: "Startup Timeout",
 };
 
@@ -626,7 +628,7 @@ function SessionsSection() {
 
 /* ── 4. Status ── */
 
-const AI_MODES: AiMode[] = ["Claude", "Gemini", "Codex", "Plain"];
+const AI_MODES: AiMode[] = ["Claude", "Gemini", "Codex", "Aider", "Plain"];
 const SESSION_STATUSES: BackendSessionStatus[] = [
   "Starting",
   "Idle",
@@ -634,12 +636,14 @@ const SESSION_STATUSES: BackendSessionStatus[] = [
   "NeedsInput",
   "Done",
   "Error",
+  "Stopped",
 ];
 
 const MODE_ICON: Record<AiMode, React.ElementType> = {
   Claude: Bot,
   Gemini: Sparkles,
   Codex: Cpu,
+  Aider: Wrench,
   Plain: Globe,
 };
 
@@ -665,11 +669,13 @@ function StatusSection() {
         NeedsInput: 0,
         Done: 0,
         Error: 0,
+        Stopped: 0,
       } as Record<BackendSessionStatus, number>,
       mode: {
         Claude: 0,
         Gemini: 0,
         Codex: 0,
+        Aider: 0,
         Plain: 0,
       } as Record<AiMode, number>,
     },
@@ -866,7 +872,6 @@ function MCPServersSection() {
                 </div>
                 {discoveredServers.map((server) => {
                   const serverType = server.type;
-                  const isHttp = serverType === "http";
                   return (
                     <div
                       key={server.name}
@@ -875,7 +880,7 @@ function MCPServersSection() {
                       <span className="h-2 w-2 shrink-0 rounded-full bg-green-500" />
                       <span className="flex-1 truncate font-medium">{server.name}</span>
                       <span className="text-[10px] text-muted-foreground">
-                        {isHttp ? "HTTP" : "stdio"}
+                        {serverType === "stdio" ? "stdio" : serverType.toUpperCase()}
                       </span>
                     </div>
                   );
@@ -17,6 +17,7 @@ const AI_MODES: { value: AiMode; label: string }[] = [
   { value: "Claude", label: "Claude Code" },
   { value: "Gemini", label: "Gemini CLI" },
   { value: "Codex", label: "Codex" },
+  { value: "Aider", label: "Aider" },
   { value: "Plain", label: "Plain Terminal" },
 ];
 
@@ -13,6 +13,7 @@ import {
   Sparkles,
   Store,
   Terminal,
+  Wrench,
   X,
   Zap,
 } from "lucide-react";
@@ -91,6 +92,7 @@ const AI_MODES: { mode: AiMode; icon: typeof BrainCircuit; label: string; color:
   { mode: "Claude", icon: BrainCircuit, label: "Claude Code", color: "text-blue-500" },
   { mode: "Gemini", icon: Sparkles, label: "Gemini CLI", color: "text-blue-400" },
   { mode: "Codex", icon: Code2, label: "Codex", color: "text-green-400" },
+  { mode: "Aider", icon: Wrench, label: "Aider", color: "text-amber-400" },
   { mode: "Plain", icon: Terminal, label: "Terminal", color: "text-muted-foreground" },
 ];
 
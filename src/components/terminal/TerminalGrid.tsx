@@ -16,8 +16,9 @@ import {
 import {
   AI_CLI_CONFIG,
   assignSessionBranch,
-  checkCliAvailable,
+  checkSessionCli,
   createSession,
+  type CliNotFound,
   killSession,
   spawnShell,
   getStatusServerInfo,
@@ -405,6 +406,18 @@ export const TerminalGrid = forwardRef<TerminalGridHandle, TerminalGridProps>(fu
     if (!slot || slot.sessionId !== null) return;
 
     try {
+      // Fail fast with an install hint rather than a shell "command not found"
+      try {
+        await checkSessionCli(slot.mode);
+      } catch (err) {
+        const missing = err as Partial<CliNotFound>;
+        if (missing?.command && missing.installHint) {
+          setError(`'${missing.command}' is not installed. Install it with: ${missing.installHint}`);
+          return;
+        }
+        throw err;
+      }
+
       // Save branch config before launching (ensures it's persisted)
       if (projectPath && slot.branch) {
         await saveBranchConfig(projectPath, slot.branch, {
@@ -494,53 +507,45 @@ export const TerminalGrid = forwardRef<TerminalGridHandle, TerminalGridProps>(fu
       if (slot.mode !== "Plain") {
         const cliConfig = AI_CLI_CONFIG[slot.mode];
         if (cliConfig.command) {
-          const isAvailable = await checkCliAvailable(cliConfig.command);
-
-          if (isAvailable) {
-            // Write MCP config IMMEDIATELY before launching CLI
-            // This allows the CLI to discover MCP servers including the Chorus status server
-            if (workingDirectory && slot.mode === "Claude") {
-              try {
-                await writeSessionMcpConfig(
-                  workingDirectory,
-                  sessionId,
-                  projectPath ?? workingDirectory,
-                  slot.enabledMcpServers
-                );
-              } catch (err) {
-                console.error("Failed to write MCP config:", err);
-                // Non-fatal - continue with CLI launch, MCP servers just won't be available
-              }
-
+          // Installation was verified by checkSessionCli before spawning.
+          // Write MCP config IMMEDIATELY before launching CLI
+          // This allows the CLI to discover MCP servers including the Chorus status server
+          if (workingDirectory && slot.mode === "Claude") {
+            try {
+              await writeSessionMcpConfig(
+                workingDirectory,
+                sessionId,
+                projectPath ?? workingDirectory,
+                slot.enabledMcpServers
+              );
+            } catch (err) {
+              console.error("Failed to write MCP config:", err);
+              // Non-fatal - continue with CLI launch, MCP servers just won't be available
             }
-
-            // Build --plugin-dir flags for enabled plugins
-            // This injects Chorus-exclusive plugins at runtime without polluting ~/.claude/
-            const pluginDirFlags = slot.enabledPlugins
-              .map((pluginId) => plugins.find((p) => p.id === pluginId))
-              .filter((p): p is NonNullable<typeof p> => p !== undefined && p.path !== null)
-              .map((p) => `--plugin-dir "${p.path}"`)
-              .join(" ");
-
-            // Brief delay for shell to initialize (reduced from 500ms)
-            await new Promise((resolve) => setTimeout(resolve, 100));
-
-            // Send CLI launch command with plugin-dir flags
-            const cliCommand = pluginDirFlags
-              ? `${cliConfig.command} ${pluginDirFlags}`
-              : cliConfig.command;
-            await writeStdin(sessionId, `${cliCommand}\r`);
-
-            // Brief delay for CLI initialization.
-            // With session-specific MCP server names (chorus-1, chorus-2, etc.),
-            // we no longer have race conditions on .mcp.json, so we only need
-            // a minimal delay for general CLI startup.
-            await new Promise((resolve) => setTimeout(resolve, 500));
-          } else {
-            console.warn(
-              `CLI '${cliConfig.command}' not found. Install with: ${cliConfig.installHint}`
-            );
           }
+
+          // Build --plugin-dir flags for enabled plugins
+          // This injects Chorus-exclusive plugins at runtime without polluting ~/.claude/
+          const pluginDirFlags = slot.enabledPlugins
+            .map((pluginId) => plugins.find((p) => p.id === pluginId))
+            .filter((p): p is NonNullable<typeof p> => p !== undefined && p.path !== null)
+            .map((p) => `--plugin-dir "${p.path}"`)
+            .join(" ");
+
+          // Brief delay for shell to initialize (reduced from 500ms)
+          await new Promise((resolve) => setTimeout(resolve, 100));
+
+          // Send CLI launch command with plugin-dir flags
+          const cliCommand = pluginDirFlags
+            ? `${cliConfig.command} ${pluginDirFlags}`
+            : cliConfig.command;
+          await writeStdin(sessionId, `${cliCommand}\r`);
+
+          // Brief delay for CLI initialization.
+          // With session-specific MCP server names (chorus-1, chorus-2, etc.),
+          // we no longer have race conditions on .mcp.json, so we only need
+          // a minimal delay for general CLI startup.
+          await new Promise((resolve) => setTimeout(resolve, 500));
         }
       }
 
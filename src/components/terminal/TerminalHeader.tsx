@@ -11,12 +11,13 @@ import {
   Smartphone,
   Sparkles,
   Terminal,
+  Wrench,
   X,
 } from "lucide-react";
 
 export type SessionStatus = "idle" | "starting" | "working" | "needs-input" | "done" | "error" | "timeout";
 
-export type AIProvider = "claude" | "gemini" | "codex" | "plain";
+export type AIProvider = "claude" | "gemini" | "codex" | "aider" | "plain";
 
 interface TerminalHeaderProps {
   sessionId: number;
@@ -67,6 +68,7 @@ const providerConfig: Record<AIProvider, { icon: typeof BrainCircuit; label: str
   claude: { icon: BrainCircuit, label: "Claude Code" },
   gemini: { icon: Sparkles, label: "Gemini CLI" },
   codex: { icon: Code2, label: "Codex" },
+  aider: { icon: Wrench, label: "Aider" },
   plain: { icon: Terminal, label: "Terminal" },
 };
 
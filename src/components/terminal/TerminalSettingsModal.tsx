@@ -1,11 +1,13 @@
 import { Loader2, RefreshCw, RotateCcw } from "lucide-react";
 import { useEffect, useState } from "react";
 import { clearFontCache, EMBEDDED_FONT } from "@/lib/fonts";
+import { getScrollbackPersistence, setScrollbackPersistence } from "@/lib/terminal";
 import { useTerminalSettingsStore } from "@/stores/useTerminalSettingsStore";
 import { Button } from "@/components/ui/button";
 import { Slider } from "@/components/ui/slider";
 import { Badge } from "@/components/ui/badge";
 import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
 import {
   Dialog,
   DialogContent,
@@ -39,6 +41,7 @@ export function TerminalSettingsModal({ onClose }: TerminalSettingsModalProps) {
   } = useTerminalSettingsStore();
 
   const [isRefreshing, setIsRefreshing] = useState(false);
+  const [persistScrollback, setPersistScrollback] = useState<boolean | null>(null);
 
   useEffect(() => {
     if (!isInitialized) {
@@ -46,6 +49,21 @@ export function TerminalSettingsModal({ onClose }: TerminalSettingsModalProps) {
     }
   }, [isInitialized, initialize]);
 
+  useEffect(() => {
+    getScrollbackPersistence()
+      .then(setPersistScrollback)
+      .catch((err) => console.error("Failed to read scrollback persistence:", err));
+  }, []);
+
+  const handlePersistScrollback = async (enabled: boolean) => {
+    try {
+      await setScrollbackPersistence(enabled);
+      setPersistScrollback(enabled);
+    } catch (err) {
+      console.error("Failed to set scrollback persistence:", err);
+    }
+  };
+
   const handleRefreshFonts = async () => {
     setIsRefreshing(true);
     clearFontCache();
@@ -179,6 +197,22 @@ export function TerminalSettingsModal({ onClose }: TerminalSettingsModalProps) {
                 />
               </div>
 
+              {/* Scrollback persistence */}
+              <div className="flex items-center justify-between gap-4">
+                <div className="space-y-1">
+                  <Label htmlFor="persist-scrollback">Keep scrollback after restart</Label>
+                  <p className="text-xs text-muted-foreground">
+                    Saves terminal output to ~/.chorus/sessions so it survives a crash.
+                  </p>
+                </div>
+                <Switch
+                  id="persist-scrollback"
+                  checked={persistScrollback ?? false}
+                  disabled={persistScrollback === null}
+                  onCheckedChange={handlePersistScrollback}
+                />
+              </div>
+
               {/* Reset */}
               <div className="flex justify-end pt-2">
                 <Button variant="ghost" size="sm" onClick={resetToDefaults}>
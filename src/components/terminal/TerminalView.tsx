@@ -71,6 +71,7 @@ function mapAiMode(mode: AiMode): AIProvider {
     Claude: "claude",
     Gemini: "gemini",
     Codex: "codex",
+    Aider: "aider",
     Plain: "plain",
   };
   const provider = map[mode];
@@ -90,6 +91,7 @@ function mapStatus(status: BackendSessionStatus): SessionStatus {
     NeedsInput: "needs-input",
     Done: "done",
     Error: "error",
+    Stopped: "done",
     Timeout: "timeout",
   };
   const mapped = map[status];
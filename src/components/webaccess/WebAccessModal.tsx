@@ -10,10 +10,20 @@ interface WebAccessTokenResult {
   expiresInSecs: number;
 }
 
+interface PortRange {
+  start: number;
+  end: number;
+}
+
 interface WebAccessStatus {
   running: boolean;
   port: number;
+  portRange: PortRange;
+  tls: boolean;
+  certFingerprint: string | null;
+  allowlist: string[];
   connectedClients: number;
+  maxClients: number;
   hasValidToken: boolean;
 }
 
@@ -36,6 +46,10 @@ export function WebAccessModal({ onClose }: WebAccessModalProps) {
   const [copied, setCopied] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [secondsLeft, setSecondsLeft] = useState(0);
+  const [portRangeDraft, setPortRangeDraft] = useState<PortRange | null>(null);
+  const [portRangeSaved, setPortRangeSaved] = useState(false);
+  const [tlsDraft, setTlsDraft] = useState<boolean | null>(null);
+  const [allowlistDraft, setAllowlistDraft] = useState<string | null>(null);
   const timerRef = useRef<ReturnType<typeof setInterval> | null>(null);
   const statusPollRef = useRef<ReturnType<typeof setInterval> | null>(null);
 
@@ -153,6 +167,60 @@ export function WebAccessModal({ onClose }: WebAccessModalProps) {
     setTimeout(() => setCopied(false), 2000);
   };
 
+  const updateMaxClients = async (value: number) => {
+    if (!status || !Number.isFinite(value)) return;
+    try {
+      const maxClients = await invoke<number>("set_web_access_max_clients", { max: value });
+      setStatus({ ...status, maxClients });
+    } catch (err) {
+      setError(err instanceof Error ? err.message : "Failed to update connection limit");
+    }
+  };
+
+  const savePortRange = async () => {
+    if (!portRangeDraft) return;
+    const { start, end } = portRangeDraft;
+    if (![start, end].every((port) => Number.isInteger(port) && port >= 1 && port <= 65535)) {
+      setError("Ports must be whole numbers between 1 and 65535");
+      return;
+    }
+    if (start > end) {
+      setError("Port range must start at or below where it ends");
+      return;
+    }
+    setError(null);
+    try {
+      const portRange = await invoke<PortRange>("set_web_access_port_range", { ...portRangeDraft });
+      setPortRangeDraft(portRange);
+      setPortRangeSaved(true);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  const toggleTls = async (enabled: boolean) => {
+    setError(null);
+    try {
+      await invoke("set_web_access_tls", { enabled });
+      setTlsDraft(enabled);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
+  const saveAllowlist = async () => {
+    if (!status || allowlistDraft === null) return;
+    setError(null);
+    try {
+      const entries = allowlistDraft.split(",").map((e) => e.trim()).filter(Boolean);
+      const allowlist = await invoke<string[]>("set_web_access_allowlist", { entries });
+      setStatus({ ...status, allowlist });
+      setAllowlistDraft(null);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : String(err));
+    }
+  };
+
   const formatTime = (secs: number) => {
     const m = Math.floor(secs / 60);
     const s = secs % 60;
@@ -280,6 +348,73 @@ export function WebAccessModal({ onClose }: WebAccessModalProps) {
               <span>Server</span>
               <span>{status.running ? `Port ${status.port}` : "Not running"}</span>
             </div>
+            <div className="mt-1 flex items-center justify-between">
+              <span>Port range</span>
+              <span className="flex items-center gap-1">
+                {(["start", "end"] as const).map((key, i) => (
+                  <span key={key} className="flex items-center gap-1">
+                    {i > 0 && "–"}
+                    <input
+                      type="number"
+                      min={1}
+                      max={65535}
+                      value={(portRangeDraft ?? status.portRange)[key]}
+                      onChange={(e) => {
+                        setPortRangeSaved(false);
+                        setPortRangeDraft({ ...(portRangeDraft ?? status.portRange), [key]: Number(e.target.value) });
+                      }}
+                      onBlur={savePortRange}
+                      className="w-16 rounded border border-border bg-muted/50 px-1.5 py-0.5 text-right text-[11px] text-foreground"
+                    />
+                  </span>
+                ))}
+              </span>
+            </div>
+            <div className="mt-1 flex items-center justify-between">
+              <span>HTTPS (self-signed)</span>
+              <input
+                type="checkbox"
+                checked={tlsDraft ?? status.tls}
+                onChange={(e) => toggleTls(e.target.checked)}
+              />
+            </div>
+            {status.certFingerprint && (
+              <div className="mt-1 flex justify-between gap-4">
+                <span className="shrink-0">SHA-256</span>
+                <code className="break-all text-right text-[10px]" title="Compare with the certificate your browser shows">
+                  {status.certFingerprint}
+                </code>
+              </div>
+            )}
+            <div className="mt-1 flex items-center justify-between gap-4">
+              <span className="shrink-0" title="Only meaningful for direct LAN access; tunnelled clients share the tunnel's address">
+                Allowed IPs
+              </span>
+              <input
+                type="text"
+                placeholder="Anyone (e.g. 192.168.1.0/24)"
+                value={allowlistDraft ?? status.allowlist.join(", ")}
+                onChange={(e) => setAllowlistDraft(e.target.value)}
+                onBlur={saveAllowlist}
+                className="min-w-0 flex-1 rounded border border-border bg-muted/50 px-1.5 py-0.5 text-right text-[11px] text-foreground"
+              />
+            </div>
+            {(portRangeSaved || (tlsDraft !== null && tlsDraft !== status.tls)) && (
+              <div className="mt-1 text-right text-[10px]">Applies after restarting Chorus</div>
+            )}
+            {status.running && (
+              <div className="mt-1 flex items-center justify-between">
+                <span>Max devices</span>
+                <input
+                  type="number"
+                  min={1}
+                  max={64}
+                  value={status.maxClients}
+                  onChange={(e) => updateMaxClients(parseInt(e.target.value, 10))}
+                  className="w-14 rounded border border-border bg-muted/50 px-1.5 py-0.5 text-right text-[11px] text-foreground"
+                />
+              </div>
+            )}
             {isTunnelReady && (
               <div className="mt-1 flex justify-between">
                 <span>Tunnel</span>
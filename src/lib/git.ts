@@ -1,5 +1,6 @@
 import { invoke } from "@/lib/transport";
 import { listWorktrees } from "./worktreeManager";
+import type { CommitInfo } from "@/stores/useGitStore";
 
 /** Branch info from the backend. */
 export interface BranchInfo {
@@ -170,3 +171,105 @@ export async function pushChanges(
 ): Promise<void> {
   return invoke<void>("git_push", { repoPath, remote, branch, setUpstream });
 }
+
+export interface FetchedRef {
+  status: "new" | "updated" | "forced" | "tag" | "pruned" | "rejected";
+  summary: string;
+  remote_ref: string;
+  local_ref: string;
+}
+
+export interface FetchSummary {
+  updated_refs: FetchedRef[];
+}
+
+/**
+ * Fetches from a remote and returns the refs that changed.
+ * @param repoPath - Path to the git repository
+ * @param remote - Remote name (optional, defaults to the upstream's remote)
+ */
+export async function fetchRemote(
+  repoPath: string,
+  remote?: string
+): Promise<FetchSummary> {
+  return invoke<FetchSummary>("git_fetch", { repoPath, remote });
+}
+
+export interface StashEntry {
+  index: number;
+  branch: string | null;
+  message: string;
+}
+
+/**
+ * Stashes all changes in the working tree, including untracked files.
+ * Rejects if there is nothing to stash.
+ * @param repoPath - Path to the git repository
+ * @param message - Optional stash message
+ */
+export async function stashSave(repoPath: string, message?: string): Promise<void> {
+  return invoke<void>("git_stash_save", { repoPath, message });
+}
+
+/**
+ * Lists stash entries, newest (index 0) first.
+ * @param repoPath - Path to the git repository
+ */
+export async function stashList(repoPath: string): Promise<StashEntry[]> {
+  return invoke<StashEntry[]>("git_stash_list", { repoPath });
+}
+
+/**
+ * Applies and drops a stash entry. If it conflicts, the promise rejects with
+ * a message naming the conflicted files and the entry is kept.
+ * @param repoPath - Path to the git repository
+ * @param index - Stash index, 0 for the most recent
+ */
+export async function stashPop(repoPath: string, index = 0): Promise<void> {
+  return invoke<void>("git_stash_pop", { repoPath, index });
+}
+
+/**
+ * Fetches recent commits for a branch (or HEAD). The backend caps `limit`
+ * at 500 and returns an empty list for a repository with no commits.
+ *
+ * @param repoPath - Path to the git repository
+ * @param limit - Maximum number of commits to return
+ * @param branch - Branch or ref to read history from; defaults to HEAD
+ */
+export async function getCommitLog(
+  repoPath: string,
+  limit = 50,
+  branch?: string
+): Promise<CommitInfo[]> {
+  return invoke<CommitInfo[]>("git_log", { repoPath, limit, branch });
+}
+
+/** Unified diff text from the backend. */
+export interface DiffOutput {
+  diff: string;
+  /** Set when the diff was cut to fit `maxBytes`. */
+  truncated: boolean;
+  total_bytes: number;
+}
+
+/**
+ * Fetches the unified diff of a worktree. Binary files appear as
+ * "Binary files ... differ" lines.
+ *
+ * @param repoPath - Path to the worktree
+ * @param options.path - Limit the diff to one file, relative to the worktree
+ * @param options.staged - Diff the index instead of the working tree
+ * @param options.maxBytes - Truncate the diff past this many bytes
+ */
+export async function getDiff(
+  repoPath: string,
+  options: { path?: string; staged?: boolean; maxBytes?: number } = {}
+): Promise<DiffOutput> {
+  return invoke<DiffOutput>("git_diff", {
+    repoPath,
+    path: options.path,
+    staged: options.staged ?? false,
+    maxBytes: options.maxBytes,
+  });
+}
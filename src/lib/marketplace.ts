@@ -6,11 +6,15 @@
 
 import { invoke } from "@/lib/transport";
 import type {
+  GitPluginPreview,
   InstallScope,
   InstalledPlugin,
   MarketplacePlugin,
+  MarketplaceSearchResult,
   MarketplaceSource,
+  PluginCategory,
   SessionMarketplaceConfig,
+  UpdateCheckResult,
 } from "@/types/marketplace";
 
 // ========== Data Loading ==========
@@ -85,6 +89,20 @@ export async function getAvailablePlugins(): Promise<MarketplacePlugin[]> {
   return invoke<MarketplacePlugin[]>("get_available_plugins");
 }
 
+/**
+ * Searches available plugins by keyword, best matches first.
+ * An empty query lists everything in `category`.
+ */
+export async function searchMarketplacePlugins(
+  query: string,
+  category?: PluginCategory
+): Promise<MarketplaceSearchResult[]> {
+  return invoke<MarketplaceSearchResult[]>("search_marketplace_plugins", {
+    query,
+    category,
+  });
+}
+
 // ========== Plugin Installation ==========
 
 /**
@@ -109,6 +127,35 @@ export async function installMarketplacePlugin(
   });
 }
 
+/**
+ * Checks out a plugin from a Git URL and returns what it would install,
+ * without installing anything.
+ */
+export async function previewGitPlugin(
+  url: string,
+  gitRef?: string
+): Promise<GitPluginPreview> {
+  return invoke<GitPluginPreview>("preview_git_plugin", { url, gitRef });
+}
+
+/**
+ * Installs a plugin directly from an https or ssh Git URL.
+ */
+export async function installPluginFromGit(
+  url: string,
+  gitRef?: string
+): Promise<InstalledPlugin> {
+  return invoke<InstalledPlugin>("install_plugin_from_git", { url, gitRef });
+}
+
+/**
+ * Checks installed plugins for newer versions. Resolves with a partial
+ * result plus warnings if some sources couldn't be reached.
+ */
+export async function checkPluginUpdates(): Promise<UpdateCheckResult> {
+  return invoke<UpdateCheckResult>("check_plugin_updates");
+}
+
 /**
  * Uninstalls a plugin by its installed ID.
  */
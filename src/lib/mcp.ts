@@ -47,15 +47,28 @@ export interface McpStdioServerConfig {
 /**
  * HTTP MCP server config (flattened from backend).
  * The backend uses `#[serde(flatten)]` so type fields are at the root level.
+ * `headers` is omitted by the backend when empty.
  */
 export interface McpHttpServerConfig {
   name: string;
   type: "http";
   url: string;
+  headers?: McpEnv;
+}
+
+/**
+ * SSE MCP server config (flattened from backend).
+ * `headers` is omitted by the backend when empty.
+ */
+export interface McpSseServerConfig {
+  name: string;
+  type: "sse";
+  url: string;
+  headers?: McpEnv;
 }
 
 /** Union of all MCP server config types. */
-export type McpServerConfig = McpStdioServerConfig | McpHttpServerConfig;
+export type McpServerConfig = McpStdioServerConfig | McpHttpServerConfig | McpSseServerConfig;
 
 /**
  * Discovers MCP servers configured in the project's `.mcp.json`.
@@ -72,6 +85,27 @@ export async function refreshProjectMcpServers(projectPath: string): Promise<Mcp
   return invoke<McpServerConfig[]>("refresh_project_mcp_servers", { projectPath });
 }
 
+/** Payload of the `mcp:servers-changed` event. */
+export interface McpServersChangedEvent {
+  projectPath: string;
+  servers: McpServerConfig[];
+}
+
+/**
+ * Starts watching the project's `.mcp.json`. External edits refresh the
+ * backend cache and emit `mcp:servers-changed`.
+ */
+export async function watchProjectMcpServers(projectPath: string): Promise<void> {
+  return invoke<void>("watch_project_mcp_servers", { projectPath });
+}
+
+/**
+ * Stops watching the project's `.mcp.json`.
+ */
+export async function unwatchProjectMcpServers(projectPath: string): Promise<void> {
+  return invoke<void>("unwatch_project_mcp_servers", { projectPath });
+}
+
 /**
  * Gets the enabled MCP server names for a specific session.
  * If not explicitly set, returns all available servers.
@@ -94,6 +128,28 @@ export async function setSessionMcpServers(
   return invoke("set_session_mcp_servers", { projectPath, sessionId, enabled });
 }
 
+/**
+ * Gets the env var overrides for a specific session.
+ */
+export async function getSessionMcpEnv(
+  projectPath: string,
+  sessionId: number
+): Promise<Record<string, string>> {
+  return invoke<Record<string, string>>("get_session_mcp_env", { projectPath, sessionId });
+}
+
+/**
+ * Sets env var overrides for a specific session, merged over each stdio
+ * server's env the next time its `.mcp.json` is written. Pass `{}` to clear.
+ */
+export async function setSessionMcpEnv(
+  projectPath: string,
+  sessionId: number,
+  env: Record<string, string>
+): Promise<void> {
+  return invoke("set_session_mcp_env", { projectPath, sessionId, env });
+}
+
 /**
  * Returns the count of enabled MCP servers for a session.
  */
@@ -150,6 +206,41 @@ export async function writeSessionMcpConfig(
   });
 }
 
+/**
+ * Returns the `.mcp.json` content `writeSessionMcpConfig` would write with the
+ * same arguments, merged against the current file but without writing it.
+ * Null if the session's CLI doesn't read `.mcp.json`, so nothing is written.
+ */
+export async function previewSessionMcpConfig(
+  workingDir: string,
+  sessionId: number,
+  projectPath: string,
+  enabledServerNames: string[]
+): Promise<string | null> {
+  return invoke<string | null>("preview_session_mcp_config", {
+    workingDir,
+    sessionId,
+    projectPath,
+    enabledServerNames,
+  });
+}
+
+/**
+ * Returns the resolved chorus-mcp-server binary path, or null when it can't be
+ * found (session status reporting is then disabled).
+ */
+export async function getChorusMcpServerPath(): Promise<string | null> {
+  return invoke<string | null>("get_chorus_mcp_server_path");
+}
+
+/**
+ * Sets a chorus-mcp-server binary path to try before the built-in locations,
+ * or clears it with null. Resolves with the newly resolved path.
+ */
+export async function setChorusMcpServerPath(path: string | null): Promise<string | null> {
+  return invoke<string | null>("set_chorus_mcp_server_path", { path });
+}
+
 /**
  * Removes a session-specific Chorus server from `.mcp.json`.
  *
@@ -174,12 +265,23 @@ export async function getCustomMcpServers(): Promise<McpCustomServer[]> {
   return invoke<McpCustomServer[]>("get_custom_mcp_servers");
 }
 
+/**
+ * Error returned when a custom MCP server fails validation.
+ * `field` names the offending form field, or is null for other failures.
+ */
+export interface McpServerValidationError {
+  field: "name" | "command" | null;
+  message: string;
+}
+
 /**
  * Saves a custom MCP server configuration.
  * If the server already exists (by ID), it will be updated.
+ * Rejects with a `McpServerValidationError`; resolves with non-fatal
+ * warnings (e.g. the command was not found on PATH).
  */
-export async function saveCustomMcpServer(server: McpCustomServer): Promise<void> {
-  return invoke("save_custom_mcp_server", { server });
+export async function saveCustomMcpServer(server: McpCustomServer): Promise<string[]> {
+  return invoke<string[]>("save_custom_mcp_server", { server });
 }
 
 /**
@@ -166,14 +166,22 @@ export async function getSessionSkills(
 }
 
 /**
- * Sets the enabled skill IDs for a specific session.
+ * Sets the enabled skill IDs for a specific session and resolves with the
+ * effective set, which includes skills pulled in as dependencies. With
+ * `autoEnableDeps` false, missing dependencies reject instead.
  */
 export async function setSessionSkills(
   projectPath: string,
   sessionId: number,
-  enabled: string[]
-): Promise<void> {
-  return invoke("set_session_skills", { projectPath, sessionId, enabled });
+  enabled: string[],
+  autoEnableDeps = true
+): Promise<string[]> {
+  return invoke<string[]>("set_session_skills", {
+    projectPath,
+    sessionId,
+    enabled,
+    autoEnableDeps,
+  });
 }
 
 /**
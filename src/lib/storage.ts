@@ -101,7 +101,7 @@ function createBrowserStorage(fileName: string): StateStorage {
     removeItem: async (name: string): Promise<void> => {
       try {
         const { invoke: wsInvoke } = await import("./transport");
-        await wsInvoke("store_set", { fileName, key: name, value: null });
+        await wsInvoke("store_delete", { fileName, key: name });
       } catch (err) {
         console.error(`browserStorage.removeItem("${name}") failed:`, err);
       }
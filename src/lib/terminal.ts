@@ -14,10 +14,23 @@ import type { BackendCapabilities, BackendType } from "./terminalTheme";
  * @param env - Environment variables to pass to the shell process. These are inherited
  *   by all child processes (including Claude CLI → MCP server). CHORUS_SESSION_ID is
  *   automatically set by the backend.
+ * @param backend - Terminal backend for this session; defaults to the backend's default.
+ * @param sessionId - Spawn the shell of this existing session (e.g. one restored as
+ *   stopped) so its custom env applies; a new ID is allocated when omitted.
  * @returns The numeric session ID assigned by the backend.
  */
-export async function spawnShell(cwd?: string, env?: Record<string, string>): Promise<number> {
-  return invoke<number>("spawn_shell", { cwd: cwd ?? null, env: env ?? null });
+export async function spawnShell(
+  cwd?: string,
+  env?: Record<string, string>,
+  backend?: BackendType,
+  sessionId?: number,
+): Promise<number> {
+  return invoke<number>("spawn_shell", {
+    cwd: cwd ?? null,
+    env: env ?? null,
+    backend: backend ?? null,
+    sessionId: sessionId ?? null,
+  });
 }
 
 /** Writes raw bytes to the PTY stdin of the given session. */
@@ -36,7 +49,7 @@ export async function killSession(sessionId: number): Promise<void> {
 }
 
 /** AI mode variants matching the backend enum. */
-export type AiMode = "Claude" | "Gemini" | "Codex" | "Plain";
+export type AiMode = "Claude" | "Gemini" | "Codex" | "Aider" | "Plain";
 
 /** CLI command configuration for each AI mode */
 export const AI_CLI_CONFIG: Record<AiMode, { command: string | null; installHint: string }> = {
@@ -52,17 +65,50 @@ export const AI_CLI_CONFIG: Record<AiMode, { command: string | null; installHint
     command: "codex",
     installHint: "npm install -g codex"
   },
+  Aider: {
+    command: "aider",
+    installHint: "python -m pip install aider-install && aider-install"
+  },
   Plain: {
     command: null,
     installHint: ""
   },
 };
 
+/** Describes an AI mode, as listed by the backend. */
+export interface AiModeInfo {
+  mode: AiMode;
+  label: string;
+  command: string | null;
+  installHint: string | null;
+  /** Whether the mode's CLI reads the session's `.mcp.json`. */
+  usesMcpConfig: boolean;
+}
+
+/** Lists the AI modes a session can be created with. */
+export async function getAiModes(): Promise<AiModeInfo[]> {
+  return invoke<AiModeInfo[]>("get_ai_modes");
+}
+
 /** Checks if a CLI tool is available in the user's PATH */
 export async function checkCliAvailable(command: string): Promise<boolean> {
   return invoke<boolean>("check_cli_available", { command });
 }
 
+/** Error from `checkSessionCli` when a mode's CLI is not installed. */
+export interface CliNotFound {
+  command: string;
+  installHint: string;
+}
+
+/**
+ * Verifies the CLI for `mode` is installed before a session is launched.
+ * Rejects with a `CliNotFound` carrying an install hint; Plain always passes.
+ */
+export async function checkSessionCli(mode: AiMode): Promise<void> {
+  return invoke<void>("check_session_cli", { mode });
+}
+
 /** Session config returned by createSession. */
 export interface SessionConfig {
   id: number;
@@ -71,15 +117,21 @@ export interface SessionConfig {
   status: string;
   worktree_path: string | null;
   project_path: string;
+  note?: string | null;
+  order?: number | null;
+  tags?: string[];
+  /** Custom environment variables applied when the shell is (re)spawned */
+  env?: Record<string, string>;
 }
 
 /** Creates a session in the SessionManager (separate from PTY spawning). */
 export async function createSession(
   id: number,
   mode: AiMode,
-  projectPath: string
+  projectPath: string,
+  env?: Record<string, string>
 ): Promise<SessionConfig> {
-  return invoke<SessionConfig>("create_session", { id, mode, projectPath });
+  return invoke<SessionConfig>("create_session", { id, mode, projectPath, env: env ?? null });
 }
 
 /** Assigns a branch and optional worktree path to a session. */
@@ -91,6 +143,126 @@ export async function assignSessionBranch(
   return invoke("assign_session_branch", { sessionId, branch, worktreePath });
 }
 
+/** Sets or clears (with `null`) a session's freeform note. */
+export async function setSessionNote(
+  sessionId: number,
+  note: string | null
+): Promise<SessionConfig> {
+  return invoke<SessionConfig>("set_session_note", { sessionId, note });
+}
+
+/** Replaces a session's tags; an empty array clears them. */
+export async function setSessionTags(sessionId: number, tags: string[]): Promise<SessionConfig> {
+  return invoke<SessionConfig>("set_session_tags", { sessionId, tags });
+}
+
+/** Result of `updateSessionsStatus`. */
+export interface BulkStatusUpdate {
+  /** Sessions whose status was updated, in the order requested */
+  updated: SessionConfig[];
+  /** Requested IDs that don't exist */
+  missing: number[];
+}
+
+/** Sets one status on several sessions in a single call; unknown IDs are reported, not fatal. */
+export async function updateSessionsStatus(
+  sessionIds: number[],
+  status: string
+): Promise<BulkStatusUpdate> {
+  return invoke<BulkStatusUpdate>("update_sessions_status", { sessionIds, status });
+}
+
+/** Returns a session's custom environment variables. */
+export async function getSessionEnv(sessionId: number): Promise<Record<string, string>> {
+  return invoke<Record<string, string>>("get_session_env", { sessionId });
+}
+
+/**
+ * Replaces a session's custom environment variables.
+ * A running shell keeps its environment; changes apply on the next spawn or restart.
+ */
+export async function setSessionEnv(
+  sessionId: number,
+  env: Record<string, string>
+): Promise<SessionConfig> {
+  return invoke<SessionConfig>("set_session_env", { sessionId, env });
+}
+
+/** Lists sessions carrying `tag`, across all projects. */
+export async function getSessionsByTag(tag: string): Promise<SessionConfig[]> {
+  return invoke<SessionConfig[]>("get_sessions_by_tag", { tag });
+}
+
+/** Sets the session display order; returns all sessions in the new order. */
+export async function reorderSessions(ids: number[]): Promise<SessionConfig[]> {
+  return invoke<SessionConfig[]>("reorder_sessions", { ids });
+}
+
+/** Result of comparing a session's assigned branch with its worktree checkout. */
+export interface SessionBranchCheck {
+  session_id: number;
+  expected_branch: string | null;
+  actual_branch: string;
+  mismatch: boolean;
+}
+
+/** Checks whether the session's worktree is still on its assigned branch. */
+export async function verifySessionBranch(sessionId: number): Promise<SessionBranchCheck> {
+  return invoke<SessionBranchCheck>("verify_session_branch", { sessionId });
+}
+
+/** How much scrollback a session's output buffer holds, in bytes. */
+export interface SessionOutputInfo {
+  bufferedBytes: number;
+  limitBytes: number;
+  droppedBytes: number;
+}
+
+/** Returns scrollback buffer info for a session, or null if it doesn't exist. */
+export async function getSessionOutputInfo(sessionId: number): Promise<SessionOutputInfo | null> {
+  return invoke<SessionOutputInfo | null>("get_session_output_info", { sessionId });
+}
+
+/** Sets a session's scrollback limit in bytes; returns the (clamped) limit applied. */
+export async function setScrollbackLimit(sessionId: number, limit: number): Promise<number> {
+  return invoke<number>("set_scrollback_limit", { sessionId, limit });
+}
+
+/**
+ * Persists each session's scrollback to `~/.chorus/sessions/<id>.log` so it
+ * can be shown again after a crash. Disabling deletes running sessions' logs.
+ * The choice is kept across restarts.
+ */
+export async function setScrollbackPersistence(enabled: boolean): Promise<void> {
+  return invoke<void>("set_scrollback_persistence", { enabled });
+}
+
+/** Whether session scrollback is currently persisted to disk. */
+export async function getScrollbackPersistence(): Promise<boolean> {
+  return invoke<boolean>("get_scrollback_persistence");
+}
+
+/**
+ * Pauses a session's output events. Output still reaches scrollback and is
+ * delivered, in order, by `resumeSessionOutput`.
+ */
+export async function pauseSessionOutput(sessionId: number): Promise<void> {
+  return invoke("pause_session_output", { sessionId });
+}
+
+/** Resumes a paused session's output events. */
+export async function resumeSessionOutput(sessionId: number): Promise<void> {
+  return invoke("resume_session_output", { sessionId });
+}
+
+/**
+ * Sets how long PTY output is held to merge bursts into one event (all
+ * sessions; 0 disables). Returns the window applied, in ms.
+ */
+export async function setOutputCoalesceWindow(windowMs: number): Promise<number> {
+  return invoke<number>("set_output_coalesce_window", { windowMs });
+}
+
 /** Updates a session's auto-generated title. */
 export async function updateSessionTitle(
   sessionId: number,
@@ -113,10 +285,165 @@ export function onPtyOutput(
   });
 }
 
+/**
+ * Starts recording a session's output to a new asciicast v2 (`.cast`) file;
+ * an existing file is never overwritten. Over web access `path` must be a
+ * bare file name, saved under `~/.chorus/recordings`.
+ */
+export async function startRecording(sessionId: number, path: string): Promise<void> {
+  return invoke<void>("start_recording", { sessionId, path });
+}
+
+/** Stops a session's recording; resolves to the cast file path, or null if not recording. */
+export async function stopRecording(sessionId: number): Promise<string | null> {
+  return invoke<string | null>("stop_recording", { sessionId });
+}
+
+/** A page of session output addressed by absolute byte offsets. */
+export interface SessionOutputChunk {
+  data: string;
+  offset: number;
+  nextOffset: number;
+  hasMore: boolean;
+  /** True if the requested offset had already scrolled out of the buffer. */
+  truncated: boolean;
+}
+
+/**
+ * Returns up to `maxBytes` (default 64 KiB) of buffered output after the
+ * absolute byte `offset`, or null if the session doesn't exist. Pass
+ * `stripAnsi` to get plain text; offsets still refer to the raw stream.
+ */
+export async function getSessionOutputSince(
+  sessionId: number,
+  offset: number,
+  maxBytes?: number,
+  stripAnsi?: boolean,
+): Promise<SessionOutputChunk | null> {
+  return invoke<SessionOutputChunk | null>("get_session_output_since", {
+    sessionId,
+    offset,
+    maxBytes,
+    stripAnsi,
+  });
+}
+
+/**
+ * Replays a session's scrollback in pages, then keeps delivering live output.
+ *
+ * All output is read from the scrollback buffer by byte offset, so replayed
+ * and live output never overlap or leave a gap; `pty-output-{id}` events only
+ * signal that more is available. Subscribes before the first read so no
+ * signal is missed. Resolves to an unlisten function; if setup fails the
+ * subscription is released and the error rethrown.
+ */
+export async function streamSessionOutput(
+  sessionId: number,
+  callback: (data: string) => void,
+  fromOffset = 0,
+): Promise<UnlistenFn> {
+  let offset = fromOffset;
+  let reading: Promise<void> | null = null;
+  let readAgain = false;
+  let stopped = false;
+
+  const readAvailable = async () => {
+    do {
+      readAgain = false;
+      for (;;) {
+        const chunk = await getSessionOutputSince(sessionId, offset);
+        if (!chunk || stopped) return;
+        if (chunk.data) callback(chunk.data);
+        offset = chunk.nextOffset;
+        if (!chunk.hasMore) break;
+      }
+    } while (readAgain && !stopped);
+  };
+
+  // Runs one read loop at a time; signals during a read trigger another pass.
+  const pump = (): Promise<void> => {
+    if (reading) {
+      readAgain = true;
+      return reading;
+    }
+    reading = readAvailable().finally(() => {
+      reading = null;
+    });
+    return reading;
+  };
+
+  const unlisten = await onPtyOutput(sessionId, () => {
+    pump().catch((err) => console.error(`Failed to read output of session ${sessionId}:`, err));
+  });
+
+  try {
+    await pump();
+  } catch (err) {
+    stopped = true;
+    unlisten();
+    throw err;
+  }
+
+  return () => {
+    stopped = true;
+    unlisten();
+  };
+}
+
+/** Payload of the `session:exited` event. */
+export interface SessionExitedPayload {
+  sessionId: number;
+  exitCode: number;
+  /** Signal name if the shell was terminated by a signal. */
+  signal: string | null;
+  /** `killed` via killSession, `exited` cleanly, or `crashed`. */
+  reason: "killed" | "exited" | "crashed";
+}
+
+/**
+ * Subscribes to `session:exited`, fired when any session's shell process exits.
+ * The caller must invoke the returned unlisten function on cleanup.
+ */
+export function onSessionExited(
+  callback: (payload: SessionExitedPayload) => void,
+): Promise<UnlistenFn> {
+  return listen<SessionExitedPayload>("session:exited", callback);
+}
+
+/** Payload of the `session:restarted` event. */
+export interface SessionRestartedPayload {
+  sessionId: number;
+  /** PID of the new shell process. */
+  pid: number;
+}
+
+/**
+ * Kills a session's shell and spawns a fresh one under the same ID, keeping
+ * its cwd, env, tags and branch. Output continues on `pty-output-{id}`.
+ * Rejects sessions with no running shell (e.g. restored as Stopped).
+ * @returns The new shell's PID.
+ */
+export async function restartSession(sessionId: number): Promise<number> {
+  return invoke<number>("restart_session", { sessionId });
+}
+
+/**
+ * Subscribes to `session:restarted`, fired after a session's shell is restarted.
+ * The caller must invoke the returned unlisten function on cleanup.
+ */
+export function onSessionRestarted(
+  callback: (payload: SessionRestartedPayload) => void,
+): Promise<UnlistenFn> {
+  return listen<SessionRestartedPayload>("session:restarted", callback);
+}
+
 /** Backend info as returned by the Rust backend. */
 export interface BackendInfo {
+  /** Backend new sessions are spawned with */
   backendType: BackendType;
   capabilities: BackendCapabilities;
+  /** Backends compiled into this build */
+  availableBackends: BackendType[];
 }
 
 /** Cached backend info to avoid repeated IPC calls. */
@@ -134,6 +461,15 @@ export async function getBackendInfo(): Promise<BackendInfo> {
   return cachedBackendInfo;
 }
 
+/**
+ * Sets the backend for sessions spawned without an explicit choice.
+ * Rejects backends that aren't compiled into this build.
+ */
+export async function setDefaultBackend(backend: BackendType): Promise<BackendInfo> {
+  cachedBackendInfo = await invoke<BackendInfo>("set_default_backend", { backend });
+  return cachedBackendInfo;
+}
+
 /** Checks if the current backend supports enhanced terminal state. */
 export async function hasEnhancedState(): Promise<boolean> {
   const info = await getBackendInfo();
@@ -161,3 +497,39 @@ export async function getStatusServerInfo(): Promise<StatusServerInfo> {
   cachedStatusServerInfo = await invoke<StatusServerInfo>("get_status_server_info");
   return cachedStatusServerInfo;
 }
+
+/** A log line forwarded by a session's MCP server. */
+export interface McpLogEntry {
+  session_id: number;
+  level: string;
+  logger: string | null;
+  message: string;
+  timestamp: string;
+}
+
+/** Returns recent MCP server log lines for a session, oldest first. */
+export async function getMcpLogs(sessionId: number): Promise<McpLogEntry[]> {
+  return invoke<McpLogEntry[]>("get_mcp_logs", { sessionId });
+}
+
+/** A session registered with the status server; `lastState` is null until it reports. */
+export interface RegisteredStatusSession {
+  sessionId: number;
+  projectPath: string;
+  lastState: string | null;
+  lastReportedAt: string | null;
+}
+
+/** Lists sessions registered for MCP status reporting, by session id. */
+export async function getRegisteredStatusSessions(): Promise<RegisteredStatusSession[]> {
+  return invoke<RegisteredStatusSession[]>("get_registered_status_sessions");
+}
+
+/**
+ * Subscribes to `mcp-log`, fired when any session's MCP server forwards a log line.
+ * The caller must invoke the returned unlisten function on cleanup.
+ */
+export function onMcpLog(callback: (entry: McpLogEntry) => void): Promise<UnlistenFn> {
+  return listen<McpLogEntry>("mcp-log", callback);
+}
+
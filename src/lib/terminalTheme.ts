@@ -211,6 +211,12 @@ export interface BackendCapabilities {
   kittyGraphics: boolean;
   /** Backend supports shell integration hooks */
   shellIntegration: boolean;
+  /** 24-bit color sequences reach the renderer intact */
+  trueColor: boolean;
+  /** Mouse reporting modes requested by programs reach the renderer */
+  mouseReporting: boolean;
+  /** Smallest resize step the backend honours; every backend resizes by cell today */
+  resizeGranularity: "cell";
   /** Name of the backend implementation */
   backendName: string;
 }
@@ -26,10 +26,14 @@ let ws: WebSocket | null = null;
 let wsReady: Promise<void> | null = null;
 let wsStatus: WsConnectionStatus = "disconnected";
 const wsStatusListeners = new Set<(status: WsConnectionStatus) => void>();
+const eventLagListeners = new Set<(dropped: number) => void>();
 let nextInvokeId = 1;
 const pendingInvokes = new Map<number, { resolve: (v: unknown) => void; reject: (e: Error) => void; timer: ReturnType<typeof setTimeout> }>();
-const eventHandlers = new Map<string, Set<(payload: unknown) => void>>();
+const eventHandlers = new Map<string, Set<(payload: unknown, event: string) => void>>();
 const subscribedEvents = new Set<string>();
+/** Per-event replay counts requested via `listen(..., { replay })`. */
+const eventReplay = new Map<string, number>();
+let tailedSessionIds: number[] = [];
 let reconnectAttempt = 0;
 let reconnectTimer: ReturnType<typeof setTimeout> | null = null;
 
@@ -44,6 +48,41 @@ export function onWsStatusChange(handler: (status: WsConnectionStatus) => void):
   return () => { wsStatusListeners.delete(handler); };
 }
 
+/**
+ * Called when the server reports that this client fell behind and missed
+ * `dropped` events. Handlers should refetch any state they derive from events.
+ */
+export function onEventsLagged(handler: (dropped: number) => void): () => void {
+  eventLagListeners.add(handler);
+  return () => { eventLagListeners.delete(handler); };
+}
+
+/** WebSocket protocol version this client speaks. */
+const PROTOCOL_VERSION = 2;
+const CLIENT_VERSION = "web";
+
+/** What the desktop announced in its handshake reply. */
+export interface ServerHello {
+  protocolVersion: number;
+  serverVersion: string;
+  /** Commands accepted by `invoke` over WebSocket. */
+  commands: string[];
+  /** Optional protocol features, e.g. `gzip-frames`. */
+  features: string[];
+}
+
+let serverHello: ServerHello | null = null;
+/** Set once the server turns out not to understand `Hello`. */
+let useLegacyHandshake = false;
+
+/**
+ * The connected server's handshake reply, for feature detection. Null in
+ * Tauri, before connecting, or against a desktop predating the handshake.
+ */
+export function getServerHello(): ServerHello | null {
+  return serverHello;
+}
+
 /** Get the current WebSocket connection status. */
 export function getWsStatus(): WsConnectionStatus {
   return wsStatus;
@@ -60,6 +99,13 @@ function getWsUrl(): string {
   return `${proto}//${window.location.host}/ws`;
 }
 
+/** Text frames are plain JSON; binary frames are gzipped JSON. */
+async function decodeFrame(data: string | ArrayBuffer): Promise<string> {
+  if (typeof data === "string") return data;
+  const stream = new Blob([data]).stream().pipeThrough(new DecompressionStream("gzip"));
+  return new Response(stream).text();
+}
+
 function connectWs(): Promise<void> {
   if (wsReady) return wsReady;
 
@@ -69,24 +115,62 @@ function connectWs(): Promise<void> {
 
     socket.onopen = () => {
       setWsStatus("authenticating");
-      const token = getTokenFromHash();
-      if (!token) {
+      if (!getTokenFromHash()) {
         rejectReady(new Error("No auth token in URL hash"));
         return;
       }
-      socket.send(JSON.stringify({ type: "Auth", token }));
+      serverHello = null;
+      if (useLegacyHandshake) {
+        socket.send(JSON.stringify({ type: "Auth", token: getTokenFromHash() }));
+      } else {
+        socket.send(JSON.stringify({ type: "Hello", protocolVersion: PROTOCOL_VERSION, clientVersion: CLIENT_VERSION }));
+      }
     };
 
+    // Once compression is on, large frames arrive gzipped as binary; decoding
+    // is async, so frames are handled through a promise chain to keep them in
+    // order. Errors are caught per frame so one bad frame can't stall the chain.
+    socket.binaryType = "arraybuffer";
+    let inbound = Promise.resolve();
     socket.onmessage = (event) => {
+      inbound = inbound
+        .then(() => decodeFrame(event.data))
+        .then(
+          (text) => {
+            try {
+              handleMessage(text);
+            } catch (e) {
+              console.error("[WS] Message handler failed:", e);
+            }
+          },
+          (e) => {
+            // Ask for plain text frames from here on
+            console.error("[WS] Failed to decompress frame, disabling compression:", e);
+            socket.send(JSON.stringify({ type: "SetCompression", enabled: false }));
+          },
+        );
+    };
+
+    const handleMessage = (text: string) => {
       let msg: { type: string; [key: string]: unknown };
       try {
-        msg = JSON.parse(event.data);
+        msg = JSON.parse(text);
       } catch {
-        console.error("[WS] Unparseable message:", event.data);
+        console.error("[WS] Unparseable message:", text);
         return;
       }
 
       switch (msg.type) {
+        case "ServerHello": {
+          serverHello = {
+            protocolVersion: msg.protocolVersion as number,
+            serverVersion: msg.serverVersion as string,
+            commands: msg.commands as string[],
+            features: msg.features as string[],
+          };
+          socket.send(JSON.stringify({ type: "Auth", token: getTokenFromHash() }));
+          break;
+        }
         case "AuthResult": {
           if (msg.success) {
             ws = socket;
@@ -94,10 +178,21 @@ function connectWs(): Promise<void> {
             reconnectAttempt = 0;
             // Re-subscribe to events that were active before reconnect
             for (const evt of subscribedEvents) {
-              socket.send(JSON.stringify({ type: "Subscribe", event: evt }));
+              const replay = eventReplay.get(evt) ?? 0;
+              socket.send(JSON.stringify({ type: "Subscribe", event: evt, replay }));
+            }
+            if (tailedSessionIds.length > 0) {
+              socket.send(JSON.stringify({ type: "TailSessions", sessionIds: tailedSessionIds }));
+            }
+            if (serverHello?.features.includes("gzip-frames") && typeof DecompressionStream === "function") {
+              socket.send(JSON.stringify({ type: "SetCompression", enabled: true }));
             }
             resolveReady();
           } else {
+            // A desktop predating the handshake rejects Hello; reconnect without it
+            if (!serverHello && msg.error === "First message must be Auth") {
+              useLegacyHandshake = true;
+            }
             rejectReady(new Error(`Auth failed: ${msg.error ?? "unknown"}`));
             socket.close();
           }
@@ -117,11 +212,21 @@ function connectWs(): Promise<void> {
           }
           break;
         }
+        case "EventLagged": {
+          const dropped = msg.dropped as number;
+          console.warn(`[WS] Missed ${dropped} events; state may be stale`);
+          for (const fn of eventLagListeners) fn(dropped);
+          break;
+        }
         case "Event": {
-          const handlers = eventHandlers.get(msg.event as string);
-          if (handlers) {
+          const name = msg.event as string;
+          for (const [pattern, handlers] of eventHandlers) {
+            const matches = pattern.endsWith("*")
+              ? name.startsWith(pattern.slice(0, -1))
+              : pattern === name;
+            if (!matches) continue;
             for (const h of handlers) {
-              try { h(msg.payload); } catch (e) { console.error("[WS] Event handler error:", e); }
+              try { h(msg.payload, name); } catch (e) { console.error("[WS] Event handler error:", e); }
             }
           }
           break;
@@ -129,7 +234,12 @@ function connectWs(): Promise<void> {
       }
     };
 
-    socket.onclose = () => {
+    socket.onclose = (event) => {
+      if (event.code === 1002) {
+        // Protocol version mismatch; retrying won't help
+        console.error(`[WS] Server refused connection: ${event.reason}`);
+        rejectReady(new Error(event.reason || "Incompatible Chorus version"));
+      }
       ws = null;
       wsReady = null;
       setWsStatus("disconnected");
@@ -139,7 +249,7 @@ function connectWs(): Promise<void> {
         pending.reject(new Error("WebSocket closed"));
         pendingInvokes.delete(id);
       }
-      scheduleReconnect();
+      if (event.code !== 1002) scheduleReconnect();
     };
 
     socket.onerror = (err) => {
@@ -205,18 +315,59 @@ export async function invoke<T>(cmd: string, args?: Record<string, unknown>): Pr
   });
 }
 
+/** Round-trip time of a `ping` and the server's clock when it answered. */
+export interface PingResult {
+  rttMs: number;
+  serverTime: number;
+}
+
+/**
+ * Measures latency to the backend with the stateless `ping` command.
+ * In Tauri there is no network hop, so this resolves immediately with 0.
+ */
+export async function ping(): Promise<PingResult> {
+  if (isTauri()) {
+    return { rttMs: 0, serverTime: Date.now() };
+  }
+
+  const nonce = Math.random().toString(36).slice(2);
+  const started = performance.now();
+  const reply = await invoke<{ nonce: unknown; serverTime: number }>("ping", { nonce });
+  if (reply.nonce !== nonce) {
+    throw new Error("Ping reply did not echo the nonce");
+  }
+  return { rttMs: performance.now() - started, serverTime: reply.serverTime };
+}
+
+/**
+ * Returns the commands the backend accepts, for feature detection.
+ * In Tauri every command is available, so this resolves to null.
+ */
+export async function listCommands(): Promise<string[] | null> {
+  if (isTauri()) {
+    return null;
+  }
+  return invoke<string[]>("list_commands");
+}
+
 /**
  * Subscribes to a backend event.
  * In Tauri: delegates to `@tauri-apps/api/event` listen.
- * In browser: sends a Subscribe message over WebSocket.
+ * In browser: sends a Subscribe message over WebSocket. With `options.replay`,
+ * the server first delivers up to that many recent events of this name (also
+ * on every reconnect), so only use it for events that are safe to see twice,
+ * like status updates. Over WebSocket, an `event` ending in `*` matches every
+ * event with that prefix (e.g. `session:*`); the handler's second argument is
+ * the concrete event name. Tauri's native listener only matches exact names.
  */
 export async function listen<T>(
   event: string,
-  handler: (payload: T) => void,
+  handler: (payload: T, event: string) => void,
+  options?: { replay?: number },
 ): Promise<UnlistenFn> {
   if (isTauri()) {
     const { listen: tauriListen } = await import("@tauri-apps/api/event");
-    return tauriListen<T>(event, (e) => handler(e.payload));
+    return tauriListen<T>(event, (e) => handler(e.payload, e.event));
   }
 
   // Register handler locally
@@ -225,15 +376,17 @@ export async function listen<T>(
     handlers = new Set();
     eventHandlers.set(event, handlers);
   }
-  const wrappedHandler = handler as (payload: unknown) => void;
+  const wrappedHandler = handler as (payload: unknown, event: string) => void;
   handlers.add(wrappedHandler);
 
   // Send subscribe message if this is the first handler for this event
   if (!subscribedEvents.has(event)) {
     subscribedEvents.add(event);
+    const replay = options?.replay ?? 0;
+    if (replay > 0) eventReplay.set(event, replay);
     try {
       const socket = await ensureWs();
-      socket.send(JSON.stringify({ type: "Subscribe", event }));
+      socket.send(JSON.stringify({ type: "Subscribe", event, replay }));
     } catch {
       // Will be subscribed on reconnect
     }
@@ -246,6 +399,7 @@ export async function listen<T>(
       if (h.size === 0) {
         eventHandlers.delete(event);
         subscribedEvents.delete(event);
+        eventReplay.delete(event);
         if (ws?.readyState === WebSocket.OPEN) {
           ws.send(JSON.stringify({ type: "Unsubscribe", event }));
         }
@@ -253,3 +407,49 @@ export async function listen<T>(
     }
   };
 }
+
+/** One output delta from a tailed session. */
+export interface TailedOutput {
+  sessionId: number;
+  data: string;
+}
+
+/**
+ * Streams the output of several sessions as one interleaved feed.
+ * In Tauri: listens on each `pty-output-{id}` event and tags the payloads.
+ * In browser: sends a TailSessions message; the server multiplexes output
+ * into `session-tail` events. Only one tail is active per connection, so a
+ * new call replaces the previous set of sessions.
+ */
+export async function tailSessions(
+  sessionIds: number[],
+  handler: (output: TailedOutput) => void,
+): Promise<UnlistenFn> {
+  if (isTauri()) {
+    const unlisteners = await Promise.all(
+      sessionIds.map((sessionId) =>
+        listen<string>(`pty-output-${sessionId}`, (data) => handler({ sessionId, data })),
+      ),
+    );
+    return () => {
+      for (const unlisten of unlisteners) unlisten();
+    };
+  }
+
+  const unlisten = await listen<TailedOutput>("session-tail", handler);
+  tailedSessionIds = [...sessionIds];
+  try {
+    const socket = await ensureWs();
+    socket.send(JSON.stringify({ type: "TailSessions", sessionIds: tailedSessionIds }));
+  } catch {
+    // Will be re-sent on reconnect
+  }
+
+  return () => {
+    unlisten();
+    tailedSessionIds = [];
+    if (ws?.readyState === WebSocket.OPEN) {
+      ws.send(JSON.stringify({ type: "TailSessions", sessionIds: [] }));
+    }
+  };
+}
@@ -264,3 +264,44 @@ export async function cleanupSessionWorktree(
     return false;
   }
 }
+
+/** Result of pruning orphaned worktrees. */
+export interface OrphanPruneResult {
+  /** Worktree paths that were removed. */
+  removed: string[];
+  /** Orphans left in place (e.g. uncommitted changes), with the reason. */
+  skipped: { path: string; reason: string }[];
+}
+
+/**
+ * Lists Chorus-managed worktrees of a project that no session references.
+ *
+ * @param projectPath - The path to the main repository
+ */
+export async function listOrphanedWorktrees(projectPath: string): Promise<WorktreeInfo[]> {
+  return invoke<WorktreeInfo[]>("list_orphaned_worktrees", { projectPath });
+}
+
+/**
+ * Removes orphaned worktrees of a project. Worktrees with uncommitted
+ * changes are never removed; they are reported in `skipped`.
+ *
+ * @param projectPath - The path to the main repository
+ */
+export async function pruneOrphanedWorktrees(projectPath: string): Promise<OrphanPruneResult> {
+  return invoke<OrphanPruneResult>("prune_orphaned_worktrees", { projectPath });
+}
+
+/** A worktree with the bytes its checkout uses (excluding `.git` and symlinks). */
+export interface WorktreeDiskUsage extends WorktreeInfo {
+  bytes: number;
+}
+
+/**
+ * Lists every worktree of a repository with its disk usage, largest first.
+ *
+ * @param repoPath - The path to the main repository
+ */
+export async function getWorktreeDiskUsage(repoPath: string): Promise<WorktreeDiskUsage[]> {
+  return invoke<WorktreeDiskUsage[]>("worktree_disk_usage", { repoPath });
+}
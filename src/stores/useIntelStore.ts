@@ -9,6 +9,12 @@ export interface BroadcastMessage {
   message: string;
   metadata?: unknown;
   timestamp: string;
+  /** Project the sender was working on; absent for older entries. */
+  project_hash?: string | null;
+  /** Seconds after `timestamp` the message expires; absent never expires. */
+  ttl_secs?: number | null;
+  /** Times the sender repeated this message within the dedup window. */
+  repeat_count?: number;
 }
 
 /** A file conflict between sessions. */
@@ -21,6 +27,9 @@ export interface FileConflict {
     action: string;
     timestamp: string;
   }>;
+  /** Every involved session acknowledged it and nobody has edited since. */
+  acknowledged: boolean;
+  project_hash?: string | null;
 }
 
 /** A shared scratchpad entry. */
@@ -31,6 +40,65 @@ export interface ScratchpadEntry {
   title: string;
   content: string;
   timestamp: string;
+  project_hash?: string | null;
+}
+
+/** A newest-first page of intel entries. */
+export interface IntelPage<T> {
+  items: T[];
+  /** Entries across all pages. */
+  total: number;
+  offset: number;
+}
+
+/** Which intel stores were cleared, sent with `intel-cleared`. */
+export interface IntelCleared {
+  broadcasts: boolean;
+  conflicts: boolean;
+  scratchpad: boolean;
+}
+
+/** A shortened entry surfaced in an intel digest. */
+export interface DigestHighlight {
+  session_id: number;
+  category: string;
+  text: string;
+  timestamp: string;
+}
+
+/** Bounded summary of intel activity since a point in time. */
+export interface IntelDigest {
+  since: string;
+  broadcast_count: number;
+  broadcasts_by_category: Record<string, number>;
+  broadcast_highlights: DigestHighlight[];
+  conflict_count: number;
+  conflicted_files: string[];
+  scratchpad_count: number;
+  scratchpad_highlights: DigestHighlight[];
+}
+
+/** Fetches a "what did I miss" digest of intel newer than `since` (ISO 8601). */
+export async function fetchIntelDigest(since: string): Promise<IntelDigest> {
+  return invoke<IntelDigest>("get_intel_digest", { since });
+}
+
+/**
+ * Subscribes to conflicts as they form: pushed once per new conflict, and
+ * again only when another session joins it.
+ */
+export function onIntelConflictDetected(
+  callback: (conflict: FileConflict) => void,
+): Promise<UnlistenFn> {
+  return listen<FileConflict>("intel:conflict", callback);
+}
+
+/**
+ * Writes the scratchpad (and, unless `includeBroadcasts` is false, notable
+ * broadcasts) to `path` as markdown.
+ */
+export async function exportIntelMarkdown(path: string, includeBroadcasts = true): Promise<void> {
+  return invoke<void>("export_intel_markdown", { path, includeBroadcasts });
 }
 
 interface IntelState {
@@ -42,6 +110,9 @@ interface IntelState {
   fetchScratchpad: () => Promise<void>;
   writeScratchpad: (category: string, title: string, content: string) => Promise<void>;
   clearScratchpad: () => Promise<void>;
+  clearBroadcasts: () => Promise<void>;
+  clearConflicts: () => Promise<void>;
+  clearAll: () => Promise<void>;
   initListeners: () => Promise<UnlistenFn>;
 }
 
@@ -56,8 +127,9 @@ export const useIntelStore = create<IntelState>()((set, get) => ({
 
   fetchBroadcasts: async () => {
     try {
-      const messages = await invoke<BroadcastMessage[]>("get_intel_broadcasts");
-      set({ broadcasts: messages });
+      const page = await invoke<IntelPage<BroadcastMessage>>("get_intel_broadcasts");
+      // The store keeps entries oldest-first so live events can be appended.
+      set({ broadcasts: [...page.items].reverse() });
     } catch (err) {
       console.error("Failed to fetch intel broadcasts:", err);
     }
@@ -74,8 +146,8 @@ export const useIntelStore = create<IntelState>()((set, get) => ({
 
   fetchScratchpad: async () => {
     try {
-      const entries = await invoke<ScratchpadEntry[]>("get_intel_scratchpad");
-      set({ scratchpad: entries });
+      const page = await invoke<IntelPage<ScratchpadEntry>>("get_intel_scratchpad");
+      set({ scratchpad: [...page.items].reverse() });
     } catch (err) {
       console.error("Failed to fetch intel scratchpad:", err);
     }
@@ -100,6 +172,33 @@ export const useIntelStore = create<IntelState>()((set, get) => ({
     }
   },
 
+  clearBroadcasts: async () => {
+    try {
+      await invoke("clear_intel_broadcasts");
+      set({ broadcasts: [] });
+    } catch (err) {
+      console.error("Failed to clear intel broadcasts:", err);
+    }
+  },
+
+  clearConflicts: async () => {
+    try {
+      await invoke("clear_intel_conflicts");
+      set({ conflicts: [] });
+    } catch (err) {
+      console.error("Failed to clear intel conflicts:", err);
+    }
+  },
+
+  clearAll: async () => {
+    try {
+      await invoke("clear_all_intel");
+      set({ broadcasts: [], conflicts: [], scratchpad: [] });
+    } catch (err) {
+      console.error("Failed to clear intel:", err);
+    }
+  },
+
   initListeners: async () => {
     listenerCount += 1;
 
@@ -107,8 +206,9 @@ export const useIntelStore = create<IntelState>()((set, get) => ({
       if (!pendingInit) {
         pendingInit = Promise.all([
           listen<BroadcastMessage>("intel-broadcast", (msg) => {
+            // A repeated broadcast reuses its id; move it to the end.
             set((state) => ({
-              broadcasts: [...state.broadcasts, msg].slice(-200),
+              broadcasts: [...state.broadcasts.filter((b) => b.id !== msg.id), msg].slice(-200),
             }));
           }),
           listen<FileConflict[]>("intel-conflict", (conflicts) => {
@@ -119,6 +219,13 @@ export const useIntelStore = create<IntelState>()((set, get) => ({
               scratchpad: [...state.scratchpad, entry].slice(-50),
             }));
           }),
+          listen<IntelCleared>("intel-cleared", (cleared) => {
+            set((state) => ({
+              broadcasts: cleared.broadcasts ? [] : state.broadcasts,
+              conflicts: cleared.conflicts ? [] : state.conflicts,
+              scratchpad: cleared.scratchpad ? [] : state.scratchpad,
+            }));
+          }),
         ])
           .then((unlistens) => {
             activeUnlistens = unlistens;
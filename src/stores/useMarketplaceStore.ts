@@ -9,6 +9,7 @@ import { create } from "zustand";
 
 import {
   addMarketplaceSource,
+  checkPluginUpdates,
   getAvailablePlugins,
   getInstalledPlugins,
   getMarketplaceSources,
@@ -26,6 +27,7 @@ import type {
   MarketplaceFilters,
   MarketplacePlugin,
   MarketplaceSource,
+  PluginUpdate,
   ViewMode,
 } from "@/types/marketplace";
 
@@ -39,6 +41,9 @@ interface MarketplaceState {
   /** All installed plugins. */
   installedPlugins: InstalledPlugin[];
 
+  /** Installed plugins with a newer version available. */
+  pluginUpdates: PluginUpdate[];
+
   /** Currently selected plugin for details view. */
   selectedPlugin: MarketplacePlugin | null;
 
@@ -92,6 +97,9 @@ interface MarketplaceState {
   /** Refreshes a single marketplace source. */
   refreshSource: (sourceId: string) => Promise<void>;
 
+  /** Checks installed plugins for newer versions. */
+  checkUpdates: () => Promise<void>;
+
   /** Adds a new marketplace source. */
   addSource: (name: string, repositoryUrl: string, isOfficial?: boolean) => Promise<void>;
 
@@ -154,6 +162,7 @@ export const useMarketplaceStore = create<MarketplaceState>()((set, get) => ({
   sources: [],
   availablePlugins: [],
   installedPlugins: [],
+  pluginUpdates: [],
   selectedPlugin: null,
   searchText: "",
   filters: defaultFilters,
@@ -185,6 +194,8 @@ export const useMarketplaceStore = create<MarketplaceState>()((set, get) => ({
     try {
       await loadMarketplaceData();
       await get().fetchAll();
+      // Runs in the background; it may need to reach the network
+      get().checkUpdates();
     } catch (err) {
       console.error("Failed to initialize marketplace:", err);
       set({ error: String(err) });
@@ -248,6 +259,18 @@ export const useMarketplaceStore = create<MarketplaceState>()((set, get) => ({
     }
   },
 
+  checkUpdates: async () => {
+    try {
+      const { updates, warnings } = await checkPluginUpdates();
+      for (const warning of warnings) {
+        console.warn("Plugin update check:", warning);
+      }
+      set({ pluginUpdates: updates });
+    } catch (err) {
+      console.error("Failed to check plugin updates:", err);
+    }
+  },
+
   refreshSource: async (sourceId: string) => {
     set({ isRefreshing: true, error: null });
 
@@ -104,14 +104,14 @@ interface McpState {
   fetchCustomServers: () => Promise<McpCustomServer[]>;
 
   /**
-   * Adds or updates a custom MCP server.
+   * Adds or updates a custom MCP server. Resolves with validation warnings.
    */
-  addCustomServer: (server: McpCustomServer) => Promise<void>;
+  addCustomServer: (server: McpCustomServer) => Promise<string[]>;
 
   /**
-   * Updates an existing custom MCP server.
+   * Updates an existing custom MCP server. Resolves with validation warnings.
    */
-  updateCustomServer: (server: McpCustomServer) => Promise<void>;
+  updateCustomServer: (server: McpCustomServer) => Promise<string[]>;
 
   /**
    * Deletes a custom MCP server by ID.
@@ -280,7 +280,7 @@ export const useMcpStore = create<McpState>()((set, get) => ({
     }));
 
     try {
-      await saveCustomMcpServer(server);
+      return await saveCustomMcpServer(server);
     } catch (err) {
       console.error("Failed to save custom MCP server:", err);
       // Revert on error
@@ -303,7 +303,7 @@ export const useMcpStore = create<McpState>()((set, get) => ({
     }));
 
     try {
-      await saveCustomMcpServer(server);
+      return await saveCustomMcpServer(server);
     } catch (err) {
       console.error("Failed to update custom MCP server:", err);
       // Revert on error
@@ -259,12 +259,15 @@ export const usePluginStore = create<PluginState>()((set, get) => ({
       projectDefaultSkills: { ...state.projectDefaultSkills, [projectPath]: enabled },
     }));
 
-    // Persist to backend (session state and project defaults)
+    // Persist to backend, then adopt the effective set, which may include
+    // skills added as dependencies
     try {
-      await Promise.all([
-        setSessionSkillsApi(projectPath, sessionId, enabled),
-        saveProjectSkillDefaults(projectPath, enabled),
-      ]);
+      const effective = await setSessionSkillsApi(projectPath, sessionId, enabled);
+      set((state) => ({
+        sessionEnabledSkills: { ...state.sessionEnabledSkills, [key]: effective },
+        projectDefaultSkills: { ...state.projectDefaultSkills, [projectPath]: effective },
+      }));
+      await saveProjectSkillDefaults(projectPath, effective);
     } catch (err) {
       console.error("Failed to save session skills:", err);
     }
@@ -2,12 +2,13 @@ import { invoke, listen, type UnlistenFn } from "@/lib/transport";
 import { create } from "zustand";
 
 /** AI provider variants supported by the backend orchestrator. */
-export type AiMode = "Claude" | "Gemini" | "Codex" | "Plain";
+export type AiMode = "Claude" | "Gemini" | "Codex" | "Aider" | "Plain";
 
 /**
  * Backend-emitted session lifecycle states.
  * Must stay in sync with the Rust `SessionStatus` enum.
  * "Timeout" is a frontend-only status for sessions stuck in Starting state.
+ * "Stopped" marks sessions restored after an app restart whose PTY is gone.
  */
 export type BackendSessionStatus =
   | "Starting"
@@ -16,11 +17,15 @@ export type BackendSessionStatus =
   | "NeedsInput"
   | "Done"
   | "Error"
+  | "Stopped"
   | "Timeout";
 
 /** Timeout in milliseconds for sessions stuck in Starting state (Bug #74) */
 const SESSION_STARTUP_TIMEOUT_MS = 30000;
 
+/** Recent status events replayed to browser clients when they (re)subscribe. */
+const STATUS_EVENT_REPLAY = 50;
+
 /**
  * Mirrors the Rust `SessionConfig` struct returned by `get_sessions`.
  *
@@ -53,6 +58,14 @@ interface SessionStatusPayload {
   needs_input_prompt?: string;
 }
 
+/** Shape of the `session:status` event: raw MCP activity transitions. */
+export interface SessionActivityPayload {
+  sessionId: number;
+  state: "idle" | "working" | "needs_input" | "finished" | "error" | string;
+  previousState: string | null;
+  message: string;
+}
+
 /**
  * Zustand store slice for session metadata (not PTY I/O -- that lives in terminal.ts).
  *
@@ -285,6 +298,8 @@ export const useSessionStore = create<SessionState>()((set, get) => ({
     try {
       if (!activeUnlisten) {
         if (!pendingInit) {
+          // Replay recent transitions so a browser client that (re)connects
+          // mid-session doesn't miss them.
           pendingInit = listen<SessionStatusPayload>("session-status-changed", (payload) => {
             const { session_id, project_path, status, message, needs_input_prompt } = payload;
 
@@ -318,7 +333,7 @@ export const useSessionStore = create<SessionState>()((set, get) => ({
                   : s
               ),
             }));
-          })
+          }, { replay: STATUS_EVENT_REPLAY })
             .then((unlisten) => {
               activeUnlisten = unlisten;
             })
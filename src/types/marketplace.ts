@@ -80,6 +80,64 @@ export interface MarketplacePlugin {
   stars: number | null;
 }
 
+/** A marketplace search hit with just the fields a result list needs. */
+export interface MarketplaceSearchResult {
+  /** Unique identifier within the marketplace. */
+  id: string;
+  /** Human-readable name. */
+  name: string;
+  /** Description, shortened for list display. */
+  description: string;
+  /** Category for filtering. */
+  category: PluginCategory;
+  /** ID of the marketplace source this came from. */
+  marketplace_id: string;
+}
+
+/** What installing a plugin from a Git URL would add. */
+export interface GitPluginPreview {
+  /** Plugin name from its manifest; also the install directory name. */
+  name: string;
+  /** Version from the manifest. */
+  version: string;
+  /** Description from the manifest. */
+  description: string;
+  /** Repository URL the plugin was fetched from. */
+  repository_url: string;
+  /** Branch, tag or commit requested, or null for the default branch. */
+  git_ref: string | null;
+  /** Commit that was checked out. */
+  commit: string;
+  /** IDs of skills provided by this plugin. */
+  skills: string[];
+  /** Names of commands provided by this plugin. */
+  commands: string[];
+  /** Names of MCP servers provided by this plugin. */
+  mcp_servers: string[];
+  /** Names of agents provided by this plugin. */
+  agents: string[];
+  /** Names of hooks provided by this plugin. */
+  hooks: string[];
+}
+
+/** An installed plugin with a newer version available. */
+export interface PluginUpdate {
+  /** ID of the installed plugin. */
+  id: string;
+  /** Human-readable name. */
+  name: string;
+  /** Installed version. */
+  current: string;
+  /** Latest available version. */
+  latest: string;
+}
+
+/** Result of an update check; unreachable sources are listed in `warnings`. */
+export interface UpdateCheckResult {
+  updates: PluginUpdate[];
+  warnings: string[];
+}
+
 /** Source of an installed plugin - marketplace variant. */
 export interface MarketplaceInstalledSource {
   source: "marketplace";